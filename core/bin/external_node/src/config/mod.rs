@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     env,
     ffi::OsString,
     num::{NonZeroU32, NonZeroU64, NonZeroUsize},
@@ -13,6 +14,7 @@ use zksync_config::{
         api::{MaxResponseSize, MaxResponseSizeOverrides},
         chain::L1BatchCommitDataGeneratorMode,
         consensus::{ConsensusConfig, ConsensusSecrets},
+        database::MerkleTreeMode,
     },
     ObjectStoreConfig,
 };
@@ -21,10 +23,12 @@ use zksync_core::{
         tx_sender::TxSenderConfig,
         web3::{state::InternalApiConfig, Namespace},
     },
+    metadata_calculator::MetadataCalculatorConfig,
     temp_config_store::decode_yaml_repr,
 };
 #[cfg(test)]
 use zksync_dal::{ConnectionPool, Core};
+use zksync_node_db_pruner::{DbPrunerConfig, DbPrunerMode, PrunedDataClass};
 use zksync_protobuf_config::proto;
 use zksync_snapshots_applier::SnapshotsApplierConfig;
 use zksync_types::{api::BridgeAddresses, url::SensitiveUrl, ETHEREUM_ADDRESS};
@@ -289,6 +293,10 @@ pub(crate) struct OptionalENConfig {
     /// Time limit in milliseconds to abort a health check and return "not ready" status for the corresponding component.
     /// If not specified, the default value in the health check crate will be used.
     healthcheck_hard_time_limit_ms: Option<u64>,
+    /// Whether to expose query plans captured for slow DB queries (see `database_slow_query_plan_sample_rate`)
+    /// on the healthcheck server at `/debug/slow_query_plans`.
+    #[serde(default)]
+    pub healthcheck_expose_slow_query_plans: bool,
 
     // Gas estimation config
     /// The factor by which to scale the gasLimit
@@ -334,12 +342,30 @@ pub(crate) struct OptionalENConfig {
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     #[serde(default = "OptionalENConfig::default_merkle_tree_stalled_writes_timeout_sec")]
     merkle_tree_stalled_writes_timeout_sec: u64,
+    /// Whether to process L1 batches by splitting them into subtrees updated in parallel. This may speed up
+    /// processing large L1 batches at the cost of increased CPU usage.
+    #[serde(default)]
+    pub merkle_tree_parallel_updates: bool,
+    /// Whether to use Merkle tree checkpoints stored in the object store configured via `EN_TREE_CHECKPOINTS_OBJECT_STORE_*`
+    /// env variables. If enabled, an uninitialized tree is bootstrapped from the latest checkpoint in the store.
+    #[serde(default)]
+    pub merkle_tree_checkpoints_enabled: bool,
+    /// Interval (in L1 batches) between Merkle tree checkpoints uploaded to the checkpoint store. If not specified,
+    /// the node doesn't upload checkpoints. Has no effect unless `merkle_tree_checkpoints_enabled` is set.
+    pub merkle_tree_checkpoint_interval_l1_batches: Option<NonZeroU32>,
+    /// Maximum number of the most recent Merkle tree checkpoints retained in the checkpoint store.
+    #[serde(default = "OptionalENConfig::default_merkle_tree_max_retained_checkpoints")]
+    pub merkle_tree_max_retained_checkpoints: usize,
 
     // Postgres config (new parameters)
     /// Threshold in milliseconds for the DB connection lifetime to denote it as long-living and log its details.
     database_long_connection_threshold_ms: Option<u64>,
     /// Threshold in milliseconds to denote a DB query as "slow" and log its details.
     database_slow_query_threshold_ms: Option<u64>,
+    /// Sample rate in `[0, 1]` for capturing query plans of slow DB queries. If not set, plans are not captured.
+    database_slow_query_plan_sample_rate: Option<f64>,
+    /// Maximum number of captured query plans of slow DB queries stored in memory.
+    database_slow_query_plan_capacity: Option<usize>,
 
     // Other config settings
     /// Capacity of the queue for asynchronous miniblock sealing. Once this many miniblocks are queued,
@@ -358,6 +384,9 @@ pub(crate) struct OptionalENConfig {
     // This is intentionally not a part of `RemoteENConfig` because fetching this info from the main node would defeat
     // its purpose; the consistency checker assumes that the main node may provide false information.
     pub contracts_diamond_proxy_addr: Option<Address>,
+    /// Maximum number of the latest L1 batches re-checked by the consistency checker on node start.
+    #[serde(default = "OptionalENConfig::default_consistency_checker_max_batches_to_recheck")]
+    pub consistency_checker_max_batches_to_recheck: u32,
     /// Number of requests per second allocated for the main node HTTP client. Default is 100 requests.
     #[serde(default = "OptionalENConfig::default_main_node_rate_limit_rps")]
     pub main_node_rate_limit_rps: NonZeroUsize,
@@ -395,6 +424,23 @@ pub(crate) struct OptionalENConfig {
     /// If set to 0, L1 batches will not be retained based on their timestamp. The default value is 1 hour.
     #[serde(default = "OptionalENConfig::default_pruning_data_retention_sec")]
     pruning_data_retention_sec: u64,
+    /// Minimum number of the latest sealed L1 batches that are retained regardless of their age.
+    /// If set to 0 (the default), L1 batches will not be retained based on their number.
+    #[serde(default)]
+    pruning_retained_l1_batches: u32,
+    /// If set, the node will not prune any data; instead, it will periodically log and report in its health check
+    /// the range of L1 batches and L2 blocks that would be pruned.
+    #[serde(default)]
+    pruning_dry_run: bool,
+    /// If set, call traces (returned by `debug_trace*` methods) are pruned for L2 blocks older than this value
+    /// (in seconds). Unlike other pruning criteria, this one is applied even if the L2 block is otherwise retained.
+    pruning_call_traces_retention_sec: Option<u64>,
+    /// If set, events (returned by `eth_getLogs` and related methods) are pruned for L2 blocks older than
+    /// this value (in seconds), even if the L2 block is otherwise retained.
+    pruning_events_retention_sec: Option<u64>,
+    /// If set, transaction payloads are pruned for L2 blocks older than this value (in seconds),
+    /// even if the L2 block is otherwise retained.
+    pruning_transaction_payloads_retention_sec: Option<u64>,
 }
 
 impl OptionalENConfig {
@@ -477,6 +523,10 @@ impl OptionalENConfig {
         30
     }
 
+    const fn default_merkle_tree_max_retained_checkpoints() -> usize {
+        3
+    }
+
     const fn default_fee_history_limit() -> u64 {
         1_024
     }
@@ -509,6 +559,10 @@ impl OptionalENConfig {
         true
     }
 
+    const fn default_consistency_checker_max_batches_to_recheck() -> u32 {
+        10
+    }
+
     fn default_main_node_rate_limit_rps() -> NonZeroUsize {
         NonZeroUsize::new(100).unwrap()
     }
@@ -587,6 +641,14 @@ impl OptionalENConfig {
             .map(Duration::from_millis)
     }
 
+    pub fn slow_query_plan_sample_rate(&self) -> Option<f64> {
+        self.database_slow_query_plan_sample_rate
+    }
+
+    pub fn slow_query_plan_capacity(&self) -> Option<usize> {
+        self.database_slow_query_plan_capacity
+    }
+
     pub fn api_namespaces(&self) -> Vec<Namespace> {
         self.api_namespaces
             .clone()
@@ -623,6 +685,31 @@ impl OptionalENConfig {
         Duration::from_secs(self.pruning_data_retention_sec)
     }
 
+    pub fn pruning_retained_l1_batches(&self) -> u32 {
+        self.pruning_retained_l1_batches
+    }
+
+    pub fn pruning_dry_run(&self) -> bool {
+        self.pruning_dry_run
+    }
+
+    pub fn pruning_data_class_retention(&self) -> HashMap<PrunedDataClass, Duration> {
+        [
+            (
+                PrunedDataClass::CallTraces,
+                self.pruning_call_traces_retention_sec,
+            ),
+            (PrunedDataClass::Events, self.pruning_events_retention_sec),
+            (
+                PrunedDataClass::TransactionPayloads,
+                self.pruning_transaction_payloads_retention_sec,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(class, retention_sec)| Some((class, Duration::from_secs(retention_sec?))))
+        .collect()
+    }
+
     #[cfg(test)]
     fn mock() -> Self {
         // Set all values to their defaults
@@ -792,6 +879,23 @@ impl SnapshotsRecoveryConfig {
     }
 }
 
+/// Configuration for Merkle tree checkpoints. Loaded optionally, only if tree checkpoints are enabled.
+#[derive(Debug)]
+pub(crate) struct TreeCheckpointsConfig {
+    pub checkpoints_object_store: ObjectStoreConfig,
+}
+
+impl TreeCheckpointsConfig {
+    pub fn new() -> anyhow::Result<Self> {
+        let checkpoints_object_store = envy::prefixed("EN_TREE_CHECKPOINTS_OBJECT_STORE_")
+            .from_env::<ObjectStoreConfig>()
+            .context("failed loading tree checkpoints object store config from env variables")?;
+        Ok(Self {
+            checkpoints_object_store,
+        })
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ApiComponentConfig {
     /// Address of the tree API used by this EN in case it does not have a
@@ -873,6 +977,28 @@ impl ExternalNodeConfig {
             tree_component: TreeComponentConfig { api_port: None },
         }
     }
+
+    /// Returns the address of the L1 diamond proxy contract, checking the value specified in the config (if any)
+    /// against the value returned by the main node.
+    pub fn diamond_proxy_addr(&self) -> anyhow::Result<Address> {
+        let remote_diamond_proxy_addr = self.remote.diamond_proxy_addr;
+        Ok(
+            if let Some(addr) = self.optional.contracts_diamond_proxy_addr {
+                anyhow::ensure!(
+                    addr == remote_diamond_proxy_addr,
+                    "Diamond proxy address {addr:?} specified in config doesn't match one returned \
+                    by main node ({remote_diamond_proxy_addr:?})"
+                );
+                addr
+            } else {
+                tracing::info!(
+                    "Diamond proxy address is not specified in config; will use address \
+                    returned by main node: {remote_diamond_proxy_addr:?}"
+                );
+                remote_diamond_proxy_addr
+            },
+        )
+    }
 }
 
 impl From<&ExternalNodeConfig> for InternalApiConfig {
@@ -929,3 +1055,39 @@ impl From<&ExternalNodeConfig> for TxSenderConfig {
         }
     }
 }
+
+impl From<&ExternalNodeConfig> for MetadataCalculatorConfig {
+    fn from(config: &ExternalNodeConfig) -> Self {
+        Self {
+            db_path: config.required.merkle_tree_path.clone(),
+            max_open_files: config.optional.merkle_tree_max_open_files,
+            mode: MerkleTreeMode::Lightweight,
+            delay_interval: config.optional.metadata_calculator_delay(),
+            max_l1_batches_per_iter: config.optional.max_l1_batches_per_tree_iter,
+            multi_get_chunk_size: config.optional.merkle_tree_multi_get_chunk_size,
+            block_cache_capacity: config.optional.merkle_tree_block_cache_size(),
+            include_indices_and_filters_in_block_cache: config
+                .optional
+                .merkle_tree_include_indices_and_filters_in_block_cache,
+            memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
+            stalled_writes_timeout: config.optional.merkle_tree_stalled_writes_timeout(),
+            checkpoint_interval: config.optional.merkle_tree_checkpoint_interval_l1_batches,
+            max_retained_checkpoints: config.optional.merkle_tree_max_retained_checkpoints,
+            parallel_updates: config.optional.merkle_tree_parallel_updates,
+        }
+    }
+}
+
+impl From<&ExternalNodeConfig> for DbPrunerConfig {
+    fn from(config: &ExternalNodeConfig) -> Self {
+        Self {
+            removal_delay: config.optional.pruning_removal_delay(),
+            pruned_batch_chunk_size: config.optional.pruning_chunk_size,
+            minimum_l1_batch_age: config.optional.pruning_data_retention(),
+            minimum_retained_l1_batches: config.optional.pruning_retained_l1_batches(),
+            mode: DbPrunerMode::ExternalNode,
+            dry_run: config.optional.pruning_dry_run(),
+            data_class_retention: config.optional.pruning_data_class_retention(),
+        }
+    }
+}
@@ -13,13 +13,11 @@ use zksync_commitment_generator::{
     CommitmentGenerator,
 };
 use zksync_concurrency::{ctx, scope};
-use zksync_config::configs::{
-    api::MerkleTreeApiConfig, chain::L1BatchCommitDataGeneratorMode, database::MerkleTreeMode,
-};
+use zksync_config::configs::{api::MerkleTreeApiConfig, chain::L1BatchCommitDataGeneratorMode};
 use zksync_core::{
     api_server::{
         execution_sandbox::VmConcurrencyLimiter,
-        healthcheck::HealthCheckHandle,
+        healthcheck::{HealthCheckHandle, HealthProbes},
         tree::{TreeApiClient, TreeApiHttpClient},
         tx_sender::{proxy::TxProxy, ApiContracts, TxSenderBuilder},
         web3::{mempool_cache::MempoolCache, ApiBuilder, Namespace},
@@ -50,6 +48,7 @@ use zksync_eth_sender::l1_batch_commit_data_generator::{
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
 use zksync_node_db_pruner::{DbPruner, DbPrunerConfig};
 use zksync_node_fee_model::l1_gas_price::MainNodeFeeParamsFetcher;
+use zksync_object_store::ObjectStoreFactory;
 use zksync_state::{PostgresStorageCaches, RocksdbStorageOptions};
 use zksync_storage::RocksDB;
 use zksync_types::L2ChainId;
@@ -63,7 +62,7 @@ use zksync_web3_decl::{
 use crate::{
     config::{
         observability::ObservabilityENConfig, ExternalNodeConfig, OptionalENConfig,
-        RequiredENConfig,
+        RequiredENConfig, TreeCheckpointsConfig,
     },
     helpers::{EthClientHealthCheck, MainNodeHealthCheck, ValidateChainIdsTask},
     init::ensure_storage_initialized,
@@ -119,7 +118,6 @@ async fn build_state_keeper(
         Box::new(main_node_client.for_component("external_io")),
         chain_id,
     )
-    .await
     .context("Failed initializing I/O for external node state keeper")?;
 
     Ok(ZkSyncStateKeeper::new(
@@ -139,20 +137,7 @@ async fn run_tree(
     stop_receiver: watch::Receiver<bool>,
     tree_pool: ConnectionPool<Core>,
 ) -> anyhow::Result<Arc<dyn TreeApiClient>> {
-    let metadata_calculator_config = MetadataCalculatorConfig {
-        db_path: config.required.merkle_tree_path.clone(),
-        max_open_files: config.optional.merkle_tree_max_open_files,
-        mode: MerkleTreeMode::Lightweight,
-        delay_interval: config.optional.metadata_calculator_delay(),
-        max_l1_batches_per_iter: config.optional.max_l1_batches_per_tree_iter,
-        multi_get_chunk_size: config.optional.merkle_tree_multi_get_chunk_size,
-        block_cache_capacity: config.optional.merkle_tree_block_cache_size(),
-        include_indices_and_filters_in_block_cache: config
-            .optional
-            .merkle_tree_include_indices_and_filters_in_block_cache,
-        memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
-        stalled_writes_timeout: config.optional.merkle_tree_stalled_writes_timeout(),
-    };
+    let metadata_calculator_config = MetadataCalculatorConfig::from(config);
 
     let max_concurrency = config
         .optional
@@ -174,6 +159,13 @@ async fn run_tree(
             .await
             .context("failed initializing metadata calculator")?
             .with_recovery_pool(recovery_pool);
+    if config.optional.merkle_tree_checkpoints_enabled {
+        let checkpoints_config = TreeCheckpointsConfig::new()?;
+        let checkpoint_store = ObjectStoreFactory::new(checkpoints_config.checkpoints_object_store)
+            .create_store()
+            .await;
+        metadata_calculator = metadata_calculator.with_checkpoint_store(checkpoint_store);
+    }
 
     let tree_reader = Arc::new(metadata_calculator.tree_reader());
     app_health.insert_custom_component(Arc::new(metadata_calculator.tree_health_check()))?;
@@ -308,14 +300,7 @@ async fn run_core(
         tracing::info!(
             "Configured pruning of batches after they become {minimum_l1_batch_age:?} old"
         );
-        let db_pruner = DbPruner::new(
-            DbPrunerConfig {
-                removal_delay: config.optional.pruning_removal_delay(),
-                pruned_batch_chunk_size: config.optional.pruning_chunk_size,
-                minimum_l1_batch_age,
-            },
-            connection_pool.clone(),
-        );
+        let db_pruner = DbPruner::new(DbPrunerConfig::from(config), connection_pool.clone());
         app_health.insert_component(db_pruner.health_check())?;
         task_handles.push(tokio::spawn(db_pruner.run(stop_receiver.clone())));
     }
@@ -323,21 +308,7 @@ async fn run_core(
     let sk_handle = task::spawn(state_keeper.run());
     let fee_params_fetcher_handle =
         tokio::spawn(fee_params_fetcher.clone().run(stop_receiver.clone()));
-    let remote_diamond_proxy_addr = config.remote.diamond_proxy_addr;
-    let diamond_proxy_addr = if let Some(addr) = config.optional.contracts_diamond_proxy_addr {
-        anyhow::ensure!(
-            addr == remote_diamond_proxy_addr,
-            "Diamond proxy address {addr:?} specified in config doesn't match one returned \
-            by main node ({remote_diamond_proxy_addr:?})"
-        );
-        addr
-    } else {
-        tracing::info!(
-            "Diamond proxy address is not specified in config; will use address \
-            returned by main node: {remote_diamond_proxy_addr:?}"
-        );
-        remote_diamond_proxy_addr
-    };
+    let diamond_proxy_addr = config.diamond_proxy_addr()?;
 
     ensure_l1_batch_commit_data_generation_mode(
         config.optional.l1_batch_commit_data_generator_mode,
@@ -362,7 +333,7 @@ async fn run_core(
 
     let consistency_checker = ConsistencyChecker::new(
         eth_client,
-        10, // TODO (BFT-97): Make it a part of a proper EN config
+        config.optional.consistency_checker_max_batches_to_recheck,
         singleton_pool_builder
             .build()
             .await
@@ -821,6 +792,12 @@ async fn main() -> anyhow::Result<()> {
     if let Some(threshold) = config.optional.long_connection_threshold() {
         ConnectionPool::<Core>::global_config().set_long_connection_threshold(threshold)?;
     }
+    if let Some(sample_rate) = config.optional.slow_query_plan_sample_rate() {
+        ConnectionPool::<Core>::global_config().set_slow_query_plan_sample_rate(sample_rate)?;
+    }
+    if let Some(capacity) = config.optional.slow_query_plan_capacity() {
+        ConnectionPool::<Core>::global_config().set_slow_query_plan_capacity(capacity);
+    }
 
     RUST_METRICS.initialize();
     EN_METRICS.observe_config(&config);
@@ -894,6 +871,8 @@ async fn run_node(
     let healthcheck_handle = HealthCheckHandle::spawn_server(
         ([0, 0, 0, 0], config.required.healthcheck_port).into(),
         app_health.clone(),
+        HealthProbes::default(),
+        config.optional.healthcheck_expose_slow_query_plans,
     );
     // Start scraping Postgres metrics before store initialization as well.
     let pool_for_metrics = singleton_pool_builder.build().await?;
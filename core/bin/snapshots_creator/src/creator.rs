@@ -1,18 +1,19 @@
 //! [`SnapshotCreator`] and tightly related types.
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use anyhow::Context as _;
 use tokio::sync::Semaphore;
 use zksync_config::SnapshotsCreatorConfig;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, DalResult};
-use zksync_object_store::ObjectStore;
+use zksync_object_store::{ObjectStore, ObjectStoreError};
 use zksync_types::{
     snapshots::{
         uniform_hashed_keys_chunk, SnapshotFactoryDependencies, SnapshotFactoryDependency,
-        SnapshotMetadata, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey, SnapshotVersion,
+        SnapshotManifest, SnapshotMetadata, SnapshotObjectDigest, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey, SnapshotVersion,
     },
-    L1BatchNumber, L2BlockNumber,
+    K256PrivateKey, L1BatchNumber, L2BlockNumber, H256,
 };
 
 use crate::metrics::{FactoryDepsStage, StorageChunkStage, METRICS};
@@ -23,6 +24,8 @@ use crate::tests::HandleEvent;
 #[derive(Debug)]
 struct SnapshotProgress {
     l1_batch_number: L1BatchNumber,
+    /// L1 batch number of the base snapshot if the snapshot is differential.
+    base_l1_batch_number: Option<L1BatchNumber>,
     /// `true` if the snapshot is new (i.e., its progress is not recovered from Postgres).
     is_new_snapshot: bool,
     chunk_count: u64,
@@ -30,9 +33,14 @@ struct SnapshotProgress {
 }
 
 impl SnapshotProgress {
-    fn new(l1_batch_number: L1BatchNumber, chunk_count: u64) -> Self {
+    fn new(
+        l1_batch_number: L1BatchNumber,
+        base_l1_batch_number: Option<L1BatchNumber>,
+        chunk_count: u64,
+    ) -> Self {
         Self {
             l1_batch_number,
+            base_l1_batch_number,
             is_new_snapshot: true,
             chunk_count,
             remaining_chunk_ids: (0..chunk_count).collect(),
@@ -49,6 +57,7 @@ impl SnapshotProgress {
 
         Self {
             l1_batch_number: snapshot.l1_batch_number,
+            base_l1_batch_number: snapshot.base_l1_batch_number,
             is_new_snapshot: false,
             chunk_count: snapshot.storage_logs_filepaths.len() as u64,
             remaining_chunk_ids,
@@ -62,6 +71,8 @@ pub(crate) struct SnapshotCreator {
     pub blob_store: Arc<dyn ObjectStore>,
     pub master_pool: ConnectionPool<Core>,
     pub replica_pool: ConnectionPool<Core>,
+    /// Key used to sign snapshot manifests. If not set, manifests are not signed.
+    pub manifest_signing_key: Option<K256PrivateKey>,
     #[cfg(test)]
     pub event_listener: Box<dyn HandleEvent>,
 }
@@ -73,18 +84,20 @@ impl SnapshotCreator {
             .await
     }
 
+    /// Returns the digest of the saved chunk, or `None` if processing was interrupted (only happens in tests).
     async fn process_storage_logs_single_chunk(
         &self,
         semaphore: &Semaphore,
+        base_l2_block_number: Option<L2BlockNumber>,
         l2_block_number: L2BlockNumber,
         l1_batch_number: L1BatchNumber,
         chunk_id: u64,
         chunk_count: u64,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Option<SnapshotObjectDigest>> {
         let _permit = semaphore.acquire().await?;
         #[cfg(test)]
         if self.event_listener.on_chunk_started().should_exit() {
-            return Ok(());
+            return Ok(None);
         }
 
         let hashed_keys_range = uniform_hashed_keys_chunk(chunk_id, chunk_count);
@@ -92,11 +105,20 @@ impl SnapshotCreator {
 
         let latency =
             METRICS.storage_logs_processing_duration[&StorageChunkStage::LoadFromPostgres].start();
-        let logs = conn
-            .snapshots_creator_dal()
-            .get_storage_logs_chunk(l2_block_number, l1_batch_number, hashed_keys_range)
+        let mut dal = conn.snapshots_creator_dal();
+        let logs = if let Some(base_l2_block_number) = base_l2_block_number {
+            dal.get_storage_logs_chunk_diff(
+                base_l2_block_number,
+                l2_block_number,
+                l1_batch_number,
+                hashed_keys_range,
+            )
             .await
-            .context("Error fetching storage logs count")?;
+        } else {
+            dal.get_storage_logs_chunk(l2_block_number, l1_batch_number, hashed_keys_range)
+                .await
+        };
+        let logs = logs.context("Error fetching storage logs count")?;
         drop(conn);
         let latency = latency.observe();
         tracing::info!(
@@ -107,6 +129,7 @@ impl SnapshotCreator {
         let latency =
             METRICS.storage_logs_processing_duration[&StorageChunkStage::SaveToGcs].start();
         let storage_logs_chunk = SnapshotStorageLogsChunk { storage_logs: logs };
+        let digest = storage_logs_chunk.digest();
         let key = SnapshotStorageLogsStorageKey {
             l1_batch_number,
             chunk_id,
@@ -138,23 +161,27 @@ impl SnapshotCreator {
             "Saved chunk {chunk_id} (overall progress {}/{chunk_count}) in {latency:?} to location: {output_filepath}",
             chunk_count - tasks_left as u64
         );
-        Ok(())
+        Ok(Some(digest))
     }
 
     async fn process_factory_deps(
         &self,
+        base_l2_block_number: Option<L2BlockNumber>,
         l2_block_number: L2BlockNumber,
         l1_batch_number: L1BatchNumber,
-    ) -> anyhow::Result<String> {
+    ) -> anyhow::Result<(String, SnapshotObjectDigest)> {
         let mut conn = self.connect_to_replica().await?;
 
         tracing::info!("Loading factory deps from Postgres...");
         let latency =
             METRICS.factory_deps_processing_duration[&FactoryDepsStage::LoadFromPostgres].start();
-        let factory_deps = conn
-            .snapshots_creator_dal()
-            .get_all_factory_deps(l2_block_number)
-            .await?;
+        let mut dal = conn.snapshots_creator_dal();
+        let factory_deps = if let Some(base_l2_block_number) = base_l2_block_number {
+            dal.get_new_factory_deps(base_l2_block_number, l2_block_number)
+                .await?
+        } else {
+            dal.get_all_factory_deps(l2_block_number).await?
+        };
         drop(conn);
         let latency = latency.observe();
         tracing::info!("Loaded {} factory deps in {latency:?}", factory_deps.len());
@@ -169,6 +196,7 @@ impl SnapshotCreator {
             })
             .collect();
         let factory_deps = SnapshotFactoryDependencies { factory_deps };
+        let digest = factory_deps.digest();
         let filename = self
             .blob_store
             .put(l1_batch_number, &factory_deps)
@@ -184,7 +212,135 @@ impl SnapshotCreator {
             factory_deps.factory_deps.len()
         );
 
-        Ok(output_filepath)
+        Ok((output_filepath, digest))
+    }
+
+    /// Writes an integrity manifest for a fully generated snapshot. Digests missing from `factory_deps_digest`
+    /// and `chunk_digests` (i.e., for objects produced before snapshot creation was resumed) are computed
+    /// by loading the corresponding objects from the object store.
+    async fn save_manifest(
+        &self,
+        progress: &SnapshotProgress,
+        l2_block_number: L2BlockNumber,
+        factory_deps_digest: Option<SnapshotObjectDigest>,
+        mut chunk_digests: HashMap<u64, SnapshotObjectDigest>,
+    ) -> anyhow::Result<()> {
+        let l1_batch_number = progress.l1_batch_number;
+        let factory_deps_digest = if let Some(digest) = factory_deps_digest {
+            digest
+        } else {
+            let factory_deps: SnapshotFactoryDependencies = self
+                .blob_store
+                .get(l1_batch_number)
+                .await
+                .context("Error loading factory deps from blob store")?;
+            factory_deps.digest()
+        };
+
+        let mut storage_logs_chunks = Vec::with_capacity(progress.chunk_count as usize);
+        for chunk_id in 0..progress.chunk_count {
+            let digest = if let Some(digest) = chunk_digests.remove(&chunk_id) {
+                digest
+            } else {
+                let key = SnapshotStorageLogsStorageKey {
+                    l1_batch_number,
+                    chunk_id,
+                };
+                let chunk: SnapshotStorageLogsChunk =
+                    self.blob_store.get(key).await.with_context(|| {
+                        format!("Error loading storage logs chunk {key:?} from blob store")
+                    })?;
+                chunk.digest()
+            };
+            storage_logs_chunks.push(digest);
+        }
+
+        let mut conn = self.connect_to_replica().await?;
+        let tree_data = conn
+            .blocks_dal()
+            .get_l1_batch_tree_data(l1_batch_number)
+            .await?
+            .with_context(|| {
+                format!("Snapshot L1 batch #{l1_batch_number} doesn't have tree data")
+            })?;
+        drop(conn);
+
+        let manifest = SnapshotManifest {
+            version: SnapshotVersion::Version0.into(),
+            l1_batch_number,
+            l2_block_number,
+            base_l1_batch_number: progress.base_l1_batch_number,
+            expected_root_hash: tree_data.hash,
+            factory_deps: factory_deps_digest,
+            storage_logs_chunks,
+            manifest_hash: H256::zero(),
+            signature: None,
+        }
+        .seal();
+        let manifest = if let Some(signing_key) = &self.manifest_signing_key {
+            manifest.sign(signing_key)?
+        } else {
+            manifest
+        };
+        let filename = self
+            .blob_store
+            .put(l1_batch_number, &manifest)
+            .await
+            .context("Error storing snapshot manifest in blob store")?;
+        tracing::info!(
+            "Saved manifest for snapshot for L1 batch {l1_batch_number} with hash {:?} (signed: {}) to {filename}",
+            manifest.manifest_hash,
+            manifest.signature.is_some()
+        );
+        Ok(())
+    }
+
+    async fn has_manifest(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<bool> {
+        match self
+            .blob_store
+            .get::<SnapshotManifest>(l1_batch_number)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(ObjectStoreError::KeyNotFound(_)) => Ok(false),
+            Err(err) => {
+                Err(anyhow::Error::from(err)
+                    .context("Error loading snapshot manifest from blob store"))
+            }
+        }
+    }
+
+    /// Checks whether a differential snapshot can be created on top of `latest_snapshot`, i.e., whether
+    /// the chain of differential snapshots ending at `latest_snapshot` is shorter than the configured maximum.
+    async fn can_be_diff_base(
+        config: &SnapshotsCreatorConfig,
+        latest_snapshot: &SnapshotMetadata,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<bool> {
+        if config.max_differential_snapshots == 0 || !latest_snapshot.is_complete() {
+            return Ok(false);
+        }
+
+        let mut diff_count = 0;
+        let mut base_l1_batch_number = latest_snapshot.base_l1_batch_number;
+        while let Some(l1_batch_number) = base_l1_batch_number {
+            diff_count += 1;
+            if diff_count >= config.max_differential_snapshots {
+                return Ok(false);
+            }
+            let base_snapshot = conn
+                .snapshots_dal()
+                .get_snapshot_metadata(l1_batch_number)
+                .await?;
+            let Some(base_snapshot) = base_snapshot else {
+                tracing::warn!(
+                    "Base snapshot for L1 batch #{l1_batch_number} is missing; the snapshot chain is broken"
+                );
+                return Ok(false);
+            };
+            base_l1_batch_number = base_snapshot.base_l1_batch_number;
+        }
+        Ok(true)
     }
 
     /// Returns `Ok(None)` if the created snapshot would coincide with `latest_snapshot`.
@@ -192,6 +348,7 @@ impl SnapshotCreator {
         config: &SnapshotsCreatorConfig,
         min_chunk_count: u64,
         latest_snapshot: Option<&SnapshotMetadata>,
+        base_snapshot: Option<&SnapshotMetadata>,
         conn: &mut Connection<'_, Core>,
     ) -> anyhow::Result<Option<SnapshotProgress>> {
         // We subtract 1 so that after restore, EN node has at least one L1 batch to fetch.
@@ -224,6 +381,31 @@ impl SnapshotCreator {
             return Ok(None);
         }
 
+        if let Some(base_snapshot) = base_snapshot {
+            let base_l1_batch_number = base_snapshot.l1_batch_number;
+            let base_l2_blocks = conn
+                .blocks_dal()
+                .get_l2_block_range_of_l1_batch(base_l1_batch_number)
+                .await?;
+            if base_l2_blocks.is_some() {
+                // Differential snapshots must use the same chunking as their base so that chunks can be merged on recovery.
+                let chunk_count = base_snapshot.storage_logs_filepaths.len() as u64;
+                tracing::info!(
+                    "Creating differential snapshot for L1 batch {l1_batch_number} on top of snapshot \
+                     for L1 batch {base_l1_batch_number} with {chunk_count} chunks"
+                );
+                return Ok(Some(SnapshotProgress::new(
+                    l1_batch_number,
+                    Some(base_l1_batch_number),
+                    chunk_count,
+                )));
+            }
+            tracing::warn!(
+                "L2 blocks for base snapshot L1 batch #{base_l1_batch_number} are missing (e.g., pruned); \
+                 creating a full snapshot instead"
+            );
+        }
+
         let distinct_storage_logs_keys_count = conn
             .snapshots_creator_dal()
             .get_distinct_storage_logs_keys_count(l1_batch_number)
@@ -238,7 +420,11 @@ impl SnapshotCreator {
             "Selected storage logs chunking for L1 batch {l1_batch_number}: \
             {chunk_count} chunks of expected size {chunk_size}"
         );
-        Ok(Some(SnapshotProgress::new(l1_batch_number, chunk_count)))
+        Ok(Some(SnapshotProgress::new(
+            l1_batch_number,
+            None,
+            chunk_count,
+        )))
     }
 
     /// Returns `Ok(None)` if a snapshot should not be created / resumed.
@@ -255,18 +441,38 @@ impl SnapshotCreator {
             .snapshots_dal()
             .get_newest_snapshot_metadata()
             .await?;
-        drop(master_conn);
 
-        let pending_snapshot = latest_snapshot
-            .as_ref()
-            .filter(|snapshot| !snapshot.is_complete());
+        let pending_snapshot = match &latest_snapshot {
+            Some(snapshot) if !snapshot.is_complete() => Some(snapshot),
+            // The creator may have been interrupted after the last storage logs chunk was persisted,
+            // but before the manifest was saved. In this case, we resume the snapshot to save the manifest.
+            Some(snapshot) if !self.has_manifest(snapshot.l1_batch_number).await? => {
+                tracing::info!(
+                    "Snapshot for L1 batch {} is complete, but doesn't have a manifest; resuming it",
+                    snapshot.l1_batch_number
+                );
+                Some(snapshot)
+            }
+            _ => None,
+        };
         if let Some(snapshot) = pending_snapshot {
             Ok(Some(SnapshotProgress::from_existing_snapshot(snapshot)))
         } else {
+            let base_snapshot = match &latest_snapshot {
+                Some(snapshot)
+                    if Self::can_be_diff_base(config, snapshot, &mut master_conn).await? =>
+                {
+                    Some(snapshot)
+                }
+                _ => None,
+            };
+            drop(master_conn);
+
             Self::initialize_snapshot_progress(
                 config,
                 min_chunk_count,
                 latest_snapshot.as_ref(),
+                base_snapshot,
                 &mut self.connect_to_replica().await?,
             )
             .await
@@ -298,6 +504,19 @@ impl SnapshotCreator {
             .get_l2_block_range_of_l1_batch(progress.l1_batch_number)
             .await?
             .context("No L2 blocks for L1 batch")?;
+        let base_l2_block_number = if let Some(base_l1_batch_number) = progress.base_l1_batch_number
+        {
+            let (_, last_l2_block_number_in_base_batch) = conn
+                .blocks_dal()
+                .get_l2_block_range_of_l1_batch(base_l1_batch_number)
+                .await?
+                .with_context(|| {
+                    format!("No L2 blocks for base snapshot L1 batch #{base_l1_batch_number}")
+                })?;
+            Some(last_l2_block_number_in_base_batch)
+        } else {
+            None
+        };
         drop(conn);
 
         METRICS.storage_logs_chunks_count.set(progress.chunk_count);
@@ -307,9 +526,14 @@ impl SnapshotCreator {
             progress.l1_batch_number
         );
 
+        let mut factory_deps_digest = None;
         if progress.is_new_snapshot {
-            let factory_deps_output_file = self
-                .process_factory_deps(last_l2_block_number_in_batch, progress.l1_batch_number)
+            let (factory_deps_output_file, digest) = self
+                .process_factory_deps(
+                    base_l2_block_number,
+                    last_l2_block_number_in_batch,
+                    progress.l1_batch_number,
+                )
                 .await?;
 
             let mut master_conn = self
@@ -321,26 +545,46 @@ impl SnapshotCreator {
                 .add_snapshot(
                     SnapshotVersion::Version0,
                     progress.l1_batch_number,
+                    progress.base_l1_batch_number,
                     progress.chunk_count,
                     &factory_deps_output_file,
                 )
                 .await?;
+            factory_deps_digest = Some(digest);
         }
 
         METRICS
             .storage_logs_chunks_left_to_process
             .set(progress.remaining_chunk_ids.len());
         let semaphore = Semaphore::new(config.concurrent_queries_count as usize);
-        let tasks = progress.remaining_chunk_ids.into_iter().map(|chunk_id| {
+        let tasks = progress.remaining_chunk_ids.iter().map(|&chunk_id| {
             self.process_storage_logs_single_chunk(
                 &semaphore,
+                base_l2_block_number,
                 last_l2_block_number_in_batch,
                 progress.l1_batch_number,
                 chunk_id,
                 progress.chunk_count,
             )
         });
-        futures::future::try_join_all(tasks).await?;
+        let chunk_digests = futures::future::try_join_all(tasks).await?;
+        let chunk_digests: Option<HashMap<_, _>> = progress
+            .remaining_chunk_ids
+            .iter()
+            .zip(chunk_digests)
+            .map(|(&chunk_id, digest)| Some((chunk_id, digest?)))
+            .collect();
+        let Some(chunk_digests) = chunk_digests else {
+            // Snapshot creation was interrupted (only happens in tests).
+            return Ok(());
+        };
+        self.save_manifest(
+            &progress,
+            last_l2_block_number_in_batch,
+            factory_deps_digest,
+            chunk_digests,
+        )
+        .await?;
 
         METRICS
             .snapshot_l1_batch
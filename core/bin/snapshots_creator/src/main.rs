@@ -8,8 +8,18 @@
 //!
 //! It is assumed that the snapshot creator is run as a singleton process (no more than 1 instance
 //! at a time).
+//!
+//! Besides creating snapshots, the utility can verify integrity of an existing snapshot using its manifest;
+//! see the `verify` subcommand.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use anyhow::Context as _;
+use clap::{Parser, Subcommand};
 use prometheus_exporter::PrometheusExporterConfig;
 use tokio::{sync::watch, task::JoinHandle};
 use zksync_config::{
@@ -18,14 +28,66 @@ use zksync_config::{
 };
 use zksync_dal::{ConnectionPool, Core};
 use zksync_env_config::{object_store::SnapshotsObjectStoreConfig, FromEnv};
-use zksync_object_store::ObjectStoreFactory;
+use zksync_merkle_tree::{PatchSet, RocksDBWrapper};
+use zksync_object_store::{ObjectStore, ObjectStoreFactory};
+use zksync_types::{K256PrivateKey, L1BatchNumber, H256, H512};
 
-use crate::creator::SnapshotCreator;
+use crate::{creator::SnapshotCreator, verifier::SnapshotVerifier};
 
 mod creator;
 mod metrics;
 #[cfg(test)]
 mod tests;
+mod verifier;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Snapshot creator",
+    long_about = None
+)]
+struct Cli {
+    /// Command to run. If not specified, a new snapshot is created.
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Creates a new snapshot or continues creating a pending one.
+    Create,
+    /// Verifies integrity of a snapshot in the object store using its manifest. Doesn't require Postgres access.
+    Verify {
+        /// L1 batch number of the snapshot to verify.
+        #[arg(long = "l1-batch")]
+        l1_batch: u32,
+        /// Path to an empty directory used to store the Merkle tree recomputed from the snapshot.
+        /// If not specified, the tree is kept in memory, which is only feasible for small snapshots.
+        #[arg(long = "tree-path")]
+        tree_path: Option<PathBuf>,
+        /// Hex-encoded uncompressed secp256k1 public key (64 bytes, without the key type prefix) corresponding
+        /// to the manifest signing key of the snapshot creator. If specified, manifests of all snapshots
+        /// in the snapshot chain must be signed with this key.
+        #[arg(long = "signer-public-key")]
+        signer_public_key: Option<H512>,
+    },
+}
+
+/// Env variable with the hex-encoded secp256k1 private key used to sign snapshot manifests.
+const MANIFEST_SIGNING_KEY_VAR: &str = "SNAPSHOTS_CREATOR_MANIFEST_SIGNING_KEY";
+
+fn manifest_signing_key_from_env() -> anyhow::Result<Option<K256PrivateKey>> {
+    let Ok(key) = std::env::var(MANIFEST_SIGNING_KEY_VAR) else {
+        return Ok(None);
+    };
+    let key_bytes: H256 = key
+        .parse()
+        .with_context(|| format!("failed parsing {MANIFEST_SIGNING_KEY_VAR}"))?;
+    let key = K256PrivateKey::from_bytes(key_bytes)
+        .with_context(|| format!("{MANIFEST_SIGNING_KEY_VAR} is not a valid private key"))?;
+    Ok(Some(key))
+}
 
 async fn maybe_enable_prometheus_metrics(
     stop_receiver: watch::Receiver<bool>,
@@ -51,6 +113,7 @@ const MIN_CHUNK_COUNT: u64 = 10;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let command = Cli::parse().command.unwrap_or(Command::Create);
     let (stop_sender, stop_receiver) = watch::channel(false);
 
     let observability_config =
@@ -69,7 +132,6 @@ async fn main() -> anyhow::Result<()> {
             .with_sentry_environment(observability_config.sentry_environment);
     }
     let _guard = builder.build();
-    tracing::info!("Starting snapshots creator");
 
     let object_store_config =
         SnapshotsObjectStoreConfig::from_env().context("SnapshotsObjectStoreConfig::from_env()")?;
@@ -77,6 +139,34 @@ async fn main() -> anyhow::Result<()> {
         .create_store()
         .await;
 
+    let result = match command {
+        Command::Create => create_snapshot(blob_store).await,
+        Command::Verify {
+            l1_batch,
+            tree_path,
+            signer_public_key,
+        } => {
+            verify_snapshot(
+                blob_store,
+                L1BatchNumber(l1_batch),
+                tree_path,
+                signer_public_key,
+            )
+            .await
+        }
+    };
+    stop_sender.send(true).ok();
+    if let Some(prometheus_exporter_task) = prometheus_exporter_task {
+        prometheus_exporter_task
+            .await?
+            .context("Prometheus did not finish gracefully")?;
+    }
+    result
+}
+
+async fn create_snapshot(blob_store: Arc<dyn ObjectStore>) -> anyhow::Result<()> {
+    tracing::info!("Starting snapshots creator");
+
     let postgres_config = PostgresConfig::from_env().context("PostgresConfig")?;
     let creator_config =
         SnapshotsCreatorConfig::from_env().context("SnapshotsCreatorConfig::from_env")?;
@@ -92,21 +182,77 @@ async fn main() -> anyhow::Result<()> {
         .build()
         .await?;
 
+    let manifest_signing_key = manifest_signing_key_from_env()?;
+    if let Some(signing_key) = &manifest_signing_key {
+        tracing::info!(
+            "Snapshot manifests will be signed with key for address {:?}",
+            signing_key.address()
+        );
+    } else {
+        tracing::warn!(
+            "{MANIFEST_SIGNING_KEY_VAR} is not set; snapshot manifests will not be signed"
+        );
+    }
+
     let creator = SnapshotCreator {
         blob_store,
         master_pool,
         replica_pool,
+        manifest_signing_key,
         #[cfg(test)]
         event_listener: Box::new(()),
     };
     creator.run(creator_config, MIN_CHUNK_COUNT).await?;
 
     tracing::info!("Finished running snapshot creator!");
-    stop_sender.send(true).ok();
-    if let Some(prometheus_exporter_task) = prometheus_exporter_task {
-        prometheus_exporter_task
-            .await?
-            .context("Prometheus did not finish gracefully")?;
+    Ok(())
+}
+
+/// Checks that the specified directory is empty or doesn't exist. Otherwise, the Merkle tree recovery
+/// would silently resume from the existing tree data.
+fn ensure_empty_dir(path: &Path) -> anyhow::Result<()> {
+    let mut entries = match path.read_dir() {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(anyhow::Error::from(err)
+                .context(format!("failed reading directory {}", path.display())))
+        }
+    };
+    anyhow::ensure!(
+        entries.next().is_none(),
+        "directory {} is not empty; the Merkle tree must be recomputed in an empty directory",
+        path.display()
+    );
+    Ok(())
+}
+
+async fn verify_snapshot(
+    blob_store: Arc<dyn ObjectStore>,
+    l1_batch_number: L1BatchNumber,
+    tree_path: Option<PathBuf>,
+    signer_public_key: Option<H512>,
+) -> anyhow::Result<()> {
+    tracing::info!(
+        "Verifying snapshot for L1 batch {l1_batch_number} in object store {blob_store:?}"
+    );
+    if signer_public_key.is_none() {
+        tracing::warn!(
+            "Signer public key is not specified; snapshot manifest signatures will not be checked"
+        );
     }
+    let verifier = SnapshotVerifier::new(blob_store, signer_public_key);
+    let root_hash = if let Some(tree_path) = tree_path {
+        ensure_empty_dir(&tree_path)?;
+        tracing::info!("Recomputing Merkle tree at {}", tree_path.display());
+        let tree_db = RocksDBWrapper::new(&tree_path)
+            .with_context(|| format!("failed initializing RocksDB at {}", tree_path.display()))?;
+        verifier.verify(l1_batch_number, tree_db).await?
+    } else {
+        verifier
+            .verify(l1_batch_number, PatchSet::default())
+            .await?
+    };
+    tracing::info!("Snapshot for L1 batch {l1_batch_number} is valid; root hash: {root_hash:?}");
     Ok(())
 }
@@ -11,27 +11,31 @@ use std::{
 
 use rand::{thread_rng, Rng};
 use zksync_dal::{Connection, CoreDal};
+use zksync_merkle_tree::{recovery::MerkleTreeRecovery, PatchSet, TreeEntry};
 use zksync_object_store::ObjectStore;
 use zksync_types::{
     block::{L1BatchHeader, L1BatchTreeData, L2BlockHeader},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotStorageLog,
-        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotManifest,
+        SnapshotStorageLog, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
     },
-    AccountTreeId, Address, L1BatchNumber, L2BlockNumber, ProtocolVersion, StorageKey, StorageLog,
-    H256,
+    AccountTreeId, Address, K256PrivateKey, L1BatchNumber, L2BlockNumber, ProtocolVersion,
+    StorageKey, StorageLog, H256,
 };
 
 use super::*;
+use crate::verifier::SnapshotVerifier;
 
 const TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 10,
+    max_differential_snapshots: 0,
     object_store: None,
 };
 const SEQUENTIAL_TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 1,
+    max_differential_snapshots: 0,
     object_store: None,
 };
 
@@ -68,6 +72,7 @@ impl SnapshotCreator {
             blob_store,
             master_pool: pool.clone(),
             replica_pool: pool,
+            manifest_signing_key: None,
             event_listener: Box::new(()),
         }
     }
@@ -424,6 +429,8 @@ async fn recovery_workflow() {
 
     let object_store = object_store_factory.create_store().await;
     assert_storage_logs(&*object_store, snapshot_l1_batch_number, &expected_outputs).await;
+    // Digests for chunks created before resuming must be included into the manifest as well.
+    assert_manifest(&*object_store, snapshot_l1_batch_number).await;
 }
 
 #[tokio::test]
@@ -470,3 +477,438 @@ async fn recovery_workflow_with_varying_chunk_size() {
     let object_store = object_store_factory.create_store().await;
     assert_storage_logs(&*object_store, snapshot_l1_batch_number, &expected_outputs).await;
 }
+
+#[tokio::test]
+async fn creating_differential_snapshots() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.connection().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    let config = SnapshotsCreatorConfig {
+        max_differential_snapshots: 1,
+        ..TEST_CONFIG
+    };
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(config.clone(), MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let base_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(L1BatchNumber(8))
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    assert_eq!(base_metadata.base_l1_batch_number, None);
+
+    // Add new L1 batches overwriting some existing keys and adding new ones.
+    let l2_block_number = L2BlockNumber(10);
+    let updated_logs: Vec<_> = conn
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await
+        .into_iter()
+        .filter(|log| log.l2_block_number < L2BlockNumber(9))
+        .take(10)
+        .map(|log| {
+            let key = StorageKey::new(AccountTreeId::new(log.address), log.key);
+            StorageLog::new_write_log(key, H256(rng.gen()))
+        })
+        .collect();
+    let new_logs = gen_storage_logs(&mut rng, 20);
+    let block_logs: Vec<_> = updated_logs.into_iter().chain(new_logs.clone()).collect();
+    create_l2_block(&mut conn, l2_block_number, block_logs).await;
+    let factory_deps = gen_factory_deps(&mut rng, 5);
+    conn.factory_deps_dal()
+        .insert_factory_deps(l2_block_number, &factory_deps)
+        .await
+        .unwrap();
+    create_l1_batch(&mut conn, L1BatchNumber(10), &new_logs).await;
+    create_l2_block(&mut conn, l2_block_number + 1, vec![]).await;
+    create_l1_batch(&mut conn, L1BatchNumber(11), &[]).await;
+
+    // The differential snapshot should contain all data changed after the base snapshot L2 block (#8).
+    let mut expected_outputs = ExpectedOutputs::default();
+    let SnapshotFactoryDependencies {
+        factory_deps: base_deps,
+    } = object_store.get(L1BatchNumber(8)).await.unwrap();
+    let base_deps: HashSet<_> = base_deps.into_iter().collect();
+    let all_deps = conn
+        .factory_deps_dal()
+        .dump_all_factory_deps_for_tests()
+        .await;
+    let all_deps = all_deps
+        .into_values()
+        .map(|bytecode| SnapshotFactoryDependency {
+            bytecode: bytecode.into(),
+        });
+    expected_outputs
+        .deps
+        .extend(all_deps.filter(|dep| !base_deps.contains(dep)));
+
+    let mut changed_logs = HashMap::new();
+    let all_logs = conn
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    for log in all_logs {
+        if log.l2_block_number > L2BlockNumber(8) {
+            // Logs are ordered by L2 block, so later logs override earlier ones.
+            changed_logs.insert(log.hashed_key, log);
+        }
+    }
+    let hashed_keys: Vec<_> = changed_logs.keys().copied().collect();
+    let expected_l1_batches_and_indices = conn
+        .storage_logs_dal()
+        .get_l1_batches_and_indices_for_initial_writes(&hashed_keys)
+        .await
+        .unwrap();
+    expected_outputs
+        .storage_logs
+        .extend(changed_logs.into_values().map(|log| {
+            let (l1_batch_number_of_initial_write, enumeration_index) =
+                expected_l1_batches_and_indices[&log.hashed_key];
+            SnapshotStorageLog {
+                key: StorageKey::new(AccountTreeId::new(log.address), log.key),
+                value: log.value,
+                l1_batch_number_of_initial_write,
+                enumeration_index,
+            }
+        }));
+
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(config.clone(), MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let diff_l1_batch_number = L1BatchNumber(10);
+    let diff_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(diff_l1_batch_number)
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    assert_eq!(diff_metadata.base_l1_batch_number, Some(L1BatchNumber(8)));
+    assert!(diff_metadata.is_complete());
+    assert_eq!(
+        diff_metadata.storage_logs_filepaths.len(),
+        base_metadata.storage_logs_filepaths.len()
+    );
+
+    let SnapshotFactoryDependencies { factory_deps } =
+        object_store.get(diff_l1_batch_number).await.unwrap();
+    let actual_deps: HashSet<_> = factory_deps.into_iter().collect();
+    assert_eq!(actual_deps, expected_outputs.deps);
+    assert_storage_logs(&*object_store, diff_l1_batch_number, &expected_outputs).await;
+
+    let diff_manifest: SnapshotManifest = object_store.get(diff_l1_batch_number).await.unwrap();
+    assert_eq!(diff_manifest.base_l1_batch_number, Some(L1BatchNumber(8)));
+    let root_hash = compute_root_hash(&mut conn, l2_block_number).await;
+    set_expected_root_hash(&*object_store, diff_l1_batch_number, root_hash).await;
+    let recomputed_root_hash = SnapshotVerifier::new(object_store.clone(), None)
+        .verify(diff_l1_batch_number, PatchSet::default())
+        .await
+        .unwrap();
+    assert_eq!(recomputed_root_hash, root_hash);
+
+    // The maximum number of differential snapshots is reached, so the next snapshot must be full.
+    create_l2_block(&mut conn, l2_block_number + 2, vec![]).await;
+    create_l1_batch(&mut conn, L1BatchNumber(12), &[]).await;
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(config, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let full_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(L1BatchNumber(11))
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    assert_eq!(full_metadata.base_l1_batch_number, None);
+}
+
+async fn assert_manifest(
+    object_store: &dyn ObjectStore,
+    snapshot_l1_batch_number: L1BatchNumber,
+) -> SnapshotManifest {
+    let manifest: SnapshotManifest = object_store.get(snapshot_l1_batch_number).await.unwrap();
+    manifest.check_hash().unwrap();
+    assert_eq!(manifest.l1_batch_number, snapshot_l1_batch_number);
+    assert_eq!(manifest.expected_root_hash, H256::zero()); // as set in `create_l1_batch()`
+
+    let factory_deps: SnapshotFactoryDependencies =
+        object_store.get(snapshot_l1_batch_number).await.unwrap();
+    assert_eq!(manifest.factory_deps, factory_deps.digest());
+    assert_eq!(manifest.storage_logs_chunks.len(), MIN_CHUNK_COUNT as usize);
+    for (chunk_id, expected_digest) in manifest.storage_logs_chunks.iter().enumerate() {
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: snapshot_l1_batch_number,
+            chunk_id: chunk_id as u64,
+        };
+        let chunk: SnapshotStorageLogsChunk = object_store.get(key).await.unwrap();
+        assert_eq!(chunk.digest(), *expected_digest);
+    }
+    manifest
+}
+
+#[tokio::test]
+async fn persisting_snapshot_manifest() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.connection().await.unwrap();
+    let expected_outputs = prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+
+    let manifest = assert_manifest(&*object_store, L1BatchNumber(8)).await;
+    assert_eq!(manifest.l2_block_number, L2BlockNumber(8));
+    assert_eq!(manifest.base_l1_batch_number, None);
+    assert_eq!(
+        manifest.factory_deps.entry_count,
+        expected_outputs.deps.len() as u64
+    );
+    let total_entry_count: u64 = manifest
+        .storage_logs_chunks
+        .iter()
+        .map(|digest| digest.entry_count)
+        .sum();
+    assert_eq!(
+        total_entry_count,
+        expected_outputs.storage_logs.len() as u64
+    );
+}
+
+#[tokio::test]
+async fn manifest_is_saved_for_complete_snapshot_after_restart() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.connection().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    // Emulate the creator crashing after persisting the last chunk, but before saving the manifest.
+    let snapshot_l1_batch_number = L1BatchNumber(8);
+    object_store
+        .remove::<SnapshotManifest>(snapshot_l1_batch_number)
+        .await
+        .unwrap();
+
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    assert_manifest(&*object_store, snapshot_l1_batch_number).await;
+    let all_snapshots = conn
+        .snapshots_dal()
+        .get_all_complete_snapshots()
+        .await
+        .unwrap();
+    assert_eq!(
+        all_snapshots.snapshots_l1_batch_numbers,
+        [snapshot_l1_batch_number]
+    );
+}
+
+/// Computes the Merkle tree root hash for storage at the specified L2 block independently of snapshots.
+async fn compute_root_hash(
+    conn: &mut Connection<'_, Core>,
+    l2_block_number: L2BlockNumber,
+) -> H256 {
+    let mut latest_logs = HashMap::new();
+    let all_logs = conn
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    for log in all_logs {
+        if log.l2_block_number <= l2_block_number {
+            latest_logs.insert(log.hashed_key, log);
+        }
+    }
+    let hashed_keys: Vec<_> = latest_logs.keys().copied().collect();
+    let enumeration_indices = conn
+        .storage_logs_dal()
+        .get_l1_batches_and_indices_for_initial_writes(&hashed_keys)
+        .await
+        .unwrap();
+
+    let entries = latest_logs
+        .into_values()
+        .map(|log| {
+            let key = StorageKey::new(AccountTreeId::new(log.address), log.key);
+            let (_, leaf_index) = enumeration_indices[&log.hashed_key];
+            TreeEntry::new(key.hashed_key_u256(), leaf_index, log.value)
+        })
+        .collect();
+    let mut tree = MerkleTreeRecovery::new(PatchSet::default(), 0);
+    tree.extend_random(entries);
+    tree.root_hash()
+}
+
+/// Replaces the expected root hash in the snapshot manifest. Necessary because `create_l1_batch()`
+/// doesn't compute real root hashes.
+async fn set_expected_root_hash(
+    object_store: &dyn ObjectStore,
+    snapshot_l1_batch_number: L1BatchNumber,
+    root_hash: H256,
+) {
+    let manifest: SnapshotManifest = object_store.get(snapshot_l1_batch_number).await.unwrap();
+    let manifest = SnapshotManifest {
+        expected_root_hash: root_hash,
+        ..manifest
+    };
+    object_store
+        .put(snapshot_l1_batch_number, &manifest.seal())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn verifying_snapshot() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.connection().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshot_l1_batch_number = L1BatchNumber(8);
+    let verifier = SnapshotVerifier::new(object_store.clone(), None);
+    let err = verifier
+        .verify(snapshot_l1_batch_number, PatchSet::default())
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("root hash"), "{err:#}");
+
+    let root_hash = compute_root_hash(&mut conn, L2BlockNumber(8)).await;
+    set_expected_root_hash(&*object_store, snapshot_l1_batch_number, root_hash).await;
+    let recomputed_root_hash = verifier
+        .verify(snapshot_l1_batch_number, PatchSet::default())
+        .await
+        .unwrap();
+    assert_eq!(recomputed_root_hash, root_hash);
+
+    // Corrupt a storage logs chunk.
+    let key = SnapshotStorageLogsStorageKey {
+        l1_batch_number: snapshot_l1_batch_number,
+        chunk_id: 3,
+    };
+    let mut chunk: SnapshotStorageLogsChunk = object_store.get(key).await.unwrap();
+    chunk.storage_logs[0].value = H256::repeat_byte(0xff);
+    object_store.put(key, &chunk).await.unwrap();
+    let err = verifier
+        .verify(snapshot_l1_batch_number, PatchSet::default())
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("chunk"), "{err:#}");
+
+    // Change the manifest so that it matches the corrupted chunk, but don't update its hash.
+    let mut manifest: SnapshotManifest = object_store.get(snapshot_l1_batch_number).await.unwrap();
+    manifest.storage_logs_chunks[3] = chunk.digest();
+    object_store
+        .put(snapshot_l1_batch_number, &manifest)
+        .await
+        .unwrap();
+    let err = verifier
+        .verify(snapshot_l1_batch_number, PatchSet::default())
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("manifest hash"), "{err:#}");
+}
+
+#[tokio::test]
+async fn verifying_signed_snapshot() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.connection().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    let signing_key = K256PrivateKey::random_using(&mut rng);
+    let mut creator = SnapshotCreator::for_tests(object_store.clone(), pool.clone());
+    creator.manifest_signing_key = Some(signing_key.clone());
+    creator.run(TEST_CONFIG, MIN_CHUNK_COUNT).await.unwrap();
+
+    let snapshot_l1_batch_number = L1BatchNumber(8);
+    let manifest = assert_manifest(&*object_store, snapshot_l1_batch_number).await;
+    manifest.check_signature(&signing_key.public()).unwrap();
+
+    let root_hash = compute_root_hash(&mut conn, L2BlockNumber(8)).await;
+    let signed_manifest = SnapshotManifest {
+        expected_root_hash: root_hash,
+        ..manifest
+    }
+    .seal()
+    .sign(&signing_key)
+    .unwrap();
+    object_store
+        .put(snapshot_l1_batch_number, &signed_manifest)
+        .await
+        .unwrap();
+    let verifier = SnapshotVerifier::new(object_store.clone(), Some(signing_key.public()));
+    let recomputed_root_hash = verifier
+        .verify(snapshot_l1_batch_number, PatchSet::default())
+        .await
+        .unwrap();
+    assert_eq!(recomputed_root_hash, root_hash);
+
+    let other_key = K256PrivateKey::random_using(&mut rng);
+    let err = SnapshotVerifier::new(object_store.clone(), Some(other_key.public()))
+        .verify(snapshot_l1_batch_number, PatchSet::default())
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("unexpected key"), "{err:#}");
+
+    // Re-sealing a modified manifest doesn't make it valid without the signing key.
+    let mut forged_manifest = signed_manifest.clone();
+    forged_manifest.storage_logs_chunks[3].entry_count += 1;
+    object_store
+        .put(snapshot_l1_batch_number, &forged_manifest.seal())
+        .await
+        .unwrap();
+    let err = verifier
+        .verify(snapshot_l1_batch_number, PatchSet::default())
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("signer"), "{err:#}");
+
+    let unsigned_manifest = SnapshotManifest {
+        signature: None,
+        ..signed_manifest
+    };
+    object_store
+        .put(snapshot_l1_batch_number, &unsigned_manifest)
+        .await
+        .unwrap();
+    let err = verifier
+        .verify(snapshot_l1_batch_number, PatchSet::default())
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("not signed"), "{err:#}");
+}
+
+#[test]
+fn tree_directory_for_verification_must_be_empty() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    ensure_empty_dir(temp_dir.path()).unwrap();
+    ensure_empty_dir(&temp_dir.path().join("missing")).unwrap();
+
+    std::fs::write(temp_dir.path().join("CURRENT"), "MANIFEST-000001").unwrap();
+    let err = ensure_empty_dir(temp_dir.path()).unwrap_err();
+    assert!(format!("{err:#}").contains("not empty"), "{err:#}");
+}
@@ -0,0 +1,217 @@
+//! [`SnapshotVerifier`] verifying integrity of snapshots using their manifests.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
+
+use anyhow::Context as _;
+use zksync_merkle_tree::{recovery::MerkleTreeRecovery, PruneDatabase, TreeEntry};
+use zksync_object_store::ObjectStore;
+use zksync_types::{
+    snapshots::{
+        SnapshotFactoryDependencies, SnapshotManifest, SnapshotStorageLog,
+        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey, SnapshotVersion,
+    },
+    L1BatchNumber, H256, H512,
+};
+
+/// Verifies integrity of a snapshot stored in an object store without accessing Postgres.
+///
+/// Verification checks digests of all snapshot objects against the snapshot manifest and recomputes
+/// the Merkle tree root hash from the snapshot storage logs. If the signer public key is specified, manifest
+/// signatures are checked against it as well. For differential snapshots, the entire chain of snapshots
+/// down to the full one is verified.
+#[derive(Debug)]
+pub(crate) struct SnapshotVerifier {
+    blob_store: Arc<dyn ObjectStore>,
+    signer_public_key: Option<H512>,
+}
+
+impl SnapshotVerifier {
+    pub fn new(blob_store: Arc<dyn ObjectStore>, signer_public_key: Option<H512>) -> Self {
+        Self {
+            blob_store,
+            signer_public_key,
+        }
+    }
+
+    /// Returns the recomputed Merkle tree root hash.
+    pub async fn verify<DB: PruneDatabase>(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        tree_db: DB,
+    ) -> anyhow::Result<H256> {
+        let started_at = Instant::now();
+        let manifests = self.load_manifest_chain(l1_batch_number).await?;
+        tracing::info!(
+            "Loaded manifests for snapshot chain {:?}",
+            manifests
+                .iter()
+                .map(|manifest| manifest.l1_batch_number)
+                .collect::<Vec<_>>()
+        );
+
+        for manifest in &manifests {
+            self.verify_factory_deps(manifest).await?;
+        }
+
+        // `unwrap()` is safe: the chain always contains at least one manifest
+        let target_manifest = manifests.last().unwrap();
+        let chunk_count = target_manifest.storage_logs_chunks.len() as u64;
+        let mut tree = MerkleTreeRecovery::new(tree_db, l1_batch_number.0.into());
+        let mut total_entry_count = 0;
+        for chunk_id in 0..chunk_count {
+            let storage_logs = self.load_storage_logs_chunk(&manifests, chunk_id).await?;
+            total_entry_count += storage_logs.len();
+            let entries = storage_logs
+                .into_iter()
+                .map(|log| {
+                    TreeEntry::new(log.key.hashed_key_u256(), log.enumeration_index, log.value)
+                })
+                .collect();
+            tree.extend_random(entries);
+            tracing::info!("Verified storage logs chunk {chunk_id} (out of {chunk_count})");
+        }
+
+        let root_hash = tree.root_hash();
+        anyhow::ensure!(
+            root_hash == target_manifest.expected_root_hash,
+            "Merkle tree root hash recomputed from snapshot storage logs ({root_hash:?}) differs from the expected \
+             root hash in the manifest ({:?})",
+            target_manifest.expected_root_hash
+        );
+        tracing::info!(
+            "Verified snapshot for L1 batch {l1_batch_number} with {total_entry_count} storage logs and root hash \
+             {root_hash:?} in {:?}",
+            started_at.elapsed()
+        );
+        Ok(root_hash)
+    }
+
+    /// Loads manifests for the snapshot chain ending at the specified L1 batch, starting from the full snapshot.
+    async fn load_manifest_chain(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Vec<SnapshotManifest>> {
+        let mut manifests: Vec<SnapshotManifest> = vec![];
+        let mut next_l1_batch_number = Some(l1_batch_number);
+        while let Some(l1_batch_number) = next_l1_batch_number {
+            let manifest: SnapshotManifest = self
+                .blob_store
+                .get(l1_batch_number)
+                .await
+                .with_context(|| {
+                    format!("Error loading manifest for snapshot for L1 batch #{l1_batch_number}")
+                })?;
+            manifest.check_hash()?;
+            if let Some(signer_public_key) = &self.signer_public_key {
+                manifest.check_signature(signer_public_key)?;
+            }
+            anyhow::ensure!(
+                manifest.l1_batch_number == l1_batch_number,
+                "Manifest for snapshot for L1 batch #{l1_batch_number} references unexpected L1 batch #{}",
+                manifest.l1_batch_number
+            );
+            SnapshotVersion::try_from(manifest.version).with_context(|| {
+                format!(
+                    "Unrecognized version {} of snapshot for L1 batch #{l1_batch_number}",
+                    manifest.version
+                )
+            })?;
+
+            if let Some(prev_manifest) = manifests.last() {
+                anyhow::ensure!(
+                    manifest.storage_logs_chunks.len() == prev_manifest.storage_logs_chunks.len(),
+                    "Snapshots for L1 batches #{l1_batch_number} and #{} have different number of storage logs chunks",
+                    prev_manifest.l1_batch_number
+                );
+            }
+            if let Some(base_l1_batch_number) = manifest.base_l1_batch_number {
+                anyhow::ensure!(
+                    base_l1_batch_number < l1_batch_number,
+                    "Differential snapshot for L1 batch #{l1_batch_number} has invalid base L1 batch #{base_l1_batch_number}"
+                );
+            }
+            next_l1_batch_number = manifest.base_l1_batch_number;
+            manifests.push(manifest);
+        }
+        manifests.reverse();
+        Ok(manifests)
+    }
+
+    async fn verify_factory_deps(&self, manifest: &SnapshotManifest) -> anyhow::Result<()> {
+        let l1_batch_number = manifest.l1_batch_number;
+        let factory_deps: SnapshotFactoryDependencies = self
+            .blob_store
+            .get(l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("Error loading factory deps for snapshot for L1 batch #{l1_batch_number}")
+            })?;
+        let digest = factory_deps.digest();
+        anyhow::ensure!(
+            digest == manifest.factory_deps,
+            "Factory deps for snapshot for L1 batch #{l1_batch_number} do not match the manifest: \
+             expected {:?}, got {digest:?}",
+            manifest.factory_deps
+        );
+        tracing::info!(
+            "Verified {} factory deps for snapshot for L1 batch {l1_batch_number}",
+            digest.entry_count
+        );
+        Ok(())
+    }
+
+    /// Loads and verifies the specified storage logs chunk for all snapshots in the chain. Returns storage logs
+    /// merged across the chain, so that logs from later snapshots override logs for the same keys from earlier ones.
+    async fn load_storage_logs_chunk(
+        &self,
+        manifests: &[SnapshotManifest],
+        chunk_id: u64,
+    ) -> anyhow::Result<Vec<SnapshotStorageLog>> {
+        let mut merged_logs = HashMap::new();
+        for manifest in manifests {
+            let key = SnapshotStorageLogsStorageKey {
+                l1_batch_number: manifest.l1_batch_number,
+                chunk_id,
+            };
+            let chunk: SnapshotStorageLogsChunk = self
+                .blob_store
+                .get(key)
+                .await
+                .with_context(|| format!("Error loading storage logs chunk {key:?}"))?;
+
+            let expected_digest = &manifest.storage_logs_chunks[chunk_id as usize];
+            let digest = chunk.digest();
+            anyhow::ensure!(
+                digest.entry_count == expected_digest.entry_count,
+                "Storage logs chunk {key:?} has {} entries, while {} entries are expected per manifest",
+                digest.entry_count,
+                expected_digest.entry_count
+            );
+            anyhow::ensure!(
+                digest.hash == expected_digest.hash,
+                "Storage logs chunk {key:?} has hash {:?}, while {:?} is expected per manifest",
+                digest.hash,
+                expected_digest.hash
+            );
+
+            let mut chunk_keys = HashSet::with_capacity(chunk.storage_logs.len());
+            for log in chunk.storage_logs {
+                anyhow::ensure!(
+                    log.enumeration_index > 0,
+                    "Storage logs chunk {key:?} contains a log with zero enumeration index: {log:?}"
+                );
+                anyhow::ensure!(
+                    chunk_keys.insert(log.key),
+                    "Storage logs chunk {key:?} contains duplicate key {:?}",
+                    log.key
+                );
+                merged_logs.insert(log.key, log);
+            }
+        }
+        Ok(merged_logs.into_values().collect())
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use clap::Parser;
+use tokio::sync::watch;
+use zksync_config::{
+    configs::{
+        chain::{NetworkConfig, StateKeeperConfig},
+        ObservabilityConfig,
+    },
+    PostgresConfig,
+};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_env_config::FromEnv;
+use zksync_types::L1BatchNumber;
+use zksync_vm_runner::VmRunnerStorage;
+
+use crate::replay::{BatchReplayer, ReplayLoader};
+
+mod replay;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Replays L1 batches through the state keeper batch executor and compares results with Postgres",
+    long_about = None
+)]
+struct Cli {
+    /// Number of the first L1 batch to replay.
+    #[arg(long)]
+    from_batch: u32,
+    /// Number of the last L1 batch to replay (inclusive). If not specified, equals to `--from-batch`.
+    #[arg(long)]
+    to_batch: Option<u32>,
+    /// Path to the RocksDB storage cache used for replay. The cache is caught up to the first replayed batch;
+    /// until then, storage is read from Postgres.
+    #[arg(long)]
+    rocksdb_path: String,
+    /// Stops after the first L1 batch with divergences.
+    #[arg(long)]
+    fail_fast: bool,
+    /// Outputs batch reports as JSON lines.
+    #[arg(long)]
+    json: bool,
+}
+
+impl Cli {
+    async fn run(
+        self,
+        pool: ConnectionPool<Core>,
+        network: NetworkConfig,
+        state_keeper: StateKeeperConfig,
+    ) -> anyhow::Result<()> {
+        let first_batch = L1BatchNumber(self.from_batch);
+        let last_batch = L1BatchNumber(self.to_batch.unwrap_or(self.from_batch));
+        anyhow::ensure!(
+            first_batch > L1BatchNumber(0),
+            "genesis L1 batch cannot be replayed"
+        );
+        anyhow::ensure!(
+            first_batch <= last_batch,
+            "invalid L1 batch range: {first_batch}..={last_batch}"
+        );
+        let mut conn = pool.connection_tagged("state_keeper_replay").await?;
+        let sealed_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .context("no L1 batches in Postgres")?;
+        anyhow::ensure!(
+            last_batch <= sealed_batch,
+            "L1 batch #{last_batch} is not sealed; the last sealed batch is #{sealed_batch}"
+        );
+        drop(conn);
+
+        let loader = ReplayLoader::new(first_batch, last_batch);
+        let (storage, sync_task) = VmRunnerStorage::new(
+            pool.clone(),
+            self.rocksdb_path,
+            loader.clone(),
+            network.zksync_network_id,
+        )
+        .await?;
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let sync_task = tokio::spawn(sync_task.run(stop_receiver.clone()));
+
+        let mut replayer = BatchReplayer::new(
+            pool,
+            Arc::new(storage),
+            loader,
+            state_keeper.save_call_traces,
+        );
+        let mut diverged_batches = vec![];
+        for number in first_batch.0..=last_batch.0 {
+            let l1_batch_number = L1BatchNumber(number);
+            let report = replayer
+                .replay_batch(l1_batch_number, &stop_receiver)
+                .await?
+                .context("replay was interrupted")?;
+
+            if self.json {
+                println!("{}", serde_json::to_string(&report)?);
+            } else if report.divergences.is_empty() {
+                tracing::info!(
+                    "L1 batch #{l1_batch_number} ({} txs) replayed in {}ms without divergences",
+                    report.tx_count,
+                    report.elapsed_ms
+                );
+            } else {
+                tracing::error!(
+                    "L1 batch #{l1_batch_number} ({} txs) has {} divergence(s):",
+                    report.tx_count,
+                    report.divergences.len()
+                );
+                for divergence in &report.divergences {
+                    tracing::error!("  {divergence:?}");
+                }
+            }
+
+            if !report.divergences.is_empty() {
+                diverged_batches.push(l1_batch_number);
+                if self.fail_fast {
+                    break;
+                }
+            }
+        }
+
+        stop_sender.send_replace(true);
+        sync_task.await.context("storage sync task panicked")??;
+        anyhow::ensure!(
+            diverged_batches.is_empty(),
+            "divergences detected in L1 batches: {diverged_batches:?}"
+        );
+        tracing::info!("Replayed L1 batches #{first_batch}..=#{last_batch} without divergences");
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build();
+
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let network_config = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    let state_keeper_config =
+        StateKeeperConfig::from_env().context("StateKeeperConfig::from_env()")?;
+    // Replay is read-only, so it can use a replica.
+    let pool = ConnectionPool::<Core>::builder(
+        postgres_config.replica_url()?,
+        postgres_config.max_connections()?,
+    )
+    .build()
+    .await
+    .context("failed to build a connection pool")?;
+    Cli::parse()
+        .run(pool, network_config, state_keeper_config)
+        .await
+}
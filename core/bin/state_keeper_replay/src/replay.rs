@@ -0,0 +1,439 @@
+//! Replaying L1 batches and comparing the results with the ones persisted in Postgres.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use multivm::interface::{FinishedL1Batch, L2BlockEnv};
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_core::state_keeper::{BatchExecutor, MainBatchExecutor, TxExecutionResult};
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_types::{
+    l2_to_l1_log::SystemL2ToL1Log, storage_writes_deduplicator::StorageWritesDeduplicator, Address,
+    L1BatchNumber, StorageKey, StorageLogQuery, VmEvent, H256, U256,
+};
+use zksync_utils::u256_to_h256;
+use zksync_vm_runner::{BatchExecuteData, VmRunnerStorage, VmRunnerStorageLoader};
+
+/// [`VmRunnerStorageLoader`] following the replay progress.
+#[derive(Debug, Clone)]
+pub(crate) struct ReplayLoader {
+    last_processed_batch: Arc<AtomicU32>,
+    last_batch: L1BatchNumber,
+}
+
+impl ReplayLoader {
+    /// Maximum number of batches loaded in memory ahead of the replayed batch.
+    const MAX_LOADED_BATCHES: u32 = 4;
+
+    pub fn new(first_batch: L1BatchNumber, last_batch: L1BatchNumber) -> Self {
+        Self {
+            last_processed_batch: Arc::new(AtomicU32::new(first_batch.0.saturating_sub(1))),
+            last_batch,
+        }
+    }
+
+    fn mark_processed(&self, l1_batch_number: L1BatchNumber) {
+        self.last_processed_batch
+            .store(l1_batch_number.0, Ordering::Release);
+    }
+}
+
+#[async_trait]
+impl VmRunnerStorageLoader for ReplayLoader {
+    fn name() -> &'static str {
+        "state_keeper_replay"
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(L1BatchNumber(
+            self.last_processed_batch.load(Ordering::Acquire),
+        ))
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let latest_processed_batch = self.latest_processed_batch(conn).await?;
+        Ok((latest_processed_batch + Self::MAX_LOADED_BATCHES).min(self.last_batch))
+    }
+}
+
+/// Divergence between a replayed L1 batch and the data persisted in Postgres.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum Divergence {
+    /// Transaction was rejected during replay.
+    TxRejected { tx_hash: H256, reason: String },
+    /// Transaction receipt is missing in Postgres.
+    TxReceiptMissing { tx_hash: H256 },
+    /// Transaction execution status differs.
+    TxStatus {
+        tx_hash: H256,
+        expected_success: bool,
+        actual_success: bool,
+    },
+    /// Gas used by a transaction differs.
+    TxGasUsed {
+        tx_hash: H256,
+        expected: Option<U256>,
+        actual: U256,
+    },
+    /// Final value of a storage slot written in the batch differs. `None` means that the slot is not written.
+    StorageWrite {
+        address: Address,
+        key: H256,
+        expected: Option<H256>,
+        actual: Option<H256>,
+    },
+    /// Events emitted in the batch differ.
+    Events {
+        expected_count: usize,
+        actual_count: usize,
+        first_mismatch_index: usize,
+    },
+    /// User L2-to-L1 logs emitted in the batch differ.
+    UserL2ToL1Logs {
+        expected_count: usize,
+        actual_count: usize,
+    },
+    /// Value of a system log differs. System logs contain commitments to the final batch state, such as
+    /// the state diff hash.
+    SystemLog {
+        key: H256,
+        expected: Option<H256>,
+        actual: Option<H256>,
+    },
+}
+
+/// Report for a single replayed L1 batch.
+#[derive(Debug, Serialize)]
+pub(crate) struct BatchReport {
+    pub l1_batch_number: L1BatchNumber,
+    pub tx_count: usize,
+    pub elapsed_ms: u64,
+    pub divergences: Vec<Divergence>,
+}
+
+#[derive(Debug)]
+struct ReplayedTx {
+    hash: H256,
+    is_success: bool,
+    gas_used: U256,
+}
+
+/// Output of replaying a single L1 batch.
+#[derive(Debug)]
+struct ReplayOutput {
+    txs: Vec<ReplayedTx>,
+    storage_writes: HashMap<StorageKey, H256>,
+    finished_batch: FinishedL1Batch,
+    divergences: Vec<Divergence>,
+}
+
+/// Re-executes L1 batches using the state keeper batch executor and compares the results with the data in Postgres.
+#[derive(Debug)]
+pub(crate) struct BatchReplayer {
+    pool: ConnectionPool<Core>,
+    storage: Arc<VmRunnerStorage<ReplayLoader>>,
+    loader: ReplayLoader,
+    executor: MainBatchExecutor,
+}
+
+impl BatchReplayer {
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        storage: Arc<VmRunnerStorage<ReplayLoader>>,
+        loader: ReplayLoader,
+        save_call_traces: bool,
+    ) -> Self {
+        // Mirror the state keeper configuration on the main node, which never uses optional bytecode compression.
+        let executor = MainBatchExecutor::new(storage.clone(), save_call_traces, false);
+        Self {
+            pool,
+            storage,
+            loader,
+            executor,
+        }
+    }
+
+    /// Replays the specified L1 batch. Returns `None` if the replay was interrupted.
+    pub async fn replay_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<BatchReport>> {
+        let started_at = Instant::now();
+        let Some(batch_data) = self.wait_for_batch(l1_batch_number, stop_receiver).await? else {
+            return Ok(None);
+        };
+        let tx_count = batch_data
+            .l2_blocks
+            .iter()
+            .map(|block| block.txs.len())
+            .sum();
+        let Some(output) = self.execute_batch(batch_data, stop_receiver).await? else {
+            return Ok(None);
+        };
+
+        let mut conn = self.pool.connection_tagged(ReplayLoader::name()).await?;
+        let divergences = compare_with_storage(&mut conn, l1_batch_number, output).await?;
+        drop(conn);
+        self.loader.mark_processed(l1_batch_number);
+
+        Ok(Some(BatchReport {
+            l1_batch_number,
+            tx_count,
+            elapsed_ms: started_at.elapsed().as_millis() as u64,
+            divergences,
+        }))
+    }
+
+    async fn wait_for_batch(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<BatchExecuteData>> {
+        while !*stop_receiver.borrow() {
+            let batch_data = self
+                .storage
+                .load_batch(l1_batch_number)
+                .await
+                .with_context(|| format!("failed loading data for L1 batch #{l1_batch_number}"))?;
+            if batch_data.is_some() {
+                return Ok(batch_data);
+            }
+            tokio::time::sleep(Self::POLL_INTERVAL).await;
+        }
+        Ok(None)
+    }
+
+    async fn execute_batch(
+        &mut self,
+        batch_data: BatchExecuteData,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<ReplayOutput>> {
+        let Some(handle) = self
+            .executor
+            .init_batch(
+                batch_data.l1_batch_env,
+                batch_data.system_env,
+                stop_receiver,
+            )
+            .await
+        else {
+            return Ok(None);
+        };
+
+        let mut txs = vec![];
+        let mut divergences = vec![];
+        let mut storage_logs_by_l2_block: Vec<Vec<StorageLogQuery>> = vec![];
+        for (i, l2_block) in batch_data.l2_blocks.into_iter().enumerate() {
+            // The first L2 block is started when the batch is initialized.
+            if i > 0 {
+                handle
+                    .start_next_l2_block(L2BlockEnv::from_l2_block_data(&l2_block))
+                    .await;
+            }
+
+            let mut storage_logs = vec![];
+            for tx in l2_block.txs {
+                let tx_hash = tx.hash();
+                let gas_limit = tx.gas_limit();
+                match handle.execute_tx(tx).await {
+                    TxExecutionResult::Success { tx_result, .. } => {
+                        storage_logs.extend_from_slice(&tx_result.logs.storage_logs);
+                        txs.push(ReplayedTx {
+                            hash: tx_hash,
+                            is_success: !tx_result.result.is_failed(),
+                            gas_used: gas_limit
+                                .saturating_sub(tx_result.refunds.gas_refunded.into()),
+                        });
+                    }
+                    result => {
+                        let reason = result.err().map(ToString::to_string).unwrap_or_default();
+                        tracing::warn!(
+                            "Transaction {tx_hash:?} was rejected during replay: {reason}"
+                        );
+                        divergences.push(Divergence::TxRejected { tx_hash, reason });
+                        // Same as in the state keeper, the rejected transaction must not affect the VM state.
+                        handle.rollback_last_tx().await;
+                    }
+                }
+            }
+            storage_logs_by_l2_block.push(storage_logs);
+        }
+
+        let finished_batch = handle.finish_batch().await;
+        // Block tip changes are persisted as a part of the last (fictive) L2 block.
+        if let Some(storage_logs) = storage_logs_by_l2_block.last_mut() {
+            storage_logs
+                .extend_from_slice(&finished_batch.block_tip_execution_result.logs.storage_logs);
+        }
+
+        // Writes are deduplicated per L2 block when persisted, so we emulate this here.
+        let mut storage_writes = HashMap::new();
+        for storage_logs in &storage_logs_by_l2_block {
+            let mut deduplicator = StorageWritesDeduplicator::new();
+            deduplicator.apply(storage_logs.iter().filter(|log| log.log_query.rw_flag));
+            let modified_slots = deduplicator.into_modified_key_values();
+            storage_writes.extend(
+                modified_slots
+                    .into_iter()
+                    .map(|(key, slot)| (key, u256_to_h256(slot.value))),
+            );
+        }
+
+        Ok(Some(ReplayOutput {
+            txs,
+            storage_writes,
+            finished_batch,
+            divergences,
+        }))
+    }
+}
+
+async fn compare_with_storage(
+    conn: &mut Connection<'_, Core>,
+    l1_batch_number: L1BatchNumber,
+    output: ReplayOutput,
+) -> anyhow::Result<Vec<Divergence>> {
+    let mut divergences = output.divergences;
+
+    let tx_hashes: Vec<_> = output.txs.iter().map(|tx| tx.hash).collect();
+    let receipts = conn
+        .transactions_web3_dal()
+        .get_transaction_receipts(&tx_hashes)
+        .await?;
+    let receipts: HashMap<_, _> = receipts
+        .into_iter()
+        .map(|receipt| (receipt.transaction_hash, receipt))
+        .collect();
+    for tx in &output.txs {
+        let Some(receipt) = receipts.get(&tx.hash) else {
+            divergences.push(Divergence::TxReceiptMissing { tx_hash: tx.hash });
+            continue;
+        };
+        let expected_success = receipt.status.as_u64() == 1;
+        if expected_success != tx.is_success {
+            divergences.push(Divergence::TxStatus {
+                tx_hash: tx.hash,
+                expected_success,
+                actual_success: tx.is_success,
+            });
+        }
+        if receipt.gas_used != Some(tx.gas_used) {
+            divergences.push(Divergence::TxGasUsed {
+                tx_hash: tx.hash,
+                expected: receipt.gas_used,
+                actual: tx.gas_used,
+            });
+        }
+    }
+
+    let expected_writes = conn
+        .storage_logs_dal()
+        .get_touched_slots_for_l1_batch(l1_batch_number)
+        .await?;
+    let all_keys: BTreeSet<_> = expected_writes
+        .keys()
+        .chain(output.storage_writes.keys())
+        .copied()
+        .collect();
+    for key in all_keys {
+        let expected = expected_writes.get(&key).copied();
+        let actual = output.storage_writes.get(&key).copied();
+        if expected != actual {
+            divergences.push(Divergence::StorageWrite {
+                address: *key.address(),
+                key: *key.key(),
+                expected,
+                actual,
+            });
+        }
+    }
+
+    let final_state = &output.finished_batch.final_execution_state;
+    let expected_events = conn
+        .events_dal()
+        .get_vm_events_for_l1_batch(l1_batch_number)
+        .await?
+        .unwrap_or_default();
+    let event_data = |event: &VmEvent| {
+        (
+            event.address,
+            event.indexed_topics.clone(),
+            event.value.clone(),
+        )
+    };
+    let first_event_mismatch = expected_events
+        .iter()
+        .map(event_data)
+        .zip(final_state.events.iter().map(event_data))
+        .position(|(expected, actual)| expected != actual);
+    let first_event_mismatch = first_event_mismatch.or_else(|| {
+        (expected_events.len() != final_state.events.len())
+            .then(|| expected_events.len().min(final_state.events.len()))
+    });
+    if let Some(first_mismatch_index) = first_event_mismatch {
+        divergences.push(Divergence::Events {
+            expected_count: expected_events.len(),
+            actual_count: final_state.events.len(),
+            first_mismatch_index,
+        });
+    }
+
+    let header = conn
+        .blocks_dal()
+        .get_l1_batch_header(l1_batch_number)
+        .await?
+        .with_context(|| format!("header for L1 batch #{l1_batch_number} is missing"))?;
+    if header.l2_to_l1_logs != final_state.user_l2_to_l1_logs {
+        divergences.push(Divergence::UserL2ToL1Logs {
+            expected_count: header.l2_to_l1_logs.len(),
+            actual_count: final_state.user_l2_to_l1_logs.len(),
+        });
+    }
+
+    let system_log_values = |logs: &[SystemL2ToL1Log]| {
+        logs.iter()
+            .map(|log| (log.0.key, log.0.value))
+            .collect::<BTreeMap<_, _>>()
+    };
+    let expected_system_logs = system_log_values(&header.system_logs);
+    let actual_system_logs = system_log_values(&final_state.system_logs);
+    let all_keys: BTreeSet<_> = expected_system_logs
+        .keys()
+        .chain(actual_system_logs.keys())
+        .copied()
+        .collect();
+    for key in all_keys {
+        let expected = expected_system_logs.get(&key).copied();
+        let actual = actual_system_logs.get(&key).copied();
+        if expected != actual {
+            divergences.push(Divergence::SystemLog {
+                key,
+                expected,
+                actual,
+            });
+        }
+    }
+    Ok(divergences)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,131 @@
+use tempfile::TempDir;
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_node_test_utils::create_l2_block;
+use zksync_types::{
+    block::{BlockGasCount, L1BatchHeader, L2BlockHasher},
+    L2BlockNumber, ProtocolVersionId, StorageLog,
+};
+
+use super::*;
+
+/// Stores an L1 batch #1 consisting of an empty L2 block and a fictive L2 block.
+async fn store_empty_l1_batch(
+    conn: &mut Connection<'_, Core>,
+    contract_hashes: BaseSystemContractsHashes,
+) {
+    let mut prev_block_hash = conn
+        .blocks_dal()
+        .get_l2_block_header(L2BlockNumber(0))
+        .await
+        .unwrap()
+        .expect("no genesis L2 block")
+        .hash;
+    for number in [1, 2] {
+        let mut l2_block = create_l2_block(number);
+        l2_block.base_system_contracts_hashes = contract_hashes;
+        // L2 block hashes are checked by the system context during execution, so they must be valid.
+        l2_block.hash = L2BlockHasher::new(l2_block.number, l2_block.timestamp, prev_block_hash)
+            .finalize(ProtocolVersionId::latest());
+        prev_block_hash = l2_block.hash;
+        conn.blocks_dal().insert_l2_block(&l2_block).await.unwrap();
+    }
+    let header = L1BatchHeader::new(
+        L1BatchNumber(1),
+        1,
+        contract_hashes,
+        ProtocolVersionId::latest(),
+    );
+    conn.blocks_dal()
+        .insert_l1_batch(
+            &header,
+            &[],
+            BlockGasCount::default(),
+            &[],
+            &[],
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    conn.blocks_dal()
+        .mark_l2_blocks_as_executed_in_l1_batch(L1BatchNumber(1))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn replaying_batch_and_comparing_state_diff() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    store_empty_l1_batch(&mut conn, genesis_params.base_system_contracts().hashes()).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let loader = ReplayLoader::new(L1BatchNumber(1), L1BatchNumber(1));
+    let (storage, sync_task) = VmRunnerStorage::new(
+        pool.clone(),
+        db_dir.path().to_str().unwrap().to_owned(),
+        loader.clone(),
+        genesis_params.config().l2_chain_id,
+    )
+    .await
+    .unwrap();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let sync_task = tokio::spawn(sync_task.run(stop_receiver.clone()));
+
+    let mut replayer = BatchReplayer::new(pool.clone(), Arc::new(storage), loader, false);
+    let batch_data = replayer
+        .wait_for_batch(L1BatchNumber(1), &stop_receiver)
+        .await
+        .unwrap()
+        .expect("replay interrupted");
+    let mut output = replayer
+        .execute_batch(batch_data, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("replay interrupted");
+    assert!(output.txs.is_empty());
+    assert!(output.divergences.is_empty(), "{:?}", output.divergences);
+    // At the very least, the batch tip updates the system context.
+    assert!(!output.storage_writes.is_empty());
+
+    // Persist the state diff as if the batch was sealed by the state keeper.
+    let storage_logs: Vec<_> = output
+        .storage_writes
+        .iter()
+        .map(|(&key, &value)| StorageLog::new_write_log(key, value))
+        .collect();
+    conn.storage_logs_dal()
+        .insert_storage_logs(L2BlockNumber(2), &[(H256::zero(), storage_logs)])
+        .await
+        .unwrap();
+
+    // Tamper with a single replayed value; it must be the only diverged storage slot.
+    let (&tampered_key, &persisted_value) = output.storage_writes.iter().next().unwrap();
+    let tampered_value = H256::repeat_byte(0xff);
+    assert_ne!(tampered_value, persisted_value);
+    output.storage_writes.insert(tampered_key, tampered_value);
+
+    let divergences = compare_with_storage(&mut conn, L1BatchNumber(1), output)
+        .await
+        .unwrap();
+    let storage_divergences: Vec<_> = divergences
+        .into_iter()
+        .filter(|divergence| matches!(divergence, Divergence::StorageWrite { .. }))
+        .collect();
+    assert_eq!(
+        storage_divergences,
+        [Divergence::StorageWrite {
+            address: *tampered_key.address(),
+            key: *tampered_key.key(),
+            expected: Some(persisted_value),
+            actual: Some(tampered_value),
+        }]
+    );
+
+    stop_sender.send_replace(true);
+    sync_task.await.unwrap().unwrap();
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use zksync_node_fee_model::l1_gas_price::L1TxParamsProvider;
+
+use crate::{metrics::METRICS, CircuitBreaker, CircuitBreakerError};
+
+/// Trips if the L1 base fee recommended by the gas adjuster exceeds the configured ceiling.
+#[derive(Debug)]
+pub struct L1BaseFeeChecker {
+    pub l1_tx_params: Arc<dyn L1TxParamsProvider>,
+    pub base_fee_ceiling: u64,
+}
+
+impl L1BaseFeeChecker {
+    /// Name of this circuit breaker.
+    pub const NAME: &str = "l1_base_fee";
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for L1BaseFeeChecker {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        // Base fee not accounting for the time spent in the mempool.
+        let base_fee = self.l1_tx_params.get_base_fee(0);
+        METRICS.l1_base_fee.set(base_fee);
+
+        if base_fee > self.base_fee_ceiling {
+            return Err(CircuitBreakerError::L1BaseFee {
+                base_fee,
+                threshold: self.base_fee_ceiling,
+            });
+        }
+        Ok(())
+    }
+}
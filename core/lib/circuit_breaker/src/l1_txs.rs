@@ -8,10 +8,15 @@ pub struct FailedL1TransactionChecker {
     pub pool: ConnectionPool<Core>,
 }
 
+impl FailedL1TransactionChecker {
+    /// Name of this circuit breaker.
+    pub const NAME: &str = "failed_l1_transaction";
+}
+
 #[async_trait::async_trait]
 impl CircuitBreaker for FailedL1TransactionChecker {
     fn name(&self) -> &'static str {
-        "failed_l1_transaction"
+        Self::NAME
     }
 
     async fn check(&self) -> Result<(), CircuitBreakerError> {
@@ -1,11 +1,27 @@
-use std::{fmt, sync::Arc, time::Duration};
+use std::{collections::HashSet, fmt, sync::Arc, time::Duration};
 
+use serde::Serialize;
 use thiserror::Error;
 use tokio::sync::{watch, Mutex};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 
+pub mod l1_gas;
 pub mod l1_txs;
 mod metrics;
 pub mod replication_lag;
+#[cfg(test)]
+mod tests;
+pub mod tree_lag;
+pub mod unproven_batches;
+
+/// Names of all circuit breakers defined in this crate.
+pub const CIRCUIT_BREAKER_NAMES: &[&str] = &[
+    l1_txs::FailedL1TransactionChecker::NAME,
+    replication_lag::ReplicationLagChecker::NAME,
+    unproven_batches::UnprovenBatchesChecker::NAME,
+    tree_lag::TreeLagChecker::NAME,
+    l1_gas::L1BaseFeeChecker::NAME,
+];
 
 #[derive(Default, Debug)]
 pub struct CircuitBreakers(Mutex<Vec<Box<dyn CircuitBreaker>>>);
@@ -27,6 +43,17 @@ impl CircuitBreakers {
         }
         Ok(())
     }
+
+    /// Runs all circuit breakers and returns the errors for tripped ones, together with the breaker names.
+    async fn check_all(&self) -> Vec<(&'static str, CircuitBreakerError)> {
+        let mut errors = vec![];
+        for circuit_breaker in self.0.lock().await.iter() {
+            if let Err(err) = circuit_breaker.check().await {
+                errors.push((circuit_breaker.name(), err));
+            }
+        }
+        errors
+    }
 }
 
 #[derive(Debug, Error)]
@@ -35,6 +62,12 @@ pub enum CircuitBreakerError {
     FailedL1Transaction,
     #[error("Replication lag ({lag:?}) is above the threshold ({threshold:?})")]
     ReplicationLag { lag: Duration, threshold: Duration },
+    #[error("Number of unproven L1 batches ({count}) is above the threshold ({threshold})")]
+    UnprovenBatches { count: u64, threshold: u64 },
+    #[error("Merkle tree lag ({lag} L1 batches) is above the threshold ({threshold})")]
+    TreeLag { lag: u32, threshold: u32 },
+    #[error("L1 base fee ({base_fee} wei) is above the threshold ({threshold} wei)")]
+    L1BaseFee { base_fee: u64, threshold: u64 },
     #[error("Internal error running circuit breaker checks")]
     Internal(#[from] anyhow::Error),
 }
@@ -45,11 +78,30 @@ impl From<zksync_dal::DalError> for CircuitBreakerError {
     }
 }
 
-/// Checks circuit breakers
+/// Details of the circuit breakers health check.
+#[derive(Debug, Serialize)]
+struct CircuitBreakersHealthDetails {
+    /// Tripped soft circuit breakers together with their errors.
+    tripped: Vec<TrippedCircuitBreaker>,
+}
+
+#[derive(Debug, Serialize)]
+struct TrippedCircuitBreaker {
+    name: &'static str,
+    error: String,
+}
+
+/// Checks circuit breakers.
+///
+/// By default, any tripped circuit breaker stops the checker with an error (which normally leads to the node shutting down).
+/// Circuit breakers can be switched to the soft mode using [`Self::with_soft_circuit_breakers()`]; tripped soft breakers
+/// are only logged and reported as [`HealthStatus::Affected`] in the checker health.
 #[derive(Debug)]
 pub struct CircuitBreakerChecker {
     circuit_breakers: Arc<CircuitBreakers>,
     sync_interval: Duration,
+    soft_circuit_breakers: HashSet<String>,
+    health_updater: HealthUpdater,
 }
 
 #[async_trait::async_trait]
@@ -64,12 +116,57 @@ impl CircuitBreakerChecker {
         Self {
             circuit_breakers,
             sync_interval,
+            soft_circuit_breakers: HashSet::new(),
+            health_updater: ReactiveHealthCheck::new("circuit_breakers").1,
+        }
+    }
+
+    /// Sets names of circuit breakers operating in the soft mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the names doesn't correspond to a known circuit breaker (see [`CIRCUIT_BREAKER_NAMES`]).
+    pub fn with_soft_circuit_breakers(
+        mut self,
+        names: impl IntoIterator<Item = String>,
+    ) -> anyhow::Result<Self> {
+        let names: HashSet<_> = names.into_iter().collect();
+        for name in &names {
+            anyhow::ensure!(
+                CIRCUIT_BREAKER_NAMES.contains(&name.as_str()),
+                "unknown soft circuit breaker `{name}`; known circuit breakers are {CIRCUIT_BREAKER_NAMES:?}"
+            );
         }
+        self.soft_circuit_breakers = names;
+        Ok(self)
+    }
+
+    /// Returns the health check for tripped soft circuit breakers.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
     }
 
     pub async fn check(&self) -> Result<(), CircuitBreakerError> {
-        self.circuit_breakers.check().await?;
+        let mut tripped_soft_breakers = vec![];
+        for (name, error) in self.circuit_breakers.check_all().await {
+            if !self.soft_circuit_breakers.contains(name) {
+                return Err(error);
+            }
+            tracing::warn!("Soft circuit breaker `{name}` is tripped: {error}");
+            tripped_soft_breakers.push(TrippedCircuitBreaker {
+                name,
+                error: error.to_string(),
+            });
+        }
 
+        let health = if tripped_soft_breakers.is_empty() {
+            Health::from(HealthStatus::Ready)
+        } else {
+            Health::from(HealthStatus::Affected).with_details(CircuitBreakersHealthDetails {
+                tripped: tripped_soft_breakers,
+            })
+        };
+        self.health_updater.update(health);
         Ok(())
     }
 
@@ -87,6 +184,8 @@ impl CircuitBreakerChecker {
                 .ok();
         }
         tracing::info!("received a stop signal; circuit breaker is shut down");
+        self.health_updater
+            .update(Health::from(HealthStatus::ShutDown));
         Ok(())
     }
 }
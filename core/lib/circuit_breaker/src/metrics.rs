@@ -9,6 +9,12 @@ use vise::{Gauge, Global, Metrics};
 pub(crate) struct CircuitBreakerMetrics {
     /// Replication lag for Postgres in seconds.
     pub replication_lag: Gauge<Duration>,
+    /// Number of L1 batches for which proofs are not generated yet.
+    pub unproven_batches: Gauge<u64>,
+    /// Lag of the Merkle tree behind the state keeper in L1 batches.
+    pub tree_lag: Gauge<u64>,
+    /// L1 base fee recommended by the gas adjuster in wei.
+    pub l1_base_fee: Gauge<u64>,
 }
 
 #[vise::register]
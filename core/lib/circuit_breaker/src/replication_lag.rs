@@ -10,10 +10,15 @@ pub struct ReplicationLagChecker {
     pub replication_lag_limit: Option<Duration>,
 }
 
+impl ReplicationLagChecker {
+    /// Name of this circuit breaker.
+    pub const NAME: &str = "replication_lag";
+}
+
 #[async_trait::async_trait]
 impl CircuitBreaker for ReplicationLagChecker {
     fn name(&self) -> &'static str {
-        "replication_lag"
+        Self::NAME
     }
 
     async fn check(&self) -> Result<(), CircuitBreakerError> {
@@ -0,0 +1,267 @@
+//! Tests for circuit breakers.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use assert_matches::assert_matches;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_health_check::CheckHealth;
+use zksync_node_fee_model::l1_gas_price::L1TxParamsProvider;
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_node_test_utils::{create_l1_batch, create_l1_batch_metadata};
+use zksync_types::L1BatchNumber;
+
+use super::*;
+use crate::{
+    l1_gas::L1BaseFeeChecker, tree_lag::TreeLagChecker, unproven_batches::UnprovenBatchesChecker,
+};
+
+#[derive(Debug)]
+struct MockCircuitBreaker {
+    name: &'static str,
+    tripped: Arc<AtomicBool>,
+}
+
+impl MockCircuitBreaker {
+    fn new(name: &'static str) -> (Self, Arc<AtomicBool>) {
+        let tripped = Arc::new(AtomicBool::new(false));
+        let this = Self {
+            name,
+            tripped: tripped.clone(),
+        };
+        (this, tripped)
+    }
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for MockCircuitBreaker {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        if self.tripped.load(Ordering::SeqCst) {
+            return Err(CircuitBreakerError::TreeLag {
+                lag: 10,
+                threshold: 5,
+            });
+        }
+        Ok(())
+    }
+}
+
+async fn create_checker(soft_breakers: &[&str]) -> (CircuitBreakerChecker, Arc<AtomicBool>) {
+    let circuit_breakers = Arc::new(CircuitBreakers::default());
+    let (breaker, tripped) = MockCircuitBreaker::new("tree_lag");
+    circuit_breakers.insert(Box::new(breaker)).await;
+    let checker = CircuitBreakerChecker::new(circuit_breakers, Duration::from_millis(10))
+        .with_soft_circuit_breakers(soft_breakers.iter().map(|&name| name.to_owned()))
+        .unwrap();
+    (checker, tripped)
+}
+
+#[tokio::test]
+async fn tripped_circuit_breaker_fails_check() {
+    let (checker, tripped) = create_checker(&[]).await;
+    checker.check().await.unwrap();
+    tripped.store(true, Ordering::SeqCst);
+    let err = checker.check().await.unwrap_err();
+    assert_matches!(err, CircuitBreakerError::TreeLag { .. });
+}
+
+#[tokio::test]
+async fn tripped_soft_circuit_breaker_affects_health() {
+    let (checker, tripped) = create_checker(&["tree_lag"]).await;
+    let health_check = checker.health_check();
+    checker.check().await.unwrap();
+    assert_matches!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+
+    tripped.store(true, Ordering::SeqCst);
+    checker.check().await.unwrap();
+    let health = health_check.check_health().await;
+    assert_matches!(health.status(), HealthStatus::Affected);
+    let details = health.details().unwrap();
+    assert_eq!(details["tripped"][0]["name"], "tree_lag");
+    assert!(details["tripped"][0]["error"]
+        .as_str()
+        .unwrap()
+        .contains("Merkle tree lag"));
+
+    tripped.store(false, Ordering::SeqCst);
+    checker.check().await.unwrap();
+    assert_matches!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+}
+
+#[tokio::test]
+async fn checker_keeps_running_with_tripped_soft_circuit_breaker() {
+    let (checker, tripped) = create_checker(&["tree_lag"]).await;
+    let mut health_check = checker.health_check();
+    tripped.store(true, Ordering::SeqCst);
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    health_check
+        .wait_for(|health| matches!(health.status(), HealthStatus::Affected))
+        .await;
+    assert!(!checker_task.is_finished());
+    tripped.store(false, Ordering::SeqCst);
+    health_check
+        .wait_for(|health| matches!(health.status(), HealthStatus::Ready))
+        .await;
+
+    stop_sender.send_replace(true);
+    checker_task.await.unwrap().unwrap();
+    assert_matches!(
+        health_check.check_health().await.status(),
+        HealthStatus::ShutDown
+    );
+}
+
+#[tokio::test]
+async fn checker_stops_on_tripped_hard_circuit_breaker() {
+    let (checker, tripped) = create_checker(&[]).await;
+    tripped.store(true, Ordering::SeqCst);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = checker.run(stop_receiver).await.unwrap_err();
+    assert!(err.to_string().contains("Merkle tree lag"), "{err}");
+}
+
+#[test]
+fn unknown_soft_circuit_breakers_are_rejected() {
+    let circuit_breakers = Arc::new(CircuitBreakers::default());
+    let err = CircuitBreakerChecker::new(circuit_breakers, Duration::from_secs(1))
+        .with_soft_circuit_breakers(["tree_lag".to_owned(), "tree_lga".to_owned()])
+        .unwrap_err();
+    assert!(err.to_string().contains("`tree_lga`"), "{err}");
+}
+
+#[tokio::test]
+async fn unproven_batches_checker_threshold() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=3 {
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(number))
+            .await
+            .unwrap();
+        storage
+            .proof_generation_dal()
+            .insert_proof_generation_details(L1BatchNumber(number), "data")
+            .await;
+    }
+
+    let checker = UnprovenBatchesChecker {
+        pool: pool.clone(),
+        unproven_batches_limit: 2,
+    };
+    let err = checker.check().await.unwrap_err();
+    assert_matches!(
+        err,
+        CircuitBreakerError::UnprovenBatches {
+            count: 3,
+            threshold: 2
+        }
+    );
+
+    storage
+        .proof_generation_dal()
+        .mark_proof_generation_job_as_skipped(L1BatchNumber(1))
+        .await
+        .unwrap();
+    // The limit is inclusive.
+    checker.check().await.unwrap();
+}
+
+#[tokio::test]
+async fn tree_lag_checker_threshold() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    let checker = TreeLagChecker {
+        pool: pool.clone(),
+        tree_lag_limit: 2,
+    };
+    // The storage is empty, so there's nothing to check.
+    checker.check().await.unwrap();
+
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=3 {
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(number))
+            .await
+            .unwrap();
+    }
+    let err = checker.check().await.unwrap_err();
+    assert_matches!(
+        err,
+        CircuitBreakerError::TreeLag {
+            lag: 3,
+            threshold: 2
+        }
+    );
+
+    storage
+        .blocks_dal()
+        .save_l1_batch_tree_data(L1BatchNumber(1), &create_l1_batch_metadata(1).tree_data())
+        .await
+        .unwrap();
+    // The limit is inclusive.
+    checker.check().await.unwrap();
+}
+
+#[derive(Debug)]
+struct MockL1TxParams {
+    base_fee: u64,
+}
+
+impl L1TxParamsProvider for MockL1TxParams {
+    fn get_base_fee(&self, _time_in_mempool: u32) -> u64 {
+        self.base_fee
+    }
+
+    fn get_blob_base_fee(&self) -> u64 {
+        unimplemented!()
+    }
+
+    fn get_priority_fee(&self) -> u64 {
+        unimplemented!()
+    }
+
+    fn get_next_block_minimal_base_fee(&self) -> u64 {
+        unimplemented!()
+    }
+}
+
+#[tokio::test]
+async fn l1_base_fee_checker_threshold() {
+    let checker = L1BaseFeeChecker {
+        l1_tx_params: Arc::new(MockL1TxParams { base_fee: 100 }),
+        base_fee_ceiling: 100,
+    };
+    // The ceiling is inclusive.
+    checker.check().await.unwrap();
+
+    let checker = L1BaseFeeChecker {
+        l1_tx_params: Arc::new(MockL1TxParams { base_fee: 101 }),
+        base_fee_ceiling: 100,
+    };
+    let err = checker.check().await.unwrap_err();
+    assert_matches!(
+        err,
+        CircuitBreakerError::L1BaseFee {
+            base_fee: 101,
+            threshold: 100
+        }
+    );
+}
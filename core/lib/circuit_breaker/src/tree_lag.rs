@@ -0,0 +1,51 @@
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+use crate::{metrics::METRICS, CircuitBreaker, CircuitBreakerError};
+
+/// Trips if the Merkle tree lags behind the state keeper by more than the configured number of L1 batches.
+#[derive(Debug)]
+pub struct TreeLagChecker {
+    pub pool: ConnectionPool<Core>,
+    pub tree_lag_limit: u32,
+}
+
+impl TreeLagChecker {
+    /// Name of this circuit breaker.
+    pub const NAME: &str = "tree_lag";
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for TreeLagChecker {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let mut storage = self.pool.connection_tagged("circuit_breaker").await?;
+        let sealed_l1_batch = storage.blocks_dal().get_sealed_l1_batch_number().await?;
+        let last_l1_batch_with_metadata = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await?;
+        drop(storage);
+
+        let Some(sealed_l1_batch) = sealed_l1_batch else {
+            // No L1 batches yet (e.g., the node is not initialized); nothing to check.
+            return Ok(());
+        };
+        let lag = match last_l1_batch_with_metadata {
+            Some(number) => sealed_l1_batch.0.saturating_sub(number.0),
+            // Only possible for a recovered node which hasn't processed any batches with the tree yet.
+            None => 0,
+        };
+        METRICS.tree_lag.set(lag.into());
+
+        if lag > self.tree_lag_limit {
+            return Err(CircuitBreakerError::TreeLag {
+                lag,
+                threshold: self.tree_lag_limit,
+            });
+        }
+        Ok(())
+    }
+}
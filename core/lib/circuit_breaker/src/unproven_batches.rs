@@ -0,0 +1,41 @@
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+use crate::{metrics::METRICS, CircuitBreaker, CircuitBreakerError};
+
+/// Trips if the number of L1 batches waiting for proofs exceeds the configured limit.
+#[derive(Debug)]
+pub struct UnprovenBatchesChecker {
+    pub pool: ConnectionPool<Core>,
+    pub unproven_batches_limit: u64,
+}
+
+impl UnprovenBatchesChecker {
+    /// Name of this circuit breaker.
+    pub const NAME: &str = "unproven_batches";
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for UnprovenBatchesChecker {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let count = self
+            .pool
+            .connection_tagged("circuit_breaker")
+            .await?
+            .proof_generation_dal()
+            .get_unproven_batches_count()
+            .await?;
+        METRICS.unproven_batches.set(count);
+
+        if count > self.unproven_batches_limit {
+            return Err(CircuitBreakerError::UnprovenBatches {
+                count,
+                threshold: self.unproven_batches_limit,
+            });
+        }
+        Ok(())
+    }
+}
@@ -142,6 +142,10 @@ pub struct Web3JsonRpcConfig {
     pub ws_port: u16,
     /// URL to access WebSocket RPC server.
     pub ws_url: String,
+    /// Port for the admin HTTP RPC server exposing the `admin` namespace (sequencer control, mempool management etc.).
+    /// The server is run alongside the state keeper and only listens on the loopback interface. If not set,
+    /// the server is disabled.
+    pub admin_port: Option<u16>,
     /// Max possible limit of entities to be requested once.
     pub req_entities_limit: Option<u32>,
     /// Whether to support HTTP methods that install filters and query filter changes.
@@ -225,6 +229,7 @@ impl Web3JsonRpcConfig {
             http_url: "http://localhost:3050".into(),
             ws_port: 3051,
             ws_url: "ws://localhost:3051".into(),
+            admin_port: None,
             req_entities_limit: Some(10000),
             filters_disabled: false,
             filters_limit: Some(10000),
@@ -354,6 +359,18 @@ pub struct HealthCheckConfig {
     /// Time limit in milliseconds to abort a health check and return "not ready" status for the corresponding component.
     /// If not specified, the default value in the health check crate will be used.
     pub hard_time_limit_ms: Option<u64>,
+    /// Components checked by the liveness probe (`/health/live`). The probe only fails if one of the components
+    /// has shut down or panicked. If empty, all components are checked.
+    #[serde(default)]
+    pub liveness_components: Vec<String>,
+    /// Components that must be healthy for the readiness probe (`/health/ready`) to succeed.
+    /// If empty, all components are checked.
+    #[serde(default)]
+    pub readiness_components: Vec<String>,
+    /// Whether to expose query plans captured for slow DB queries at `/debug/slow_query_plans`.
+    /// Disabled by default since captured plans may contain query args.
+    #[serde(default)]
+    pub expose_slow_query_plans: bool,
 }
 
 impl HealthCheckConfig {
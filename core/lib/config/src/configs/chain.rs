@@ -151,6 +151,21 @@ pub struct StateKeeperConfig {
     /// Max number of computational gas that validation step is allowed to take.
     pub validation_computational_gas_limit: u32,
     pub save_call_traces: bool,
+    /// Whether to persist protective reads when persisting L1 batches. Protective reads are required to generate
+    /// witness inputs; they can be disabled if protective reads are produced asynchronously by a separate component
+    /// (the protective reads writer). Can only be disabled for nodes built with the node framework.
+    #[serde(default = "StateKeeperConfig::default_protective_reads_persistence_enabled")]
+    pub protective_reads_persistence_enabled: bool,
+    /// Path to a JSON file with transaction filtering rules (deny- and allow-lists for senders, target contracts
+    /// and function selectors). If not set, transactions are not filtered. The file is periodically checked
+    /// for changes and reloaded without restarting the node.
+    pub tx_filter_path: Option<String>,
+    /// Interval between checks of the transaction filter file for changes.
+    #[serde(default = "StateKeeperConfig::default_tx_filter_reload_interval_ms")]
+    pub tx_filter_reload_interval_ms: u64,
+    /// Path to a JSON file with overrides for seal and timeout params (e.g., `transaction_slots` or
+    /// `block_commit_deadline_ms`). The file is watched for changes, which are applied without restarting the node.
+    pub seal_params_path: Option<String>,
 
     /// The maximal number of circuits that a batch can support.
     /// Note, that this number corresponds to the "base layer" circuits, i.e. it does not include
@@ -169,6 +184,18 @@ pub struct StateKeeperConfig {
 }
 
 impl StateKeeperConfig {
+    fn default_protective_reads_persistence_enabled() -> bool {
+        true
+    }
+
+    fn default_tx_filter_reload_interval_ms() -> u64 {
+        10_000
+    }
+
+    pub fn tx_filter_reload_interval(&self) -> Duration {
+        Duration::from_millis(self.tx_filter_reload_interval_ms)
+    }
+
     /// Creates a config object suitable for use in unit tests.
     /// Values mostly repeat the values used in the localhost environment.
     pub fn for_tests() -> Self {
@@ -199,6 +226,10 @@ impl StateKeeperConfig {
             fee_model_version: FeeModelVersion::V2,
             validation_computational_gas_limit: 300000,
             save_call_traces: true,
+            protective_reads_persistence_enabled: true,
+            tx_filter_path: None,
+            tx_filter_reload_interval_ms: 10_000,
+            seal_params_path: None,
             max_circuits_per_batch: 24100,
             bootloader_hash: None,
             default_aa_hash: None,
@@ -225,6 +256,17 @@ pub struct CircuitBreakerConfig {
     pub http_req_max_retry_number: usize,
     pub http_req_retry_interval_sec: u8,
     pub replication_lag_limit_sec: Option<u32>,
+    /// Maximum number of L1 batches without generated proofs. If not set, the number of unproven batches is not checked.
+    pub unproven_batches_limit: Option<u64>,
+    /// Maximum lag of the Merkle tree behind the state keeper in L1 batches. If not set, the tree lag is not checked.
+    pub tree_lag_limit: Option<u32>,
+    /// Maximum L1 base fee (in wei) recommended by the gas adjuster. If not set, the base fee is not checked.
+    /// The check is performed regardless of the components run by the node.
+    pub l1_base_fee_ceiling: Option<u64>,
+    /// Names of circuit breakers operating in the soft mode, i.e., reporting affected health instead of
+    /// shutting down the node once tripped. Unknown names are rejected on node startup.
+    #[serde(default)]
+    pub soft_circuit_breakers: Vec<String>,
 }
 
 impl CircuitBreakerConfig {
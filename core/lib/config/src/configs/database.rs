@@ -47,6 +47,18 @@ pub struct MerkleTreeConfig {
     /// Maximum number of L1 batches to be processed by the Merkle tree at a time.
     #[serde(default = "MerkleTreeConfig::default_max_l1_batches_per_iter")]
     pub max_l1_batches_per_iter: usize,
+    /// Interval (in L1 batches) between RocksDB checkpoints of the tree uploaded to the object store.
+    /// If not specified, checkpoints are not created.
+    #[serde(default)]
+    pub checkpoint_interval_l1_batches: Option<u32>,
+    /// Maximum number of the most recent tree checkpoints retained in the object store. Older checkpoints
+    /// are removed after a new checkpoint is successfully uploaded.
+    #[serde(default = "MerkleTreeConfig::default_max_retained_checkpoints")]
+    pub max_retained_checkpoints: usize,
+    /// Whether to process L1 batches by splitting them into subtrees updated in parallel. Only has effect
+    /// in the lightweight mode.
+    #[serde(default)]
+    pub parallel_updates: bool,
 }
 
 impl Default for MerkleTreeConfig {
@@ -59,6 +71,9 @@ impl Default for MerkleTreeConfig {
             memtable_capacity_mb: Self::default_memtable_capacity_mb(),
             stalled_writes_timeout_sec: Self::default_stalled_writes_timeout_sec(),
             max_l1_batches_per_iter: Self::default_max_l1_batches_per_iter(),
+            checkpoint_interval_l1_batches: None,
+            max_retained_checkpoints: Self::default_max_retained_checkpoints(),
+            parallel_updates: false,
         }
     }
 }
@@ -88,6 +103,10 @@ impl MerkleTreeConfig {
         20
     }
 
+    pub const fn default_max_retained_checkpoints() -> usize {
+        3
+    }
+
     /// Returns the size of block cache size for Merkle tree in bytes.
     pub fn block_cache_size(&self) -> usize {
         self.block_cache_size_mb * super::BYTES_IN_MEGABYTE
@@ -137,6 +156,11 @@ pub struct PostgresConfig {
     pub replica_url: Option<SensitiveUrl>,
     /// URL for the prover database.
     pub prover_url: Option<SensitiveUrl>,
+    /// URLs of additional read replicas used by the API servers. Read-only API requests are routed to a replica
+    /// with an acceptable replication lag, falling back to the database specified by `replica_url`.
+    pub read_replica_urls: Vec<SensitiveUrl>,
+    /// Maximum replication lag in seconds for a read replica to be eligible for serving API requests.
+    pub max_replication_lag_sec: Option<u64>,
     /// Maximum size of the connection pool.
     pub max_connections: Option<u32>,
     /// Maximum size of the connection pool to master DB.
@@ -152,6 +176,11 @@ pub struct PostgresConfig {
     pub long_connection_threshold_ms: Option<u64>,
     /// Threshold in milliseconds to denote a DB query as "slow" and log its details.
     pub slow_query_threshold_ms: Option<u64>,
+    /// Sample rate in `[0, 1]` for capturing query plans of slow queries. If not set, plans are not captured.
+    pub slow_query_plan_sample_rate: Option<f64>,
+    /// Maximum number of captured query plans of slow queries stored in memory. If not set, a reasonable default
+    /// is used.
+    pub slow_query_plan_capacity: Option<usize>,
     pub test_server_url: Option<String>,
     pub test_prover_url: Option<String>,
 }
@@ -198,4 +227,9 @@ impl PostgresConfig {
     pub fn slow_query_threshold(&self) -> Option<Duration> {
         self.slow_query_threshold_ms.map(Duration::from_millis)
     }
+
+    /// Returns the maximum replication lag for read replicas.
+    pub fn max_replication_lag(&self) -> Option<Duration> {
+        self.max_replication_lag_sec.map(Duration::from_secs)
+    }
 }
@@ -59,6 +59,7 @@ impl EthConfig {
             }),
             watcher: Some(EthWatchConfig {
                 confirmations_for_eth_event: None,
+                use_finalized_block_tag: false,
                 eth_node_poll_interval: 0,
             }),
             web3_url: "localhost:8545".parse().unwrap(),
@@ -8,6 +8,10 @@ pub struct EthWatchConfig {
     /// Amount of confirmations for the priority operation to be processed.
     /// If not specified operation will be processed once its block is finalized.
     pub confirmations_for_eth_event: Option<u64>,
+    /// If set, operations are processed once their block is finalized (i.e., is not later than the block
+    /// with the `finalized` tag), regardless of `confirmations_for_eth_event`.
+    #[serde(default)]
+    pub use_finalized_block_tag: bool,
     /// How often we want to poll the Ethereum node.
     /// Value in milliseconds.
     pub eth_node_poll_interval: u64,
@@ -18,4 +22,14 @@ impl EthWatchConfig {
     pub fn poll_interval(&self) -> Duration {
         Duration::from_millis(self.eth_node_poll_interval)
     }
+
+    /// Returns the number of confirmations for L1 events, or `None` if events should be processed
+    /// once their block is finalized.
+    pub fn event_confirmations(&self) -> Option<u64> {
+        if self.use_finalized_block_tag {
+            None
+        } else {
+            self.confirmations_for_eth_event
+        }
+    }
 }
@@ -25,7 +25,7 @@ pub struct FriWitnessGeneratorConfig {
     pub shall_save_to_public_bucket: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WitnessGenerationTimeouts {
     basic: Duration,
     leaf: Duration,
@@ -0,0 +1,53 @@
+use std::{num::NonZeroU64, time::Duration};
+
+use serde::Deserialize;
+
+/// Configuration of the Postgres pruner running on the main node.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PruningConfig {
+    /// Enables pruning of the historical node state in Postgres.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Number of L1 batches pruned at a time.
+    #[serde(default = "PruningConfig::default_chunk_size")]
+    pub chunk_size: u32,
+    /// Delta between soft- and hard-removing data from Postgres.
+    #[serde(default = "PruningConfig::default_removal_delay_sec")]
+    pub removal_delay_sec: NonZeroU64,
+    /// If set to a non-zero value, L1 batches will be pruned after the batch timestamp is this old (in seconds).
+    #[serde(default)]
+    pub data_retention_sec: u64,
+    /// Minimum number of the latest sealed L1 batches retained regardless of their age.
+    #[serde(default)]
+    pub retained_l1_batches: u32,
+    /// If set, the pruner only reports data that would be pruned without removing it.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// If set, call traces are pruned for L2 blocks older than this value (in seconds), regardless of
+    /// other pruning settings.
+    pub call_traces_retention_sec: Option<u64>,
+    /// If set, events are pruned for L2 blocks older than this value (in seconds), regardless of
+    /// other pruning settings.
+    pub events_retention_sec: Option<u64>,
+    /// If set, transaction payloads (input, data and execution info) are pruned for L2 blocks older
+    /// than this value (in seconds), regardless of other pruning settings.
+    pub transaction_payloads_retention_sec: Option<u64>,
+}
+
+impl PruningConfig {
+    const fn default_chunk_size() -> u32 {
+        10
+    }
+
+    fn default_removal_delay_sec() -> NonZeroU64 {
+        NonZeroU64::new(60).unwrap()
+    }
+
+    pub fn removal_delay(&self) -> Duration {
+        Duration::from_secs(self.removal_delay_sec.get())
+    }
+
+    pub fn data_retention(&self) -> Duration {
+        Duration::from_secs(self.data_retention_sec)
+    }
+}
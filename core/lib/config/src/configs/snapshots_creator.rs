@@ -9,6 +9,11 @@ pub struct SnapshotsCreatorConfig {
 
     #[serde(default = "snapshots_creator_concurrent_queries_count")]
     pub concurrent_queries_count: u32,
+    /// Maximum number of consecutive differential snapshots created on top of a full snapshot.
+    /// A differential snapshot only contains storage logs and factory deps changed since the previous snapshot.
+    /// If set to 0 (the default), only full snapshots are created.
+    #[serde(default)]
+    pub max_differential_snapshots: u32,
     pub object_store: Option<ObjectStoreConfig>,
 }
 
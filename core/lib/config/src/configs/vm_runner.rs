@@ -0,0 +1,27 @@
+use serde::Deserialize;
+
+/// Configuration of the protective reads writer, a VM runner that re-executes sealed L1 batches
+/// and persists protective reads and basic witness inputs for them.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ProtectiveReadsWriterConfig {
+    /// Path to the RocksDB cache used by the writer.
+    #[serde(default = "ProtectiveReadsWriterConfig::default_db_path")]
+    pub db_path: String,
+    /// Maximum number of L1 batches loaded into memory ahead of processing.
+    #[serde(default = "ProtectiveReadsWriterConfig::default_window_size")]
+    pub window_size: u32,
+    /// First L1 batch processed by the writer. Batches before it are considered to already have protective reads
+    /// (e.g., persisted by the state keeper). The genesis batch is never processed.
+    #[serde(default)]
+    pub first_processed_batch: u32,
+}
+
+impl ProtectiveReadsWriterConfig {
+    fn default_db_path() -> String {
+        "./db/main/protective_reads".to_owned()
+    }
+
+    fn default_window_size() -> u32 {
+        3
+    }
+}
@@ -1,4 +1,4 @@
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU64, NonZeroUsize};
 
 use rand::{distributions::Distribution, Rng};
 use zksync_basic_types::{
@@ -56,6 +56,7 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             http_url: self.sample(rng),
             ws_port: self.sample(rng),
             ws_url: self.sample(rng),
+            admin_port: self.sample(rng),
             req_entities_limit: self.sample(rng),
             filters_disabled: self.sample(rng),
             filters_limit: self.sample(rng),
@@ -103,6 +104,9 @@ impl Distribution<configs::api::HealthCheckConfig> for EncodeDist {
             port: self.sample(rng),
             slow_time_limit_ms: self.sample(rng),
             hard_time_limit_ms: self.sample(rng),
+            liveness_components: self.sample_range(rng).map(|_| self.sample(rng)).collect(),
+            readiness_components: self.sample_range(rng).map(|_| self.sample(rng)).collect(),
+            expose_slow_query_plans: self.sample(rng),
         }
     }
 }
@@ -183,6 +187,10 @@ impl Distribution<configs::chain::StateKeeperConfig> for EncodeDist {
             fee_model_version: self.sample(rng),
             validation_computational_gas_limit: self.sample(rng),
             save_call_traces: self.sample(rng),
+            protective_reads_persistence_enabled: self.sample(rng),
+            tx_filter_path: self.sample(rng),
+            tx_filter_reload_interval_ms: self.sample(rng),
+            seal_params_path: self.sample(rng),
             max_circuits_per_batch: self.sample(rng),
             // These values are not involved into files serialization skip them
             fee_account_addr: None,
@@ -208,6 +216,10 @@ impl Distribution<configs::chain::CircuitBreakerConfig> for EncodeDist {
             http_req_max_retry_number: self.sample(rng),
             http_req_retry_interval_sec: self.sample(rng),
             replication_lag_limit_sec: self.sample(rng),
+            unproven_batches_limit: self.sample(rng),
+            tree_lag_limit: self.sample(rng),
+            l1_base_fee_ceiling: self.sample(rng),
+            soft_circuit_breakers: self.sample_collect(rng),
         }
     }
 }
@@ -280,6 +292,9 @@ impl Distribution<configs::database::MerkleTreeConfig> for EncodeDist {
             memtable_capacity_mb: self.sample(rng),
             stalled_writes_timeout_sec: self.sample(rng),
             max_l1_batches_per_iter: self.sample(rng),
+            checkpoint_interval_l1_batches: self.sample(rng),
+            max_retained_checkpoints: self.sample(rng),
+            parallel_updates: self.sample(rng),
         }
     }
 }
@@ -309,12 +324,18 @@ impl Distribution<configs::database::PostgresConfig> for EncodeDist {
             master_url: Some(format!("localhost:{}", rng.gen::<u16>()).parse().unwrap()),
             replica_url: Some(format!("localhost:{}", rng.gen::<u16>()).parse().unwrap()),
             prover_url: Some(format!("localhost:{}", rng.gen::<u16>()).parse().unwrap()),
+            read_replica_urls: (0..rng.gen_range(0..3))
+                .map(|_| format!("localhost:{}", rng.gen::<u16>()).parse().unwrap())
+                .collect(),
+            max_replication_lag_sec: self.sample(rng),
             max_connections: self.sample(rng),
             max_connections_master: self.sample(rng),
             acquire_timeout_sec: self.sample(rng),
             statement_timeout_sec: self.sample(rng),
             long_connection_threshold_ms: self.sample(rng),
             slow_query_threshold_ms: self.sample(rng),
+            slow_query_plan_sample_rate: self.sample(rng),
+            slow_query_plan_capacity: self.sample(rng),
             test_server_url: self.sample(rng),
             test_prover_url: self.sample(rng),
         }
@@ -411,6 +432,7 @@ impl Distribution<configs::EthWatchConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::EthWatchConfig {
         configs::EthWatchConfig {
             confirmations_for_eth_event: self.sample(rng),
+            use_finalized_block_tag: self.sample(rng),
             eth_node_poll_interval: self.sample(rng),
         }
     }
@@ -647,11 +669,28 @@ impl Distribution<configs::SnapshotsCreatorConfig> for EncodeDist {
         configs::SnapshotsCreatorConfig {
             storage_logs_chunk_size: self.sample(rng),
             concurrent_queries_count: self.sample(rng),
+            max_differential_snapshots: self.sample(rng),
             object_store: self.sample(rng),
         }
     }
 }
 
+impl Distribution<configs::PruningConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::PruningConfig {
+        configs::PruningConfig {
+            enabled: self.sample(rng),
+            chunk_size: self.sample(rng),
+            removal_delay_sec: NonZeroU64::new(self.sample(rng)).unwrap_or(NonZeroU64::MAX),
+            data_retention_sec: self.sample(rng),
+            retained_l1_batches: self.sample(rng),
+            dry_run: self.sample(rng),
+            call_traces_retention_sec: self.sample(rng),
+            events_retention_sec: self.sample(rng),
+            transaction_payloads_retention_sec: self.sample(rng),
+        }
+    }
+}
+
 impl Distribution<configs::ObservabilityConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::ObservabilityConfig {
         configs::ObservabilityConfig {
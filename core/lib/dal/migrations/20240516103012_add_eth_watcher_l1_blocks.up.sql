@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS eth_watcher_l1_blocks
+(
+    l1_block_number BIGINT PRIMARY KEY,
+    l1_block_hash   BYTEA     NOT NULL,
+    has_events      BOOLEAN   NOT NULL DEFAULT TRUE,
+    created_at      TIMESTAMP NOT NULL
+);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS vm_runner_progress
+(
+    runner_name             TEXT PRIMARY KEY,
+    last_processed_l1_batch BIGINT,
+    last_ready_l1_batch     BIGINT,
+    created_at              TIMESTAMP NOT NULL,
+    updated_at              TIMESTAMP NOT NULL
+);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS data_class_pruning_log
+(
+    data_class                 TEXT PRIMARY KEY,
+    last_soft_pruned_miniblock BIGINT NOT NULL,
+    last_hard_pruned_miniblock BIGINT,
+    created_at                 TIMESTAMP NOT NULL,
+    updated_at                 TIMESTAMP NOT NULL
+);
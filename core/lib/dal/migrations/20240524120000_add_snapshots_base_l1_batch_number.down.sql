@@ -0,0 +1 @@
+ALTER TABLE snapshots DROP COLUMN IF EXISTS base_l1_batch_number;
@@ -124,6 +124,32 @@ impl BlocksDal<'_, '_> {
         Ok(row.number.map(|number| L2BlockNumber(number as u32)))
     }
 
+    /// Returns the number of the latest L2 block with the timestamp not exceeding `timestamp`, or `None`
+    /// if there are no such blocks.
+    pub async fn get_last_l2_block_before_timestamp(
+        &mut self,
+        timestamp: u64,
+    ) -> DalResult<Option<L2BlockNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(number) AS "number"
+            FROM
+                miniblocks
+            WHERE
+                timestamp <= $1
+            "#,
+            timestamp as i64
+        )
+        .instrument("get_last_l2_block_before_timestamp")
+        .with_arg("timestamp", &timestamp)
+        .report_latency()
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.number.map(|number| L2BlockNumber(number as u32)))
+    }
+
     /// Returns the number of the earliest L1 batch present in the DB, or `None` if there are no L1 batches.
     pub async fn get_earliest_l1_batch_number(&mut self) -> DalResult<Option<L1BatchNumber>> {
         let row = sqlx::query!(
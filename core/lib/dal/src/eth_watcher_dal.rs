@@ -0,0 +1,277 @@
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::H256;
+
+use crate::Core;
+
+/// Information about an L1 block processed by the Ethereum watcher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessedL1Block {
+    pub number: u64,
+    pub hash: H256,
+    /// Whether the block contains events fed to event processors. Blocks without events are persisted
+    /// to detect L1 reorgs affecting the processed block range.
+    pub has_events: bool,
+}
+
+/// DAL for checkpoints of L1 event processors and hashes of processed L1 blocks used by the Ethereum watcher.
+#[derive(Debug)]
+pub struct EthWatcherDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl EthWatcherDal<'_, '_> {
+    /// Returns the last L1 block processed by the specified event processor, or `None` if the processor
+    /// has no checkpoint yet.
+    pub async fn get_processor_checkpoint(
+        &mut self,
+        processor_name: &str,
+    ) -> DalResult<Option<u64>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                last_processed_l1_block
+            FROM
+                eth_watcher_checkpoints
+            WHERE
+                processor_name = $1
+            "#,
+            processor_name
+        )
+        .instrument("get_processor_checkpoint")
+        .with_arg("processor_name", &processor_name)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| row.last_processed_l1_block as u64))
+    }
+
+    /// Sets the last L1 block processed by the specified event processor.
+    pub async fn set_processor_checkpoint(
+        &mut self,
+        processor_name: &str,
+        last_processed_l1_block: u64,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                eth_watcher_checkpoints (processor_name, last_processed_l1_block, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (processor_name) DO
+            UPDATE
+            SET
+                last_processed_l1_block = $2,
+                updated_at = NOW()
+            "#,
+            processor_name,
+            last_processed_l1_block as i64
+        )
+        .instrument("set_processor_checkpoint")
+        .with_arg("processor_name", &processor_name)
+        .with_arg("last_processed_l1_block", &last_processed_l1_block)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Rewinds checkpoints of all event processors that are greater than `last_processed_l1_block`
+    /// to `last_processed_l1_block`.
+    pub async fn rewind_processor_checkpoints(
+        &mut self,
+        last_processed_l1_block: u64,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE eth_watcher_checkpoints
+            SET
+                last_processed_l1_block = $1,
+                updated_at = NOW()
+            WHERE
+                last_processed_l1_block > $1
+            "#,
+            last_processed_l1_block as i64
+        )
+        .instrument("rewind_processor_checkpoints")
+        .with_arg("last_processed_l1_block", &last_processed_l1_block)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Saves hashes of processed L1 blocks. Hashes for already saved blocks are not overwritten; the `has_events`
+    /// flag is set for an already saved block if it's set for the block being saved.
+    pub async fn insert_l1_block_hashes(&mut self, blocks: &[ProcessedL1Block]) -> DalResult<()> {
+        let block_numbers: Vec<_> = blocks.iter().map(|block| block.number as i64).collect();
+        let block_hashes: Vec<_> = blocks.iter().map(|block| block.hash.as_bytes()).collect();
+        let has_events: Vec<_> = blocks.iter().map(|block| block.has_events).collect();
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                eth_watcher_l1_blocks (l1_block_number, l1_block_hash, has_events, created_at)
+            SELECT
+                u.l1_block_number,
+                u.l1_block_hash,
+                u.has_events,
+                NOW()
+            FROM
+                UNNEST($1::BIGINT[], $2::bytea[], $3::BOOLEAN[]) AS u (l1_block_number, l1_block_hash, has_events)
+            ON CONFLICT (l1_block_number) DO
+            UPDATE
+            SET
+                has_events = eth_watcher_l1_blocks.has_events
+                OR excluded.has_events
+            "#,
+            &block_numbers,
+            &block_hashes as &[&[u8]],
+            &has_events
+        )
+        .instrument("insert_l1_block_hashes")
+        .with_arg("blocks.len", &blocks.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns up to `limit` latest processed L1 blocks, ordered by descending block number.
+    pub async fn get_latest_l1_block_hashes(
+        &mut self,
+        limit: usize,
+    ) -> DalResult<Vec<ProcessedL1Block>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_block_number,
+                l1_block_hash,
+                has_events
+            FROM
+                eth_watcher_l1_blocks
+            ORDER BY
+                l1_block_number DESC
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_latest_l1_block_hashes")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ProcessedL1Block {
+                number: row.l1_block_number as u64,
+                hash: H256::from_slice(&row.l1_block_hash),
+                has_events: row.has_events,
+            })
+            .collect())
+    }
+
+    /// Removes hashes of L1 blocks with numbers less than `first_retained_block`.
+    pub async fn prune_l1_block_hashes(&mut self, first_retained_block: u64) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM eth_watcher_l1_blocks
+            WHERE
+                l1_block_number < $1
+            "#,
+            first_retained_block as i64
+        )
+        .instrument("prune_l1_block_hashes")
+        .with_arg("first_retained_block", &first_retained_block)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes hashes of L1 blocks with numbers greater than `last_retained_block`.
+    pub async fn remove_l1_block_hashes_after(
+        &mut self,
+        last_retained_block: u64,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM eth_watcher_l1_blocks
+            WHERE
+                l1_block_number > $1
+            "#,
+            last_retained_block as i64
+        )
+        .instrument("remove_l1_block_hashes_after")
+        .with_arg("last_retained_block", &last_retained_block)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::H256;
+
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn manipulating_processor_checkpoints() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.eth_watcher_dal();
+
+        let checkpoint = dal.get_processor_checkpoint("test").await.unwrap();
+        assert_eq!(checkpoint, None);
+
+        dal.set_processor_checkpoint("test", 10).await.unwrap();
+        dal.set_processor_checkpoint("other", 5).await.unwrap();
+        let checkpoint = dal.get_processor_checkpoint("test").await.unwrap();
+        assert_eq!(checkpoint, Some(10));
+
+        dal.set_processor_checkpoint("test", 20).await.unwrap();
+        let checkpoint = dal.get_processor_checkpoint("test").await.unwrap();
+        assert_eq!(checkpoint, Some(20));
+        let checkpoint = dal.get_processor_checkpoint("other").await.unwrap();
+        assert_eq!(checkpoint, Some(5));
+
+        dal.rewind_processor_checkpoints(8).await.unwrap();
+        let checkpoint = dal.get_processor_checkpoint("test").await.unwrap();
+        assert_eq!(checkpoint, Some(8));
+        let checkpoint = dal.get_processor_checkpoint("other").await.unwrap();
+        assert_eq!(checkpoint, Some(5));
+    }
+
+    #[tokio::test]
+    async fn manipulating_l1_block_hashes() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.eth_watcher_dal();
+
+        let mut blocks: Vec<_> = (10..15)
+            .map(|number| ProcessedL1Block {
+                number,
+                hash: H256::repeat_byte(number as u8),
+                has_events: number % 2 == 0,
+            })
+            .collect();
+        dal.insert_l1_block_hashes(&blocks).await.unwrap();
+        // Existing hashes must not be overwritten, but the events flag must be updated.
+        dal.insert_l1_block_hashes(&[ProcessedL1Block {
+            number: 13,
+            hash: H256::zero(),
+            has_events: true,
+        }])
+        .await
+        .unwrap();
+        blocks[3].has_events = true;
+
+        let latest = dal.get_latest_l1_block_hashes(2).await.unwrap();
+        assert_eq!(latest, [blocks[4], blocks[3]]);
+
+        dal.prune_l1_block_hashes(12).await.unwrap();
+        let latest = dal.get_latest_l1_block_hashes(10).await.unwrap();
+        assert_eq!(latest, [blocks[4], blocks[3], blocks[2]]);
+
+        dal.remove_l1_block_hashes_after(13).await.unwrap();
+        let latest = dal.get_latest_l1_block_hashes(10).await.unwrap();
+        assert_eq!(latest, [blocks[3], blocks[2]]);
+    }
+}
@@ -2,6 +2,7 @@ use std::{convert::TryInto, str::FromStr};
 
 use bigdecimal::Zero;
 use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
+use zksync_db_connection::{error::DalResult, instrument::Instrumented};
 use zksync_types::{
     api::{self, TransactionDetails, TransactionReceipt, TransactionStatus},
     fee::Fee,
@@ -289,6 +290,24 @@ impl From<StorageTransaction> for ProtocolUpgradeTxCommonData {
     }
 }
 
+impl StorageTransaction {
+    /// Returns an error if the transaction payload was cleared by data class pruning. Such a transaction
+    /// cannot be converted into a [`Transaction`].
+    pub(crate) fn ensure_payload_not_pruned(&self, method: &'static str) -> DalResult<()> {
+        let is_pruned = self
+            .data
+            .as_object()
+            .map_or(false, serde_json::Map::is_empty);
+        if is_pruned {
+            let tx_hash = H256::from_slice(&self.hash);
+            return Err(Instrumented::new(method)
+                .with_arg("tx_hash", &tx_hash)
+                .constraint_error(anyhow::anyhow!("transaction payload is pruned")));
+        }
+        Ok(())
+    }
+}
+
 impl From<StorageTransaction> for Transaction {
     fn from(tx: StorageTransaction) -> Self {
         let hash = H256::from_slice(&tx.hash);
@@ -2,7 +2,10 @@
 use std::time::Duration;
 
 use strum::{Display, EnumString};
-use zksync_db_connection::{connection::Connection, utils::pg_interval_from_duration};
+use zksync_db_connection::{
+    connection::Connection, error::DalResult, instrument::InstrumentExt,
+    utils::pg_interval_from_duration,
+};
 use zksync_types::L1BatchNumber;
 
 use crate::{Core, SqlxError};
@@ -185,4 +188,93 @@ impl ProofGenerationDal<'_, '_> {
 
         result
     }
+
+    /// Returns the number of L1 batches for which proofs are neither generated nor skipped.
+    pub async fn get_unproven_batches_count(&mut self) -> DalResult<u64> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                proof_generation_details
+            WHERE
+                status NOT IN ('generated', 'skipped')
+            "#,
+        )
+        .instrument("get_unproven_batches_count")
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.count as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
+
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn counting_unproven_batches() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        for number in 1..=4 {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::default(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+        let count = conn
+            .proof_generation_dal()
+            .get_unproven_batches_count()
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+
+        for number in 1..=4 {
+            conn.proof_generation_dal()
+                .insert_proof_generation_details(L1BatchNumber(number), "data")
+                .await;
+        }
+        let count = conn
+            .proof_generation_dal()
+            .get_unproven_batches_count()
+            .await
+            .unwrap();
+        assert_eq!(count, 4);
+
+        conn.proof_generation_dal()
+            .save_proof_artifacts_metadata(L1BatchNumber(1), "proof")
+            .await
+            .unwrap();
+        conn.proof_generation_dal()
+            .mark_proof_generation_job_as_skipped(L1BatchNumber(2))
+            .await
+            .unwrap();
+        // Batches picked by a prover are still unproven.
+        let picked_batch = conn
+            .proof_generation_dal()
+            .get_next_block_to_be_proven(Duration::from_secs(60))
+            .await;
+        assert_eq!(picked_batch, Some(L1BatchNumber(3)));
+        let count = conn
+            .proof_generation_dal()
+            .get_unproven_batches_count()
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+    }
 }
@@ -34,6 +34,66 @@ pub struct HardPruningStats {
     pub deleted_l2_to_l1_logs: u64,
 }
 
+/// Statistics about hard pruning a single chunk of a [`PrunedDataClass`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DataClassHardPruningStats {
+    /// Number of removed (or cleared) rows.
+    pub affected_rows: u64,
+    /// Last hard-pruned L2 block after pruning the chunk, or `None` if there was nothing to prune.
+    pub last_pruned_l2_block: Option<L2BlockNumber>,
+    /// Whether there is soft-pruned data remaining to be hard-pruned after this chunk.
+    pub has_more: bool,
+}
+
+/// Class of L2 block data that can be pruned separately from (and earlier than) the rest of L2 block data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrunedDataClass {
+    /// Call traces of transactions, returned by `debug_trace*` RPC methods.
+    CallTraces,
+    /// Events, returned by `eth_getLogs` and related RPC methods.
+    Events,
+    /// Raw transaction payloads (input, data and execution info). Upgrade transactions are never pruned.
+    TransactionPayloads,
+}
+
+impl PrunedDataClass {
+    /// All supported data classes.
+    pub const ALL: [Self; 3] = [Self::CallTraces, Self::Events, Self::TransactionPayloads];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::CallTraces => "call_traces",
+            Self::Events => "events",
+            Self::TransactionPayloads => "transaction_payloads",
+        }
+    }
+
+    fn from_str(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|class| class.as_str() == s)
+    }
+}
+
+/// Information about pruning of a single [`PrunedDataClass`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DataClassPruningInfo {
+    pub last_soft_pruned_l2_block: Option<L2BlockNumber>,
+    pub last_hard_pruned_l2_block: Option<L2BlockNumber>,
+}
+
+/// Information about pruning of all [`PrunedDataClass`]es.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DataClassesPruningInfo([DataClassPruningInfo; PrunedDataClass::ALL.len()]);
+
+impl DataClassesPruningInfo {
+    pub fn get(&self, class: PrunedDataClass) -> DataClassPruningInfo {
+        self.0[class as usize]
+    }
+
+    fn get_mut(&mut self, class: PrunedDataClass) -> &mut DataClassPruningInfo {
+        &mut self.0[class as usize]
+    }
+}
+
 #[derive(Debug, sqlx::Type)]
 #[sqlx(type_name = "prune_type")]
 enum PruneType {
@@ -206,6 +266,141 @@ impl PruningDal<'_, '_> {
         Ok(stats)
     }
 
+    pub async fn get_data_classes_pruning_info(&mut self) -> DalResult<DataClassesPruningInfo> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                data_class,
+                last_soft_pruned_miniblock,
+                last_hard_pruned_miniblock
+            FROM
+                data_class_pruning_log
+            "#
+        )
+        .instrument("get_data_classes_pruning_info")
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut info = DataClassesPruningInfo::default();
+        for row in rows {
+            let Some(class) = PrunedDataClass::from_str(&row.data_class) else {
+                tracing::warn!(
+                    "Unknown pruned data class in `data_class_pruning_log`: {}",
+                    row.data_class
+                );
+                continue;
+            };
+            *info.get_mut(class) = DataClassPruningInfo {
+                last_soft_pruned_l2_block: Some(L2BlockNumber(
+                    row.last_soft_pruned_miniblock as u32,
+                )),
+                last_hard_pruned_l2_block: row
+                    .last_hard_pruned_miniblock
+                    .map(|number| L2BlockNumber(number as u32)),
+            };
+        }
+        Ok(info)
+    }
+
+    /// Marks data of the specified class in L2 blocks up to and including `last_l2_block_to_prune` as pruned.
+    /// The data is not removed until [`Self::hard_prune_data_class()`] is called.
+    pub async fn soft_prune_data_class(
+        &mut self,
+        class: PrunedDataClass,
+        last_l2_block_to_prune: L2BlockNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                data_class_pruning_log (
+                    data_class,
+                    last_soft_pruned_miniblock,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (data_class) DO
+            UPDATE
+            SET
+                last_soft_pruned_miniblock = GREATEST(
+                    data_class_pruning_log.last_soft_pruned_miniblock,
+                    excluded.last_soft_pruned_miniblock
+                ),
+                updated_at = NOW()
+            "#,
+            class.as_str(),
+            i64::from(last_l2_block_to_prune.0),
+        )
+        .instrument("soft_prune_data_class")
+        .with_arg("class", &class)
+        .with_arg("last_l2_block_to_prune", &last_l2_block_to_prune)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes a chunk of data of the specified class soft-pruned since the last call to this method. At most
+    /// `max_l2_block_count` L2 blocks are processed per call, so that a single call doesn't lock affected tables
+    /// for long; the hard pruning horizon of the class is advanced to the last processed L2 block.
+    pub async fn hard_prune_data_class(
+        &mut self,
+        class: PrunedDataClass,
+        max_l2_block_count: u32,
+    ) -> DalResult<DataClassHardPruningStats> {
+        let info = self.get_data_classes_pruning_info().await?.get(class);
+        let Some(last_soft_pruned_l2_block) = info.last_soft_pruned_l2_block else {
+            return Ok(DataClassHardPruningStats::default());
+        };
+        let first_l2_block_to_prune = info
+            .last_hard_pruned_l2_block
+            .map_or(L2BlockNumber(0), |number| number + 1);
+        if first_l2_block_to_prune > last_soft_pruned_l2_block {
+            return Ok(DataClassHardPruningStats::default());
+        }
+        let last_l2_block_to_prune = L2BlockNumber(
+            first_l2_block_to_prune
+                .0
+                .saturating_add(max_l2_block_count.max(1) - 1),
+        )
+        .min(last_soft_pruned_l2_block);
+
+        let l2_blocks_to_prune = first_l2_block_to_prune..=last_l2_block_to_prune;
+        let affected_rows = match class {
+            PrunedDataClass::CallTraces => self.delete_call_traces(l2_blocks_to_prune).await?,
+            PrunedDataClass::Events => self.delete_events(l2_blocks_to_prune).await?,
+            PrunedDataClass::TransactionPayloads => {
+                self.clear_transaction_fields(l2_blocks_to_prune).await?
+            }
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE data_class_pruning_log
+            SET
+                last_hard_pruned_miniblock = $2,
+                updated_at = NOW()
+            WHERE
+                data_class = $1
+            "#,
+            class.as_str(),
+            i64::from(last_l2_block_to_prune.0),
+        )
+        .instrument("hard_prune_data_class#update_pruning_log")
+        .with_arg("class", &class)
+        .with_arg("last_l2_block_to_prune", &last_l2_block_to_prune)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(DataClassHardPruningStats {
+            affected_rows,
+            last_pruned_l2_block: Some(last_l2_block_to_prune),
+            has_more: last_l2_block_to_prune < last_soft_pruned_l2_block,
+        })
+    }
+
     async fn delete_events(
         &mut self,
         l2_blocks_to_prune: ops::RangeInclusive<L2BlockNumber>,
@@ -281,11 +476,13 @@ impl PruningDal<'_, '_> {
 
     // The pruned fields are accessed as follows:
     //
-    // - `input`: is a part of `StorageTransaction`, read via `TransactionsDal` (`get_l2_blocks_to_reexecute`,
+    // - `input`, `data`: are a part of `StorageTransaction`, read via `TransactionsDal` (`get_l2_blocks_to_reexecute`,
     //   `get_l2_blocks_to_execute_for_l1_batch`, and `get_tx_by_hash`) and `TransactionsWeb3Dal::get_raw_l2_block_transactions()`.
-    //   `get_tx_by_hash()` is only called on upgrade transactions, which are not pruned. The remaining methods tie transactions
-    //   to a certain L1 batch / L2 block, and thus do naturally check pruning.
-    // - `data`: used by `TransactionsWeb3Dal` queries, which explicitly check whether it was pruned.
+    //   All these methods return an error for transactions with pruned payloads. Payloads are only pruned for executed
+    //   L1 batches processed by all VM runners, so the node never re-executes pruned transactions; the API server checks
+    //   pruning before calling `get_raw_l2_block_transactions()`.
+    // - `data`: also used by `TransactionsWeb3Dal` queries, which filter out transactions with pruned data.
+    //   The API server checks pruning for these queries as well, so that it returns a "pruned data" error instead of `null`.
     // - `execution_info`: not used in queries.
     async fn clear_transaction_fields(
         &mut self,
@@ -517,4 +517,153 @@ async fn transactions_are_handled_correctly_after_pruning() {
         .await
         .unwrap();
     assert!(transaction_details.is_none(), "{transaction_details:?}");
+
+    // The transaction location must still be available, so that the API server can distinguish pruned transactions.
+    let l2_block_number = conn
+        .transactions_web3_dal()
+        .get_l2_block_number_of_transaction(tx_hash)
+        .await
+        .unwrap();
+    assert_eq!(l2_block_number, Some(L2BlockNumber(1)));
+
+    // Methods returning full transactions must error rather than return bogus data.
+    let err = conn
+        .transactions_dal()
+        .get_tx_by_hash(tx_hash)
+        .await
+        .unwrap_err();
+    assert!(matches!(err.inner(), sqlx::Error::Decode(_)), "{err:?}");
+    let err = conn
+        .transactions_web3_dal()
+        .get_raw_l2_block_transactions(L2BlockNumber(1))
+        .await
+        .unwrap_err();
+    assert!(matches!(err.inner(), sqlx::Error::Decode(_)), "{err:?}");
+    let err = conn
+        .transactions_dal()
+        .get_l2_blocks_to_reexecute()
+        .await
+        .unwrap_err();
+    assert!(matches!(err.inner(), sqlx::Error::Decode(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn data_classes_can_be_pruned_separately() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_realistic_l1_batches(&mut conn, 10).await;
+
+    let info = conn
+        .pruning_dal()
+        .get_data_classes_pruning_info()
+        .await
+        .unwrap();
+    assert_eq!(info, DataClassesPruningInfo::default());
+
+    conn.pruning_dal()
+        .soft_prune_data_class(PrunedDataClass::Events, L2BlockNumber(5))
+        .await
+        .unwrap();
+    let info = conn
+        .pruning_dal()
+        .get_data_classes_pruning_info()
+        .await
+        .unwrap();
+    assert_eq!(
+        info.get(PrunedDataClass::Events),
+        DataClassPruningInfo {
+            last_soft_pruned_l2_block: Some(L2BlockNumber(5)),
+            last_hard_pruned_l2_block: None,
+        }
+    );
+    assert_eq!(
+        info.get(PrunedDataClass::CallTraces),
+        DataClassPruningInfo::default()
+    );
+
+    let stats = conn
+        .pruning_dal()
+        .hard_prune_data_class(PrunedDataClass::Events, 10)
+        .await
+        .unwrap();
+    assert_eq!(
+        stats,
+        DataClassHardPruningStats {
+            affected_rows: 30,
+            last_pruned_l2_block: Some(L2BlockNumber(5)),
+            has_more: false,
+        }
+    );
+    let stats = conn
+        .pruning_dal()
+        .hard_prune_data_class(PrunedDataClass::Events, 10)
+        .await
+        .unwrap();
+    assert_eq!(stats, DataClassHardPruningStats::default());
+
+    // Soft pruning horizon must not move backwards.
+    conn.pruning_dal()
+        .soft_prune_data_class(PrunedDataClass::Events, L2BlockNumber(3))
+        .await
+        .unwrap();
+    let info = conn
+        .pruning_dal()
+        .get_data_classes_pruning_info()
+        .await
+        .unwrap();
+    assert_eq!(
+        info.get(PrunedDataClass::Events),
+        DataClassPruningInfo {
+            last_soft_pruned_l2_block: Some(L2BlockNumber(5)),
+            last_hard_pruned_l2_block: Some(L2BlockNumber(5)),
+        }
+    );
+
+    conn.pruning_dal()
+        .soft_prune_data_class(PrunedDataClass::Events, L2BlockNumber(9))
+        .await
+        .unwrap();
+    // Hard pruning must be performed in chunks.
+    let stats = conn
+        .pruning_dal()
+        .hard_prune_data_class(PrunedDataClass::Events, 3)
+        .await
+        .unwrap();
+    assert_eq!(
+        stats,
+        DataClassHardPruningStats {
+            affected_rows: 15,
+            last_pruned_l2_block: Some(L2BlockNumber(8)),
+            has_more: true,
+        }
+    );
+    let info = conn
+        .pruning_dal()
+        .get_data_classes_pruning_info()
+        .await
+        .unwrap();
+    assert_eq!(
+        info.get(PrunedDataClass::Events).last_hard_pruned_l2_block,
+        Some(L2BlockNumber(8))
+    );
+    let stats = conn
+        .pruning_dal()
+        .hard_prune_data_class(PrunedDataClass::Events, 3)
+        .await
+        .unwrap();
+    assert_eq!(
+        stats,
+        DataClassHardPruningStats {
+            affected_rows: 5,
+            last_pruned_l2_block: Some(L2BlockNumber(9)),
+            has_more: false,
+        }
+    );
+
+    // Other L2 block data must be retained.
+    assert_l1_batch_objects_exists(&mut conn, L1BatchNumber(1)..=L1BatchNumber(9)).await;
+    assert_eq!(
+        conn.pruning_dal().get_pruning_info().await.unwrap(),
+        PruningInfo::default()
+    );
 }
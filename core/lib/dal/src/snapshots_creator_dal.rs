@@ -111,6 +111,82 @@ impl SnapshotsCreatorDal<'_, '_> {
         Ok(storage_logs)
     }
 
+    /// Constructs a differential `storage_logs` chunk containing only logs for keys that were changed after
+    /// `base_l2_block_number` up to and including `l2_block_number`. Values in the returned logs correspond
+    /// to the state AFTER processing `[0..l1_batch_number]` batches; `l2_block_number` MUST be the last L2 block
+    /// of the `l1_batch_number` batch.
+    pub async fn get_storage_logs_chunk_diff(
+        &mut self,
+        base_l2_block_number: L2BlockNumber,
+        l2_block_number: L2BlockNumber,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys_range: std::ops::RangeInclusive<H256>,
+    ) -> DalResult<Vec<SnapshotStorageLog>> {
+        // See `get_storage_logs_chunk()` for the reasoning behind filtering by `l1_batch_number`.
+        // Filtering by `base_l2_block_number` in the subquery is equivalent to filtering the latest ops: the latest op
+        // for a key is after the base L2 block iff the key has an op in `(base_l2_block_number, l2_block_number]`.
+        let storage_logs = sqlx::query!(
+            r#"
+            SELECT
+                storage_logs.key AS "key!",
+                storage_logs.value AS "value!",
+                storage_logs.address AS "address!",
+                storage_logs.miniblock_number AS "miniblock_number!",
+                initial_writes.l1_batch_number AS "l1_batch_number!",
+                initial_writes.index
+            FROM
+                (
+                    SELECT
+                        hashed_key,
+                        MAX(ARRAY[miniblock_number, operation_number]::INT[]) AS op
+                    FROM
+                        storage_logs
+                    WHERE
+                        miniblock_number <= $1
+                        AND miniblock_number > $5
+                        AND hashed_key >= $3
+                        AND hashed_key <= $4
+                    GROUP BY
+                        hashed_key
+                    ORDER BY
+                        hashed_key
+                ) AS keys
+                INNER JOIN storage_logs ON keys.hashed_key = storage_logs.hashed_key
+                AND storage_logs.miniblock_number = keys.op[1]
+                AND storage_logs.operation_number = keys.op[2]
+                INNER JOIN initial_writes ON keys.hashed_key = initial_writes.hashed_key
+            WHERE
+                initial_writes.l1_batch_number <= $2
+            "#,
+            i64::from(l2_block_number.0),
+            i64::from(l1_batch_number.0),
+            hashed_keys_range.start().as_bytes(),
+            hashed_keys_range.end().as_bytes(),
+            i64::from(base_l2_block_number.0)
+        )
+        .instrument("get_storage_logs_chunk_diff")
+        .with_arg("base_l2_block_number", &base_l2_block_number)
+        .with_arg("l2_block_number", &l2_block_number)
+        .with_arg("min_hashed_key", &hashed_keys_range.start())
+        .with_arg("max_hashed_key", &hashed_keys_range.end())
+        .report_latency()
+        .expect_slow_query()
+        .fetch_all(self.storage)
+        .await?
+        .iter()
+        .map(|row| SnapshotStorageLog {
+            key: StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            ),
+            value: H256::from_slice(&row.value),
+            l1_batch_number_of_initial_write: L1BatchNumber(row.l1_batch_number as u32),
+            enumeration_index: row.index as u64,
+        })
+        .collect();
+        Ok(storage_logs)
+    }
+
     /// Returns all factory dependencies up to and including the specified `l2_block_number`.
     pub async fn get_all_factory_deps(
         &mut self,
@@ -139,6 +215,39 @@ impl SnapshotsCreatorDal<'_, '_> {
             .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
             .collect())
     }
+
+    /// Returns factory dependencies added after `base_l2_block_number` up to and including `l2_block_number`.
+    pub async fn get_new_factory_deps(
+        &mut self,
+        base_l2_block_number: L2BlockNumber,
+        l2_block_number: L2BlockNumber,
+    ) -> DalResult<Vec<(H256, Vec<u8>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                bytecode_hash,
+                bytecode
+            FROM
+                factory_deps
+            WHERE
+                miniblock_number > $1
+                AND miniblock_number <= $2
+            "#,
+            i64::from(base_l2_block_number.0),
+            i64::from(l2_block_number.0),
+        )
+        .instrument("get_new_factory_deps")
+        .with_arg("base_l2_block_number", &base_l2_block_number)
+        .with_arg("l2_block_number", &l2_block_number)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -311,4 +420,78 @@ mod tests {
         assert_eq!(logs[0].value, real_write.value);
         assert_eq!(logs[0].l1_batch_number_of_initial_write, L1BatchNumber(2));
     }
+
+    #[tokio::test]
+    async fn getting_storage_log_chunk_diffs() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+
+        let logs: Vec<_> = (0..20)
+            .map(|i| {
+                let key = StorageKey::new(AccountTreeId::default(), H256::from_low_u64_be(i));
+                StorageLog::new_write_log(key, H256::repeat_byte(1))
+            })
+            .collect();
+        let written_keys: Vec<_> = logs.iter().map(|log| log.key).collect();
+        conn.storage_logs_dal()
+            .insert_storage_logs(L2BlockNumber(1), &[(H256::zero(), logs.clone())])
+            .await
+            .unwrap();
+        conn.storage_logs_dedup_dal()
+            .insert_initial_writes(L1BatchNumber(1), &written_keys)
+            .await
+            .unwrap();
+
+        let new_log = StorageLog::new_write_log(
+            StorageKey::new(AccountTreeId::default(), H256::from_low_u64_be(100)),
+            H256::repeat_byte(2),
+        );
+        let updated_log = StorageLog {
+            value: H256::repeat_byte(3),
+            ..logs[5]
+        };
+        conn.storage_logs_dal()
+            .insert_storage_logs(
+                L2BlockNumber(2),
+                &[(H256::zero(), vec![new_log, updated_log])],
+            )
+            .await
+            .unwrap();
+        conn.storage_logs_dedup_dal()
+            .insert_initial_writes(L1BatchNumber(2), &[new_log.key])
+            .await
+            .unwrap();
+
+        let all_keys = H256::zero()..=H256::repeat_byte(0xff);
+        let diff = conn
+            .snapshots_creator_dal()
+            .get_storage_logs_chunk_diff(
+                L2BlockNumber(1),
+                L2BlockNumber(1),
+                L1BatchNumber(1),
+                all_keys.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(diff, []);
+
+        let mut diff = conn
+            .snapshots_creator_dal()
+            .get_storage_logs_chunk_diff(
+                L2BlockNumber(1),
+                L2BlockNumber(2),
+                L1BatchNumber(2),
+                all_keys,
+            )
+            .await
+            .unwrap();
+        diff.sort_unstable_by_key(|log| log.enumeration_index);
+        assert_eq!(diff.len(), 2);
+        assert_eq!(diff[0].key, updated_log.key);
+        assert_eq!(diff[0].value, updated_log.value);
+        assert_eq!(diff[0].l1_batch_number_of_initial_write, L1BatchNumber(1));
+        assert_eq!(diff[1].key, new_log.key);
+        assert_eq!(diff[1].value, new_log.value);
+        assert_eq!(diff[1].l1_batch_number_of_initial_write, L1BatchNumber(2));
+    }
 }
@@ -14,6 +14,7 @@ use crate::Core;
 struct StorageSnapshotMetadata {
     version: i32,
     l1_batch_number: i64,
+    base_l1_batch_number: Option<i64>,
     storage_logs_filepaths: Vec<String>,
     factory_deps_filepath: String,
 }
@@ -28,6 +29,9 @@ impl TryFrom<StorageSnapshotMetadata> for SnapshotMetadata {
         Ok(Self {
             version,
             l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            base_l1_batch_number: row
+                .base_l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
             storage_logs_filepaths: row
                 .storage_logs_filepaths
                 .into_iter()
@@ -44,10 +48,13 @@ pub struct SnapshotsDal<'a, 'c> {
 }
 
 impl SnapshotsDal<'_, '_> {
+    /// Adds a new snapshot. If `base_l1_batch_number` is specified, the snapshot is differential
+    /// relative to the snapshot at this L1 batch.
     pub async fn add_snapshot(
         &mut self,
         version: SnapshotVersion,
         l1_batch_number: L1BatchNumber,
+        base_l1_batch_number: Option<L1BatchNumber>,
         storage_logs_chunk_count: u64,
         factory_deps_filepaths: &str,
     ) -> DalResult<()> {
@@ -57,22 +64,25 @@ impl SnapshotsDal<'_, '_> {
                 snapshots (
                     VERSION,
                     l1_batch_number,
+                    base_l1_batch_number,
                     storage_logs_filepaths,
                     factory_deps_filepath,
                     created_at,
                     updated_at
                 )
             VALUES
-                ($1, $2, ARRAY_FILL(''::TEXT, ARRAY[$3::INTEGER]), $4, NOW(), NOW())
+                ($1, $2, $3, ARRAY_FILL(''::TEXT, ARRAY[$4::INTEGER]), $5, NOW(), NOW())
             "#,
             version as i32,
             l1_batch_number.0 as i32,
+            base_l1_batch_number.map(|number| i64::from(number.0)),
             storage_logs_chunk_count as i32,
             factory_deps_filepaths,
         )
         .instrument("add_snapshot")
         .with_arg("version", &version)
         .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("base_l1_batch_number", &base_l1_batch_number)
         .report_latency()
         .execute(self.storage)
         .await?;
@@ -135,6 +145,36 @@ impl SnapshotsDal<'_, '_> {
         })
     }
 
+    /// Same as [`Self::get_all_complete_snapshots()`], but only returns full (i.e., non-differential) snapshots.
+    pub async fn get_all_complete_full_snapshots(&mut self) -> DalResult<AllSnapshots> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number
+            FROM
+                snapshots
+            WHERE
+                NOT (''::TEXT = ANY (storage_logs_filepaths))
+                AND base_l1_batch_number IS NULL
+            ORDER BY
+                l1_batch_number DESC
+            "#
+        )
+        .instrument("get_all_complete_full_snapshots")
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        let snapshots_l1_batch_numbers = rows
+            .into_iter()
+            .map(|row| L1BatchNumber(row.l1_batch_number as u32))
+            .collect();
+
+        Ok(AllSnapshots {
+            snapshots_l1_batch_numbers,
+        })
+    }
+
     pub async fn get_newest_snapshot_metadata(&mut self) -> DalResult<Option<SnapshotMetadata>> {
         sqlx::query_as!(
             StorageSnapshotMetadata,
@@ -142,6 +182,7 @@ impl SnapshotsDal<'_, '_> {
             SELECT
                 VERSION,
                 l1_batch_number,
+                base_l1_batch_number,
                 factory_deps_filepath,
                 storage_logs_filepaths
             FROM
@@ -169,6 +210,7 @@ impl SnapshotsDal<'_, '_> {
             SELECT
                 VERSION,
                 l1_batch_number,
+                base_l1_batch_number,
                 factory_deps_filepath,
                 storage_logs_filepaths
             FROM
@@ -200,6 +242,7 @@ impl SnapshotsDal<'_, '_> {
             RETURNING
                 VERSION,
                 l1_batch_number,
+                base_l1_batch_number,
                 factory_deps_filepath,
                 storage_logs_filepaths
             "#,
@@ -231,6 +274,7 @@ mod tests {
         dal.add_snapshot(
             SnapshotVersion::Version0,
             l1_batch_number,
+            None,
             2,
             "gs:///bucket/factory_deps.bin",
         )
@@ -265,6 +309,67 @@ mod tests {
             .unwrap()
             .expect("snapshot is not persisted");
         assert_eq!(snapshot_metadata.l1_batch_number, l1_batch_number);
+        assert_eq!(snapshot_metadata.base_l1_batch_number, None);
+    }
+
+    #[tokio::test]
+    async fn adding_differential_snapshot() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.snapshots_dal();
+        dal.add_snapshot(
+            SnapshotVersion::Version0,
+            L1BatchNumber(100),
+            None,
+            2,
+            "gs:///bucket/factory_deps.bin",
+        )
+        .await
+        .unwrap();
+        dal.add_snapshot(
+            SnapshotVersion::Version0,
+            L1BatchNumber(110),
+            Some(L1BatchNumber(100)),
+            2,
+            "gs:///bucket/factory_deps_diff.bin",
+        )
+        .await
+        .unwrap();
+
+        let snapshot_metadata = dal
+            .get_newest_snapshot_metadata()
+            .await
+            .unwrap()
+            .expect("snapshot is not persisted");
+        assert_eq!(snapshot_metadata.l1_batch_number, L1BatchNumber(110));
+        assert_eq!(
+            snapshot_metadata.base_l1_batch_number,
+            Some(L1BatchNumber(100))
+        );
+        assert_eq!(snapshot_metadata.storage_logs_filepaths, [None, None]);
+
+        for l1_batch_number in [L1BatchNumber(100), L1BatchNumber(110)] {
+            for i in 0..2 {
+                dal.add_storage_logs_filepath_for_snapshot(
+                    l1_batch_number,
+                    i,
+                    "gs:///bucket/chunk.bin",
+                )
+                .await
+                .unwrap();
+            }
+        }
+
+        let snapshots = dal.get_all_complete_snapshots().await.unwrap();
+        assert_eq!(
+            snapshots.snapshots_l1_batch_numbers,
+            [L1BatchNumber(110), L1BatchNumber(100)]
+        );
+        let full_snapshots = dal.get_all_complete_full_snapshots().await.unwrap();
+        assert_eq!(
+            full_snapshots.snapshots_l1_batch_numbers,
+            [L1BatchNumber(100)]
+        );
     }
 
     #[tokio::test]
@@ -276,6 +381,7 @@ mod tests {
         dal.add_snapshot(
             SnapshotVersion::Version0,
             l1_batch_number,
+            None,
             2,
             "gs:///bucket/factory_deps.bin",
         )
@@ -339,6 +445,7 @@ mod tests {
         dal.add_snapshot(
             SnapshotVersion::Version0,
             l1_batch_number,
+            None,
             2,
             "gs:///bucket/factory_deps.bin",
         )
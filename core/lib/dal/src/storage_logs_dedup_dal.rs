@@ -172,6 +172,40 @@ impl StorageLogsDedupDal<'_, '_> {
         .map(|max| max as u64))
     }
 
+    /// Returns the maximum enumeration index assigned in a specific L1 batch or earlier L1 batches,
+    /// or `None` if there are no initial writes in these batches.
+    pub async fn max_enumeration_index_by_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<Option<u64>> {
+        // Enumeration indices increase with L1 batch numbers, so it's sufficient to only check the latest L1 batch
+        // with initial writes. This allows to use the index on `l1_batch_number` instead of scanning the entire table.
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                MAX(INDEX) AS "max?"
+            FROM
+                initial_writes
+            WHERE
+                l1_batch_number = (
+                    SELECT
+                        MAX(l1_batch_number)
+                    FROM
+                        initial_writes
+                    WHERE
+                        l1_batch_number <= $1
+                )
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("max_enumeration_index_by_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
+        .await?
+        .max
+        .map(|max| max as u64))
+    }
+
     pub async fn initial_writes_for_batch(
         &mut self,
         l1_batch_number: L1BatchNumber,
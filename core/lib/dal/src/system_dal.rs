@@ -1,8 +1,8 @@
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, future::Future, pin::Pin, time::Duration};
 
 use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
 
-use crate::Core;
+use crate::{Core, CoreDal};
 
 #[derive(Debug)]
 pub(crate) struct TableSize {
@@ -17,7 +17,17 @@ pub struct SystemDal<'a, 'c> {
     pub(crate) storage: &'a mut Connection<'c, Core>,
 }
 
+/// Replication lag check for [`RoutingConnectionPool`](crate::RoutingConnectionPool)s delegating
+/// to [`SystemDal::get_replication_lag()`].
+pub fn check_replication_lag<'a>(
+    connection: &'a mut Connection<'_, Core>,
+) -> Pin<Box<dyn Future<Output = DalResult<Duration>> + Send + 'a>> {
+    Box::pin(async move { connection.system_dal().get_replication_lag().await })
+}
+
 impl SystemDal<'_, '_> {
+    /// Returns the replication lag for the database the connection is established to. For the primary database
+    /// or a fully synced replica, the returned lag is zero.
     pub async fn get_replication_lag(&mut self) -> DalResult<Duration> {
         // NOTE: lag (seconds) has a special meaning here
         // (it is not the same that `replay_lag/write_lag/flush_lag` from `pg_stat_replication` view)
@@ -28,7 +38,7 @@ impl SystemDal<'_, '_> {
             SELECT
                 PG_LAST_WAL_RECEIVE_LSN() = PG_LAST_WAL_REPLAY_LSN() AS synced,
                 EXTRACT(
-                    SECONDS
+                    EPOCH
                     FROM
                         NOW() - PG_LAST_XACT_REPLAY_TIMESTAMP()
                 )::INT AS LAG
@@ -39,7 +49,7 @@ impl SystemDal<'_, '_> {
         .await?;
 
         Ok(match row.synced {
-            Some(false) => Duration::from_secs(row.lag.unwrap_or(0) as u64),
+            Some(false) => Duration::from_secs(row.lag.unwrap_or(0).max(0) as u64),
             _ => Duration::ZERO, // We are synced, no lag
         })
     }
@@ -79,3 +89,19 @@ impl SystemDal<'_, '_> {
         Ok(table_sizes.collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn replication_lag_for_primary_is_zero() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let lag = conn.system_dal().get_replication_lag().await.unwrap();
+        assert_eq!(lag, Duration::ZERO);
+        let lag = check_replication_lag(&mut conn).await.unwrap();
+        assert_eq!(lag, Duration::ZERO);
+    }
+}
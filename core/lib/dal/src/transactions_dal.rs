@@ -977,6 +977,36 @@ impl TransactionsDal<'_, '_> {
         Ok(())
     }
 
+    /// Marks a pending L2 transaction (i.e., one not included into an L2 block yet) as rejected, so that it's not loaded
+    /// into the mempool again. Returns `false` if there is no such transaction.
+    pub async fn reject_pending_l2_transaction(
+        &mut self,
+        transaction_hash: H256,
+        error: &str,
+    ) -> DalResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET
+                error = $1,
+                in_mempool = FALSE,
+                updated_at = NOW()
+            WHERE
+                hash = $2
+                AND miniblock_number IS NULL
+                AND is_priority = FALSE
+                AND error IS NULL
+            "#,
+            error,
+            transaction_hash.as_bytes()
+        )
+        .instrument("reject_pending_l2_transaction")
+        .with_arg("transaction_hash", &transaction_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn reset_transactions_state(
         &mut self,
         l2_block_number: L2BlockNumber,
@@ -1313,6 +1343,9 @@ impl TransactionsDal<'_, '_> {
         transactions: Vec<StorageTransaction>,
         fictive_l2_block: Option<L2BlockNumber>,
     ) -> DalResult<Vec<L2BlockExecutionData>> {
+        for tx in &transactions {
+            tx.ensure_payload_not_pruned("map_transactions_to_execution_data")?;
+        }
         let mut transactions_by_l2_block: Vec<(L2BlockNumber, Vec<Transaction>)> = transactions
             .into_iter()
             .group_by(|tx| tx.miniblock_number.unwrap())
@@ -1474,7 +1507,7 @@ impl TransactionsDal<'_, '_> {
     }
 
     pub(crate) async fn get_tx_by_hash(&mut self, hash: H256) -> DalResult<Option<Transaction>> {
-        sqlx::query_as!(
+        let tx = sqlx::query_as!(
             StorageTransaction,
             r#"
             SELECT
@@ -1486,11 +1519,16 @@ impl TransactionsDal<'_, '_> {
             "#,
             hash.as_bytes()
         )
-        .map(Into::into)
         .instrument("get_tx_by_hash")
         .with_arg("hash", &hash)
         .fetch_optional(self.storage)
-        .await
+        .await?;
+
+        let Some(tx) = tx else {
+            return Ok(None);
+        };
+        tx.ensure_payload_not_pruned("get_tx_by_hash")?;
+        Ok(Some(tx.into()))
     }
 }
 
@@ -1544,4 +1582,37 @@ mod tests {
             .expect("no call trace");
         assert_eq!(call_trace, expected_call_trace);
     }
+
+    #[tokio::test]
+    async fn rejecting_pending_transaction() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+
+        let rejected = conn
+            .transactions_dal()
+            .reject_pending_l2_transaction(tx_hash, "removed")
+            .await
+            .unwrap();
+        assert!(rejected);
+        // Repeated rejection is a no-op.
+        let rejected = conn
+            .transactions_dal()
+            .reject_pending_l2_transaction(tx_hash, "removed")
+            .await
+            .unwrap();
+        assert!(!rejected);
+
+        let synced_txs = conn
+            .transactions_dal()
+            .sync_mempool(&[], &[], 0, 0, 100)
+            .await
+            .unwrap();
+        assert!(synced_txs.is_empty(), "{synced_txs:?}");
+    }
 }
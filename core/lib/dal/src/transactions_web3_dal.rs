@@ -288,6 +288,33 @@ impl TransactionsWeb3Dal<'_, '_> {
         Ok(row.map(Into::into))
     }
 
+    /// Returns the number of the L2 block containing the specified transaction, or `None` if the transaction
+    /// is unknown or is not included into a block yet.
+    pub async fn get_l2_block_number_of_transaction(
+        &mut self,
+        tx_hash: H256,
+    ) -> DalResult<Option<L2BlockNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number
+            FROM
+                transactions
+            WHERE
+                hash = $1
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("get_l2_block_number_of_transaction")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row
+            .and_then(|row| row.miniblock_number)
+            .map(|number| L2BlockNumber(number as u32)))
+    }
+
     /// Returns hashes of txs which were received after `from_timestamp` and the time of receiving the last tx.
     pub async fn get_pending_txs_hashes_after(
         &mut self,
@@ -403,6 +430,9 @@ impl TransactionsWeb3Dal<'_, '_> {
         .fetch_all(self.storage)
         .await?;
 
+        for row in &rows {
+            row.ensure_payload_not_pruned("get_raw_l2_block_transactions")?;
+        }
         Ok(rows.into_iter().map(Into::into).collect())
     }
 }
@@ -0,0 +1,182 @@
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::L1BatchNumber;
+
+use crate::Core;
+
+/// Progress of a VM runner instance.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VmRunnerProgress {
+    /// Last L1 batch fully processed by the runner, i.e., one for which all output handlers have finished.
+    pub last_processed_l1_batch: Option<L1BatchNumber>,
+    /// Last L1 batch that was observed to be ready for processing by the runner.
+    pub last_ready_l1_batch: Option<L1BatchNumber>,
+}
+
+/// DAL for progress of VM runner instances.
+#[derive(Debug)]
+pub struct VmRunnerDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl VmRunnerDal<'_, '_> {
+    /// Returns progress of the specified VM runner. If the runner has not persisted any progress yet,
+    /// returns the default value.
+    pub async fn get_progress(&mut self, runner_name: &str) -> DalResult<VmRunnerProgress> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                last_processed_l1_batch,
+                last_ready_l1_batch
+            FROM
+                vm_runner_progress
+            WHERE
+                runner_name = $1
+            "#,
+            runner_name
+        )
+        .instrument("get_vm_runner_progress")
+        .with_arg("runner_name", &runner_name)
+        .fetch_optional(self.storage)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(VmRunnerProgress::default());
+        };
+        Ok(VmRunnerProgress {
+            last_processed_l1_batch: row
+                .last_processed_l1_batch
+                .map(|number| L1BatchNumber(number as u32)),
+            last_ready_l1_batch: row
+                .last_ready_l1_batch
+                .map(|number| L1BatchNumber(number as u32)),
+        })
+    }
+
+    /// Returns the first L1 batch not processed by at least one VM runner that has persisted its progress, or `None`
+    /// if no VM runner has persisted progress. VM runners may re-execute L1 batches starting from this batch,
+    /// so data required for re-execution must be retained for it.
+    pub async fn get_first_unprocessed_l1_batch(&mut self) -> DalResult<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MIN(COALESCE(last_processed_l1_batch + 1, 0)) AS "number"
+            FROM
+                vm_runner_progress
+            "#
+        )
+        .instrument("get_first_l1_batch_unprocessed_by_vm_runners")
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.number.map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Marks the specified L1 batch as processed by the VM runner.
+    pub async fn mark_l1_batch_as_processed(
+        &mut self,
+        runner_name: &str,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                vm_runner_progress (runner_name, last_processed_l1_batch, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (runner_name) DO
+            UPDATE
+            SET
+                last_processed_l1_batch = $2,
+                updated_at = NOW()
+            "#,
+            runner_name,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("mark_vm_runner_l1_batch_as_processed")
+        .with_arg("runner_name", &runner_name)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Sets the last L1 batch ready to be processed by the VM runner.
+    pub async fn set_last_ready_l1_batch(
+        &mut self,
+        runner_name: &str,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                vm_runner_progress (runner_name, last_ready_l1_batch, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (runner_name) DO
+            UPDATE
+            SET
+                last_ready_l1_batch = $2,
+                updated_at = NOW()
+            "#,
+            runner_name,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("set_vm_runner_last_ready_l1_batch")
+        .with_arg("runner_name", &runner_name)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, Core, CoreDal};
+
+    #[tokio::test]
+    async fn manipulating_vm_runner_progress() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.vm_runner_dal();
+
+        let progress = dal.get_progress("test").await.unwrap();
+        assert_eq!(progress, VmRunnerProgress::default());
+        assert_eq!(dal.get_first_unprocessed_l1_batch().await.unwrap(), None);
+
+        dal.set_last_ready_l1_batch("test", L1BatchNumber(3))
+            .await
+            .unwrap();
+        let progress = dal.get_progress("test").await.unwrap();
+        assert_eq!(progress.last_processed_l1_batch, None);
+        assert_eq!(progress.last_ready_l1_batch, Some(L1BatchNumber(3)));
+        // A runner that hasn't processed any batches may need to re-execute any batch.
+        assert_eq!(
+            dal.get_first_unprocessed_l1_batch().await.unwrap(),
+            Some(L1BatchNumber(0))
+        );
+
+        dal.mark_l1_batch_as_processed("test", L1BatchNumber(1))
+            .await
+            .unwrap();
+        dal.mark_l1_batch_as_processed("other", L1BatchNumber(5))
+            .await
+            .unwrap();
+        let progress = dal.get_progress("test").await.unwrap();
+        assert_eq!(
+            progress,
+            VmRunnerProgress {
+                last_processed_l1_batch: Some(L1BatchNumber(1)),
+                last_ready_l1_batch: Some(L1BatchNumber(3)),
+            }
+        );
+        let progress = dal.get_progress("other").await.unwrap();
+        assert_eq!(progress.last_processed_l1_batch, Some(L1BatchNumber(5)));
+        assert_eq!(progress.last_ready_l1_batch, None);
+        assert_eq!(
+            dal.get_first_unprocessed_l1_batch().await.unwrap(),
+            Some(L1BatchNumber(2))
+        );
+    }
+}
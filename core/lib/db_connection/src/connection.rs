@@ -10,7 +10,8 @@ use std::{
 };
 
 use sqlx::{
-    pool::PoolConnection, types::chrono, Connection as _, PgConnection, Postgres, Transaction,
+    pool::PoolConnection, types::chrono, Connection as _, PgConnection, PgPool, Postgres,
+    Transaction,
 };
 
 use crate::{
@@ -155,6 +156,8 @@ pub trait DbMarker: 'static + Send + Sync + Clone {}
 #[derive(Debug)]
 pub struct Connection<'a, DB: DbMarker> {
     inner: ConnectionInner<'a>,
+    /// Pool this connection was acquired from. Used to acquire auxiliary connections (e.g., for query plan capture).
+    pool: &'a PgPool,
     _marker: std::marker::PhantomData<DB>,
 }
 
@@ -163,6 +166,7 @@ impl<'a, DB: DbMarker> Connection<'a, DB> {
     /// This method borrows one of the connections from the pool, and releases it
     /// after `drop`.
     pub(crate) fn from_pool(
+        pool: &'a PgPool,
         connection: PoolConnection<Postgres>,
         tags: Option<ConnectionTags>,
         traced_connections: Option<&'a TracedConnections>,
@@ -179,11 +183,13 @@ impl<'a, DB: DbMarker> Connection<'a, DB> {
         });
         Self {
             inner,
+            pool,
             _marker: Default::default(),
         }
     }
 
     pub async fn start_transaction(&mut self) -> DalResult<Connection<'_, DB>> {
+        let pool = self.pool;
         let (conn, tags) = self.conn_and_tags();
         let inner = ConnectionInner::Transaction {
             transaction: conn
@@ -194,6 +200,7 @@ impl<'a, DB: DbMarker> Connection<'a, DB> {
         };
         Ok(Connection {
             inner,
+            pool,
             _marker: Default::default(),
         })
     }
@@ -222,6 +229,10 @@ impl<'a, DB: DbMarker> Connection<'a, DB> {
         self.conn_and_tags().0
     }
 
+    pub(crate) fn pool(&self) -> &PgPool {
+        self.pool
+    }
+
     pub fn conn_and_tags(&mut self) -> (&mut PgConnection, Option<&ConnectionTags>) {
         match &mut self.inner {
             ConnectionInner::Pooled(pooled) => (&mut pooled.connection, pooled.tags.as_ref()),
@@ -22,6 +22,7 @@ use crate::{
     connection::{Connection, ConnectionTags, DbMarker, TracedConnections},
     error::{DalConnectionError, DalResult},
     metrics::CONNECTION_METRICS,
+    query_plans,
 };
 
 /// Builder for [`ConnectionPool`]s.
@@ -77,6 +78,12 @@ impl<DB: DbMarker> ConnectionPoolBuilder<DB> {
         self.max_size
     }
 
+    /// Returns database URL for the pool. It may include authentication info, so be mindful of
+    /// outputting it into logs etc.
+    pub fn database_url(&self) -> &SensitiveUrl {
+        &self.database_url
+    }
+
     /// Builds a connection pool from this builder.
     pub async fn build(&self) -> anyhow::Result<ConnectionPool<DB>> {
         let options = PgPoolOptions::new()
@@ -209,6 +216,8 @@ pub struct GlobalConnectionPoolConfig {
     // We consider millisecond precision to be enough for config purposes.
     long_connection_threshold_ms: AtomicU64,
     slow_query_threshold_ms: AtomicU64,
+    /// Bit representation of an `f64` sample rate; 0 means that plan capture is disabled.
+    slow_query_plan_sample_rate_bits: AtomicU64,
 }
 
 impl GlobalConnectionPoolConfig {
@@ -216,6 +225,7 @@ impl GlobalConnectionPoolConfig {
         Self {
             long_connection_threshold_ms: AtomicU64::new(5_000), // 5 seconds
             slow_query_threshold_ms: AtomicU64::new(100),        // 0.1 seconds
+            slow_query_plan_sample_rate_bits: AtomicU64::new(0), // `0.0_f64.to_bits() == 0`
         }
     }
 
@@ -227,6 +237,13 @@ impl GlobalConnectionPoolConfig {
         Duration::from_millis(self.slow_query_threshold_ms.load(Ordering::Relaxed))
     }
 
+    pub(crate) fn slow_query_plan_sample_rate(&self) -> f64 {
+        f64::from_bits(
+            self.slow_query_plan_sample_rate_bits
+                .load(Ordering::Relaxed),
+        )
+    }
+
     /// Sets the threshold for the DB connection lifetime to denote a connection as long-living and log its details.
     pub fn set_long_connection_threshold(&self, threshold: Duration) -> anyhow::Result<&Self> {
         let millis = u64::try_from(threshold.as_millis())
@@ -246,6 +263,27 @@ impl GlobalConnectionPoolConfig {
         tracing::info!("Set slow query threshold to {threshold:?}");
         Ok(self)
     }
+
+    /// Sets the sample rate for capturing query plans of slow queries. The rate must be in `[0, 1]`;
+    /// 0 (the default) disables plan capture. See [`query_plans`](crate::query_plans) for details.
+    pub fn set_slow_query_plan_sample_rate(&self, sample_rate: f64) -> anyhow::Result<&Self> {
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&sample_rate),
+            "slow_query_plan_sample_rate must be in [0, 1], got {sample_rate}"
+        );
+        self.slow_query_plan_sample_rate_bits
+            .store(sample_rate.to_bits(), Ordering::Relaxed);
+        tracing::info!("Set slow query plan sample rate to {sample_rate}");
+        Ok(self)
+    }
+
+    /// Sets the maximum number of captured query plans stored in memory. If there are more plans,
+    /// the oldest ones are discarded.
+    pub fn set_slow_query_plan_capacity(&self, capacity: usize) -> &Self {
+        query_plans::set_captured_plans_capacity(capacity);
+        tracing::info!("Set slow query plan capacity to {capacity}");
+        self
+    }
 }
 
 /// Pool of reusable database connections.
@@ -372,7 +410,7 @@ impl<DB: DbMarker> ConnectionPool<DB> {
         }
     }
 
-    async fn connection_inner(
+    pub(crate) async fn connection_inner(
         &self,
         tags: Option<ConnectionTags>,
     ) -> DalResult<Connection<'_, DB>> {
@@ -384,6 +422,36 @@ impl<DB: DbMarker> ConnectionPool<DB> {
         }
 
         Ok(Connection::<DB>::from_pool(
+            &self.inner,
+            conn,
+            tags,
+            self.traced_connections.as_deref(),
+        ))
+    }
+
+    /// Same as [`Self::connection_inner()`], but makes a single attempt to acquire a connection, which is bounded
+    /// by the specified `timeout` rather than by the pool acquire timeout. Used for connections that have a fallback
+    /// (e.g., read replicas), so that an overloaded or unavailable database does not stall callers.
+    pub(crate) async fn try_connection(
+        &self,
+        tags: Option<ConnectionTags>,
+        timeout: Duration,
+    ) -> DalResult<Connection<'_, DB>> {
+        let acquire_latency = CONNECTION_METRICS.acquire.start();
+        let conn = tokio::time::timeout(timeout, self.inner.acquire())
+            .await
+            .unwrap_or(Err(sqlx::Error::PoolTimedOut))
+            .map_err(|err| {
+                Self::report_connection_error(&err);
+                DalConnectionError::acquire_connection(err, tags)
+            })?;
+        let elapsed = acquire_latency.observe();
+        if let Some(tags) = &tags {
+            CONNECTION_METRICS.acquire_tagged[&tags.requester].observe(elapsed);
+        }
+
+        Ok(Connection::<DB>::from_pool(
+            &self.inner,
             conn,
             tags,
             self.traced_connections.as_deref(),
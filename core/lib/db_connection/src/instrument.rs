@@ -5,6 +5,7 @@
 //! - Report query latency as a metric
 //! - Report slow and failing queries as metrics
 //! - Log slow and failing queries together with their arguments, which makes it easier to debug.
+//! - Capture query plans for slow queries (opt-in; see [`query_plans`](crate::query_plans) for details).
 //!
 //! The entry point for instrumentation is the [`InstrumentExt`] trait. After it is imported into the scope,
 //! its `instrument()` method can be placed on the output of `query*` functions or macros. You can then call
@@ -25,6 +26,7 @@ use crate::{
     connection_pool::ConnectionPool,
     error::{DalError, DalRequestError, DalResult},
     metrics::REQUEST_METRICS,
+    query_plans::{self, PendingPlanCapture, SqlLiteral},
     utils::InternalMarker,
 };
 
@@ -185,6 +187,7 @@ struct InstrumentedData<'a> {
     name: &'static str,
     location: &'static Location<'static>,
     args: QueryArgs<'a>,
+    bound_args: Option<&'a [&'a dyn SqlLiteral]>,
     report_latency: bool,
     slow_query_reporting_enabled: bool,
 }
@@ -195,6 +198,7 @@ impl<'a> InstrumentedData<'a> {
             name,
             location,
             args: QueryArgs::default(),
+            bound_args: None,
             report_latency: false,
             slow_query_reporting_enabled: true,
         }
@@ -203,12 +207,14 @@ impl<'a> InstrumentedData<'a> {
     async fn fetch<R>(
         self,
         connection_tags: Option<&ConnectionTags>,
+        sql: Option<&str>,
         query_future: impl Future<Output = Result<R, sqlx::Error>>,
-    ) -> DalResult<R> {
+    ) -> FetchOutput<R> {
         let Self {
             name,
             location,
             args,
+            bound_args,
             report_latency,
             slow_query_reporting_enabled,
         } = self;
@@ -258,12 +264,44 @@ impl<'a> InstrumentedData<'a> {
             );
         }
 
-        output.map_err(|err| {
+        let plan_capture = sql
+            .filter(|_| is_slow && output.is_ok() && query_plans::should_capture())
+            .map(|sql| PendingPlanCapture {
+                name,
+                location,
+                args: args.to_owned(),
+                bound_args: bound_args
+                    .map(|args| args.iter().map(|arg| arg.to_sql_literal()).collect()),
+                sql: sql.to_owned(),
+                elapsed,
+            });
+        let output = output.map_err(|err| {
             DalRequestError::new(err, name, location)
                 .with_args(args.to_owned())
                 .with_connection_tags(connection_tags.cloned())
                 .into()
-        })
+        });
+        FetchOutput {
+            output,
+            plan_capture,
+        }
+    }
+}
+
+/// Output of an instrumented query together with an optional query plan capture.
+#[derive(Debug)]
+struct FetchOutput<R> {
+    output: DalResult<R>,
+    plan_capture: Option<PendingPlanCapture>,
+}
+
+impl<R> FetchOutput<R> {
+    /// Spawns query plan capture if necessary and returns the query output.
+    fn capture_plan<DB: DbMarker>(self, storage: &Connection<'_, DB>) -> DalResult<R> {
+        if let Some(plan_capture) = self.plan_capture {
+            plan_capture.spawn(storage.pool().clone());
+        }
+        self.output
     }
 }
 
@@ -349,11 +387,19 @@ impl<'a, Q> Instrumented<'a, Q> {
         self.data.args.inner.push((name, value));
         self
     }
+
+    /// Records values bound to the query parameters (`$1`, `$2`, ...), in the order of parameters. If a query plan
+    /// is captured for the query, these values are substituted into the query as literals, so that the plan
+    /// can be analyzed with actual execution stats. See [`query_plans`](crate::query_plans) for details.
+    pub fn with_bound_args(mut self, args: &'a [&'a dyn SqlLiteral]) -> Self {
+        self.data.bound_args = Some(args);
+        self
+    }
 }
 
 impl<'q, A> Instrumented<'_, Query<'q, Postgres, A>>
 where
-    A: 'q + IntoArguments<'q, Postgres>,
+    A: 'q + Send + IntoArguments<'q, Postgres>,
 {
     /// Executes an SQL statement using this query.
     pub async fn execute<DB: DbMarker>(
@@ -361,7 +407,12 @@ where
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<PgQueryResult> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.execute(conn)).await
+        let sql = query_plans::sql_for_capture(&self.query);
+        let output = self
+            .data
+            .fetch(tags, sql, self.query.execute(&mut *conn))
+            .await;
+        output.capture_plan(storage)
     }
 
     /// Fetches an optional row using this query.
@@ -370,13 +421,18 @@ where
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Option<PgRow>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        let sql = query_plans::sql_for_capture(&self.query);
+        let output = self
+            .data
+            .fetch(tags, sql, self.query.fetch_optional(&mut *conn))
+            .await;
+        output.capture_plan(storage)
     }
 }
 
 impl<'q, O, A> Instrumented<'_, QueryAs<'q, Postgres, O, A>>
 where
-    A: 'q + IntoArguments<'q, Postgres>,
+    A: 'q + Send + IntoArguments<'q, Postgres>,
     O: Send + Unpin + for<'r> FromRow<'r, PgRow>,
 {
     /// Fetches all rows using this query and collects them into a `Vec`.
@@ -385,13 +441,18 @@ where
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Vec<O>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_all(conn)).await
+        let sql = query_plans::sql_for_capture(&self.query);
+        let output = self
+            .data
+            .fetch(tags, sql, self.query.fetch_all(&mut *conn))
+            .await;
+        output.capture_plan(storage)
     }
 }
 
 impl<'q, O, A> Instrumented<'_, QueryScalar<'q, Postgres, O, A>>
 where
-    A: 'q + IntoArguments<'q, Postgres>,
+    A: 'q + Send + IntoArguments<'q, Postgres>,
     O: Send + Unpin,
     (O,): for<'r> FromRow<'r, PgRow>,
 {
@@ -401,13 +462,23 @@ where
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Option<O>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        let sql = query_plans::sql_for_capture(&self.query);
+        let output = self
+            .data
+            .fetch(tags, sql, self.query.fetch_optional(&mut *conn))
+            .await;
+        output.capture_plan(storage)
     }
 
     /// Fetches a single row using this query.
     pub async fn fetch_one<DB: DbMarker>(self, storage: &mut Connection<'_, DB>) -> DalResult<O> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_one(conn)).await
+        let sql = query_plans::sql_for_capture(&self.query);
+        let output = self
+            .data
+            .fetch(tags, sql, self.query.fetch_one(&mut *conn))
+            .await;
+        output.capture_plan(storage)
     }
 }
 
@@ -423,13 +494,23 @@ where
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Option<O>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        let sql = query_plans::sql_for_capture(&self.query);
+        let output = self
+            .data
+            .fetch(tags, sql, self.query.fetch_optional(&mut *conn))
+            .await;
+        output.capture_plan(storage)
     }
 
     /// Fetches a single row using this query.
     pub async fn fetch_one<DB: DbMarker>(self, storage: &mut Connection<'_, DB>) -> DalResult<O> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_one(conn)).await
+        let sql = query_plans::sql_for_capture(&self.query);
+        let output = self
+            .data
+            .fetch(tags, sql, self.query.fetch_one(&mut *conn))
+            .await;
+        output.capture_plan(storage)
     }
 
     /// Fetches all rows using this query and collects them into a `Vec`.
@@ -438,7 +519,12 @@ where
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Vec<O>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_all(conn)).await
+        let sql = query_plans::sql_for_capture(&self.query);
+        let output = self
+            .data
+            .fetch(tags, sql, self.query.fetch_all(&mut *conn))
+            .await;
+        output.capture_plan(storage)
     }
 }
 
@@ -488,6 +574,73 @@ mod tests {
             .unwrap_err();
     }
 
+    async fn wait_for_plan(name: &str) -> query_plans::CapturedQueryPlan {
+        loop {
+            let plans = query_plans::captured_query_plans();
+            if let Some(plan) = plans.into_iter().find(|plan| plan.name == name) {
+                return plan;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn capturing_plans_for_slow_queries() {
+        let pool = ConnectionPool::<InternalMarker>::test_pool().await;
+        ConnectionPool::<InternalMarker>::global_config()
+            .set_slow_query_plan_sample_rate(1.0)
+            .unwrap();
+
+        let mut conn = pool.connection().await.unwrap();
+        sqlx::query("SELECT pg_sleep(0.2)")
+            .map(drop)
+            .instrument("slow_with_plan")
+            .fetch_optional(&mut conn)
+            .await
+            .unwrap();
+        let plan = wait_for_plan("slow_with_plan").await;
+        assert_eq!(plan.kind, query_plans::QueryPlanKind::Analyzed);
+        let plan_text = plan.plan.as_ref().unwrap();
+        assert!(plan_text.contains("Execution Time"), "{plan:?}");
+
+        sqlx::query("SELECT pg_sleep($1)")
+            .bind(0.2_f64)
+            .map(drop)
+            .instrument("slow_with_bound_args")
+            .with_bound_args(&[&0.2_f64])
+            .fetch_optional(&mut conn)
+            .await
+            .unwrap();
+        let plan = wait_for_plan("slow_with_bound_args").await;
+        assert_eq!(plan.kind, query_plans::QueryPlanKind::Analyzed);
+        assert_eq!(plan.bound_args.as_deref().unwrap(), ["0.2"]);
+        let plan_text = plan.plan.as_ref().unwrap();
+        assert!(plan_text.contains("Execution Time"), "{plan:?}");
+
+        sqlx::query("SELECT pg_sleep($1)")
+            .bind(0.2_f64)
+            .map(drop)
+            .instrument("slow_with_generic_plan")
+            .with_arg("duration", &0.2_f64)
+            .fetch_optional(&mut conn)
+            .await
+            .unwrap();
+        let plan = wait_for_plan("slow_with_generic_plan").await;
+        assert_eq!(plan.kind, query_plans::QueryPlanKind::Generic);
+        assert!(plan.plan.is_some(), "{plan:?}");
+        assert_eq!(plan.args["duration"], "0.2");
+
+        // The connection should remain usable after capturing plans.
+        sqlx::query("SELECT 1")
+            .map(drop)
+            .instrument("fast")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        let plans = query_plans::captured_query_plans();
+        assert!(!plans.iter().any(|plan| plan.name == "fast"));
+    }
+
     #[tokio::test]
     async fn instrumenting_slow_query() {
         let pool = ConnectionPool::<InternalMarker>::test_pool().await;
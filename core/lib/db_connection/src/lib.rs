@@ -6,6 +6,8 @@ pub mod error;
 pub mod healthcheck;
 pub mod instrument;
 pub mod metrics;
+pub mod query_plans;
+pub mod routing_pool;
 #[macro_use]
 pub mod macro_utils;
 pub mod utils;
@@ -1,7 +1,7 @@
 use std::{thread, time::Duration};
 
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, LabeledFamily,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
     LatencyObserver, Metrics, Unit,
 };
 
@@ -18,6 +18,16 @@ pub(crate) struct RequestMetrics {
     /// Counter of errored DB requests.
     #[metrics(labels = ["method"])]
     pub request_error: LabeledFamily<&'static str, Counter>,
+    /// Counter of query plans captured for slow DB requests.
+    #[metrics(labels = ["method"])]
+    pub request_plan_captured: LabeledFamily<&'static str, Counter>,
+    /// Counter of failed attempts to capture query plans for slow DB requests.
+    #[metrics(labels = ["method"])]
+    pub request_plan_capture_failed: LabeledFamily<&'static str, Counter>,
+    /// Counter of query plan captures for slow DB requests skipped because another plan was being captured,
+    /// or there were no idle connections.
+    #[metrics(labels = ["method"])]
+    pub request_plan_capture_skipped: LabeledFamily<&'static str, Counter>,
 }
 
 #[vise::register]
@@ -94,3 +104,36 @@ pub(crate) struct ConnectionMetrics {
 
 #[vise::register]
 pub(crate) static CONNECTION_METRICS: vise::Global<ConnectionMetrics> = vise::Global::new();
+
+/// Reason for routing a read-only connection request to the primary database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(crate) enum PrimaryFallbackReason {
+    /// No replica is healthy or has an acceptable replication lag.
+    NoHealthyReplicas,
+    /// The selected replica has failed to provide a connection.
+    ReplicaError,
+}
+
+/// Metrics for read replicas used in [`RoutingConnectionPool`](crate::routing_pool::RoutingConnectionPool).
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "sql_replica")]
+pub(crate) struct ReplicaMetrics {
+    /// Last observed replication lag of a replica.
+    #[metrics(labels = ["replica"])]
+    pub lag: LabeledFamily<String, Gauge<Duration>>,
+    /// Whether a replica is eligible for serving read-only connections (1) or not (0).
+    #[metrics(labels = ["replica"])]
+    pub is_healthy: LabeledFamily<String, Gauge<u64>>,
+    /// Number of read-only connections served by a replica.
+    #[metrics(labels = ["replica"])]
+    pub read_connections: LabeledFamily<String, Counter>,
+    /// Number of errors acquiring a connection from a replica or checking its replication lag.
+    #[metrics(labels = ["replica"])]
+    pub errors: LabeledFamily<String, Counter>,
+    /// Number of read-only connection requests routed to the primary database.
+    pub primary_fallbacks: Family<PrimaryFallbackReason, Counter>,
+}
+
+#[vise::register]
+pub(crate) static REPLICA_METRICS: vise::Global<ReplicaMetrics> = vise::Global::new();
@@ -0,0 +1,534 @@
+//! Capturing query plans for slow DAL queries.
+//!
+//! Plan capture is opt-in; it is enabled by setting a positive sample rate using
+//! [`GlobalConnectionPoolConfig::set_slow_query_plan_sample_rate()`](crate::connection_pool::GlobalConnectionPoolConfig::set_slow_query_plan_sample_rate()).
+//! If enabled, each [instrumented](crate::instrument) query that executes for longer than the slow query threshold
+//! is sampled with the configured rate. For sampled queries, the query plan is obtained using `EXPLAIN (ANALYZE, BUFFERS)`
+//! and is stored in a bounded in-memory ring that can be [inspected](captured_query_plans()) e.g. by a debug HTTP endpoint.
+//! The ring capacity can be configured using
+//! [`GlobalConnectionPoolConfig::set_slow_query_plan_capacity()`](crate::connection_pool::GlobalConnectionPoolConfig::set_slow_query_plan_capacity()).
+//!
+//! # Plan kinds
+//!
+//! `sqlx` query arguments are consumed when the query is executed, so they cannot be bound to an `EXPLAIN` statement
+//! afterwards. Instead, bound args can be recorded as SQL literals using
+//! [`Instrumented::with_bound_args()`](crate::instrument::Instrumented::with_bound_args()). Hence, the plan kind
+//! depends on the query:
+//!
+//! - For queries without parameters, or with all bound args recorded, the query (with parameters substituted
+//!   by the recorded literals) is executed using `EXPLAIN (ANALYZE, BUFFERS)`. Such plans contain actual execution stats.
+//! - For other queries, a generic plan (i.e., one not depending on the parameter values) is captured
+//!   by preparing the query and running `EXPLAIN EXECUTE` with `plan_cache_mode = force_generic_plan`.
+//!   Such plans only contain estimates.
+//!
+//! # Isolation
+//!
+//! Plans are captured in a background task on a separate connection from the same pool, so that plan capture
+//! doesn't add latency to the original query. Plan capture waits for a connection for a short time only, and is skipped
+//! if a connection isn't acquired in time. At most one plan is captured at a time; slow queries sampled while another plan
+//! is being captured are skipped as well. Each capture runs in a transaction that is always rolled back, so queries
+//! modifying data are safe to analyze. The statement timeout for the capture is bounded based on the original
+//! query latency.
+//!
+//! As a consequence, the analyzed query doesn't see uncommitted changes made by the transaction
+//! that has executed the original query, and may wait for locks taken by this transaction.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    panic::Location,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, SystemTime},
+};
+
+use serde::Serialize;
+use sqlx::{Connection as _, PgConnection, PgPool};
+
+use crate::{connection_pool::ConnectionPool, metrics::REQUEST_METRICS, utils::InternalMarker};
+
+/// Kind of a captured query plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryPlanKind {
+    /// Plan obtained using `EXPLAIN (ANALYZE, BUFFERS)`. Contains actual execution stats.
+    Analyzed,
+    /// Generic plan for a parameterized query without recorded bound args. Contains only estimates.
+    Generic,
+}
+
+/// Query plan captured for a slow query.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturedQueryPlan {
+    /// Query name specified during instrumentation.
+    pub name: &'static str,
+    /// Location of the query in code, in the `file:line` format.
+    pub location: String,
+    /// Query args logged during instrumentation.
+    pub args: BTreeMap<&'static str, String>,
+    /// SQL literals substituted for the query parameters, if they were recorded during instrumentation.
+    pub bound_args: Option<Vec<String>>,
+    /// SQL text of the query.
+    pub sql: String,
+    /// Time it took to execute the query, in milliseconds.
+    pub elapsed_ms: u64,
+    /// UNIX timestamp (in seconds) when the plan was captured.
+    pub captured_at: u64,
+    pub kind: QueryPlanKind,
+    /// Query plan in the text format. `None` if capturing the plan has failed.
+    pub plan: Option<String>,
+    /// Error capturing the plan, if any.
+    pub error: Option<String>,
+}
+
+/// Value that can be rendered as an SQL literal. Used to record bound query args for plan capture;
+/// see [`Instrumented::with_bound_args()`](crate::instrument::Instrumented::with_bound_args()).
+pub trait SqlLiteral: fmt::Debug + Send + Sync {
+    /// Renders this value as an SQL literal.
+    fn to_sql_literal(&self) -> String;
+}
+
+macro_rules! impl_sql_literal_for_numbers {
+    ($($ty:ty),+) => {
+        $(
+        impl SqlLiteral for $ty {
+            fn to_sql_literal(&self) -> String {
+                self.to_string()
+            }
+        }
+        )+
+    };
+}
+
+impl_sql_literal_for_numbers!(i16, i32, i64, f64);
+
+impl SqlLiteral for bool {
+    fn to_sql_literal(&self) -> String {
+        if *self { "TRUE" } else { "FALSE" }.to_owned()
+    }
+}
+
+impl SqlLiteral for str {
+    fn to_sql_literal(&self) -> String {
+        format!("'{}'", self.replace('\'', "''"))
+    }
+}
+
+impl SqlLiteral for String {
+    fn to_sql_literal(&self) -> String {
+        self.as_str().to_sql_literal()
+    }
+}
+
+impl SqlLiteral for [u8] {
+    fn to_sql_literal(&self) -> String {
+        let hex: String = self.iter().map(|byte| format!("{byte:02x}")).collect();
+        format!("'\\x{hex}'::BYTEA")
+    }
+}
+
+impl SqlLiteral for Vec<u8> {
+    fn to_sql_literal(&self) -> String {
+        self.as_slice().to_sql_literal()
+    }
+}
+
+/// Renders array literals. Implemented for concrete element types to not conflict with the bytes implementation.
+macro_rules! impl_sql_literal_for_arrays {
+    ($($ty:ty),+) => {
+        $(
+        impl SqlLiteral for [$ty] {
+            fn to_sql_literal(&self) -> String {
+                if self.is_empty() {
+                    // The element type is inferred from the context.
+                    return "'{}'".to_owned();
+                }
+                let items: Vec<_> = self.iter().map(SqlLiteral::to_sql_literal).collect();
+                format!("ARRAY[{}]", items.join(", "))
+            }
+        }
+
+        impl SqlLiteral for Vec<$ty> {
+            fn to_sql_literal(&self) -> String {
+                self.as_slice().to_sql_literal()
+            }
+        }
+        )+
+    };
+}
+
+impl_sql_literal_for_arrays!(i16, i32, i64, f64, bool, String, Vec<u8>, &[u8]);
+
+impl<T: SqlLiteral> SqlLiteral for Option<T> {
+    fn to_sql_literal(&self) -> String {
+        self.as_ref()
+            .map_or_else(|| "NULL".to_owned(), SqlLiteral::to_sql_literal)
+    }
+}
+
+impl<T: SqlLiteral + ?Sized> SqlLiteral for &T {
+    fn to_sql_literal(&self) -> String {
+        (**self).to_sql_literal()
+    }
+}
+
+/// Bounded ring of captured query plans.
+#[derive(Debug)]
+struct CapturedPlans {
+    capacity: usize,
+    plans: VecDeque<CapturedQueryPlan>,
+}
+
+impl CapturedPlans {
+    const DEFAULT_CAPACITY: usize = 64;
+
+    const fn new() -> Self {
+        Self {
+            capacity: Self::DEFAULT_CAPACITY,
+            plans: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, plan: CapturedQueryPlan) {
+        while self.plans.len() >= self.capacity {
+            self.plans.pop_front();
+        }
+        if self.capacity > 0 {
+            self.plans.push_back(plan);
+        }
+    }
+}
+
+static CAPTURED_PLANS: Mutex<CapturedPlans> = Mutex::new(CapturedPlans::new());
+/// Set while a plan is being captured.
+static IS_CAPTURING: AtomicBool = AtomicBool::new(false);
+
+/// Returns all currently stored query plans, from the most recent to the oldest one.
+pub fn captured_query_plans() -> Vec<CapturedQueryPlan> {
+    let plans = CAPTURED_PLANS.lock().expect("captured plans are poisoned");
+    plans.plans.iter().rev().cloned().collect()
+}
+
+pub(crate) fn set_captured_plans_capacity(capacity: usize) {
+    let mut plans = CAPTURED_PLANS.lock().expect("captured plans are poisoned");
+    plans.capacity = capacity;
+    while plans.plans.len() > capacity {
+        plans.plans.pop_front();
+    }
+}
+
+/// Returns the SQL text of a query if plan capture is enabled.
+pub(crate) fn sql_for_capture<'q>(
+    query: &impl sqlx::Execute<'q, sqlx::Postgres>,
+) -> Option<&'q str> {
+    let is_enabled =
+        ConnectionPool::<InternalMarker>::global_config().slow_query_plan_sample_rate() > 0.0;
+    is_enabled.then(|| query.sql())
+}
+
+/// Decides whether to capture a plan for a slow query.
+pub(crate) fn should_capture() -> bool {
+    let sample_rate =
+        ConnectionPool::<InternalMarker>::global_config().slow_query_plan_sample_rate();
+    sample_rate > 0.0 && rand::random::<f64>() < sample_rate
+}
+
+/// Iterates over bind parameters (`$1`, `$2`, ...) in the provided SQL text, returning their byte ranges
+/// and 1-based indices. Parameters inside string literals and quoted identifiers are ignored.
+fn params(sql: &str) -> impl Iterator<Item = (std::ops::Range<usize>, usize)> + '_ {
+    let mut quote = None;
+    sql.char_indices().filter_map(move |(pos, ch)| {
+        match (quote, ch) {
+            (Some(q), _) if ch == q => quote = None,
+            (Some(_), _) => { /* inside a literal or quoted identifier */ }
+            (None, '\'' | '"') => quote = Some(ch),
+            (None, '$') => {
+                let digits_len = sql[pos + 1..]
+                    .find(|ch: char| !ch.is_ascii_digit())
+                    .unwrap_or(sql.len() - pos - 1);
+                let range = pos..pos + 1 + digits_len;
+                if let Ok(param) = sql[pos + 1..range.end].parse::<usize>() {
+                    return Some((range, param));
+                }
+            }
+            _ => { /* ordinary char */ }
+        }
+        None
+    })
+}
+
+/// Returns the number of bind parameters in the provided SQL text.
+fn param_count(sql: &str) -> usize {
+    params(sql).map(|(_, param)| param).max().unwrap_or(0)
+}
+
+/// Substitutes bind parameters in the provided SQL text with the specified literals. Returns `None`
+/// if there are not enough literals.
+fn substitute_params(sql: &str, literals: &[String]) -> Option<String> {
+    let mut substituted = String::with_capacity(sql.len());
+    let mut last_end = 0;
+    for (range, param) in params(sql) {
+        let literal = literals.get(param.checked_sub(1)?)?;
+        substituted.push_str(&sql[last_end..range.start]);
+        substituted.push_str(literal);
+        last_end = range.end;
+    }
+    substituted.push_str(&sql[last_end..]);
+    Some(substituted)
+}
+
+/// Information about a slow query necessary to capture its plan.
+pub(crate) struct PendingPlanCapture {
+    pub name: &'static str,
+    pub location: &'static Location<'static>,
+    pub args: Vec<(&'static str, String)>,
+    /// SQL literals for bound args recorded during instrumentation.
+    pub bound_args: Option<Vec<String>>,
+    pub sql: String,
+    pub elapsed: Duration,
+}
+
+impl fmt::Debug for PendingPlanCapture {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("PendingPlanCapture")
+            .field("name", &self.name)
+            .field("location", &self.location)
+            .field("elapsed", &self.elapsed)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Resets [`IS_CAPTURING`] on drop, including if the capture task panics.
+#[derive(Debug)]
+struct CaptureGuard(());
+
+impl CaptureGuard {
+    fn new() -> Option<Self> {
+        let was_capturing = IS_CAPTURING.swap(true, Ordering::AcqRel);
+        (!was_capturing).then_some(Self(()))
+    }
+}
+
+impl Drop for CaptureGuard {
+    fn drop(&mut self) {
+        IS_CAPTURING.store(false, Ordering::Release);
+    }
+}
+
+impl PendingPlanCapture {
+    /// Name of the prepared statement used to obtain generic plans.
+    const STATEMENT_NAME: &'static str = "zksync_captured_plan";
+    /// Minimum statement timeout for plan capture.
+    const MIN_STATEMENT_TIMEOUT: Duration = Duration::from_secs(1);
+    /// Timeout to acquire a connection for plan capture.
+    const ACQUIRE_TIMEOUT: Duration = Duration::from_millis(100);
+
+    /// Spawns a background task capturing the query plan on a connection from the provided pool.
+    /// If another plan is being captured, or a connection cannot be acquired quickly, the capture is skipped.
+    pub fn spawn(self, pool: PgPool) {
+        let Some(guard) = CaptureGuard::new() else {
+            REQUEST_METRICS.request_plan_capture_skipped[&self.name].inc();
+            return;
+        };
+        tokio::spawn(async move {
+            let _guard = guard;
+            let conn = tokio::time::timeout(Self::ACQUIRE_TIMEOUT, pool.acquire()).await;
+            let Ok(Ok(mut conn)) = conn else {
+                REQUEST_METRICS.request_plan_capture_skipped[&self.name].inc();
+                return;
+            };
+            self.run(&mut conn).await;
+        });
+    }
+
+    /// Captures the query plan on the provided connection and stores it. Errors are logged and stored
+    /// together with the plan; they are not propagated, since plan capture is auxiliary.
+    async fn run(self, conn: &mut PgConnection) {
+        let param_count = param_count(&self.sql);
+        let analyzed_sql = if param_count == 0 {
+            Some(self.sql.clone())
+        } else {
+            self.bound_args
+                .as_deref()
+                .and_then(|literals| substitute_params(&self.sql, literals))
+        };
+        let (kind, plan) = if let Some(analyzed_sql) = &analyzed_sql {
+            (
+                QueryPlanKind::Analyzed,
+                self.explain(conn, analyzed_sql).await,
+            )
+        } else {
+            let plan = self.explain_generic(conn, param_count).await;
+            (QueryPlanKind::Generic, plan)
+        };
+        if let Err(err) = &plan {
+            tracing::info!(
+                "Failed capturing plan for slow query {name} called at {file}:{line}: {err}",
+                name = self.name,
+                file = self.location.file(),
+                line = self.location.line()
+            );
+            REQUEST_METRICS.request_plan_capture_failed[&self.name].inc();
+        } else {
+            REQUEST_METRICS.request_plan_captured[&self.name].inc();
+        }
+
+        let captured_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |timestamp| timestamp.as_secs());
+        let (plan, error) = match plan {
+            Ok(plan) => (Some(plan), None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+        let captured = CapturedQueryPlan {
+            name: self.name,
+            location: format!("{}:{}", self.location.file(), self.location.line()),
+            args: self.args.into_iter().collect(),
+            bound_args: self.bound_args,
+            sql: self.sql,
+            elapsed_ms: u64::try_from(self.elapsed.as_millis()).unwrap_or(u64::MAX),
+            captured_at,
+            kind,
+            plan,
+            error,
+        };
+        CAPTURED_PLANS
+            .lock()
+            .expect("captured plans are poisoned")
+            .push(captured);
+    }
+
+    /// Sets the statement timeout for the current transaction.
+    async fn set_statement_timeout(&self, conn: &mut PgConnection) -> sqlx::Result<()> {
+        let timeout = (self.elapsed * 2).max(Self::MIN_STATEMENT_TIMEOUT);
+        let set_timeout_sql = format!("SET LOCAL statement_timeout = {}", timeout.as_millis());
+        sqlx::query(&set_timeout_sql).execute(conn).await?;
+        Ok(())
+    }
+
+    async fn explain(&self, conn: &mut PgConnection, sql: &str) -> sqlx::Result<String> {
+        // `ANALYZE` executes the query, so we run it in a transaction that is always rolled back.
+        let mut transaction = conn.begin().await?;
+        let plan = async {
+            self.set_statement_timeout(&mut transaction).await?;
+            let explain_sql = format!("EXPLAIN (ANALYZE, BUFFERS) {sql}");
+            let plan = sqlx::query_scalar::<_, String>(&explain_sql)
+                .fetch_all(&mut *transaction)
+                .await?;
+            Ok(plan.join("\n"))
+        }
+        .await;
+        transaction.rollback().await?;
+        plan
+    }
+
+    async fn explain_generic(
+        &self,
+        conn: &mut PgConnection,
+        param_count: usize,
+    ) -> sqlx::Result<String> {
+        let mut transaction = conn.begin().await?;
+        let mut is_prepared = false;
+        let plan = async {
+            self.set_statement_timeout(&mut transaction).await?;
+            Self::explain_generic_inner(&mut transaction, &self.sql, param_count, &mut is_prepared)
+                .await
+        }
+        .await;
+        transaction.rollback().await?;
+        // Prepared statements are not transactional, so the statement needs to be deallocated explicitly.
+        if is_prepared {
+            let deallocate_sql = format!("DEALLOCATE {}", Self::STATEMENT_NAME);
+            sqlx::query(&deallocate_sql).execute(&mut *conn).await?;
+        }
+        plan
+    }
+
+    async fn explain_generic_inner(
+        conn: &mut PgConnection,
+        sql: &str,
+        param_count: usize,
+        is_prepared: &mut bool,
+    ) -> sqlx::Result<String> {
+        sqlx::query("SET LOCAL plan_cache_mode = force_generic_plan")
+            .execute(&mut *conn)
+            .await?;
+        let prepare_sql = format!("PREPARE {} AS {sql}", Self::STATEMENT_NAME);
+        sqlx::query(&prepare_sql).execute(&mut *conn).await?;
+        *is_prepared = true;
+
+        // Parameter values do not influence a generic plan, so we can supply `NULL`s.
+        let null_args = vec!["NULL"; param_count].join(", ");
+        let explain_sql = format!("EXPLAIN EXECUTE {}({null_args})", Self::STATEMENT_NAME);
+        let plan = sqlx::query_scalar::<_, String>(&explain_sql)
+            .fetch_all(&mut *conn)
+            .await?;
+        Ok(plan.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counting_params() {
+        assert_eq!(param_count("SELECT 1"), 0);
+        assert_eq!(param_count("SELECT * FROM t WHERE a = $1"), 1);
+        assert_eq!(param_count("SELECT * FROM t WHERE a = $2 AND b = $1"), 2);
+        assert_eq!(param_count("SELECT * FROM t WHERE a = $10"), 10);
+        assert_eq!(param_count("SELECT '$3' FROM \"$4\" WHERE a = $1"), 1);
+        assert_eq!(param_count("SELECT $"), 0);
+    }
+
+    #[test]
+    fn substituting_params() {
+        let literals = ["1".to_owned(), "'a''b'".to_owned()];
+        assert_eq!(
+            substitute_params("SELECT * FROM t WHERE a = $2 AND b = $1", &literals).unwrap(),
+            "SELECT * FROM t WHERE a = 'a''b' AND b = 1"
+        );
+        assert_eq!(
+            substitute_params("SELECT '$1' FROM t WHERE a = $1", &literals).unwrap(),
+            "SELECT '$1' FROM t WHERE a = 1"
+        );
+        assert_eq!(substitute_params("SELECT $3", &literals), None);
+    }
+
+    #[test]
+    fn rendering_sql_literals() {
+        assert_eq!(42_i64.to_sql_literal(), "42");
+        assert_eq!(true.to_sql_literal(), "TRUE");
+        assert_eq!("it's".to_sql_literal(), "'it''s'");
+        assert_eq!([0xab_u8, 0xcd].to_sql_literal(), "'\\xabcd'::BYTEA");
+        assert_eq!(vec![1_i32, 2].to_sql_literal(), "ARRAY[1, 2]");
+        assert_eq!(Vec::<i32>::new().to_sql_literal(), "'{}'");
+        assert_eq!(None::<i64>.to_sql_literal(), "NULL");
+        assert_eq!(Some(1_i64).to_sql_literal(), "1");
+    }
+
+    #[test]
+    fn captured_plans_are_bounded() {
+        let mut plans = CapturedPlans::new();
+        plans.capacity = 2;
+        for name in ["first", "second", "third"] {
+            plans.push(CapturedQueryPlan {
+                name,
+                location: String::new(),
+                args: BTreeMap::new(),
+                bound_args: None,
+                sql: "SELECT 1".to_owned(),
+                elapsed_ms: 0,
+                captured_at: 0,
+                kind: QueryPlanKind::Analyzed,
+                plan: None,
+                error: None,
+            });
+        }
+        let names: Vec<_> = plans.plans.iter().map(|plan| plan.name).collect();
+        assert_eq!(names, ["second", "third"]);
+    }
+}
@@ -0,0 +1,595 @@
+//! Connection pool routing read-only requests to read replicas.
+
+use std::{
+    fmt,
+    future::Future,
+    panic::Location,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use futures::future::BoxFuture;
+use rand::seq::SliceRandom;
+use tokio::sync::watch;
+use zksync_basic_types::url::SensitiveUrl;
+
+use crate::{
+    connection::{Connection, ConnectionTags, DbMarker},
+    connection_pool::{ConnectionPool, ConnectionPoolBuilder},
+    error::DalResult,
+    metrics::{PrimaryFallbackReason, REPLICA_METRICS},
+};
+
+/// Function checking the replication lag for the database the connection is established to. For the primary database
+/// or a fully synced replica, the function should return zero lag.
+///
+/// For the core database, this is `SystemDal::get_replication_lag()` in the DAL crate.
+pub type ReplicationLagCheck<DB> =
+    for<'a> fn(&'a mut Connection<'_, DB>) -> BoxFuture<'a, DalResult<Duration>>;
+
+/// Read replica together with its last observed state.
+struct Replica<DB: DbMarker> {
+    name: String,
+    pool: ConnectionPool<DB>,
+    /// Last observed replication lag in milliseconds, or [`Self::UNHEALTHY`].
+    lag_ms: AtomicU64,
+}
+
+impl<DB: DbMarker> fmt::Debug for Replica<DB> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Replica")
+            .field("name", &self.name)
+            .field("pool", &self.pool)
+            .field("lag", &self.lag())
+            .finish()
+    }
+}
+
+impl<DB: DbMarker> Replica<DB> {
+    const UNHEALTHY: u64 = u64::MAX;
+    /// Timeout to acquire a connection for a replication lag check.
+    const LAG_CHECK_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(1);
+
+    fn new(name: String, pool: ConnectionPool<DB>) -> Self {
+        Self {
+            name,
+            pool,
+            lag_ms: AtomicU64::new(Self::UNHEALTHY),
+        }
+    }
+
+    /// Returns `None` if the replica is unhealthy or its lag is not known yet.
+    fn lag(&self) -> Option<Duration> {
+        let lag_ms = self.lag_ms.load(Ordering::Relaxed);
+        (lag_ms != Self::UNHEALTHY).then(|| Duration::from_millis(lag_ms))
+    }
+
+    fn set_lag(&self, lag: Duration, max_lag: Duration) {
+        let lag_ms = u64::try_from(lag.as_millis()).unwrap_or(u64::MAX - 1);
+        self.lag_ms.store(lag_ms, Ordering::Relaxed);
+        REPLICA_METRICS.lag[&self.name].set(lag);
+        REPLICA_METRICS.is_healthy[&self.name].set((lag <= max_lag).into());
+    }
+
+    fn mark_unhealthy(&self) {
+        self.lag_ms.store(Self::UNHEALTHY, Ordering::Relaxed);
+        REPLICA_METRICS.is_healthy[&self.name].set(0);
+        REPLICA_METRICS.errors[&self.name].inc();
+    }
+
+    async fn update_lag(&self, lag_check: ReplicationLagCheck<DB>, max_lag: Duration) {
+        let tags = ConnectionTags {
+            requester: "replication_lag_monitor",
+            location: Location::caller(),
+        };
+        let lag = match self
+            .pool
+            .try_connection(Some(tags), Self::LAG_CHECK_ACQUIRE_TIMEOUT)
+            .await
+        {
+            Ok(mut connection) => lag_check(&mut connection).await,
+            Err(err) => Err(err),
+        };
+        match lag {
+            Ok(lag) => {
+                if lag > max_lag {
+                    tracing::info!(
+                        "Replica `{}` lags behind by {lag:?}, which exceeds the limit {max_lag:?}",
+                        self.name
+                    );
+                }
+                self.set_lag(lag, max_lag);
+            }
+            Err(err) => {
+                tracing::warn!(
+                    "Failed checking replication lag for replica `{}`: {err}",
+                    self.name
+                );
+                self.mark_unhealthy();
+            }
+        }
+    }
+}
+
+/// Builder for [`RoutingConnectionPool`]s.
+#[derive(Debug)]
+pub struct RoutingConnectionPoolBuilder<DB: DbMarker> {
+    primary: ConnectionPool<DB>,
+    replicas: Vec<ConnectionPoolBuilder<DB>>,
+    lag_check: ReplicationLagCheck<DB>,
+    max_replication_lag: Duration,
+    replica_acquire_timeout: Duration,
+}
+
+impl<DB: DbMarker> RoutingConnectionPoolBuilder<DB> {
+    /// Creates a builder with the specified primary database pool and no replicas. `lag_check` is used
+    /// to determine replication lag for replicas.
+    pub fn new(primary: ConnectionPool<DB>, lag_check: ReplicationLagCheck<DB>) -> Self {
+        Self {
+            primary,
+            replicas: vec![],
+            lag_check,
+            max_replication_lag: Duration::from_secs(5),
+            replica_acquire_timeout: Duration::from_millis(100),
+        }
+    }
+
+    /// Adds a read replica.
+    pub fn add_replica(&mut self, replica: ConnectionPoolBuilder<DB>) -> &mut Self {
+        self.replicas.push(replica);
+        self
+    }
+
+    /// Sets the maximum replication lag for a replica to be eligible for serving read-only connections.
+    /// The default value is 5 seconds.
+    pub fn set_max_replication_lag(&mut self, lag: Duration) -> &mut Self {
+        self.max_replication_lag = lag;
+        self
+    }
+
+    /// Sets the timeout to acquire a read-only connection from a replica. If the timeout elapses, the connection
+    /// is acquired from the primary database instead. The default value is 100 milliseconds.
+    pub fn set_replica_acquire_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.replica_acquire_timeout = timeout;
+        self
+    }
+
+    /// Builds a routing connection pool from this builder. Replication lag is checked for each replica
+    /// during the build; to keep the lag up to date afterwards, use [`RoutingConnectionPool::lag_monitor()`].
+    pub async fn build(&self) -> anyhow::Result<RoutingConnectionPool<DB>> {
+        let mut replicas = Vec::with_capacity(self.replicas.len());
+        for (i, replica) in self.replicas.iter().enumerate() {
+            let name = replica_name(i, replica.database_url());
+            let pool = replica
+                .build()
+                .await
+                .with_context(|| format!("failed building pool for replica `{name}`"))?;
+            replicas.push(Replica::new(name, pool));
+        }
+
+        let pool = RoutingConnectionPool {
+            primary: self.primary.clone(),
+            replicas: replicas.into(),
+            lag_check: self.lag_check,
+            max_replication_lag: self.max_replication_lag,
+            replica_acquire_timeout: self.replica_acquire_timeout,
+        };
+        pool.update_lags().await;
+        Ok(pool)
+    }
+}
+
+/// Returns a replica name to be used in logs and metrics. The name does not contain sensitive info
+/// (e.g., credentials) from the database URL.
+fn replica_name(index: usize, url: &SensitiveUrl) -> String {
+    let url = url.expose_url();
+    match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{host}:{port}"),
+        (Some(host), None) => host.to_owned(),
+        _ => format!("replica_{index}"),
+    }
+}
+
+/// Connection pool that routes read-only connections to read replicas and falls back to the primary database
+/// if no replica is available.
+///
+/// A replica is eligible to serve a read-only connection if its last observed replication lag doesn't exceed
+/// the configured limit. Among eligible replicas, a replica with the least lag is selected (with random selection
+/// among replicas with equal lag). If acquiring a connection from the replica fails, the replica is marked
+/// as unhealthy until the next successful lag check, and the connection is acquired from the primary database.
+///
+/// Replication lag is only checked when the pool is built; to keep it up to date, run
+/// the [`ReplicationLagMonitor`] returned by [`Self::lag_monitor()`].
+#[derive(Clone)]
+pub struct RoutingConnectionPool<DB: DbMarker> {
+    primary: ConnectionPool<DB>,
+    replicas: Arc<[Replica<DB>]>,
+    lag_check: ReplicationLagCheck<DB>,
+    max_replication_lag: Duration,
+    replica_acquire_timeout: Duration,
+}
+
+impl<DB: DbMarker> fmt::Debug for RoutingConnectionPool<DB> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RoutingConnectionPool")
+            .field("primary", &self.primary)
+            .field("replicas", &self.replicas)
+            .field("max_replication_lag", &self.max_replication_lag)
+            .field("replica_acquire_timeout", &self.replica_acquire_timeout)
+            .finish()
+    }
+}
+
+impl<DB: DbMarker> RoutingConnectionPool<DB> {
+    /// Initializes a builder for routing pools with the specified primary pool. All replica pools
+    /// will have the specified maximum size.
+    pub fn builder(
+        primary: ConnectionPool<DB>,
+        replica_urls: impl IntoIterator<Item = SensitiveUrl>,
+        max_pool_size: u32,
+        lag_check: ReplicationLagCheck<DB>,
+    ) -> RoutingConnectionPoolBuilder<DB> {
+        let mut builder = RoutingConnectionPoolBuilder::new(primary, lag_check);
+        for url in replica_urls {
+            builder.add_replica(ConnectionPool::builder(url, max_pool_size));
+        }
+        builder
+    }
+
+    /// Returns the pool for the primary database.
+    pub fn primary(&self) -> &ConnectionPool<DB> {
+        &self.primary
+    }
+
+    /// Returns the number of read replicas in this pool.
+    pub fn replica_count(&self) -> usize {
+        self.replicas.len()
+    }
+
+    /// Acquires a connection to the primary database. This connection should be used for all requests
+    /// that modify data or require to read the latest data.
+    #[track_caller]
+    pub fn connection_tagged(
+        &self,
+        requester: &'static str,
+    ) -> impl Future<Output = DalResult<Connection<'_, DB>>> + '_ {
+        self.primary.connection_tagged(requester)
+    }
+
+    /// Acquires a connection for read-only requests. The connection may be established to a read replica,
+    /// so the returned data may lag behind the primary database by up to the configured replication lag.
+    #[track_caller] // In order to use it, we have to de-sugar `async fn`
+    pub fn read_connection_tagged(
+        &self,
+        requester: &'static str,
+    ) -> impl Future<Output = DalResult<Connection<'_, DB>>> + '_ {
+        let location = Location::caller();
+        async move {
+            let tags = ConnectionTags {
+                requester,
+                location,
+            };
+            self.read_connection_inner(tags, None).await
+        }
+    }
+
+    /// Creates a new empty scope for read-only connections. See [`ReadScope`] for details.
+    pub fn read_scope(&self) -> ReadScope {
+        ReadScope::default()
+    }
+
+    /// Acquires a connection for read-only requests in the specified scope. Unlike [`Self::read_connection_tagged()`],
+    /// all connections in the scope are established to the same database, so that the data observed via them
+    /// never goes back in time.
+    #[track_caller] // In order to use it, we have to de-sugar `async fn`
+    pub fn scoped_read_connection_tagged(
+        &self,
+        scope: ReadScope,
+        requester: &'static str,
+    ) -> impl Future<Output = DalResult<Connection<'_, DB>>> + '_ {
+        let location = Location::caller();
+        async move {
+            let tags = ConnectionTags {
+                requester,
+                location,
+            };
+            self.read_connection_inner(tags, Some(&scope)).await
+        }
+    }
+
+    async fn read_connection_inner(
+        &self,
+        tags: ConnectionTags,
+        scope: Option<&ReadScope>,
+    ) -> DalResult<Connection<'_, DB>> {
+        let target = match scope {
+            Some(scope) => scope.get_or_init(|| self.select_target()),
+            None => self.select_target(),
+        };
+        let replica = match target {
+            ReadTarget::Replica(idx) => self.replicas.get(idx),
+            ReadTarget::Primary => None,
+        };
+        let Some(replica) = replica else {
+            return self.primary.connection_inner(Some(tags)).await;
+        };
+
+        match replica
+            .pool
+            .try_connection(Some(tags), self.replica_acquire_timeout)
+            .await
+        {
+            Ok(connection) => {
+                REPLICA_METRICS.read_connections[&replica.name].inc();
+                Ok(connection)
+            }
+            Err(err) => {
+                tracing::warn!(
+                    "Failed acquiring connection from replica `{}`, falling back to primary: {err}",
+                    replica.name
+                );
+                replica.mark_unhealthy();
+                REPLICA_METRICS.primary_fallbacks[&PrimaryFallbackReason::ReplicaError].inc();
+                if let Some(scope) = scope {
+                    // The primary database is ahead of the replica, so switching to it doesn't break
+                    // the scope guarantees; switching back to a replica later would.
+                    scope.set(ReadTarget::Primary);
+                }
+                self.primary.connection_inner(Some(tags)).await
+            }
+        }
+    }
+
+    fn select_target(&self) -> ReadTarget {
+        if let Some(idx) = self.select_replica() {
+            ReadTarget::Replica(idx)
+        } else {
+            REPLICA_METRICS.primary_fallbacks[&PrimaryFallbackReason::NoHealthyReplicas].inc();
+            ReadTarget::Primary
+        }
+    }
+
+    /// Returns the index of the selected replica.
+    fn select_replica(&self) -> Option<usize> {
+        let eligible_replicas = self
+            .replicas
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, replica)| {
+                let lag = replica.lag()?;
+                (lag <= self.max_replication_lag).then_some((idx, lag))
+            });
+        let min_lag = eligible_replicas.clone().map(|(_, lag)| lag).min()?;
+        let best_replicas: Vec<_> = eligible_replicas
+            .filter_map(|(idx, lag)| (lag == min_lag).then_some(idx))
+            .collect();
+        best_replicas.choose(&mut rand::thread_rng()).copied()
+    }
+
+    async fn update_lags(&self) {
+        let updates = self
+            .replicas
+            .iter()
+            .map(|replica| replica.update_lag(self.lag_check, self.max_replication_lag));
+        futures::future::join_all(updates).await;
+    }
+
+    /// Creates a monitor periodically updating replication lag for all replicas in this pool.
+    pub fn lag_monitor(&self, update_interval: Duration) -> ReplicationLagMonitor<DB> {
+        ReplicationLagMonitor {
+            pool: self.clone(),
+            update_interval,
+        }
+    }
+}
+
+/// Database serving read-only connections.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ReadTarget {
+    Primary,
+    /// Replica with the specified index in [`RoutingConnectionPool`].
+    Replica(usize),
+}
+
+/// Scope for read-only connections acquired from a [`RoutingConnectionPool`], such as a single API request.
+///
+/// The database serving connections is selected when the first connection in the scope is acquired, and all
+/// subsequent connections are established to the same database. Without scoping, connections could be acquired
+/// from replicas with different replication lag, so that data observed via them could go back in time.
+/// If acquiring a connection from the selected replica fails, the scope is switched to the primary database.
+///
+/// A scope must only be used with the pool that has created it.
+#[derive(Debug, Clone, Default)]
+pub struct ReadScope(Arc<AtomicUsize>);
+
+impl ReadScope {
+    const UNSET: usize = 0;
+    const PRIMARY: usize = usize::MAX;
+
+    fn get_or_init(&self, init: impl FnOnce() -> ReadTarget) -> ReadTarget {
+        let raw = self.0.load(Ordering::Acquire);
+        if raw != Self::UNSET {
+            return Self::decode(raw);
+        }
+        let raw_target = Self::encode(init());
+        match self
+            .0
+            .compare_exchange(Self::UNSET, raw_target, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => Self::decode(raw_target),
+            // Another connection in the scope has selected the target concurrently; use it instead.
+            Err(raw) => Self::decode(raw),
+        }
+    }
+
+    fn set(&self, target: ReadTarget) {
+        self.0.store(Self::encode(target), Ordering::Release);
+    }
+
+    fn encode(target: ReadTarget) -> usize {
+        match target {
+            ReadTarget::Primary => Self::PRIMARY,
+            ReadTarget::Replica(idx) => idx + 1,
+        }
+    }
+
+    fn decode(raw: usize) -> ReadTarget {
+        match raw {
+            Self::PRIMARY => ReadTarget::Primary,
+            _ => ReadTarget::Replica(raw - 1),
+        }
+    }
+}
+
+/// Task periodically checking replication lag for replicas in a [`RoutingConnectionPool`].
+#[derive(Debug)]
+pub struct ReplicationLagMonitor<DB: DbMarker> {
+    pool: RoutingConnectionPool<DB>,
+    update_interval: Duration,
+}
+
+impl<DB: DbMarker> ReplicationLagMonitor<DB> {
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        if self.pool.replicas.is_empty() {
+            tracing::info!("Routing pool has no replicas; replication lag monitor is not needed");
+            return Ok(());
+        }
+
+        while !*stop_receiver.borrow_and_update() {
+            self.pool.update_lags().await;
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.update_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Received a stop signal; replication lag monitor is shut down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{connection_pool::TestTemplate, instrument::InstrumentExt, utils::InternalMarker};
+
+    /// Lag check ensuring that the connection is alive. The primary database reports zero lag,
+    /// so it can be used as a replica in tests.
+    fn test_lag_check<'a>(
+        connection: &'a mut Connection<'_, InternalMarker>,
+    ) -> BoxFuture<'a, DalResult<Duration>> {
+        Box::pin(async move {
+            sqlx::query("SELECT 1")
+                .instrument("test_lag_check")
+                .execute(connection)
+                .await?;
+            Ok(Duration::ZERO)
+        })
+    }
+
+    async fn test_pool_builder() -> RoutingConnectionPoolBuilder<InternalMarker> {
+        let primary = TestTemplate::empty()
+            .unwrap()
+            .create_db::<InternalMarker>(2)
+            .await
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let db_url = primary.database_url().clone();
+        RoutingConnectionPool::builder(primary, [db_url], 2, test_lag_check)
+    }
+
+    #[tokio::test]
+    async fn read_connections_are_routed_to_healthy_replica() {
+        let pool = test_pool_builder().await.build().await.unwrap();
+        assert_eq!(pool.replica_count(), 1);
+        assert_eq!(pool.replicas[0].lag(), Some(Duration::ZERO));
+
+        assert_eq!(pool.select_replica(), Some(0));
+        pool.read_connection_tagged("test").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn scoped_read_connections_are_pinned_to_database() {
+        let pool = test_pool_builder().await.build().await.unwrap();
+        let scope = pool.read_scope();
+        pool.scoped_read_connection_tagged(scope.clone(), "test")
+            .await
+            .unwrap();
+        assert_eq!(scope.get_or_init(|| unreachable!()), ReadTarget::Replica(0));
+
+        // A scope pinned to the primary database shouldn't switch to a replica even if it's healthy.
+        let scope = pool.read_scope();
+        scope.set(ReadTarget::Primary);
+        pool.scoped_read_connection_tagged(scope.clone(), "test")
+            .await
+            .unwrap();
+        assert_eq!(scope.get_or_init(|| unreachable!()), ReadTarget::Primary);
+
+        // If the pinned replica fails, the scope should switch to the primary database.
+        let scope = pool.read_scope();
+        scope.set(ReadTarget::Replica(0));
+        let mut replica_connections = vec![];
+        for _ in 0..2 {
+            replica_connections.push(pool.replicas[0].pool.connection().await.unwrap());
+        }
+        pool.scoped_read_connection_tagged(scope.clone(), "test")
+            .await
+            .unwrap();
+        assert_eq!(scope.get_or_init(|| unreachable!()), ReadTarget::Primary);
+    }
+
+    #[tokio::test]
+    async fn read_connections_fall_back_to_primary() {
+        let pool = test_pool_builder().await.build().await.unwrap();
+        pool.replicas[0].mark_unhealthy();
+        assert!(pool.select_replica().is_none());
+        pool.read_connection_tagged("test").await.unwrap();
+
+        // The lag monitor should restore the replica.
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let monitor = pool.lag_monitor(Duration::from_millis(10));
+        let monitor_task = tokio::spawn(monitor.run(stop_receiver));
+        while pool.select_replica().is_none() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        stop_sender.send_replace(true);
+        monitor_task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn read_connections_do_not_wait_for_busy_replica() {
+        let pool = test_pool_builder().await.build().await.unwrap();
+        let mut replica_connections = vec![];
+        for _ in 0..2 {
+            replica_connections.push(pool.replicas[0].pool.connection().await.unwrap());
+        }
+
+        // The replica pool is exhausted, so the connection should be acquired from the primary.
+        tokio::time::timeout(Duration::from_secs(5), pool.read_connection_tagged("test"))
+            .await
+            .expect("read connection was not acquired in time")
+            .unwrap();
+        assert_eq!(pool.replicas[0].lag(), None);
+    }
+
+    #[tokio::test]
+    async fn replicas_exceeding_lag_limit_are_not_selected() {
+        let mut builder = test_pool_builder().await;
+        let pool = builder
+            .set_max_replication_lag(Duration::from_secs(1))
+            .build()
+            .await
+            .unwrap();
+        pool.replicas[0].set_lag(Duration::from_secs(10), pool.max_replication_lag);
+        assert!(pool.select_replica().is_none());
+        pool.replicas[0].set_lag(Duration::from_millis(500), pool.max_replication_lag);
+        assert!(pool.select_replica().is_some());
+    }
+}
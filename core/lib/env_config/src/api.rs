@@ -60,6 +60,7 @@ mod tests {
                 http_url: "http://127.0.0.1:3050".into(),
                 ws_port: 3051,
                 ws_url: "ws://127.0.0.1:3051".into(),
+                admin_port: Some(3055),
                 req_entities_limit: Some(10000),
                 filters_disabled: false,
                 filters_limit: Some(10000),
@@ -108,6 +109,9 @@ mod tests {
                 port: 8081,
                 slow_time_limit_ms: Some(250),
                 hard_time_limit_ms: Some(2_000),
+                liveness_components: vec![],
+                readiness_components: vec!["state_keeper".to_owned(), "eth_sender".to_owned()],
+                expose_slow_query_plans: true,
             },
             merkle_tree: MerkleTreeApiConfig { port: 8082 },
         }
@@ -121,6 +125,7 @@ mod tests {
             API_WEB3_JSON_RPC_HTTP_URL="http://127.0.0.1:3050"
             API_WEB3_JSON_RPC_WS_PORT="3051"
             API_WEB3_JSON_RPC_WS_URL="ws://127.0.0.1:3051"
+            API_WEB3_JSON_RPC_ADMIN_PORT=3055
             API_WEB3_JSON_RPC_REQ_ENTITIES_LIMIT=10000
             API_WEB3_JSON_RPC_FILTERS_DISABLED=false
             API_WEB3_JSON_RPC_FILTERS_LIMIT=10000
@@ -153,6 +158,8 @@ mod tests {
             API_HEALTHCHECK_PORT=8081
             API_HEALTHCHECK_SLOW_TIME_LIMIT_MS=250
             API_HEALTHCHECK_HARD_TIME_LIMIT_MS=2000
+            API_HEALTHCHECK_READINESS_COMPONENTS="state_keeper,eth_sender"
+            API_HEALTHCHECK_EXPOSE_SLOW_QUERY_PLANS=true
             API_MERKLE_TREE_PORT=8082
         "#;
         lock.set_env(config);
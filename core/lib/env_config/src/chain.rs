@@ -96,6 +96,10 @@ mod tests {
             fee_model_version: FeeModelVersion::V2,
             validation_computational_gas_limit: 10_000_000,
             save_call_traces: false,
+            protective_reads_persistence_enabled: true,
+            tx_filter_path: None,
+            tx_filter_reload_interval_ms: 10_000,
+            seal_params_path: None,
             bootloader_hash: Some(hash(
                 "0x010007ede999d096c84553fb514d3d6ca76fbf39789dda76bfeda9f3ae06236e",
             )),
@@ -194,6 +198,10 @@ mod tests {
             http_req_max_retry_number: 5,
             http_req_retry_interval_sec: 2,
             replication_lag_limit_sec: Some(10),
+            unproven_batches_limit: Some(100),
+            tree_lag_limit: None,
+            l1_base_fee_ceiling: Some(500_000_000_000),
+            soft_circuit_breakers: vec!["l1_base_fee".to_owned(), "unproven_batches".to_owned()],
         }
     }
 
@@ -205,6 +213,9 @@ mod tests {
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_MAX_RETRY_NUMBER="5"
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_RETRY_INTERVAL_SEC="2"
             CHAIN_CIRCUIT_BREAKER_REPLICATION_LAG_LIMIT_SEC="10"
+            CHAIN_CIRCUIT_BREAKER_UNPROVEN_BATCHES_LIMIT="100"
+            CHAIN_CIRCUIT_BREAKER_L1_BASE_FEE_CEILING="500000000000"
+            CHAIN_CIRCUIT_BREAKER_SOFT_CIRCUIT_BREAKERS="l1_base_fee,unproven_batches"
         "#;
         lock.set_env(config);
 
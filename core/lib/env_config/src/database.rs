@@ -45,6 +45,17 @@ impl FromEnv for PostgresConfig {
             .map(|s| s.parse())
             .transpose()?
             .or_else(|| master_url.clone());
+        let read_replica_urls = env::var("DATABASE_READ_REPLICA_URLS")
+            .ok()
+            .map(|urls| {
+                urls.split(',')
+                    .map(|url| url.trim().parse())
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()
+            .context("failed to parse env variable DATABASE_READ_REPLICA_URLS")?
+            .unwrap_or_default();
+        let max_replication_lag_sec = parse_optional_var("DATABASE_MAX_REPLICATION_LAG_SEC")?;
         let test_server_url = env::var("TEST_DATABASE_URL").ok();
         let test_prover_url = env::var("TEST_DATABASE_PROVER_URL").ok();
         let max_connections = parse_optional_var("DATABASE_POOL_SIZE")?;
@@ -54,17 +65,24 @@ impl FromEnv for PostgresConfig {
         let long_connection_threshold_ms =
             parse_optional_var("DATABASE_LONG_CONNECTION_THRESHOLD_MS")?;
         let slow_query_threshold_ms = parse_optional_var("DATABASE_SLOW_QUERY_THRESHOLD_MS")?;
+        let slow_query_plan_sample_rate =
+            parse_optional_var("DATABASE_SLOW_QUERY_PLAN_SAMPLE_RATE")?;
+        let slow_query_plan_capacity = parse_optional_var("DATABASE_SLOW_QUERY_PLAN_CAPACITY")?;
 
         Ok(Self {
             master_url,
             replica_url,
             prover_url,
+            read_replica_urls,
+            max_replication_lag_sec,
             max_connections,
             max_connections_master,
             acquire_timeout_sec,
             statement_timeout_sec,
             long_connection_threshold_ms,
             slow_query_threshold_ms,
+            slow_query_plan_sample_rate,
+            slow_query_plan_capacity,
             test_server_url,
             test_prover_url,
         })
@@ -93,6 +111,9 @@ mod tests {
             DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB=512
             DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC=60
             DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER=50
+            DATABASE_MERKLE_TREE_CHECKPOINT_INTERVAL_L1_BATCHES=1000
+            DATABASE_MERKLE_TREE_MAX_RETAINED_CHECKPOINTS=5
+            DATABASE_MERKLE_TREE_PARALLEL_UPDATES=true
             DATABASE_EXPERIMENTAL_STATE_KEEPER_DB_BLOCK_CACHE_CAPACITY_MB=64
             DATABASE_EXPERIMENTAL_STATE_KEEPER_DB_MAX_OPEN_FILES=100
         "#;
@@ -106,6 +127,12 @@ mod tests {
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 512);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 60);
+        assert_eq!(
+            db_config.merkle_tree.checkpoint_interval_l1_batches,
+            Some(1000)
+        );
+        assert_eq!(db_config.merkle_tree.max_retained_checkpoints, 5);
+        assert!(db_config.merkle_tree.parallel_updates);
         assert_eq!(
             db_config
                 .experimental
@@ -133,6 +160,9 @@ mod tests {
             "DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB",
             "DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC",
             "DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER",
+            "DATABASE_MERKLE_TREE_CHECKPOINT_INTERVAL_L1_BATCHES",
+            "DATABASE_MERKLE_TREE_MAX_RETAINED_CHECKPOINTS",
+            "DATABASE_MERKLE_TREE_PARALLEL_UPDATES",
         ]);
 
         let db_config = DBConfig::from_env().unwrap();
@@ -144,6 +174,9 @@ mod tests {
         assert_eq!(db_config.merkle_tree.block_cache_size_mb, 128);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 256);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 30);
+        assert_eq!(db_config.merkle_tree.checkpoint_interval_l1_batches, None);
+        assert_eq!(db_config.merkle_tree.max_retained_checkpoints, 3);
+        assert!(!db_config.merkle_tree.parallel_updates);
         assert_eq!(
             db_config
                 .experimental
@@ -180,6 +213,10 @@ mod tests {
             DATABASE_STATEMENT_TIMEOUT_SEC=300
             DATABASE_LONG_CONNECTION_THRESHOLD_MS=3000
             DATABASE_SLOW_QUERY_THRESHOLD_MS=150
+            DATABASE_SLOW_QUERY_PLAN_SAMPLE_RATE=0.1
+            DATABASE_SLOW_QUERY_PLAN_CAPACITY=32
+            DATABASE_READ_REPLICA_URLS=postgres://postgres@replica1/zksync_local,postgres://postgres@replica2/zksync_local
+            DATABASE_MAX_REPLICATION_LAG_SEC=3
         "#;
         lock.set_env(config);
 
@@ -207,5 +244,18 @@ mod tests {
             postgres_config.slow_query_threshold(),
             Some(Duration::from_millis(150))
         );
+        assert_eq!(postgres_config.slow_query_plan_sample_rate, Some(0.1));
+        assert_eq!(postgres_config.slow_query_plan_capacity, Some(32));
+        assert_eq!(
+            postgres_config.read_replica_urls,
+            [
+                "postgres://postgres@replica1/zksync_local".parse().unwrap(),
+                "postgres://postgres@replica2/zksync_local".parse().unwrap(),
+            ]
+        );
+        assert_eq!(
+            postgres_config.max_replication_lag(),
+            Some(Duration::from_secs(3))
+        );
     }
 }
@@ -81,6 +81,7 @@ mod tests {
             }),
             watcher: Some(EthWatchConfig {
                 confirmations_for_eth_event: Some(0),
+                use_finalized_block_tag: false,
                 eth_node_poll_interval: 300,
             }),
             web3_url: "http://127.0.0.1:8545".parse().unwrap(),
@@ -18,6 +18,7 @@ mod tests {
     fn expected_config() -> EthWatchConfig {
         EthWatchConfig {
             confirmations_for_eth_event: Some(0),
+            use_finalized_block_tag: true,
             eth_node_poll_interval: 300,
         }
     }
@@ -27,6 +28,7 @@ mod tests {
         let mut lock = MUTEX.lock();
         let config = r#"
             ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT="0"
+            ETH_WATCH_USE_FINALIZED_BLOCK_TAG="true"
             ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
         "#;
         lock.set_env(config);
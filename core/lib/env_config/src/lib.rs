@@ -18,8 +18,10 @@ mod house_keeper;
 pub mod object_store;
 mod observability;
 mod proof_data_handler;
+mod pruning;
 mod snapshots_creator;
 mod utils;
+mod vm_runner;
 
 mod genesis;
 #[cfg(test)]
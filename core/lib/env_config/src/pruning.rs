@@ -0,0 +1,50 @@
+use zksync_config::configs::PruningConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for PruningConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("pruning", "PRUNING_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            PRUNING_ENABLED=true
+            PRUNING_CHUNK_SIZE=5
+            PRUNING_DATA_RETENTION_SEC=86400
+            PRUNING_RETAINED_L1_BATCHES=1000
+            PRUNING_DRY_RUN=true
+            PRUNING_CALL_TRACES_RETENTION_SEC=3600
+            PRUNING_EVENTS_RETENTION_SEC=604800
+        "#;
+        lock.set_env(config);
+
+        let actual = PruningConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            PruningConfig {
+                enabled: true,
+                chunk_size: 5,
+                removal_delay_sec: NonZeroU64::new(60).unwrap(),
+                data_retention_sec: 86_400,
+                retained_l1_batches: 1_000,
+                dry_run: true,
+                call_traces_retention_sec: Some(3_600),
+                events_retention_sec: Some(604_800),
+                transaction_payloads_retention_sec: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use zksync_config::configs::ProtectiveReadsWriterConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for ProtectiveReadsWriterConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("protective_reads_writer", "VM_RUNNER_PROTECTIVE_READS_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            VM_RUNNER_PROTECTIVE_READS_DB_PATH="/db/protective_reads"
+            VM_RUNNER_PROTECTIVE_READS_WINDOW_SIZE=10
+            VM_RUNNER_PROTECTIVE_READS_FIRST_PROCESSED_BATCH=100
+        "#;
+        lock.set_env(config);
+
+        let actual = ProtectiveReadsWriterConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            ProtectiveReadsWriterConfig {
+                db_path: "/db/protective_reads".to_owned(),
+                window_size: 10,
+                first_processed_batch: 100,
+            }
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Bounded history of component health transitions.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use crate::{metrics::METRICS, Health, HealthStatus};
+
+/// Transition of a component health status.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthTransition {
+    /// UNIX timestamp of the transition in milliseconds.
+    pub timestamp_ms: u64,
+    pub from: HealthStatus,
+    pub to: HealthStatus,
+    /// Changes in health details. For object details, contains changed top-level fields with their new values
+    /// (`null` for removed fields); otherwise, contains the new details.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details_diff: Option<serde_json::Value>,
+}
+
+impl HealthTransition {
+    fn new(old_health: &Health, new_health: &Health) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |timestamp| timestamp.as_millis() as u64);
+        Self {
+            timestamp_ms,
+            from: old_health.status,
+            to: new_health.status,
+            details_diff: details_diff(old_health.details.as_ref(), new_health.details.as_ref()),
+        }
+    }
+}
+
+fn details_diff(
+    old_details: Option<&serde_json::Value>,
+    new_details: Option<&serde_json::Value>,
+) -> Option<serde_json::Value> {
+    match (old_details, new_details) {
+        (Some(serde_json::Value::Object(old)), Some(serde_json::Value::Object(new))) => {
+            let changed_fields = new
+                .iter()
+                .filter(|&(key, value)| old.get(key) != Some(value))
+                .map(|(key, value)| (key.clone(), value.clone()));
+            let removed_fields = old
+                .keys()
+                .filter(|&key| !new.contains_key(key))
+                .map(|key| (key.clone(), serde_json::Value::Null));
+            let diff: serde_json::Map<_, _> = changed_fields.chain(removed_fields).collect();
+            (!diff.is_empty()).then_some(serde_json::Value::Object(diff))
+        }
+        (old, new) if old == new => None,
+        (_, new) => Some(new.cloned().unwrap_or(serde_json::Value::Null)),
+    }
+}
+
+/// Bounded history of health status transitions for all components of an application.
+#[derive(Debug)]
+pub(crate) struct HealthHistory {
+    capacity: usize,
+    transitions: Mutex<HashMap<&'static str, VecDeque<HealthTransition>>>,
+}
+
+impl HealthHistory {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            transitions: Mutex::default(),
+        }
+    }
+
+    /// Records a transition if the health status has changed. Changes in details without a status change
+    /// are not recorded.
+    pub(crate) fn observe(
+        &self,
+        component: &'static str,
+        old_health: &Health,
+        new_health: &Health,
+    ) {
+        if old_health.status == new_health.status {
+            return;
+        }
+        METRICS.observe_transition(component, new_health.status);
+
+        let transition = HealthTransition::new(old_health, new_health);
+        let mut transitions = self
+            .transitions
+            .lock()
+            .expect("`HealthHistory` is poisoned");
+        let component_transitions = transitions.entry(component).or_default();
+        if component_transitions.len() >= self.capacity {
+            component_transitions.pop_front();
+        }
+        component_transitions.push_back(transition);
+    }
+
+    /// Returns recorded transitions for all components, oldest first.
+    pub(crate) fn get(&self) -> HashMap<&'static str, Vec<HealthTransition>> {
+        let transitions = self
+            .transitions
+            .lock()
+            .expect("`HealthHistory` is poisoned");
+        transitions
+            .iter()
+            .map(|(&component, transitions)| (component, transitions.iter().cloned().collect()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn computing_details_diff() {
+        let old = json!({ "a": 1, "b": "test", "c": [1, 2] });
+        let new = json!({ "a": 2, "b": "test", "d": true });
+        let diff = details_diff(Some(&old), Some(&new)).unwrap();
+        assert_eq!(diff, json!({ "a": 2, "c": null, "d": true }));
+
+        assert_eq!(details_diff(Some(&old), Some(&old)), None);
+        assert_eq!(details_diff(None, None), None);
+        assert_eq!(details_diff(None, Some(&new)), Some(new.clone()));
+        assert_eq!(
+            details_diff(Some(&old), None),
+            Some(serde_json::Value::Null)
+        );
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let history = HealthHistory::new(2);
+        let ready = Health::from(HealthStatus::Ready);
+        let affected =
+            Health::from(HealthStatus::Affected).with_details(json!({ "error": "oops" }));
+
+        history.observe("test", &Health::from(HealthStatus::NotReady), &ready);
+        history.observe(
+            "test",
+            &ready,
+            &ready.clone().with_details("no status change"),
+        );
+        history.observe("test", &ready, &affected);
+        history.observe("test", &affected, &ready);
+
+        let transitions = &history.get()["test"];
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].from, HealthStatus::Ready);
+        assert_eq!(transitions[0].to, HealthStatus::Affected);
+        assert_eq!(
+            transitions[0].details_diff,
+            Some(json!({ "error": "oops" }))
+        );
+        assert_eq!(transitions[1].from, HealthStatus::Affected);
+        assert_eq!(transitions[1].to, HealthStatus::Ready);
+        assert_eq!(transitions[1].details_diff, Some(serde_json::Value::Null));
+    }
+}
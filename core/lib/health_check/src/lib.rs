@@ -1,7 +1,7 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, OnceLock},
     thread,
     time::Duration,
 };
@@ -11,17 +11,24 @@ pub use async_trait::async_trait;
 use futures::future;
 use serde::Serialize;
 use tokio::sync::watch;
+use vise::EncodeLabelValue;
 
-use self::metrics::{CheckResult, METRICS};
+pub use self::history::HealthTransition;
+use self::{
+    history::HealthHistory,
+    metrics::{CheckResult, METRICS},
+};
 use crate::metrics::AppHealthCheckConfig;
 
+mod history;
 mod metrics;
 #[cfg(test)]
 mod tests;
 
 /// Health status returned as a part of `Health`.
-#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, EncodeLabelValue)]
 #[serde(rename_all = "snake_case")]
+#[metrics(rename_all = "snake_case")]
 #[non_exhaustive]
 pub enum HealthStatus {
     /// Component is initializing and is not ready yet.
@@ -45,6 +52,12 @@ impl HealthStatus {
         matches!(self, Self::Ready | Self::Affected)
     }
 
+    /// Checks whether a component is alive according to this status, i.e., it has neither shut down nor panicked.
+    /// Unlike [`Self::is_healthy()`], this is true for components that are not ready yet or are shutting down.
+    pub fn is_alive(self) -> bool {
+        !matches!(self, Self::ShutDown | Self::Panicked)
+    }
+
     fn priority_for_aggregation(self) -> usize {
         match self {
             Self::Ready => 0,
@@ -109,6 +122,12 @@ pub struct AppHealthCheck {
     components: Mutex<Vec<Arc<dyn CheckHealth>>>,
     slow_time_limit: Duration,
     hard_time_limit: Duration,
+    history: Arc<HealthHistory>,
+    /// Components reporting their health transitions to `history` themselves (i.e., reactive health checks).
+    self_reporting_components: Mutex<HashSet<&'static str>>,
+    /// Last polled health for components not reporting transitions themselves. Transitions for such components
+    /// are only observed when their health is checked.
+    polled_health: Mutex<HashMap<&'static str, Health>>,
 }
 
 impl Default for AppHealthCheck {
@@ -118,6 +137,9 @@ impl Default for AppHealthCheck {
 }
 
 impl AppHealthCheck {
+    /// Maximum number of health transitions retained for each component.
+    const HISTORY_CAPACITY: usize = 50;
+
     pub fn new(slow_time_limit: Option<Duration>, hard_time_limit: Option<Duration>) -> Self {
         const DEFAULT_SLOW_TIME_LIMIT: Duration = Duration::from_millis(500);
         const DEFAULT_HARD_TIME_LIMIT: Duration = Duration::from_secs(3);
@@ -141,6 +163,9 @@ impl AppHealthCheck {
             components: Mutex::default(),
             slow_time_limit,
             hard_time_limit,
+            history: Arc::new(HealthHistory::new(Self::HISTORY_CAPACITY)),
+            self_reporting_components: Mutex::default(),
+            polled_health: Mutex::default(),
         }
     }
 
@@ -153,7 +178,17 @@ impl AppHealthCheck {
         &self,
         health_check: ReactiveHealthCheck,
     ) -> Result<(), AppHealthCheckError> {
-        self.insert_custom_component(Arc::new(health_check))
+        let name = health_check.name;
+        let history_slot = health_check.history.clone();
+        self.insert_custom_component(Arc::new(health_check))?;
+        // Reactive checks report all transitions on update, so that they are recorded even if they happen between checks.
+        if history_slot.set(self.history.clone()).is_ok() {
+            self.self_reporting_components
+                .lock()
+                .expect("`AppHealthCheck` is poisoned")
+                .insert(name);
+        }
+        Ok(())
     }
 
     /// Inserts a custom health check for a component.
@@ -179,12 +214,33 @@ impl AppHealthCheck {
 
     /// Checks the overall application health. This will query all component checks concurrently.
     pub async fn check_health(&self) -> AppHealth {
+        self.check_selected_health(&[]).await
+    }
+
+    /// Checks health of the specified components, or of all components if `components` is empty.
+    /// Component checks are queried concurrently. If any of the specified components is not registered,
+    /// the aggregated status is at least as bad as [`HealthStatus::NotReady`].
+    pub async fn check_selected_health(&self, components: &[String]) -> AppHealth {
         // Clone checks so that we don't hold a lock for them across a wait point.
-        let health_checks = self
+        let mut health_checks = self
             .components
             .lock()
             .expect("`AppHealthCheck` is poisoned")
             .clone();
+        if !components.is_empty() {
+            health_checks.retain(|check| components.iter().any(|name| name == check.name()));
+        }
+        let missing_components: Vec<_> = components
+            .iter()
+            .filter(|&name| {
+                !health_checks
+                    .iter()
+                    .any(|check| check.name() == name.as_str())
+            })
+            .collect();
+        if !missing_components.is_empty() {
+            tracing::warn!("Requested health of unknown components: {missing_components:?}");
+        }
 
         let check_futures = health_checks.iter().map(|check| {
             Self::check_health_with_time_limit(
@@ -195,13 +251,17 @@ impl AppHealthCheck {
         });
         let components: HashMap<_, _> = future::join_all(check_futures).await.into_iter().collect();
 
+        let missing_status = (!missing_components.is_empty()).then_some(HealthStatus::NotReady);
         let aggregated_status = components
             .values()
             .map(|health| health.status)
+            .chain(missing_status)
             .max_by_key(|status| status.priority_for_aggregation())
             .unwrap_or(HealthStatus::Ready);
         let inner = aggregated_status.into();
 
+        self.observe_polled_health(&components);
+
         let health = AppHealth { inner, components };
         if !health.inner.status.is_healthy() {
             // Only log non-ready application health so that logs are not spammed without a reason.
@@ -210,6 +270,49 @@ impl AppHealthCheck {
         health
     }
 
+    fn observe_polled_health(&self, components: &HashMap<&'static str, Health>) {
+        let self_reporting_components = self
+            .self_reporting_components
+            .lock()
+            .expect("`AppHealthCheck` is poisoned");
+        let mut polled_health = self
+            .polled_health
+            .lock()
+            .expect("`AppHealthCheck` is poisoned");
+        for (&name, health) in components {
+            if self_reporting_components.contains(name) {
+                continue;
+            }
+            if let Some(old_health) = polled_health.insert(name, health.clone()) {
+                self.history.observe(name, &old_health, health);
+            }
+        }
+    }
+
+    /// Returns the recorded history of health status transitions for all components, oldest transitions first.
+    /// The history is bounded; only the latest transitions are retained for each component.
+    pub fn health_history(&self) -> HashMap<&'static str, Vec<HealthTransition>> {
+        self.history.get()
+    }
+
+    /// Checks health of a single component. Returns `None` if the component is not registered.
+    pub async fn check_component_health(&self, name: &str) -> Option<Health> {
+        let health_check = self
+            .components
+            .lock()
+            .expect("`AppHealthCheck` is poisoned")
+            .iter()
+            .find(|check| check.name() == name)
+            .cloned()?;
+        let (_, health) = Self::check_health_with_time_limit(
+            health_check.as_ref(),
+            self.slow_time_limit,
+            self.hard_time_limit,
+        )
+        .await;
+        Some(health)
+    }
+
     async fn check_health_with_time_limit(
         check: &dyn CheckHealth,
         slow_time_limit: Duration,
@@ -289,6 +392,13 @@ impl AppHealth {
         self.inner.status.is_healthy()
     }
 
+    /// Checks whether all components are alive (see [`HealthStatus::is_alive()`]).
+    pub fn is_alive(&self) -> bool {
+        self.components
+            .values()
+            .all(|health| health.status.is_alive())
+    }
+
     /// Returns a reference to the overall health of the application.
     pub fn inner(&self) -> &Health {
         &self.inner
@@ -334,6 +444,8 @@ impl<T: CheckHealth + ?Sized> CheckHealth for Arc<T> {
 pub struct ReactiveHealthCheck {
     name: &'static str,
     health_receiver: watch::Receiver<Health>,
+    /// History of the app health check the component is inserted into.
+    history: Arc<OnceLock<Arc<HealthHistory>>>,
 }
 
 impl ReactiveHealthCheck {
@@ -341,14 +453,17 @@ impl ReactiveHealthCheck {
     /// The check will return [`HealthStatus::NotReady`] initially.
     pub fn new(name: &'static str) -> (Self, HealthUpdater) {
         let (health_sender, health_receiver) = watch::channel(HealthStatus::NotReady.into());
+        let history = Arc::<OnceLock<_>>::default();
         let this = Self {
             name,
             health_receiver,
+            history: history.clone(),
         };
         let updater = HealthUpdater {
             name,
             should_track_drop: true,
             health_sender,
+            history,
         };
         (this, updater)
     }
@@ -385,6 +500,7 @@ pub struct HealthUpdater {
     name: &'static str,
     should_track_drop: bool,
     health_sender: watch::Sender<Health>,
+    history: Arc<OnceLock<Arc<HealthHistory>>>,
 }
 
 impl HealthUpdater {
@@ -400,6 +516,9 @@ impl HealthUpdater {
                 serde_json::to_string(&old_health).unwrap_or_else(|_| format!("{old_health:?}")),
                 serde_json::to_string(&health).unwrap_or_else(|_| format!("{health:?}"))
             );
+            if let Some(history) = self.history.get() {
+                history.observe(self.name, &old_health, &health);
+            }
             return true;
         }
         false
@@ -416,6 +535,7 @@ impl HealthUpdater {
         ReactiveHealthCheck {
             name: self.name,
             health_receiver: self.health_sender.subscribe(),
+            history: self.history.clone(),
         }
     }
 }
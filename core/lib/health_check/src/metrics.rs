@@ -3,10 +3,12 @@
 use std::time::Duration;
 
 use vise::{
-    Buckets, DurationAsSecs, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Info, Metrics,
-    Unit,
+    Buckets, Counter, DurationAsSecs, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Info,
+    Metrics, Unit,
 };
 
+use crate::HealthStatus;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
 #[metrics(rename_all = "snake_case")]
 pub(crate) enum CheckResult {
@@ -21,6 +23,12 @@ struct AbnormalCheckLabels {
     result: CheckResult,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
+struct TransitionLabels {
+    component: &'static str,
+    status: HealthStatus,
+}
+
 #[derive(Debug, EncodeLabelSet)]
 pub(crate) struct AppHealthCheckConfig {
     #[metrics(unit = Unit::Seconds)]
@@ -38,6 +46,8 @@ pub(crate) struct HealthMetrics {
     /// skips normal checks.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     abnormal_check_latency: Family<AbnormalCheckLabels, Histogram<Duration>>,
+    /// Number of health status transitions by component and the new status.
+    status_transitions: Family<TransitionLabels, Counter>,
 }
 
 impl HealthMetrics {
@@ -50,6 +60,11 @@ impl HealthMetrics {
         let labels = AbnormalCheckLabels { component, result };
         self.abnormal_check_latency[&labels].observe(duration);
     }
+
+    pub fn observe_transition(&self, component: &'static str, status: HealthStatus) {
+        let labels = TransitionLabels { component, status };
+        self.status_transitions[&labels].inc();
+    }
 }
 
 #[vise::register]
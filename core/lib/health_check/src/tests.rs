@@ -148,3 +148,82 @@ fn adding_duplicate_component() {
         .unwrap_err();
     assert_matches!(err, AppHealthCheckError::RedefinedComponent("test"));
 }
+
+#[tokio::test]
+async fn checking_selected_components() {
+    let (first_check, first_updater) = ReactiveHealthCheck::new("first");
+    let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+    let checks = AppHealthCheck {
+        components: Mutex::new(vec![Arc::new(first_check), Arc::new(second_check)]),
+        ..AppHealthCheck::default()
+    };
+    first_updater.update(HealthStatus::Ready.into());
+
+    let app_health = checks.check_selected_health(&["first".to_owned()]).await;
+    assert!(app_health.is_healthy());
+    assert_eq!(app_health.components.len(), 1);
+    assert_matches!(app_health.components["first"].status, HealthStatus::Ready);
+
+    let app_health = checks.check_selected_health(&[]).await;
+    assert!(!app_health.is_healthy());
+    assert!(app_health.is_alive());
+    assert_eq!(app_health.components.len(), 2);
+
+    // Unknown components are considered not ready.
+    let components = ["first".to_owned(), "unknown".to_owned()];
+    let app_health = checks.check_selected_health(&components).await;
+    assert!(!app_health.is_healthy());
+    assert_matches!(app_health.inner.status(), HealthStatus::NotReady);
+
+    let health = checks.check_component_health("first").await.unwrap();
+    assert_matches!(health.status(), HealthStatus::Ready);
+    assert!(checks.check_component_health("unknown").await.is_none());
+
+    drop(second_updater);
+    let app_health = checks.check_selected_health(&[]).await;
+    assert!(!app_health.is_alive());
+    let app_health = checks.check_selected_health(&["first".to_owned()]).await;
+    assert!(app_health.is_alive());
+}
+
+#[tokio::test]
+async fn recording_health_history() {
+    let checks = AppHealthCheck::default();
+    let (health_check, health_updater) = ReactiveHealthCheck::new("reactive");
+    checks.insert_component(health_check).unwrap();
+
+    // Reactive components report transitions even if the health isn't checked in between.
+    health_updater.update(HealthStatus::Ready.into());
+    health_updater.update(HealthStatus::Affected.into());
+    health_updater.update(HealthStatus::Ready.into());
+
+    let history = checks.health_history();
+    let statuses: Vec<_> = history["reactive"]
+        .iter()
+        .map(|transition| (transition.from, transition.to))
+        .collect();
+    assert_eq!(
+        statuses,
+        [
+            (HealthStatus::NotReady, HealthStatus::Ready),
+            (HealthStatus::Ready, HealthStatus::Affected),
+            (HealthStatus::Affected, HealthStatus::Ready),
+        ]
+    );
+
+    // Custom components are tracked when their health is checked.
+    let (custom_check, custom_updater) = ReactiveHealthCheck::new("custom");
+    checks
+        .insert_custom_component(Arc::new(custom_check))
+        .unwrap();
+    checks.check_health().await;
+    custom_updater.update(HealthStatus::Ready.into());
+    checks.check_health().await;
+
+    let history = checks.health_history();
+    assert_eq!(history["reactive"].len(), 3);
+    let custom_history = &history["custom"];
+    assert_eq!(custom_history.len(), 1);
+    assert_eq!(custom_history[0].from, HealthStatus::NotReady);
+    assert_eq!(custom_history[0].to, HealthStatus::Ready);
+}
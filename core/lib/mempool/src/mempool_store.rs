@@ -1,7 +1,7 @@
 use std::collections::{hash_map, BTreeSet, HashMap, HashSet};
 
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
+    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction, H256,
 };
 
 use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
@@ -205,6 +205,38 @@ impl MempoolStore {
         }
     }
 
+    /// Removes an L2 transaction with the specified hash from the mempool. Returns the removed transaction,
+    /// or `None` if the transaction is not in the mempool.
+    ///
+    /// Transactions from the same account with greater nonces are retained, but they won't be returned
+    /// from [`Self::next_transaction()`] until the nonce gap is filled.
+    pub fn remove_l2_transaction(&mut self, hash: H256) -> Option<L2Tx> {
+        let (transaction, score) = self
+            .l2_transactions_per_account
+            .values_mut()
+            .find_map(|transactions| transactions.remove(hash))?;
+        if let Some(score) = score {
+            self.l2_priority_queue.remove(&score);
+        }
+        self.size = self
+            .size
+            .checked_sub(1)
+            .expect("mempool size can't be negative");
+        Some(transaction)
+    }
+
+    /// Iterates over pending L1 transactions in no particular order.
+    pub fn l1_transactions(&self) -> impl Iterator<Item = &L1Tx> + '_ {
+        self.l1_transactions.values()
+    }
+
+    /// Iterates over pending L2 transactions in no particular order.
+    pub fn l2_transactions(&self) -> impl Iterator<Item = &L2Tx> + '_ {
+        self.l2_transactions_per_account
+            .values()
+            .flat_map(AccountTransactions::iter)
+    }
+
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
         MempoolInfo {
             stashed_accounts: std::mem::take(&mut self.stashed_accounts),
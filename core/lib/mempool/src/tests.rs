@@ -365,6 +365,56 @@ fn mempool_capacity() {
     );
 }
 
+#[test]
+fn removing_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions: Vec<_> = [(account0, 0), (account0, 1), (account1, 0)]
+        .into_iter()
+        .map(|(account, nonce)| {
+            let mut tx = gen_l2_tx(account, Nonce(nonce));
+            set_random_hash(&mut tx);
+            tx
+        })
+        .collect();
+    let hashes: Vec<_> = transactions.iter().map(Transaction::hash).collect();
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(mempool.l2_transactions().count(), 3);
+
+    assert!(mempool
+        .remove_l2_transaction(H256::repeat_byte(0xfe))
+        .is_none());
+    let removed = mempool.remove_l2_transaction(hashes[0]).unwrap();
+    assert_eq!(removed.hash(), hashes[0]);
+    assert!(mempool.remove_l2_transaction(hashes[0]).is_none());
+    assert_eq!(mempool.stats().l2_transaction_count, 2);
+    assert_eq!(mempool.l2_transactions().count(), 2);
+
+    // The remaining transaction from `account0` cannot be executed because of the nonce gap.
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account1, 0)
+    );
+    assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
+    mempool.insert(vec![gen_l2_tx(account0, Nonce(0))], HashMap::new());
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account0, 0)
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account0, 1)
+    );
+}
+
+fn set_random_hash(transaction: &mut Transaction) {
+    let ExecuteTransactionCommon::L2(data) = &mut transaction.common_data else {
+        unreachable!();
+    };
+    data.set_input(vec![], H256::random());
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
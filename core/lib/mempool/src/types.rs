@@ -1,7 +1,7 @@
 use std::{cmp::Ordering, collections::HashMap};
 
 use zksync_types::{
-    fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, Address, Nonce, Transaction, U256,
+    fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, Address, Nonce, Transaction, H256, U256,
 };
 
 /// Pending mempool transactions of account
@@ -70,10 +70,27 @@ impl AccountTransactions {
             .map(Self::score_for_transaction)
     }
 
+    /// Removes a transaction with the specified hash. Returns the removed transaction and its score
+    /// if the transaction was the next one to be executed for the account (i.e., its score is present in the priority queue).
+    pub fn remove(&mut self, hash: H256) -> Option<(L2Tx, Option<MempoolScore>)> {
+        let nonce = *self
+            .transactions
+            .iter()
+            .find(|(_, transaction)| transaction.hash() == hash)?
+            .0;
+        let transaction = self.transactions.remove(&nonce)?;
+        let score = (nonce == self.nonce).then(|| Self::score_for_transaction(&transaction));
+        Some((transaction, score))
+    }
+
     pub fn len(&self) -> usize {
         self.transactions.len()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &L2Tx> + '_ {
+        self.transactions.values()
+    }
+
     fn score_for_transaction(transaction: &L2Tx) -> MempoolScore {
         MempoolScore {
             account: transaction.initiator_account(),
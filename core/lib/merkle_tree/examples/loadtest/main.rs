@@ -37,6 +37,10 @@ struct Cli {
     /// Generate Merkle proofs for each operation.
     #[arg(name = "proofs", long)]
     proofs: bool,
+    /// Split each commit into subtrees processed in parallel (only applicable without proofs,
+    /// since proof generation is always parallelized).
+    #[arg(name = "parallel", long, conflicts_with = "proofs")]
+    parallel: bool,
     /// Additional number of reads of previously written keys per commit.
     #[arg(name = "reads", long, default_value = "0", requires = "proofs")]
     reads_per_commit: usize,
@@ -128,6 +132,8 @@ impl Cli {
         let mut tree = MerkleTree::with_hasher(db, hasher);
         let mut next_key_idx = 0_u64;
         let mut next_value_idx = 0_u64;
+        let mut total_ops = 0_u64;
+        let mut total_elapsed = Duration::ZERO;
         for version in 0..self.commit_count {
             let new_keys: Vec<_> = Self::generate_keys(next_key_idx..)
                 .take(self.writes_per_commit)
@@ -156,6 +162,9 @@ impl Cli {
                 let instructions = kvs.map(TreeInstruction::Write).chain(reads).collect();
                 let output = tree.extend_with_proofs(instructions);
                 output.root_hash().unwrap()
+            } else if self.parallel {
+                let output = tree.extend_in_parallel(kvs.collect());
+                output.root_hash
             } else {
                 let output = tree.extend(kvs.collect());
                 output.root_hash
@@ -172,8 +181,16 @@ impl Cli {
 
             let elapsed = start.elapsed();
             tracing::info!("Processed block #{version} in {elapsed:?}, root hash = {root_hash:?}");
+            total_ops += (self.writes_per_commit + self.updates_per_commit) as u64;
+            total_elapsed += elapsed;
         }
 
+        let throughput = total_ops as f64 / total_elapsed.as_secs_f64();
+        tracing::info!(
+            "Processed {total_ops} writes in {total_elapsed:?} ({throughput:.0} writes/s, parallel = {})",
+            self.parallel
+        );
+
         tracing::info!("Verifying tree consistency...");
         let start = Instant::now();
         tree.verify_consistency(self.commit_count - 1, false)
@@ -43,6 +43,7 @@ pub struct ZkSyncTree {
     tree: MerkleTree<Patched<RocksDBWrapper>>,
     thread_pool: Option<ThreadPool>,
     mode: TreeMode,
+    parallel_updates: bool,
     pruning_enabled: bool,
 }
 
@@ -95,6 +96,7 @@ impl ZkSyncTree {
             tree: MerkleTree::new(Patched::new(db)),
             thread_pool: None,
             mode,
+            parallel_updates: false,
             pruning_enabled: false,
         }
     }
@@ -146,6 +148,13 @@ impl ZkSyncTree {
         self.thread_pool = Some(Self::create_thread_pool(thread_count));
     }
 
+    /// Signals that the tree should process L1 batches in the lightweight mode by splitting them
+    /// into subtrees updated in parallel (see [`MerkleTree::extend_in_parallel()`]). This has no effect
+    /// in the full mode, which always parallelizes updates.
+    pub fn set_parallel_updates(&mut self, parallel_updates: bool) {
+        self.parallel_updates = parallel_updates;
+    }
+
     /// Returns the current root hash of this tree.
     pub fn root_hash(&self) -> ValueHash {
         self.tree.latest_root_hash()
@@ -298,10 +307,19 @@ impl ZkSyncTree {
             .map(|entry| entry.map_key(StorageKey::hashed_key_u256))
             .collect();
 
+        let parallel_updates = self.parallel_updates;
+        let tree = &mut self.tree;
+        let extend = move || {
+            if parallel_updates {
+                tree.extend_in_parallel(kvs_with_derived_key)
+            } else {
+                tree.extend(kvs_with_derived_key)
+            }
+        };
         let output = if let Some(thread_pool) = &self.thread_pool {
-            thread_pool.install(|| self.tree.extend(kvs_with_derived_key.clone()))
+            thread_pool.install(extend)
         } else {
-            self.tree.extend(kvs_with_derived_key.clone())
+            extend()
         };
 
         tracing::info!(
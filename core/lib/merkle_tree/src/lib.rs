@@ -222,6 +222,22 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
         output
     }
 
+    /// Extends this tree by creating its new version, similarly to [`Self::extend()`]. Unlike `extend()`,
+    /// this method splits `entries` by the first key nibble and loads, updates and hashes
+    /// the corresponding subtrees in parallel using `rayon`. The output and the resulting tree
+    /// are the same as for `extend()`.
+    ///
+    /// # Return value
+    ///
+    /// Returns information about the update such as the final tree hash.
+    pub fn extend_in_parallel(&mut self, entries: Vec<TreeEntry>) -> BlockOutput {
+        let next_version = self.db.manifest().unwrap_or_default().version_count;
+        let storage = Storage::new(&self.db, &self.hasher, next_version, true);
+        let (output, patch) = storage.extend_in_parallel(entries);
+        self.db.apply_patch(patch);
+        output
+    }
+
     /// Extends this tree by creating its new version, computing an authenticity Merkle proof
     /// for each provided instruction.
     ///
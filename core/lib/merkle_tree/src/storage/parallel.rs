@@ -0,0 +1,177 @@
+//! Parallelized version of the lightweight tree operation mode.
+//!
+//! # How it works
+//!
+//! Similarly to [the full operation mode](super::proofs), entries are split by the first key nibble
+//! into 16 groups, each of which is applied to a separate subtree with the root at level 4.
+//! Unlike the full mode, all stages of the update are parallelized:
+//!
+//! 1. Ancestor nodes are loaded from the database independently for each subtree.
+//! 2. Entries are inserted into each subtree.
+//! 3. Hashes of the changed nodes are computed for each subtree up to the subtree root.
+//!
+//! Since subtrees are disjoint, the only shared node is the root one, which is copied
+//! to each group. After processing the groups, the root node is assembled from the subtree
+//! child references and hashes, and is hashed separately.
+//!
+//! The resulting patch set is identical to the one produced by [`Storage::extend()`].
+//! The only nuance is that the root must be an internal node for the split to work;
+//! if the root is a leaf, it is converted to an internal node beforehand and converted back
+//! after the update if necessary.
+
+use rayon::prelude::*;
+
+use crate::{
+    metrics::{HashingStats, BLOCK_TIMINGS, GENERAL_METRICS},
+    storage::{proofs::SUBTREE_COUNT, PatchSet, SortedKeys, Storage, TreeUpdater},
+    types::{BlockOutput, Nibbles, Node, TreeEntry, TreeLogEntry},
+    utils::merge_by_index,
+    Database,
+};
+
+/// [`TreeEntry`] together with its 0-based index in the block.
+#[derive(Debug)]
+struct EntryWithIndex {
+    index: usize,
+    entry: TreeEntry,
+}
+
+impl EntryWithIndex {
+    /// Creates groups of entries to be used during parallelized tree update.
+    fn split(entries: Vec<TreeEntry>) -> [Vec<Self>; SUBTREE_COUNT] {
+        const EMPTY_VEC: Vec<EntryWithIndex> = Vec::new();
+        // ^ Need to extract this to a constant to be usable as an array initializer.
+
+        let mut parts = [EMPTY_VEC; SUBTREE_COUNT];
+        for (index, entry) in entries.into_iter().enumerate() {
+            let first_nibble = Nibbles::nibble(&entry.key, 0);
+            parts[first_nibble as usize].push(Self { index, entry });
+        }
+        parts
+    }
+}
+
+impl<'a, DB: Database + ?Sized> Storage<'a, DB> {
+    /// Extends the Merkle tree in the lightweight operation mode, processing subtrees
+    /// in parallel using `rayon`.
+    pub fn extend_in_parallel(mut self, entries: Vec<TreeEntry>) -> (BlockOutput, PatchSet) {
+        let load_nodes_latency = BLOCK_TIMINGS.load_nodes.start();
+        let has_internal_root = matches!(
+            self.updater.patch_set.get(&Nibbles::EMPTY),
+            Some(Node::Internal(_))
+        );
+        // If the root is not an internal node, there is nothing to load.
+        let initial_root = self.updater.patch_set.ensure_internal_root_node();
+        let initial_metrics = self.updater.metrics;
+        let entry_parts = EntryWithIndex::split(entries);
+        let storage_parts = self.updater.split();
+
+        let db = self.db;
+        let parts =
+            storage_parts
+                .into_par_iter()
+                .zip_eq(entry_parts)
+                .map(|(mut storage, entries)| {
+                    let parent_nibbles = if has_internal_root {
+                        let sorted_keys =
+                            SortedKeys::new(entries.iter().map(|entry| entry.entry.key));
+                        storage.load_ancestors(&sorted_keys, db)
+                    } else {
+                        vec![Nibbles::EMPTY; entries.len()]
+                    };
+                    (storage, entries, parent_nibbles)
+                });
+        let parts: Vec<_> = parts.collect();
+        let load_nodes_latency = load_nodes_latency.observe();
+        tracing::debug!("Load stage took {load_nodes_latency:?}");
+
+        let extend_patch_latency = BLOCK_TIMINGS.extend_patch.start();
+        let parts = parts
+            .into_par_iter()
+            .map(|(mut storage, entries, parent_nibbles)| {
+                let logs = entries.into_iter().zip(parent_nibbles);
+                let logs = logs.map(|(EntryWithIndex { index, entry }, parent_nibbles)| {
+                    let (log, _) = storage.insert(entry, &parent_nibbles);
+                    (index, log)
+                });
+                let logs: Vec<_> = logs.collect();
+                (storage, logs)
+            });
+        let (storage_parts, logs): (Vec<_>, Vec<_>) = parts.unzip();
+        let extend_patch_latency = extend_patch_latency.observe();
+        tracing::debug!("Tree traversal stage took {extend_patch_latency:?}");
+
+        let finalize_patch_latency = BLOCK_TIMINGS.finalize_patch.start();
+        let hashing_stats = HashingStats::default();
+        let hasher = self.hasher;
+        let (storage_parts, subtree_hashes): (Vec<_>, Vec<_>) = storage_parts
+            .into_par_iter()
+            .map_init(
+                || hasher.with_stats(&hashing_stats),
+                |hasher, mut storage| {
+                    let subtree_hash = storage.patch_set.hash_subtree(hasher);
+                    (storage, subtree_hash)
+                },
+            )
+            .unzip();
+
+        // Assemble the root node from child references in the subtree parts.
+        let mut root = initial_root;
+        let it = storage_parts.iter().zip(subtree_hashes).enumerate();
+        for (subtree_idx, (storage, subtree_hash)) in it {
+            let nibble = u8::try_from(subtree_idx).unwrap();
+            let Some(child_ref) = storage.patch_set.child_ref(&Nibbles::EMPTY, nibble) else {
+                continue;
+            };
+            let mut child_ref = *child_ref;
+            if let Some(subtree_hash) = subtree_hash {
+                child_ref.hash = subtree_hash;
+            }
+            root.insert_child_ref(nibble, child_ref);
+        }
+
+        self.updater = storage_parts
+            .into_iter()
+            .reduce(TreeUpdater::merge)
+            .unwrap();
+        // ^ `unwrap()` is safe: `storage_parts` is non-empty
+        self.updater.metrics += initial_metrics;
+        self.updater.patch_set.set_merged_root_node(root);
+
+        let logs = merge_by_index(logs);
+        let logs: Vec<_> = logs.into_iter().map(|(_, log)| log).collect();
+        let inserted_count = logs
+            .iter()
+            .filter(|log| matches!(log, TreeLogEntry::Inserted))
+            .count();
+        self.leaf_count += inserted_count as u64;
+        tracing::debug!(
+            "Finished updating tree; total leaf count: {}, stats: {:?}",
+            self.leaf_count,
+            self.updater.metrics
+        );
+        self.updater.metrics.report();
+
+        let mut hasher = hasher.with_stats(&hashing_stats);
+        let (root_hash, patch) = self.updater.patch_set.finalize_with_hashed_subtrees(
+            self.manifest,
+            self.leaf_count,
+            &mut hasher,
+        );
+        drop(hasher);
+        GENERAL_METRICS.leaf_count.set(self.leaf_count);
+        let finalize_patch_latency = finalize_patch_latency.observe();
+        tracing::debug!(
+            "Tree finalization stage took {finalize_patch_latency:?}; hashed {:?}B",
+            hashing_stats.hashed_bytes
+        );
+        hashing_stats.report();
+
+        let output = BlockOutput {
+            root_hash,
+            leaf_count: self.leaf_count,
+            logs,
+        };
+        (output, patch)
+    }
+}
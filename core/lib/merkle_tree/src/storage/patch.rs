@@ -11,7 +11,10 @@ use rayon::prelude::*;
 use crate::{
     hasher::{HashTree, HasherWithStats, MerklePath},
     metrics::HashingStats,
-    storage::{proofs::SUBTREE_COUNT, Operation, SortedKeys, TraverseOutcome},
+    storage::{
+        proofs::{SUBTREE_COUNT, SUBTREE_ROOT_LEVEL},
+        Operation, SortedKeys, TraverseOutcome,
+    },
     types::{
         ChildRef, InternalNode, Key, LeafNode, Manifest, Nibbles, NibblesBytes, Node, NodeKey,
         Root, ValueHash, KEY_SIZE,
@@ -344,6 +347,88 @@ impl WorkingPatchSet {
         }
     }
 
+    /// Computes hashes for all changed nodes in a subtree part produced by [`Self::split()`]
+    /// and stores them in the child references of parent nodes. The root node (which is shared
+    /// among all parts) is not touched; instead, the hash of the subtree root at level 4 is returned
+    /// if the subtree was changed.
+    pub(super) fn hash_subtree(&mut self, hasher: &mut HasherWithStats<'_>) -> Option<ValueHash> {
+        let levels = &mut self.changes_by_nibble_count;
+        for nibble_count in (2..levels.len()).rev() {
+            let (upper_levels, lower_levels) = levels.split_at_mut(nibble_count);
+            let parent_level = upper_levels.last_mut().unwrap();
+            // ^ `unwrap()` is safe: `nibble_count >= 2`
+            let tree_level = nibble_count * 4;
+            for (nibbles, node) in &lower_levels[0] {
+                let nibbles = Nibbles::from_parts(*nibbles, nibble_count);
+                let node_hash = node.inner.hash(hasher, tree_level);
+                let (parent_nibbles, last_nibble) = nibbles.split_last().unwrap();
+                let parent = parent_level.get_mut(parent_nibbles.bytes()).unwrap();
+                let Node::Internal(parent) = &mut parent.inner else {
+                    unreachable!("Node parent must be an internal node");
+                };
+                parent.child_ref_mut(last_nibble).unwrap().hash = node_hash;
+                // ^ `unwrap()`s are safe by construction, similarly to `Self::finalize_inner()`
+            }
+        }
+
+        let subtree_level = levels.get(1)?;
+        debug_assert!(subtree_level.len() <= 1, "Patch set is not a subtree part");
+        let subtree_root = subtree_level.values().next()?;
+        Some(subtree_root.inner.hash(hasher, SUBTREE_ROOT_LEVEL))
+    }
+
+    /// Sets the root node after merging subtree parts. If the root has a single leaf child
+    /// (which can only happen if a leaf root was converted by [`Self::ensure_internal_root_node()`]),
+    /// the leaf is moved back to the root so that the tree layout is the same as if the update
+    /// was performed without splitting.
+    pub(super) fn set_merged_root_node(&mut self, root: InternalNode) {
+        if root.child_count() == 0 {
+            // We cannot save the empty internal root node because it'll fail deserialization
+            // checks later.
+            self.take_root();
+            return;
+        }
+
+        let (nibble, child_ref) = root.last_child_ref();
+        if root.child_count() == 1 && child_ref.is_leaf {
+            let leaf_nibbles = Nibbles::single(nibble);
+            let leaf = self.changes_by_nibble_count[1]
+                .remove(leaf_nibbles.bytes())
+                .unwrap();
+            // ^ `unwrap()` is safe: the leaf must have been moved by `ensure_internal_root_node()`
+            debug_assert!(leaf.prev_version.is_none());
+            self.insert(Nibbles::EMPTY, leaf.inner);
+        } else {
+            self.insert(Nibbles::EMPTY, root.into());
+        }
+    }
+
+    /// Serializes this change set, assuming that hashes of all non-root nodes are already computed
+    /// with [`Self::hash_subtree()`]. Only the root node is hashed.
+    pub(super) fn finalize_with_hashed_subtrees(
+        self,
+        manifest: Manifest,
+        leaf_count: u64,
+        hasher: &mut HasherWithStats<'_>,
+    ) -> (ValueHash, PatchSet) {
+        let (root_hash, patch) = self.finalize_inner(
+            manifest,
+            leaf_count,
+            Operation::Insert,
+            |nibble_count, level_changes| {
+                let level_changes = level_changes.into_iter();
+                let hashed_nodes = level_changes.map(|(nibbles, node)| {
+                    let nibbles = Nibbles::from_parts(nibbles, nibble_count);
+                    let node_hash = (nibble_count == 0).then(|| node.inner.hash(hasher, 0));
+                    (nibbles, node_hash, node)
+                });
+                hashed_nodes.collect::<Vec<_>>()
+            },
+        );
+        let root_hash = root_hash.unwrap_or_else(|| hasher.as_ref().empty_tree_hash());
+        (root_hash, patch)
+    }
+
     /// Computes hashes and serializes this change set.
     pub(super) fn finalize(
         self,
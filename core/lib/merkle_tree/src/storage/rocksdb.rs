@@ -156,6 +156,16 @@ impl RocksDBWrapper {
         })
     }
 
+    /// Creates a RocksDB checkpoint of the tree at the specified directory. The checkpoint reflects
+    /// all changes persisted by the tree so far and can be opened as an ordinary tree database.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB I/O errors, e.g. if the `path` already exists.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), rocksdb::Error> {
+        self.db.create_checkpoint(path)
+    }
+
     /// Returns the wrapped RocksDB instance.
     pub fn into_inner(self) -> RocksDB<MerkleTreeColumnFamily> {
         self.db
@@ -808,3 +808,80 @@ fn recovery_pruning_equivalence(
         test_recovery_pruning_equivalence(kind, chunk_size, recovery_chunk_size, hasher);
     }
 }
+
+fn assert_parallel_extend_equivalence(
+    database: &mut PatchSet,
+    hasher: &dyn HashTree,
+    version: u64,
+    entries: Vec<TreeEntry>,
+) {
+    let storage = Storage::new(&*database, hasher, version, true);
+    let (expected_output, expected_patch) = storage.extend(entries.clone());
+    let storage = Storage::new(&*database, hasher, version, true);
+    let (output, patch) = storage.extend_in_parallel(entries);
+    assert_eq!(output, expected_output);
+
+    assert_eq!(patch.manifest, expected_patch.manifest);
+    assert_eq!(patch.updated_version, expected_patch.updated_version);
+    assert_eq!(patch.patches_by_version.len(), 1);
+    let sub_patch = &patch.patches_by_version[&version];
+    let expected_sub_patch = &expected_patch.patches_by_version[&version];
+    assert_eq!(sub_patch.root, expected_sub_patch.root);
+    assert_eq!(sub_patch.nodes, expected_sub_patch.nodes);
+
+    let stale_keys: HashSet<_> = patch.stale_keys_by_version[&version].iter().collect();
+    let expected_stale_keys: HashSet<_> = expected_patch.stale_keys_by_version[&version]
+        .iter()
+        .collect();
+    assert_eq!(stale_keys, expected_stale_keys);
+
+    database.apply_patch(patch);
+}
+
+#[test]
+fn parallel_extend_with_leaf_root() {
+    let mut database = PatchSet::default();
+    let blocks = [
+        vec![],
+        vec![TreeEntry::new(FIRST_KEY, 1, H256::zero())],
+        vec![TreeEntry::new(FIRST_KEY, 1, H256::repeat_byte(1))],
+        vec![],
+        vec![TreeEntry::new(SECOND_KEY, 2, H256::zero())],
+        vec![
+            TreeEntry::new(E_KEY, 3, H256::zero()),
+            TreeEntry::new(THIRD_KEY, 4, H256::zero()),
+        ],
+    ];
+    for (version, entries) in blocks.into_iter().enumerate() {
+        assert_parallel_extend_equivalence(&mut database, &Blake2Hasher, version as u64, entries);
+    }
+}
+
+#[test_casing(8, test_casing::Product((HASHERS, [1, 10, 50, 200])))]
+fn parallel_extend_equivalence(hasher: &'static dyn HashTree, writes_per_block: usize) {
+    const RNG_SEED: u64 = 42;
+
+    let mut rng = StdRng::seed_from_u64(RNG_SEED);
+    let mut database = PatchSet::default();
+    let mut keys = vec![];
+    for version in 0..20 {
+        let updated_keys = keys
+            .iter()
+            .copied()
+            .enumerate()
+            .choose_multiple(&mut rng, writes_per_block / 2);
+        let updates = updated_keys
+            .into_iter()
+            .map(|(idx, key)| TreeEntry::new(key, idx as u64 + 1, H256(rng.gen())));
+        let mut entries: Vec<_> = updates.collect();
+
+        for _ in 0..writes_per_block {
+            let key = U256::from_big_endian(&rng.gen::<[u8; 32]>());
+            keys.push(key);
+            entries.push(TreeEntry::new(key, keys.len() as u64, H256(rng.gen())));
+        }
+        entries.shuffle(&mut rng);
+
+        assert_parallel_extend_equivalence(&mut database, hasher, version, entries);
+    }
+}
@@ -5,6 +5,7 @@ use std::slice;
 use serde::{Deserialize, Serialize};
 use serde_with::{hex::Hex, serde_as};
 use tempfile::TempDir;
+use test_casing::test_casing;
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_merkle_tree::{domain::ZkSyncTree, HashTree, TreeEntry, TreeInstruction};
 use zksync_prover_interface::inputs::StorageLogMetadata;
@@ -70,8 +71,8 @@ fn basic_workflow() {
     assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(1));
 }
 
-#[test]
-fn basic_workflow_multiblock() {
+#[test_casing(2, [false, true])]
+fn basic_workflow_multiblock(parallel: bool) {
     let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
     let logs = gen_storage_logs();
     let blocks = logs.chunks(9);
@@ -80,6 +81,7 @@ fn basic_workflow_multiblock() {
         let db = RocksDB::new(temp_dir.as_ref()).unwrap();
         let mut tree = ZkSyncTree::new_lightweight(db.into());
         tree.use_dedicated_thread_pool(2);
+        tree.set_parallel_updates(parallel);
         for block in blocks {
             tree.process_l1_batch(block);
         }
@@ -6,8 +6,8 @@ use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use test_casing::test_casing;
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_merkle_tree::{
-    Database, HashTree, MerkleTree, PatchSet, Patched, TreeEntry, TreeInstruction, TreeLogEntry,
-    TreeRangeDigest,
+    BlockOutput, Database, HashTree, MerkleTree, PatchSet, Patched, TreeEntry, TreeInstruction,
+    TreeLogEntry, TreeRangeDigest,
 };
 use zksync_types::{AccountTreeId, Address, StorageKey, H256, U256};
 
@@ -174,12 +174,24 @@ fn proofs_are_computed_correctly_for_missing_keys() {
         .unwrap();
 }
 
-fn test_intermediate_commits(db: &mut impl Database, chunk_size: usize) {
+fn extend_tree<DB: Database>(
+    tree: &mut MerkleTree<DB>,
+    entries: Vec<TreeEntry>,
+    parallel: bool,
+) -> BlockOutput {
+    if parallel {
+        tree.extend_in_parallel(entries)
+    } else {
+        tree.extend(entries)
+    }
+}
+
+fn test_intermediate_commits(db: &mut impl Database, chunk_size: usize, parallel: bool) {
     let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
     let mut final_hash = H256::zero();
     let mut tree = MerkleTree::new(db);
     for chunk in kvs.chunks(chunk_size) {
-        let output = tree.extend(chunk.to_vec());
+        let output = extend_tree(&mut tree, chunk.to_vec(), parallel);
         final_hash = output.root_hash;
     }
     assert_eq!(final_hash, *expected_hash);
@@ -190,9 +202,9 @@ fn test_intermediate_commits(db: &mut impl Database, chunk_size: usize) {
     }
 }
 
-#[test_casing(6, [3, 5, 10, 17, 28, 42])]
-fn root_hash_is_computed_correctly_with_intermediate_commits(chunk_size: usize) {
-    test_intermediate_commits(&mut PatchSet::default(), chunk_size);
+#[test_casing(12, test_casing::Product(([3, 5, 10, 17, 28, 42], [false, true])))]
+fn root_hash_is_computed_correctly_with_intermediate_commits(chunk_size: usize, parallel: bool) {
+    test_intermediate_commits(&mut PatchSet::default(), chunk_size, parallel);
 }
 
 #[test_casing(6, [3, 5, 10, 17, 28, 42])]
@@ -420,7 +432,7 @@ fn proofs_are_computed_correctly_with_key_updates(updated_keys: usize) {
 }
 
 // Taken from the integration tests for the previous tree implementation.
-fn test_root_hash_equals_to_previous_implementation(db: &mut impl Database) {
+fn test_root_hash_equals_to_previous_implementation(db: &mut impl Database, parallel: bool) {
     const PREV_IMPL_HASH: H256 = H256([
         125, 25, 107, 171, 182, 155, 32, 70, 138, 108, 238, 150, 140, 205, 193, 39, 90, 92, 122,
         233, 118, 238, 248, 201, 160, 55, 58, 206, 244, 216, 188, 10,
@@ -453,15 +465,15 @@ fn test_root_hash_equals_to_previous_implementation(db: &mut impl Database) {
 
     let mut tree = MerkleTree::new(db);
     assert!(tree.latest_version().is_none());
-    let output = tree.extend(kvs);
+    let output = extend_tree(&mut tree, kvs, parallel);
     assert_eq!(output.root_hash, PREV_IMPL_HASH);
     assert_eq!(tree.latest_version(), Some(0));
     assert_eq!(tree.root_hash(0), Some(PREV_IMPL_HASH));
 }
 
-#[test]
-fn root_hash_equals_to_previous_implementation() {
-    test_root_hash_equals_to_previous_implementation(&mut PatchSet::default());
+#[test_casing(2, [false, true])]
+fn root_hash_equals_to_previous_implementation(parallel: bool) {
+    test_root_hash_equals_to_previous_implementation(&mut PatchSet::default(), parallel);
 }
 
 #[test_casing(7, [2, 3, 5, 10, 17, 28, 42])]
@@ -591,13 +603,14 @@ mod rocksdb {
         }
     }
 
-    #[test]
-    fn root_hash_equals_to_previous_implementation() {
+    #[test_casing(2, [false, true])]
+    fn root_hash_equals_to_previous_implementation(parallel: bool) {
         let mut harness = Harness::new();
-        test_root_hash_equals_to_previous_implementation(&mut harness.db);
+        test_root_hash_equals_to_previous_implementation(&mut harness.db, parallel);
 
         // Snapshot the DB storage to ensure its backward compatibility. This works because
-        // the storage contents is fully deterministic.
+        // the storage contents is fully deterministic. Parallel updates must produce
+        // the same storage contents, so they share the snapshot.
         let raw_db = harness.db.into_inner();
         insta::assert_yaml_snapshot!("db-snapshot", DatabaseSnapshot::new(&raw_db));
     }
@@ -608,10 +621,13 @@ mod rocksdb {
         test_root_hash_computing_with_key_updates(harness.db);
     }
 
-    #[test_casing(3, [3, 8, 21])]
-    fn root_hash_is_computed_correctly_with_intermediate_commits(chunk_size: usize) {
+    #[test_casing(6, test_casing::Product(([3, 8, 21], [false, true])))]
+    fn root_hash_is_computed_correctly_with_intermediate_commits(
+        chunk_size: usize,
+        parallel: bool,
+    ) {
         let Harness { mut db, dir: _dir } = Harness::new();
-        test_intermediate_commits(&mut db, chunk_size);
+        test_intermediate_commits(&mut db, chunk_size, parallel);
 
         let raw_db = db.into_inner();
         let snapshot_name = format!("db-snapshot-{chunk_size}-chunked-commits");
@@ -621,7 +637,7 @@ mod rocksdb {
     #[test_casing(3, [3, 8, 21])]
     fn snapshot_for_pruned_tree(chunk_size: usize) {
         let Harness { mut db, dir: _dir } = Harness::new();
-        test_intermediate_commits(&mut db, chunk_size);
+        test_intermediate_commits(&mut db, chunk_size, false);
         let (mut pruner, _handle) = MerkleTreePruner::new(&mut db);
         pruner.prune_up_to(pruner.last_prunable_version().unwrap());
 
@@ -7,50 +7,101 @@ use zksync_mini_merkle_tree::MiniMerkleTree;
 
 const TREE_SIZES: &[usize] = &[32, 64, 128, 256, 512, 1_024];
 
+fn leaves(tree_size: usize) -> impl Iterator<Item = [u8; 88]> + Clone {
+    (0..tree_size).map(|i| [i as u8; 88])
+}
+
 fn compute_merkle_root(bencher: &mut Bencher<'_>, tree_size: usize) {
-    let leaves = (0..tree_size).map(|i| [i as u8; 88]);
-    let tree = MiniMerkleTree::new(leaves, None);
-    bencher.iter_batched(
-        || tree.clone(),
-        MiniMerkleTree::merkle_root,
-        BatchSize::SmallInput,
-    );
+    bencher.iter(|| MiniMerkleTree::new(leaves(tree_size), None).merkle_root());
+}
+
+fn compute_cached_merkle_root(bencher: &mut Bencher<'_>, tree_size: usize) {
+    let tree = MiniMerkleTree::new(leaves(tree_size), None);
+    bencher.iter(|| tree.merkle_root());
 }
 
 fn compute_merkle_path(bencher: &mut Bencher<'_>, tree_size: usize) {
-    let leaves = (0..tree_size).map(|i| [i as u8; 88]);
-    let tree = MiniMerkleTree::new(leaves, None);
+    bencher
+        .iter(|| MiniMerkleTree::new(leaves(tree_size), None).merkle_root_and_path(tree_size / 3));
+}
+
+fn compute_cached_merkle_path(bencher: &mut Bencher<'_>, tree_size: usize) {
+    let tree = MiniMerkleTree::new(leaves(tree_size), None);
+    bencher.iter(|| tree.merkle_root_and_path(tree_size / 3));
+}
+
+fn compute_all_merkle_paths(bencher: &mut Bencher<'_>, tree_size: usize) {
+    let tree = MiniMerkleTree::new(leaves(tree_size), None);
+    bencher.iter(|| tree.merkle_root_and_paths(0..tree_size));
+}
+
+fn push_leaf_and_compute_merkle_root(bencher: &mut Bencher<'_>, tree_size: usize) {
+    let tree = MiniMerkleTree::new(leaves(tree_size), None);
     bencher.iter_batched(
         || tree.clone(),
-        |tree| tree.merkle_root_and_path(tree_size / 3),
+        |mut tree| {
+            tree.push([0; 88]);
+            tree.merkle_root()
+        },
         BatchSize::SmallInput,
     );
 }
 
-fn basic_benches(criterion: &mut Criterion) {
-    let mut merkle_root_benches = criterion.benchmark_group("merkle_root");
-    for &tree_size in TREE_SIZES {
-        merkle_root_benches
-            .bench_with_input(
-                BenchmarkId::new("tree_size", tree_size),
-                &tree_size,
-                |bencher, &tree_size| compute_merkle_root(bencher, tree_size),
-            )
-            .throughput(Throughput::Elements(tree_size as u64));
-    }
-    merkle_root_benches.finish();
+fn rebuild_and_compute_merkle_root(bencher: &mut Bencher<'_>, tree_size: usize) {
+    bencher.iter(|| {
+        let leaves = leaves(tree_size).chain([[0; 88]]);
+        MiniMerkleTree::new(leaves, None).merkle_root()
+    });
+}
+
+type BenchFn = fn(&mut Bencher<'_>, usize);
 
-    let mut merkle_path_benches = criterion.benchmark_group("merkle_path");
+fn bench_group(criterion: &mut Criterion, name: &str, benches: &[(&str, BenchFn)]) {
+    let mut group = criterion.benchmark_group(name);
     for &tree_size in TREE_SIZES {
-        merkle_path_benches
-            .bench_with_input(
-                BenchmarkId::new("tree_size", tree_size),
-                &tree_size,
-                |bencher, &tree_size| compute_merkle_path(bencher, tree_size),
-            )
-            .throughput(Throughput::Elements(tree_size as u64));
+        for &(bench_name, bench_fn) in benches {
+            group
+                .bench_with_input(
+                    BenchmarkId::new(bench_name, tree_size),
+                    &tree_size,
+                    |bencher, &tree_size| bench_fn(bencher, tree_size),
+                )
+                .throughput(Throughput::Elements(tree_size as u64));
+        }
     }
-    merkle_path_benches.finish();
+    group.finish();
+}
+
+fn basic_benches(criterion: &mut Criterion) {
+    bench_group(
+        criterion,
+        "merkle_root",
+        &[
+            ("rebuilt", compute_merkle_root),
+            ("cached", compute_cached_merkle_root),
+        ],
+    );
+    bench_group(
+        criterion,
+        "merkle_path",
+        &[
+            ("rebuilt", compute_merkle_path),
+            ("cached", compute_cached_merkle_path),
+        ],
+    );
+    bench_group(
+        criterion,
+        "all_merkle_paths",
+        &[("cached", compute_all_merkle_paths)],
+    );
+    bench_group(
+        criterion,
+        "push",
+        &[
+            ("rebuilt", rebuild_and_compute_merkle_root),
+            ("incremental", push_leaf_and_compute_merkle_root),
+        ],
+    );
 }
 
 criterion_group!(benches, basic_benches);
@@ -5,7 +5,7 @@
 #![warn(clippy::all, clippy::pedantic)]
 #![allow(clippy::must_use_candidate, clippy::similar_names)]
 
-use std::iter;
+use std::{cmp, collections::VecDeque, iter};
 
 use once_cell::sync::Lazy;
 
@@ -19,15 +19,28 @@ use zksync_crypto::hasher::{keccak::KeccakHasher, Hasher};
 /// we unlikely to ever hit.
 const MAX_TREE_DEPTH: usize = 32;
 
-/// In-memory Merkle tree of bounded depth (no more than 10).
+/// In-memory Merkle tree of bounded depth (no more than 32).
 ///
 /// The tree is left-leaning, meaning that during its initialization, the size of a tree
 /// can be specified larger than the number of provided leaves. In this case, the remaining leaves
 /// will be considered to equal `[0_u8; LEAF_SIZE]`.
+///
+/// The tree is incremental: new leaves can be appended via [`Self::push()`], and hashes of internal nodes
+/// covering only filled leaves are cached, so that the root hash and Merkle paths are computed
+/// in `O(depth)` without rehashing the entire tree. Leaves from the start of the tree
+/// can be removed via [`Self::trim_start()`]; this retains the ability to compute the root hash
+/// and Merkle paths for the remaining leaves.
 #[derive(Debug, Clone)]
 pub struct MiniMerkleTree<const LEAF_SIZE: usize, H = KeccakHasher> {
     hasher: H,
-    hashes: Box<[H256]>,
+    /// Hashes of complete tree nodes (i.e., ones all leaves of which are filled) grouped by the tree level,
+    /// starting from leaves. Nodes at level `i` are stored starting from the index returned
+    /// by [`Self::first_retained_index()`].
+    levels: Vec<VecDeque<H256>>,
+    /// Total number of leaves in the tree, including trimmed ones.
+    leaf_count: usize,
+    /// Index of the first non-trimmed leaf.
+    start_index: usize,
     binary_tree_size: usize,
 }
 
@@ -62,92 +75,202 @@ where
     /// Panics if any of the following conditions applies:
     ///
     /// - `min_tree_size` (if supplied) is not a power of 2.
+    /// - The number of leaves is greater than `2^32`.
     pub fn with_hasher(
         hasher: H,
         leaves: impl Iterator<Item = [u8; LEAF_SIZE]>,
         min_tree_size: Option<usize>,
     ) -> Self {
-        let hashes: Box<[H256]> = leaves.map(|bytes| hasher.hash_bytes(&bytes)).collect();
-        let mut binary_tree_size = hashes.len().next_power_of_two();
-        if let Some(min_tree_size) = min_tree_size {
+        let binary_tree_size = min_tree_size.map_or(1, |min_tree_size| {
             assert!(
                 min_tree_size.is_power_of_two(),
                 "tree size must be a power of 2"
             );
-            binary_tree_size = min_tree_size.max(binary_tree_size);
-        }
+            min_tree_size
+        });
         assert!(
             tree_depth_by_size(binary_tree_size) <= MAX_TREE_DEPTH,
             "Tree contains more than {} items; this is not supported",
-            1 << MAX_TREE_DEPTH
+            1_u64 << MAX_TREE_DEPTH
         );
 
-        Self {
+        let mut this = Self {
             hasher,
-            hashes,
+            levels: vec![],
+            leaf_count: 0,
+            start_index: 0,
             binary_tree_size,
+        };
+        for leaf in leaves {
+            this.push(leaf);
         }
+        this
     }
 
-    /// Returns the root hash of this tree.
+    /// Returns the number of leaves in this tree, including trimmed ones.
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    /// Returns the index of the first leaf which was not trimmed via [`Self::trim_start()`].
+    pub fn start_index(&self) -> usize {
+        self.start_index
+    }
+
+    /// Checks whether this tree has no leaves.
+    pub fn is_empty(&self) -> bool {
+        self.leaf_count == 0
+    }
+
+    /// Appends a new leaf to the tree. If the tree is full, its size is doubled.
+    ///
     /// # Panics
-    /// Will panic if the constant below is invalid.
-    pub fn merkle_root(self) -> H256 {
-        if self.hashes.is_empty() {
-            let depth = tree_depth_by_size(self.binary_tree_size);
-            self.hasher.empty_subtree_hash(depth)
-        } else {
-            self.compute_merkle_root_and_path(0, None)
+    ///
+    /// Panics if the number of leaves becomes greater than `2^32`.
+    pub fn push(&mut self, leaf: [u8; LEAF_SIZE]) {
+        let mut hash = self.hasher.hash_bytes(&leaf);
+        let mut index = self.leaf_count;
+        self.leaf_count += 1;
+        if self.leaf_count > self.binary_tree_size {
+            self.binary_tree_size *= 2;
+            assert!(
+                tree_depth_by_size(self.binary_tree_size) <= MAX_TREE_DEPTH,
+                "Tree contains more than {} items; this is not supported",
+                1_u64 << MAX_TREE_DEPTH
+            );
+        }
+
+        // Cache all nodes that became complete after adding the leaf.
+        for level in 0.. {
+            if level == self.levels.len() {
+                self.levels.push(VecDeque::new());
+            }
+            let nodes = &mut self.levels[level];
+            nodes.push_back(hash);
+            if index % 2 == 0 {
+                break;
+            }
+            let left_sibling = nodes[nodes.len() - 2];
+            // ^ Indexing is safe: left siblings of complete nodes are never trimmed
+            hash = self.hasher.compress(&left_sibling, &hash);
+            index /= 2;
         }
     }
 
-    /// Returns the root hash and the Merkle proof for a leaf with the specified 0-based `index`.
-    pub fn merkle_root_and_path(self, index: usize) -> (H256, Vec<H256>) {
-        let mut merkle_path = vec![];
-        let root_hash = self.compute_merkle_root_and_path(index, Some(&mut merkle_path));
-        (root_hash, merkle_path)
+    /// Removes the specified number of leaves from the start of the tree. Trimmed leaves
+    /// are still accounted for in the root hash, but Merkle paths cannot be computed for them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` exceeds the number of non-trimmed leaves.
+    pub fn trim_start(&mut self, count: usize) {
+        assert!(
+            count <= self.leaf_count - self.start_index,
+            "cannot trim {count} leaves; the tree contains {} untrimmed leaves",
+            self.leaf_count - self.start_index
+        );
+
+        let prev_start_index = self.start_index;
+        self.start_index += count;
+        for (level, nodes) in self.levels.iter_mut().enumerate() {
+            let prev_first_index = Self::first_retained_index(prev_start_index, level);
+            let first_index = Self::first_retained_index(self.start_index, level);
+            nodes.drain(..first_index - prev_first_index);
+        }
     }
 
-    fn compute_merkle_root_and_path(
-        self,
-        mut index: usize,
-        mut merkle_path: Option<&mut Vec<H256>>,
-    ) -> H256 {
-        assert!(index < self.hashes.len(), "invalid tree leaf index");
+    /// Returns the index of the first retained node at the specified level. We retain all nodes
+    /// that can be used in Merkle paths for non-trimmed leaves, i.e., ones starting from
+    /// the left sibling of the first non-trimmed leaf ancestor.
+    fn first_retained_index(start_index: usize, level: usize) -> usize {
+        (start_index >> level) & !1
+    }
 
+    fn node(&self, level: usize, index: usize) -> H256 {
+        let first_index = Self::first_retained_index(self.start_index, level);
+        self.levels[level][index - first_index]
+    }
+
+    /// Returns the root hash of this tree.
+    pub fn merkle_root(&self) -> H256 {
         let depth = tree_depth_by_size(self.binary_tree_size);
-        if let Some(merkle_path) = merkle_path.as_deref_mut() {
-            merkle_path.reserve(depth);
-        }
+        self.partial_hashes()[depth]
+    }
 
-        let mut hashes = self.hashes;
-        let mut level_len = hashes.len();
-        for level in 0..depth {
-            let empty_hash_at_level = self.hasher.empty_subtree_hash(level);
-
-            if let Some(merkle_path) = merkle_path.as_deref_mut() {
-                let adjacent_idx = index ^ 1;
-                let adjacent_hash = if adjacent_idx < level_len {
-                    hashes[adjacent_idx]
-                } else {
-                    empty_hash_at_level
-                };
-                merkle_path.push(adjacent_hash);
-            }
+    /// Returns the root hash and the Merkle proof for a leaf with the specified 0-based `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the leaf with the specified index is not present in the tree or was trimmed.
+    pub fn merkle_root_and_path(&self, index: usize) -> (H256, Vec<H256>) {
+        let (root_hash, mut paths) = self.merkle_root_and_paths(iter::once(index));
+        (root_hash, paths.pop().unwrap())
+    }
 
-            for i in 0..(level_len / 2) {
-                hashes[i] = self.hasher.compress(&hashes[2 * i], &hashes[2 * i + 1]);
-            }
-            if level_len % 2 == 1 {
-                hashes[level_len / 2] = self
-                    .hasher
-                    .compress(&hashes[level_len - 1], &empty_hash_at_level);
-            }
+    /// Returns the root hash and Merkle proofs for leaves with the specified 0-based `indices`.
+    /// This is more efficient than calling [`Self::merkle_root_and_path()`] for each index,
+    /// since the hashes of incomplete nodes are only computed once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the leaves with the specified indices is not present in the tree or was trimmed.
+    pub fn merkle_root_and_paths(
+        &self,
+        indices: impl IntoIterator<Item = usize>,
+    ) -> (H256, Vec<Vec<H256>>) {
+        let depth = tree_depth_by_size(self.binary_tree_size);
+        let partial_hashes = self.partial_hashes();
+        let paths = indices.into_iter().map(|index| {
+            assert!(
+                (self.start_index..self.leaf_count).contains(&index),
+                "invalid tree leaf index"
+            );
+            let path = (0..depth).map(|level| {
+                let complete_node_count = self.leaf_count >> level;
+                let sibling_index = (index >> level) ^ 1;
+                match sibling_index.cmp(&complete_node_count) {
+                    cmp::Ordering::Less => self.node(level, sibling_index),
+                    // The sibling is either a partially filled node or is empty; `partial_hashes`
+                    // covers both cases.
+                    cmp::Ordering::Equal => partial_hashes[level],
+                    cmp::Ordering::Greater => self.hasher.empty_subtree_hash(level),
+                }
+            });
+            path.collect()
+        });
+        let paths = paths.collect();
+        (partial_hashes[depth], paths)
+    }
 
-            index /= 2;
-            level_len = level_len / 2 + level_len % 2;
+    /// Computes hashes of the rightmost node at each tree level, for levels `0..=depth`,
+    /// that is not a complete node (i.e., covers non-filled leaves). If such a node is empty,
+    /// the hash of an empty subtree is returned. At the root level, this returns the root hash
+    /// of the tree even if the tree is complete.
+    fn partial_hashes(&self) -> Vec<H256> {
+        let depth = tree_depth_by_size(self.binary_tree_size);
+        let mut hashes = Vec::with_capacity(depth + 1);
+        let mut partial_hash = None;
+        for level in 0..depth {
+            let empty_hash = self.hasher.empty_subtree_hash(level);
+            hashes.push(partial_hash.unwrap_or(empty_hash));
+
+            let complete_node_count = self.leaf_count >> level;
+            partial_hash = if complete_node_count % 2 == 1 {
+                let left_child = self.node(level, complete_node_count - 1);
+                let right_child = partial_hash.unwrap_or(empty_hash);
+                Some(self.hasher.compress(&left_child, &right_child))
+            } else {
+                partial_hash.map(|left_child| self.hasher.compress(&left_child, &empty_hash))
+            };
         }
-        hashes[0]
+
+        let root_hash = if self.leaf_count == self.binary_tree_size {
+            self.node(depth, 0)
+        } else {
+            partial_hash.unwrap_or_else(|| self.hasher.empty_subtree_hash(depth))
+        };
+        hashes.push(root_hash);
+        hashes
     }
 }
 
@@ -217,3 +217,101 @@ fn merkle_proofs_are_valid_in_very_small_trees() {
         }
     }
 }
+
+#[test]
+fn pushing_leaves_is_equivalent_to_creating_tree() {
+    for min_tree_size in [None, Some(32)] {
+        let mut tree = MiniMerkleTree::new(iter::empty(), min_tree_size);
+        assert!(tree.is_empty());
+        for byte in 1_u8..=70 {
+            tree.push([byte; 88]);
+            let leaves = (1..=byte).map(|byte| [byte; 88]);
+            let expected_tree = MiniMerkleTree::new(leaves, min_tree_size);
+            assert_eq!(tree.leaf_count(), usize::from(byte));
+            assert_eq!(tree.merkle_root(), expected_tree.merkle_root());
+
+            for i in [0, usize::from(byte) / 2, usize::from(byte) - 1] {
+                let (merkle_root, path) = tree.merkle_root_and_path(i);
+                let item_count = usize::from(byte)
+                    .next_power_of_two()
+                    .max(min_tree_size.unwrap_or(1));
+                let item = [u8::try_from(i + 1).unwrap(); 88];
+                verify_merkle_proof(&item, i, item_count, &path, merkle_root);
+            }
+        }
+    }
+}
+
+#[test]
+fn computing_multiple_merkle_paths() {
+    let leaves = (1_u8..=50).map(|byte| [byte; 88]);
+    let tree = MiniMerkleTree::new(leaves, None);
+
+    let (merkle_root, paths) = tree.merkle_root_and_paths(0..50);
+    assert_eq!(merkle_root, tree.merkle_root());
+    assert_eq!(paths.len(), 50);
+    for (i, path) in paths.into_iter().enumerate() {
+        assert_eq!(tree.merkle_root_and_path(i), (merkle_root, path));
+    }
+}
+
+#[test]
+fn trimming_leaves_retains_root_hash_and_paths() {
+    let leaves = (1_u8..=50).map(|byte| [byte; 88]);
+    let reference_tree = MiniMerkleTree::new(leaves.clone(), None);
+
+    for trimmed_count in [0, 1, 2, 3, 17, 32, 33, 49, 50] {
+        let mut tree = MiniMerkleTree::new(leaves.clone(), None);
+        tree.trim_start(trimmed_count);
+        assert_eq!(tree.start_index(), trimmed_count);
+        assert_eq!(tree.merkle_root(), reference_tree.merkle_root());
+        for i in trimmed_count..50 {
+            assert_eq!(
+                tree.merkle_root_and_path(i),
+                reference_tree.merkle_root_and_path(i)
+            );
+        }
+
+        // Check that the tree can be extended after trimming.
+        let mut reference_tree = reference_tree.clone();
+        for byte in 51_u8..=80 {
+            tree.push([byte; 88]);
+            reference_tree.push([byte; 88]);
+        }
+        assert_eq!(tree.merkle_root(), reference_tree.merkle_root());
+        for i in trimmed_count..80 {
+            assert_eq!(
+                tree.merkle_root_and_path(i),
+                reference_tree.merkle_root_and_path(i)
+            );
+        }
+    }
+}
+
+#[test]
+fn incremental_trimming() {
+    let leaves = (1_u8..=100).map(|byte| [byte; 88]);
+    let reference_tree = MiniMerkleTree::new(leaves.clone(), Some(256));
+    let mut tree = reference_tree.clone();
+    for _ in 0..20 {
+        tree.trim_start(5);
+        let start_index = tree.start_index();
+        assert_eq!(tree.merkle_root(), reference_tree.merkle_root());
+        if start_index < 100 {
+            assert_eq!(
+                tree.merkle_root_and_path(start_index),
+                reference_tree.merkle_root_and_path(start_index)
+            );
+        }
+    }
+    assert_eq!(tree.start_index(), 100);
+}
+
+#[test]
+#[should_panic(expected = "invalid tree leaf index")]
+fn merkle_path_for_trimmed_leaf() {
+    let leaves = (1_u8..=10).map(|byte| [byte; 88]);
+    let mut tree = MiniMerkleTree::new(leaves, None);
+    tree.trim_start(3);
+    tree.merkle_root_and_path(2);
+}
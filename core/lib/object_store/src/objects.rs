@@ -8,7 +8,8 @@ use prost::Message;
 use zksync_protobuf::{decode, ProtoFmt};
 use zksync_types::{
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotFactoryDependencies, SnapshotManifest, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
     },
     L1BatchNumber,
 };
@@ -117,6 +118,23 @@ impl StoredObject for SnapshotStorageLogsChunk {
     }
 }
 
+impl StoredObject for SnapshotManifest {
+    const BUCKET: Bucket = Bucket::StorageSnapshot;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("snapshot_l1_batch_{key}_manifest.json")
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        serde_json::to_vec_pretty(self).map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        serde_json::from_slice(&bytes).map_err(From::from)
+    }
+}
+
 impl dyn ObjectStore + '_ {
     /// Fetches the value for the given key if it exists.
     ///
@@ -165,9 +183,11 @@ impl dyn ObjectStore + '_ {
 #[cfg(test)]
 mod tests {
     use zksync_types::{
-        snapshots::{SnapshotFactoryDependency, SnapshotStorageLog},
+        snapshots::{
+            SnapshotFactoryDependency, SnapshotObjectDigest, SnapshotStorageLog, SnapshotVersion,
+        },
         web3::Bytes,
-        AccountTreeId, StorageKey, H160, H256,
+        AccountTreeId, L2BlockNumber, StorageKey, H160, H256,
     };
 
     use super::*;
@@ -247,4 +267,36 @@ mod tests {
         let reconstructed_factory_deps = store.get(key).await.unwrap();
         assert_eq!(factory_deps, reconstructed_factory_deps);
     }
+
+    #[tokio::test]
+    async fn test_manifest_can_be_serialized_and_deserialized() {
+        let store = ObjectStoreFactory::mock().create_store().await;
+        let key = L1BatchNumber(123);
+        let manifest = SnapshotManifest {
+            version: SnapshotVersion::Version0.into(),
+            l1_batch_number: key,
+            l2_block_number: L2BlockNumber(456),
+            base_l1_batch_number: Some(L1BatchNumber(100)),
+            expected_root_hash: H256::random(),
+            factory_deps: SnapshotObjectDigest {
+                entry_count: 2,
+                hash: H256::random(),
+            },
+            storage_logs_chunks: vec![
+                SnapshotObjectDigest {
+                    entry_count: 10,
+                    hash: H256::random(),
+                };
+                3
+            ],
+            manifest_hash: H256::zero(),
+            signature: None,
+        }
+        .seal();
+        let filename = store.put(key, &manifest).await.unwrap();
+        assert_eq!(filename, "snapshot_l1_batch_123_manifest.json");
+        let reconstructed_manifest: SnapshotManifest = store.get(key).await.unwrap();
+        assert_eq!(manifest, reconstructed_manifest);
+        reconstructed_manifest.check_hash().unwrap();
+    }
 }
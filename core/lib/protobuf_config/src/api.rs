@@ -79,6 +79,11 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .and_then(|p| Ok((*p).try_into()?))
                 .context("ws_port")?,
             ws_url: required(&self.ws_url).context("ws_url")?.clone(),
+            admin_port: self
+                .admin_port
+                .map(|p| p.try_into())
+                .transpose()
+                .context("admin_port")?,
             req_entities_limit: self.req_entities_limit,
             filters_disabled: self.filters_disabled.unwrap_or(false),
             filters_limit: self.filters_limit,
@@ -163,6 +168,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
             http_url: Some(this.http_url.clone()),
             ws_port: Some(this.ws_port.into()),
             ws_url: Some(this.ws_url.clone()),
+            admin_port: this.admin_port.map(Into::into),
             req_entities_limit: this.req_entities_limit,
             filters_disabled: Some(this.filters_disabled),
             mempool_cache_update_interval: this.mempool_cache_update_interval,
@@ -236,6 +242,9 @@ impl ProtoRepr for proto::HealthCheck {
                 .context("port")?,
             slow_time_limit_ms: self.slow_time_limit_ms,
             hard_time_limit_ms: self.hard_time_limit_ms,
+            liveness_components: self.liveness_components.clone(),
+            readiness_components: self.readiness_components.clone(),
+            expose_slow_query_plans: self.expose_slow_query_plans.unwrap_or(false),
         })
     }
 
@@ -244,6 +253,9 @@ impl ProtoRepr for proto::HealthCheck {
             port: Some(this.port.into()),
             slow_time_limit_ms: this.slow_time_limit_ms,
             hard_time_limit_ms: this.hard_time_limit_ms,
+            liveness_components: this.liveness_components.clone(),
+            readiness_components: this.readiness_components.clone(),
+            expose_slow_query_plans: Some(this.expose_slow_query_plans),
         }
     }
 }
@@ -75,6 +75,12 @@ impl ProtoRepr for proto::StateKeeper {
             validation_computational_gas_limit: *required(&self.validation_computational_gas_limit)
                 .context("validation_computational_gas_limit")?,
             save_call_traces: *required(&self.save_call_traces).context("save_call_traces")?,
+            protective_reads_persistence_enabled: self
+                .protective_reads_persistence_enabled
+                .unwrap_or(true),
+            tx_filter_path: self.tx_filter_path.clone(),
+            tx_filter_reload_interval_ms: self.tx_filter_reload_interval_ms.unwrap_or(10_000),
+            seal_params_path: self.seal_params_path.clone(),
             max_circuits_per_batch: required(&self.max_circuits_per_batch)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_circuits_per_batch")?,
@@ -114,6 +120,10 @@ impl ProtoRepr for proto::StateKeeper {
             fee_model_version: Some(proto::FeeModelVersion::new(&this.fee_model_version).into()),
             validation_computational_gas_limit: Some(this.validation_computational_gas_limit),
             save_call_traces: Some(this.save_call_traces),
+            protective_reads_persistence_enabled: Some(this.protective_reads_persistence_enabled),
+            tx_filter_path: this.tx_filter_path.clone(),
+            tx_filter_reload_interval_ms: Some(this.tx_filter_reload_interval_ms),
+            seal_params_path: this.seal_params_path.clone(),
             max_circuits_per_batch: Some(this.max_circuits_per_batch.try_into().unwrap()),
         }
     }
@@ -16,6 +16,10 @@ impl ProtoRepr for proto::CircuitBreaker {
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("http_req_retry_interval_sec")?,
             replication_lag_limit_sec: self.replication_lag_limit_sec,
+            unproven_batches_limit: self.unproven_batches_limit,
+            tree_lag_limit: self.tree_lag_limit,
+            l1_base_fee_ceiling: self.l1_base_fee_ceiling,
+            soft_circuit_breakers: self.soft_circuit_breakers.clone(),
         })
     }
 
@@ -25,6 +29,10 @@ impl ProtoRepr for proto::CircuitBreaker {
             http_req_max_retry_number: Some(this.http_req_max_retry_number.try_into().unwrap()),
             http_req_retry_interval_sec: Some(this.http_req_retry_interval_sec.into()),
             replication_lag_limit_sec: this.replication_lag_limit_sec,
+            unproven_batches_limit: this.unproven_batches_limit,
+            tree_lag_limit: this.tree_lag_limit,
+            l1_base_fee_ceiling: this.l1_base_fee_ceiling,
+            soft_circuit_breakers: this.soft_circuit_breakers.clone(),
         }
     }
 }
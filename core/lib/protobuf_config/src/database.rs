@@ -49,6 +49,12 @@ impl ProtoRepr for proto::MerkleTree {
             max_l1_batches_per_iter: required(&self.max_l1_batches_per_iter)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_l1_batches_per_iter")?,
+            checkpoint_interval_l1_batches: self.checkpoint_interval_l1_batches,
+            max_retained_checkpoints: match self.max_retained_checkpoints {
+                Some(count) => count.try_into().context("max_retained_checkpoints")?,
+                None => Self::Type::default_max_retained_checkpoints(),
+            },
+            parallel_updates: self.parallel_updates.unwrap_or(false),
         })
     }
 
@@ -61,6 +67,9 @@ impl ProtoRepr for proto::MerkleTree {
             memtable_capacity_mb: Some(this.memtable_capacity_mb.try_into().unwrap()),
             stalled_writes_timeout_sec: Some(this.stalled_writes_timeout_sec),
             max_l1_batches_per_iter: Some(this.max_l1_batches_per_iter.try_into().unwrap()),
+            checkpoint_interval_l1_batches: this.checkpoint_interval_l1_batches,
+            max_retained_checkpoints: Some(this.max_retained_checkpoints.try_into().unwrap()),
+            parallel_updates: Some(this.parallel_updates),
         }
     }
 }
@@ -124,17 +133,31 @@ impl ProtoRepr for proto::Postgres {
             .map(str::parse::<SensitiveUrl>)
             .transpose()
             .context("prover_url")?;
+        let read_replica_urls = self
+            .read_replica_urls
+            .iter()
+            .map(|url| url.parse::<SensitiveUrl>())
+            .collect::<Result<_, _>>()
+            .context("read_replica_urls")?;
 
         Ok(Self::Type {
             master_url,
             replica_url,
             prover_url,
+            read_replica_urls,
+            max_replication_lag_sec: self.max_replication_lag_sec,
             max_connections: self.max_connections,
             max_connections_master: self.max_connections_master,
             acquire_timeout_sec: self.acquire_timeout_sec,
             statement_timeout_sec: self.statement_timeout_sec,
             long_connection_threshold_ms: self.long_connection_threshold_ms,
             slow_query_threshold_ms: self.slow_query_threshold_ms,
+            slow_query_plan_sample_rate: self.slow_query_plan_sample_rate,
+            slow_query_plan_capacity: self
+                .slow_query_plan_capacity
+                .map(|capacity| capacity.try_into())
+                .transpose()
+                .context("slow_query_plan_capacity")?,
             test_server_url,
             test_prover_url,
         })
@@ -154,12 +177,22 @@ impl ProtoRepr for proto::Postgres {
                 .prover_url
                 .as_ref()
                 .map(|url| url.expose_str().to_owned()),
+            read_replica_urls: this
+                .read_replica_urls
+                .iter()
+                .map(|url| url.expose_str().to_owned())
+                .collect(),
+            max_replication_lag_sec: this.max_replication_lag_sec,
             max_connections: this.max_connections,
             max_connections_master: this.max_connections_master,
             acquire_timeout_sec: this.acquire_timeout_sec,
             statement_timeout_sec: this.statement_timeout_sec,
             long_connection_threshold_ms: this.long_connection_threshold_ms,
             slow_query_threshold_ms: this.slow_query_threshold_ms,
+            slow_query_plan_sample_rate: this.slow_query_plan_sample_rate,
+            slow_query_plan_capacity: this
+                .slow_query_plan_capacity
+                .map(|capacity| capacity.try_into().unwrap()),
             test: Some(proto::TestDatabase {
                 server_url: this.test_server_url.clone(),
                 prover_url: this.test_prover_url.clone(),
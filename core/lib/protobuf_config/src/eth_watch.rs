@@ -0,0 +1,26 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::{required, ProtoRepr};
+
+use crate::proto::eth_watch as proto;
+
+impl ProtoRepr for proto::EthWatch {
+    type Type = configs::EthWatchConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            confirmations_for_eth_event: self.confirmations_for_eth_event,
+            use_finalized_block_tag: self.use_finalized_block_tag.unwrap_or(false),
+            eth_node_poll_interval: *required(&self.eth_node_poll_interval)
+                .context("eth_node_poll_interval")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            confirmations_for_eth_event: this.confirmations_for_eth_event,
+            use_finalized_block_tag: Some(this.use_finalized_block_tag),
+            eth_node_poll_interval: Some(this.eth_node_poll_interval),
+        }
+    }
+}
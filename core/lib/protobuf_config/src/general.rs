@@ -37,6 +37,7 @@ impl ProtoRepr for proto::GeneralConfig {
             snapshot_creator: read_optional_repr(&self.snapshot_creator)
                 .context("snapshot_creator")?,
             observability: read_optional_repr(&self.observability).context("observability")?,
+            pruning: read_optional_repr(&self.pruning).context("pruning")?,
         })
     }
 
@@ -68,6 +69,7 @@ impl ProtoRepr for proto::GeneralConfig {
             eth: this.eth.as_ref().map(ProtoRepr::build),
             snapshot_creator: this.snapshot_creator.as_ref().map(ProtoRepr::build),
             observability: this.observability.as_ref().map(ProtoRepr::build),
+            pruning: this.pruning.as_ref().map(ProtoRepr::build),
         }
     }
 }
@@ -12,6 +12,7 @@ mod contract_verifier;
 mod contracts;
 mod database;
 mod eth;
+mod eth_watch;
 mod experimental;
 mod general;
 mod genesis;
@@ -21,6 +22,7 @@ mod observability;
 mod proof_data_handler;
 pub mod proto;
 mod prover;
+mod pruning;
 mod snapshots_creator;
 pub mod testonly;
 #[cfg(test)]
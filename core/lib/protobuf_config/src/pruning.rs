@@ -0,0 +1,42 @@
+use std::num::NonZeroU64;
+
+use anyhow::Context as _;
+use zksync_config::configs::PruningConfig;
+use zksync_protobuf::{repr::ProtoRepr, required};
+
+use crate::proto::pruning as proto;
+
+impl ProtoRepr for proto::Pruning {
+    type Type = PruningConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            enabled: self.enabled.unwrap_or(false),
+            chunk_size: *required(&self.chunk_size).context("chunk_size")?,
+            removal_delay_sec: NonZeroU64::new(
+                *required(&self.removal_delay_sec).context("removal_delay_sec")?,
+            )
+            .context("removal_delay_sec cannot be 0")?,
+            data_retention_sec: self.data_retention_sec.unwrap_or(0),
+            retained_l1_batches: self.retained_l1_batches.unwrap_or(0),
+            dry_run: self.dry_run.unwrap_or(false),
+            call_traces_retention_sec: self.call_traces_retention_sec,
+            events_retention_sec: self.events_retention_sec,
+            transaction_payloads_retention_sec: self.transaction_payloads_retention_sec,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            enabled: Some(this.enabled),
+            chunk_size: Some(this.chunk_size),
+            removal_delay_sec: Some(this.removal_delay_sec.get()),
+            data_retention_sec: Some(this.data_retention_sec),
+            retained_l1_batches: Some(this.retained_l1_batches),
+            dry_run: Some(this.dry_run),
+            call_traces_retention_sec: this.call_traces_retention_sec,
+            events_retention_sec: this.events_retention_sec,
+            transaction_payloads_retention_sec: this.transaction_payloads_retention_sec,
+        }
+    }
+}
@@ -17,6 +17,7 @@ impl ProtoRepr for proto::SnapshotsCreator {
                 .context("storage_logs_chunk_size")?,
             concurrent_queries_count: *required(&self.concurrent_queries_count)
                 .context("concurrent_queries_count")?,
+            max_differential_snapshots: self.max_differential_snapshots.unwrap_or(0),
             object_store,
         })
     }
@@ -25,6 +26,7 @@ impl ProtoRepr for proto::SnapshotsCreator {
         Self {
             storage_logs_chunk_size: Some(this.storage_logs_chunk_size),
             concurrent_queries_count: Some(this.concurrent_queries_count),
+            max_differential_snapshots: Some(this.max_differential_snapshots),
             object_store: this.object_store.as_ref().map(ProtoRepr::build),
         }
     }
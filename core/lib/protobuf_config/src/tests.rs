@@ -28,6 +28,7 @@ fn test_encoding() {
     test_encode_all_formats::<ReprConv<proto::database::Db>>(rng);
     test_encode_all_formats::<ReprConv<proto::database::Postgres>>(rng);
     test_encode_all_formats::<ReprConv<proto::eth::Eth>>(rng);
+    test_encode_all_formats::<ReprConv<proto::eth_watch::EthWatch>>(rng);
     test_encode_all_formats::<ReprConv<proto::prover::ProofCompressor>>(rng);
     test_encode_all_formats::<ReprConv<proto::prover::Prover>>(rng);
     test_encode_all_formats::<ReprConv<proto::prover::ProverGateway>>(rng);
@@ -39,6 +40,7 @@ fn test_encoding() {
     test_encode_all_formats::<ReprConv<proto::prover::ProofDataHandler>>(rng);
     test_encode_all_formats::<ReprConv<proto::snapshot_creator::SnapshotsCreator>>(rng);
     test_encode_all_formats::<ReprConv<proto::observability::Observability>>(rng);
+    test_encode_all_formats::<ReprConv<proto::pruning::Pruning>>(rng);
 }
 
 pub fn decode_yaml_repr<T: ProtoRepr>(
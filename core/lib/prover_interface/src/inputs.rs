@@ -3,7 +3,7 @@ use std::{convert::TryInto, fmt::Debug};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, Bytes};
 use zksync_object_store::{serialize_using_bincode, Bucket, StoredObject};
-use zksync_types::{L1BatchNumber, H256, U256};
+use zksync_types::{L1BatchNumber, ProtocolVersionId, StorageKey, H256, U256};
 
 const HASH_LEN: usize = H256::len_bytes();
 
@@ -144,6 +144,40 @@ pub struct BasicCircuitWitnessGeneratorInput {
     pub merkle_paths_input: PrepareBasicCircuitsJob,
 }
 
+/// Basic witness inputs for an L1 batch produced by re-executing the batch in the VM. Unlike [`PrepareBasicCircuitsJob`],
+/// these inputs don't depend on the Merkle tree, so they can be produced independently of it.
+///
+/// # Stability
+///
+/// This type is serialized using `bincode` to be passed to the witness generator. As such, changes
+/// in its `serde` serialization must be backwards-compatible.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VmRunWitnessInputData {
+    pub l1_batch_number: L1BatchNumber,
+    pub protocol_version: ProtocolVersionId,
+    /// Hashes of bytecodes used during batch execution.
+    pub used_bytecodes_hashes: Vec<U256>,
+    /// Bootloader memory after batch execution. Empty for old VM versions.
+    pub initial_heap_content: Vec<(usize, U256)>,
+    /// Refunds returned by the storage oracle.
+    pub storage_refunds: Vec<u32>,
+    /// Pubdata costs returned by the storage oracle. Empty for VM versions before 1.5.0.
+    pub pubdata_costs: Vec<i32>,
+    /// Storage slots read, but not written by the batch.
+    pub protective_reads: Vec<StorageKey>,
+}
+
+impl StoredObject for VmRunWitnessInputData {
+    const BUCKET: Bucket = Bucket::WitnessInput;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("vm_run_data_{key}.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,4 +216,20 @@ mod tests {
         let logs_from_job: Vec<_> = job.into_merkle_paths().collect();
         assert_eq!(logs_from_job, logs);
     }
+
+    #[test]
+    fn vm_run_witness_input_data_roundtrip() {
+        let data = VmRunWitnessInputData {
+            l1_batch_number: L1BatchNumber(1),
+            protocol_version: ProtocolVersionId::latest(),
+            used_bytecodes_hashes: vec![U256::from(1), U256::from(2)],
+            initial_heap_content: vec![(0, U256::from(3))],
+            storage_refunds: vec![5, 0],
+            pubdata_costs: vec![-1, 10],
+            protective_reads: vec![StorageKey::new(Default::default(), H256::repeat_byte(1))],
+        };
+        let bytes = StoredObject::serialize(&data).unwrap();
+        let restored = <VmRunWitnessInputData as StoredObject>::deserialize(bytes).unwrap();
+        assert_eq!(restored, data);
+    }
 }
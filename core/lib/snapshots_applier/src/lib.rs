@@ -128,6 +128,11 @@ pub trait SnapshotsApplierMainNodeClient: fmt::Debug + Send + Sync {
 
     async fn fetch_newest_snapshot(&self) -> EnrichedClientResult<Option<SnapshotHeader>>;
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>>;
+
     async fn fetch_tokens(
         &self,
         at_l2_block: L2BlockNumber,
@@ -158,15 +163,22 @@ impl SnapshotsApplierMainNodeClient for BoxedL2Client {
 
     async fn fetch_newest_snapshot(&self) -> EnrichedClientResult<Option<SnapshotHeader>> {
         let snapshots = self
-            .get_all_snapshots()
+            .get_all_snapshots(Some(true))
             .rpc_context("get_all_snapshots")
             .await?;
         let Some(newest_snapshot) = snapshots.snapshots_l1_batch_numbers.first() else {
             return Ok(None);
         };
-        self.get_snapshot_by_l1_batch_number(*newest_snapshot)
+        self.fetch_snapshot(*newest_snapshot).await
+    }
+
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        self.get_snapshot_by_l1_batch_number(l1_batch_number)
             .rpc_context("get_snapshot_by_l1_batch_number")
-            .with_arg("number", newest_snapshot)
+            .with_arg("number", &l1_batch_number)
             .await
     }
 
@@ -451,6 +463,9 @@ struct SnapshotsApplier<'a> {
     main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
     blob_store: &'a dyn ObjectStore,
     applied_snapshot_status: SnapshotRecoveryStatus,
+    /// L1 batch numbers of snapshots that need to be applied, starting from a full snapshot and followed
+    /// by differential snapshots (if any). The last element always corresponds to `applied_snapshot_status`.
+    snapshot_chain: Vec<L1BatchNumber>,
     health_updater: &'a HealthUpdater,
     max_concurrency: usize,
     factory_deps_recovered: bool,
@@ -483,12 +498,15 @@ impl<'a> SnapshotsApplier<'a> {
             SnapshotRecoveryStrategy::New => true,
             SnapshotRecoveryStrategy::Resumed => false,
         };
+        let snapshot_chain =
+            Self::fetch_snapshot_chain(main_node_client, &applied_snapshot_status).await?;
 
         let mut this = Self {
             connection_pool,
             main_node_client,
             blob_store,
             applied_snapshot_status,
+            snapshot_chain,
             health_updater,
             max_concurrency,
             factory_deps_recovered: !created_from_scratch,
@@ -546,6 +564,56 @@ impl<'a> SnapshotsApplier<'a> {
         Ok((strategy, this.applied_snapshot_status))
     }
 
+    /// Resolves the chain of snapshots (a full snapshot followed by zero or more differential snapshots)
+    /// that must be applied to recover the node to the snapshot specified by `status`.
+    async fn fetch_snapshot_chain(
+        main_node_client: &dyn SnapshotsApplierMainNodeClient,
+        status: &SnapshotRecoveryStatus,
+    ) -> Result<Vec<L1BatchNumber>, SnapshotsApplierError> {
+        let latency =
+            METRICS.initial_stage_duration[&InitialStage::FetchSnapshotChainFromMainNode].start();
+        let chunk_count = status.storage_logs_chunks_processed.len();
+        let mut chain = vec![];
+        let mut l1_batch_number = status.l1_batch_number;
+        loop {
+            let header = main_node_client
+                .fetch_snapshot(l1_batch_number)
+                .await?
+                .with_context(|| {
+                    format!("snapshot for L1 batch #{l1_batch_number} is missing on main node")
+                })?;
+            SnapshotRecoveryStrategy::check_snapshot_version(header.version)?;
+            if header.storage_logs_chunks.len() != chunk_count {
+                let err = anyhow::anyhow!(
+                    "snapshot for L1 batch #{l1_batch_number} has {} storage log chunks, while {chunk_count} chunks \
+                     were expected; all snapshots in a differential chain must use the same chunking",
+                    header.storage_logs_chunks.len()
+                );
+                return Err(err.into());
+            }
+            chain.push(l1_batch_number);
+
+            let Some(base_l1_batch_number) = header.base_l1_batch_number else {
+                break;
+            };
+            if base_l1_batch_number >= l1_batch_number {
+                let err = anyhow::anyhow!(
+                    "differential snapshot for L1 batch #{l1_batch_number} has invalid base L1 batch #{base_l1_batch_number}"
+                );
+                return Err(err.into());
+            }
+            l1_batch_number = base_l1_batch_number;
+        }
+        chain.reverse();
+
+        let latency = latency.observe();
+        tracing::info!(
+            "Resolved chain of {} snapshot(s) to apply in {latency:?}: {chain:?}",
+            chain.len()
+        );
+        Ok(chain)
+    }
+
     fn update_health(&self) {
         let details = SnapshotsApplierHealthDetails {
             snapshot_l2_block: self.applied_snapshot_status.l2_block_number,
@@ -574,25 +642,30 @@ impl<'a> SnapshotsApplier<'a> {
     ) -> Result<(), SnapshotsApplierError> {
         let latency = METRICS.initial_stage_duration[&InitialStage::ApplyFactoryDeps].start();
 
-        tracing::debug!("Fetching factory dependencies from object store");
-        let l1_batch_number = self.applied_snapshot_status.l1_batch_number;
-        let factory_deps: SnapshotFactoryDependencies =
-            self.blob_store.get(l1_batch_number).await.map_err(|err| {
-                let context = format!(
-                    "cannot fetch factory deps for L1 batch #{l1_batch_number} from object store"
-                );
-                SnapshotsApplierError::object_store(err, context)
-            })?;
-        tracing::debug!(
-            "Fetched {} factory dependencies from object store",
-            factory_deps.factory_deps.len()
-        );
+        let mut all_deps_hashmap = HashMap::<H256, Vec<u8>>::new();
+        for &l1_batch_number in &self.snapshot_chain {
+            tracing::debug!(
+                "Fetching factory dependencies for L1 batch #{l1_batch_number} from object store"
+            );
+            let factory_deps: SnapshotFactoryDependencies =
+                self.blob_store.get(l1_batch_number).await.map_err(|err| {
+                    let context = format!(
+                        "cannot fetch factory deps for L1 batch #{l1_batch_number} from object store"
+                    );
+                    SnapshotsApplierError::object_store(err, context)
+                })?;
+            tracing::debug!(
+                "Fetched {} factory dependencies for L1 batch #{l1_batch_number} from object store",
+                factory_deps.factory_deps.len()
+            );
 
-        let all_deps_hashmap: HashMap<H256, Vec<u8>> = factory_deps
-            .factory_deps
-            .into_iter()
-            .map(|dep| (hash_bytecode(&dep.bytecode.0), dep.bytecode.0))
-            .collect();
+            all_deps_hashmap.extend(
+                factory_deps
+                    .factory_deps
+                    .into_iter()
+                    .map(|dep| (hash_bytecode(&dep.bytecode.0), dep.bytecode.0)),
+            );
+        }
         storage
             .factory_deps_dal()
             .insert_factory_deps(
@@ -647,17 +720,8 @@ impl<'a> SnapshotsApplier<'a> {
         let latency =
             METRICS.storage_logs_chunks_duration[&StorageLogsChunksStage::LoadFromGcs].start();
 
-        let storage_key = SnapshotStorageLogsStorageKey {
-            chunk_id,
-            l1_batch_number: self.applied_snapshot_status.l1_batch_number,
-        };
-        let storage_snapshot_chunk: SnapshotStorageLogsChunk =
-            self.blob_store.get(storage_key).await.map_err(|err| {
-                let context =
-                    format!("cannot fetch storage logs {storage_key:?} from object store");
-                SnapshotsApplierError::object_store(err, context)
-            })?;
-        let storage_logs = &storage_snapshot_chunk.storage_logs;
+        let storage_logs = self.load_storage_logs_chunk(chunk_id).await?;
+        let storage_logs = &storage_logs;
         self.validate_storage_logs_chunk(storage_logs)?;
         let latency = latency.observe();
         tracing::info!(
@@ -693,6 +757,41 @@ impl<'a> SnapshotsApplier<'a> {
         Ok(())
     }
 
+    /// Loads the specified storage logs chunk for all snapshots in the chain and merges them, so that
+    /// logs from later snapshots override logs for the same keys from earlier ones.
+    async fn load_storage_logs_chunk(
+        &self,
+        chunk_id: u64,
+    ) -> Result<Vec<SnapshotStorageLog>, SnapshotsApplierError> {
+        let mut merged_logs = HashMap::new();
+        for &l1_batch_number in &self.snapshot_chain {
+            let storage_key = SnapshotStorageLogsStorageKey {
+                chunk_id,
+                l1_batch_number,
+            };
+            let storage_snapshot_chunk: SnapshotStorageLogsChunk =
+                self.blob_store.get(storage_key).await.map_err(|err| {
+                    let context =
+                        format!("cannot fetch storage logs {storage_key:?} from object store");
+                    SnapshotsApplierError::object_store(err, context)
+                })?;
+            if self.snapshot_chain.len() == 1 {
+                // Fast path: there's nothing to merge for a full snapshot.
+                return Ok(storage_snapshot_chunk.storage_logs);
+            }
+            merged_logs.extend(
+                storage_snapshot_chunk
+                    .storage_logs
+                    .into_iter()
+                    .map(|log| (log.key, log)),
+            );
+        }
+
+        let mut storage_logs: Vec<_> = merged_logs.into_values().collect();
+        storage_logs.sort_unstable_by_key(|log| log.enumeration_index);
+        Ok(storage_logs)
+    }
+
     /// Performs basic sanity check for a storage logs chunk.
     fn validate_storage_logs_chunk(
         &self,
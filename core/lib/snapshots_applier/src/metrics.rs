@@ -15,6 +15,7 @@ pub(crate) enum StorageLogsChunksStage {
 #[metrics(label = "stage", rename_all = "snake_case")]
 pub(crate) enum InitialStage {
     FetchMetadataFromMainNode,
+    FetchSnapshotChainFromMainNode,
     ApplyFactoryDeps,
 }
 
@@ -13,12 +13,15 @@ use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{
     api::{BlockDetails, L1BatchDetails},
     block::L1BatchHeader,
-    get_code_key, L1BatchNumber, ProtocolVersion, ProtocolVersionId,
+    get_code_key,
+    snapshots::SnapshotFactoryDependency,
+    L1BatchNumber, ProtocolVersion, ProtocolVersionId,
 };
 
 use self::utils::{
-    mock_l2_block_header, mock_recovery_status, mock_snapshot_header, mock_tokens, prepare_clients,
-    random_storage_logs, MockMainNodeClient, ObjectStoreWithErrors,
+    l1_batch_details, l2_block_details, mock_l2_block_header, mock_recovery_status,
+    mock_snapshot_header, mock_tokens, prepare_clients, random_storage_logs, MockMainNodeClient,
+    ObjectStoreWithErrors,
 };
 use super::*;
 
@@ -139,6 +142,140 @@ async fn snapshots_creator_can_successfully_recover_db(
     assert!(!stats.done_work);
 }
 
+#[tokio::test]
+async fn recovering_from_differential_snapshot() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let base_status = SnapshotRecoveryStatus {
+        l1_batch_number: L1BatchNumber(100),
+        l2_block_number: L2BlockNumber(200),
+        ..mock_recovery_status()
+    };
+    let base_storage_logs = random_storage_logs(base_status.l1_batch_number, 200);
+    let (object_store, mut client) = prepare_clients(&base_status, &base_storage_logs).await;
+    let base_header = client.fetch_newest_snapshot_response.take().unwrap();
+    client
+        .fetch_snapshot_responses
+        .insert(base_status.l1_batch_number, base_header);
+
+    // Prepare a differential snapshot with the same chunking as the base one. Each chunk contains
+    // updated logs for some keys from the corresponding base chunk and a few new keys.
+    let expected_status = mock_recovery_status();
+    let base_chunk_size = base_storage_logs.len() / 2;
+    let mut next_enumeration_index = base_storage_logs.len() as u64 + 1;
+    let mut expected_logs: HashMap<_, _> = base_storage_logs
+        .iter()
+        .map(|log| (log.key.hashed_key(), log.clone()))
+        .collect();
+    for (chunk_id, base_chunk) in base_storage_logs.chunks(base_chunk_size).enumerate() {
+        let updated_logs = base_chunk.iter().step_by(10).map(|log| SnapshotStorageLog {
+            value: H256::repeat_byte(0xff),
+            ..log.clone()
+        });
+        let mut new_logs = random_storage_logs(expected_status.l1_batch_number, 5);
+        for log in &mut new_logs {
+            log.enumeration_index = next_enumeration_index;
+            next_enumeration_index += 1;
+        }
+        let storage_logs: Vec<_> = updated_logs.chain(new_logs).collect();
+        expected_logs.extend(
+            storage_logs
+                .iter()
+                .map(|log| (log.key.hashed_key(), log.clone())),
+        );
+
+        let chunk_key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: expected_status.l1_batch_number,
+            chunk_id: chunk_id as u64,
+        };
+        object_store
+            .put(chunk_key, &SnapshotStorageLogsChunk { storage_logs })
+            .await
+            .unwrap();
+    }
+    let new_factory_dep = vec![1_u8; 64];
+    let factory_deps = SnapshotFactoryDependencies {
+        factory_deps: vec![SnapshotFactoryDependency {
+            bytecode: new_factory_dep.clone().into(),
+        }],
+    };
+    object_store
+        .put(expected_status.l1_batch_number, &factory_deps)
+        .await
+        .unwrap();
+
+    client.fetch_newest_snapshot_response = Some(SnapshotHeader {
+        base_l1_batch_number: Some(base_status.l1_batch_number),
+        ..mock_snapshot_header(&expected_status)
+    });
+    client.fetch_l1_batch_responses.insert(
+        expected_status.l1_batch_number,
+        l1_batch_details(
+            expected_status.l1_batch_number,
+            expected_status.l1_batch_root_hash,
+        ),
+    );
+    client.fetch_l2_block_responses.insert(
+        expected_status.l2_block_number,
+        l2_block_details(
+            expected_status.l2_block_number,
+            expected_status.l1_batch_number,
+            expected_status.l2_block_hash,
+        ),
+    );
+
+    let task = SnapshotsApplierTask::new(
+        SnapshotsApplierConfig::for_tests(),
+        pool.clone(),
+        Box::new(client),
+        object_store,
+    );
+    let stats = task.run().await.unwrap();
+    assert!(stats.done_work);
+
+    let mut storage = pool.connection().await.unwrap();
+    let current_db_status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert_eq!(current_db_status.unwrap(), expected_status);
+
+    let all_storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(all_storage_logs.len(), expected_logs.len());
+    for db_log in all_storage_logs {
+        let expected_log = &expected_logs[&db_log.hashed_key];
+        assert_eq!(db_log.value, expected_log.value);
+        assert_eq!(db_log.l2_block_number, expected_status.l2_block_number);
+    }
+
+    let all_initial_writes = storage
+        .storage_logs_dedup_dal()
+        .dump_all_initial_writes_for_tests()
+        .await;
+    assert_eq!(all_initial_writes.len(), expected_logs.len());
+    for initial_write in all_initial_writes {
+        let log = &expected_logs[&initial_write.hashed_key];
+        assert_eq!(
+            initial_write.l1_batch_number,
+            log.l1_batch_number_of_initial_write
+        );
+        assert_eq!(initial_write.index, log.enumeration_index);
+    }
+
+    let all_factory_deps = storage
+        .factory_deps_dal()
+        .dump_all_factory_deps_for_tests()
+        .await;
+    assert_eq!(all_factory_deps.len(), 2);
+    assert_eq!(
+        all_factory_deps[&hash_bytecode(&new_factory_dep)],
+        new_factory_dep
+    );
+}
+
 #[tokio::test]
 async fn health_status_immediately_after_task_start() {
     #[derive(Debug, Clone)]
@@ -167,6 +304,14 @@ async fn health_status_immediately_after_task_start() {
             future::pending().await
         }
 
+        async fn fetch_snapshot(
+            &self,
+            _l1_batch_number: L1BatchNumber,
+        ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+            self.0.wait().await;
+            future::pending().await
+        }
+
         async fn fetch_tokens(
             &self,
             _at_l2_block: L2BlockNumber,
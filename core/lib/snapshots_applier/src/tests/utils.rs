@@ -26,6 +26,8 @@ pub(super) struct MockMainNodeClient {
     pub fetch_l1_batch_responses: HashMap<L1BatchNumber, api::L1BatchDetails>,
     pub fetch_l2_block_responses: HashMap<L2BlockNumber, api::BlockDetails>,
     pub fetch_newest_snapshot_response: Option<SnapshotHeader>,
+    /// Snapshot headers returned in addition to `fetch_newest_snapshot_response`.
+    pub fetch_snapshot_responses: HashMap<L1BatchNumber, SnapshotHeader>,
     pub tokens_response: Vec<TokenInfo>,
 }
 
@@ -49,6 +51,19 @@ impl SnapshotsApplierMainNodeClient for MockMainNodeClient {
         Ok(self.fetch_newest_snapshot_response.clone())
     }
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        let newest_snapshot = self
+            .fetch_newest_snapshot_response
+            .as_ref()
+            .filter(|header| header.l1_batch_number == l1_batch_number);
+        Ok(newest_snapshot
+            .or_else(|| self.fetch_snapshot_responses.get(&l1_batch_number))
+            .cloned())
+    }
+
     async fn fetch_tokens(
         &self,
         _at_l2_block: L2BlockNumber,
@@ -144,7 +159,7 @@ fn block_details_base(hash: H256) -> api::BlockDetailsBase {
     }
 }
 
-fn l2_block_details(
+pub(super) fn l2_block_details(
     number: L2BlockNumber,
     l1_batch_number: L1BatchNumber,
     hash: H256,
@@ -158,7 +173,7 @@ fn l2_block_details(
     }
 }
 
-fn l1_batch_details(number: L1BatchNumber, root_hash: H256) -> api::L1BatchDetails {
+pub(super) fn l1_batch_details(number: L1BatchNumber, root_hash: H256) -> api::L1BatchDetails {
     api::L1BatchDetails {
         number,
         base: block_details_base(root_hash),
@@ -223,6 +238,7 @@ pub(super) fn mock_snapshot_header(status: &SnapshotRecoveryStatus) -> SnapshotH
         version: SnapshotVersion::Version0.into(),
         l1_batch_number: status.l1_batch_number,
         l2_block_number: status.l2_block_number,
+        base_l1_batch_number: None,
         storage_logs_chunks: vec![
             SnapshotStorageLogsChunkMetadata {
                 chunk_id: 0,
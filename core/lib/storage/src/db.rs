@@ -468,6 +468,16 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
         }
     }
 
+    /// Creates a consistent point-in-time checkpoint of the database at the specified `path`.
+    /// The directory at `path` must not exist; it will be created by RocksDB. If `path` is located
+    /// on the same filesystem as the database, SST files are hard-linked, so creating a checkpoint is cheap.
+    ///
+    /// This method is blocking and should be wrapped in `spawn_blocking(_)` if run in the async context.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), rocksdb::Error> {
+        let checkpoint = rocksdb::checkpoint::Checkpoint::new(&self.inner.db)?;
+        checkpoint.create_checkpoint(path)
+    }
+
     pub fn estimated_number_of_entries(&self, cf: CF) -> u64 {
         const ERROR_MSG: &str = "failed to get estimated number of entries";
 
@@ -1079,6 +1089,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn creating_checkpoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = RocksDB::<NewColumnFamilies>::new(&temp_dir.path().join("db"))
+            .unwrap()
+            .with_sync_writes();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Default, b"test", b"value");
+        batch.put_cf(NewColumnFamilies::Other, b"test2", b"value2");
+        db.write(batch).unwrap();
+
+        let checkpoint_path = temp_dir.path().join("checkpoint");
+        db.create_checkpoint(&checkpoint_path).unwrap();
+        // Changes after the checkpoint must not be visible in it.
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Default, b"test3", b"value3");
+        db.write(batch).unwrap();
+        drop(db);
+
+        let checkpoint_db = RocksDB::<NewColumnFamilies>::new(&checkpoint_path).unwrap();
+        let value = checkpoint_db
+            .get_cf(NewColumnFamilies::Default, b"test")
+            .unwrap();
+        assert_eq!(value.unwrap(), b"value");
+        let value = checkpoint_db
+            .get_cf(NewColumnFamilies::Other, b"test2")
+            .unwrap();
+        assert_eq!(value.unwrap(), b"value2");
+        let value = checkpoint_db
+            .get_cf(NewColumnFamilies::Default, b"test3")
+            .unwrap();
+        assert!(value.is_none());
+    }
+
     #[test]
     fn parsing_metrics_str() {
         let metrics_str = "\
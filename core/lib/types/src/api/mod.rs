@@ -13,7 +13,7 @@ pub use crate::transaction_request::{
 use crate::{
     protocol_version::L1VerifierConfig,
     vm_trace::{Call, CallType},
-    Address, L2BlockNumber, ProtocolVersionId,
+    Address, L2BlockNumber, Nonce, PriorityOpId, ProtocolVersionId,
 };
 
 pub mod en;
@@ -751,3 +751,40 @@ pub struct ApiStorageLog {
     pub key: U256,
     pub written_value: U256,
 }
+
+/// Information about a transaction in the mempool of the state keeper.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolTransaction {
+    pub hash: H256,
+    pub initiator_address: Address,
+    /// Nonce of an L2 transaction; `None` for L1 transactions.
+    pub nonce: Option<Nonce>,
+    /// Priority operation ID of an L1 transaction; `None` for L2 transactions.
+    pub priority_op_id: Option<PriorityOpId>,
+    pub max_fee_per_gas: U256,
+    pub gas_per_pubdata_limit: U256,
+    pub received_timestamp_ms: u64,
+}
+
+/// Summary of the state keeper mempool contents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolContents {
+    pub l1_transaction_count: usize,
+    pub l2_transaction_count: u64,
+    /// Transactions in the mempool ordered by the received timestamp (oldest first). May be truncated.
+    pub transactions: Vec<MempoolTransaction>,
+}
+
+/// Audit log entry for an update of state keeper seal params applied at runtime.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigUpdateEntry {
+    /// Time when the update was applied.
+    pub timestamp: DateTime<Utc>,
+    /// Origin of the update, e.g. a path to the watched file.
+    pub origin: String,
+    /// Human-readable descriptions of changed params.
+    pub changes: Vec<String>,
+}
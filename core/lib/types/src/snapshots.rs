@@ -3,11 +3,15 @@ use std::ops;
 use anyhow::Context;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::{AccountTreeId, L1BatchNumber, L2BlockNumber, H256};
+use zksync_basic_types::{AccountTreeId, Address, L1BatchNumber, L2BlockNumber, H256, H512};
 use zksync_protobuf::{required, ProtoFmt};
 use zksync_utils::u256_to_h256;
 
-use crate::{utils, web3::Bytes, ProtocolVersionId, StorageKey, StorageValue, U256};
+use crate::{
+    utils,
+    web3::{keccak256, Bytes},
+    K256PrivateKey, PackedEthSignature, ProtocolVersionId, StorageKey, StorageValue, U256,
+};
 
 /// Information about all snapshots persisted by the node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +37,9 @@ pub struct SnapshotMetadata {
     pub version: SnapshotVersion,
     /// L1 batch for the snapshot. The data in the snapshot captures node storage at the end of this batch.
     pub l1_batch_number: L1BatchNumber,
+    /// L1 batch of the base snapshot if this snapshot is differential. A differential snapshot only contains
+    /// storage logs and factory deps changed after the base snapshot, and uses the same storage logs chunking.
+    pub base_l1_batch_number: Option<L1BatchNumber>,
     /// Path to the factory dependencies blob.
     pub factory_deps_filepath: String,
     /// Paths to the storage log blobs. Ordered by the chunk ID. If a certain chunk is not produced yet,
@@ -58,6 +65,9 @@ pub struct SnapshotHeader {
     pub l1_batch_number: L1BatchNumber,
     #[serde(rename = "miniblockNumber")] // legacy naming
     pub l2_block_number: L2BlockNumber,
+    /// L1 batch of the base snapshot if this snapshot is differential; see [`SnapshotMetadata`] for details.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_l1_batch_number: Option<L1BatchNumber>,
     /// Ordered by chunk IDs.
     pub storage_logs_chunks: Vec<SnapshotStorageLogsChunkMetadata>,
     pub factory_deps_filepath: String,
@@ -202,6 +212,161 @@ impl ProtoFmt for SnapshotStorageLogsChunk {
     }
 }
 
+/// Digest of a snapshot object (a storage logs chunk or factory dependencies) recorded in a [`SnapshotManifest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotObjectDigest {
+    /// Number of entries (storage logs or factory deps) in the object.
+    pub entry_count: u64,
+    /// Rolling hash of the object entries.
+    pub hash: H256,
+}
+
+impl SnapshotObjectDigest {
+    fn new(entries: impl Iterator<Item = Vec<u8>>) -> Self {
+        let mut entry_count = 0;
+        let mut hash = H256(keccak256(&[]));
+        for entry in entries {
+            let mut preimage = hash.0.to_vec();
+            preimage.extend_from_slice(&entry);
+            hash = H256(keccak256(&preimage));
+            entry_count += 1;
+        }
+        Self { entry_count, hash }
+    }
+}
+
+impl SnapshotStorageLogsChunk {
+    /// Computes the digest of this chunk. The digest depends on all fields of the storage logs
+    /// and on their order in the chunk.
+    pub fn digest(&self) -> SnapshotObjectDigest {
+        SnapshotObjectDigest::new(self.storage_logs.iter().map(|log| {
+            let mut entry = Vec::with_capacity(96);
+            entry.extend_from_slice(log.key.address().as_bytes());
+            entry.extend_from_slice(log.key.key().as_bytes());
+            entry.extend_from_slice(log.value.as_bytes());
+            entry.extend_from_slice(&log.l1_batch_number_of_initial_write.0.to_be_bytes());
+            entry.extend_from_slice(&log.enumeration_index.to_be_bytes());
+            entry
+        }))
+    }
+}
+
+impl SnapshotFactoryDependencies {
+    /// Computes the digest of factory dependencies. The digest depends on the order of dependencies.
+    pub fn digest(&self) -> SnapshotObjectDigest {
+        SnapshotObjectDigest::new(
+            self.factory_deps
+                .iter()
+                .map(|dep| keccak256(&dep.bytecode.0).to_vec()),
+        )
+    }
+}
+
+/// Integrity manifest for a snapshot written by the snapshot creator alongside snapshot data. Allows to validate
+/// snapshot objects without a Postgres connection. If the snapshot creator has a signing key configured, the manifest
+/// hash is signed, which allows to check that the manifest was produced by the creator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotManifest {
+    pub version: u16,
+    pub l1_batch_number: L1BatchNumber,
+    pub l2_block_number: L2BlockNumber,
+    /// L1 batch of the base snapshot if the snapshot is differential.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_l1_batch_number: Option<L1BatchNumber>,
+    /// Expected root hash of the Merkle tree recovered from the snapshot. For differential snapshots,
+    /// this is the root hash after applying the entire snapshot chain.
+    pub expected_root_hash: H256,
+    pub factory_deps: SnapshotObjectDigest,
+    /// Ordered by chunk IDs.
+    pub storage_logs_chunks: Vec<SnapshotObjectDigest>,
+    /// Hash of all other manifest fields (except for `signature`); see [`Self::compute_hash()`].
+    pub manifest_hash: H256,
+    /// ECDSA (secp256k1) signature of `manifest_hash` by the snapshot creator. Without a signature, the manifest
+    /// only guards against accidental corruption, since its hash can be recomputed after modifying the manifest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<PackedEthSignature>,
+}
+
+impl SnapshotManifest {
+    /// Computes the hash of the manifest contents (i.e., all fields except `manifest_hash` and `signature`).
+    pub fn compute_hash(&self) -> H256 {
+        let mut preimage = vec![];
+        preimage.extend_from_slice(&self.version.to_be_bytes());
+        preimage.extend_from_slice(&self.l1_batch_number.0.to_be_bytes());
+        preimage.extend_from_slice(&self.l2_block_number.0.to_be_bytes());
+        if let Some(base_l1_batch_number) = self.base_l1_batch_number {
+            preimage.push(1);
+            preimage.extend_from_slice(&base_l1_batch_number.0.to_be_bytes());
+        } else {
+            preimage.push(0);
+        }
+        preimage.extend_from_slice(self.expected_root_hash.as_bytes());
+        let digests = [&self.factory_deps]
+            .into_iter()
+            .chain(&self.storage_logs_chunks);
+        for digest in digests {
+            preimage.extend_from_slice(&digest.entry_count.to_be_bytes());
+            preimage.extend_from_slice(digest.hash.as_bytes());
+        }
+        H256(keccak256(&preimage))
+    }
+
+    /// Sets `manifest_hash` based on the manifest contents.
+    pub fn seal(mut self) -> Self {
+        self.manifest_hash = self.compute_hash();
+        self
+    }
+
+    /// Checks that `manifest_hash` matches the manifest contents.
+    pub fn check_hash(&self) -> anyhow::Result<()> {
+        let expected_hash = self.compute_hash();
+        anyhow::ensure!(
+            self.manifest_hash == expected_hash,
+            "manifest hash mismatch for snapshot at L1 batch #{}: expected {expected_hash:?}, got {:?}",
+            self.l1_batch_number,
+            self.manifest_hash
+        );
+        Ok(())
+    }
+
+    /// Signs `manifest_hash` with the provided key. Must be called after [`Self::seal()`].
+    pub fn sign(mut self, signing_key: &K256PrivateKey) -> anyhow::Result<Self> {
+        let signature = PackedEthSignature::sign_raw(signing_key, &self.manifest_hash)
+            .context("failed signing manifest hash")?;
+        self.signature = Some(signature);
+        Ok(self)
+    }
+
+    /// Checks that the manifest is signed by the key corresponding to the provided secp256k1 public key
+    /// (in the uncompressed form without the key type byte). This only checks that `manifest_hash` is signed;
+    /// use [`Self::check_hash()`] to check that the hash matches the manifest contents.
+    pub fn check_signature(&self, signer_public_key: &H512) -> anyhow::Result<()> {
+        let signature = self.signature.as_ref().with_context(|| {
+            format!(
+                "manifest for snapshot at L1 batch #{} is not signed",
+                self.l1_batch_number
+            )
+        })?;
+        let signer = signature
+            .signature_recover_signer(&self.manifest_hash)
+            .context("failed recovering manifest signer")?;
+        let expected_signer = public_key_to_address(signer_public_key);
+        anyhow::ensure!(
+            signer == expected_signer,
+            "manifest for snapshot at L1 batch #{} is signed by unexpected key: expected signer {expected_signer:?}, \
+             got {signer:?}",
+            self.l1_batch_number
+        );
+        Ok(())
+    }
+}
+
+fn public_key_to_address(public_key: &H512) -> Address {
+    Address::from_slice(&keccak256(public_key.as_bytes())[12..])
+}
+
 /// Status of snapshot recovery process stored in Postgres.
 #[derive(derive_more::Debug, PartialEq)]
 pub struct SnapshotRecoveryStatus {
@@ -308,4 +473,123 @@ mod tests {
             assert!(max_chunk_size - min_chunk_size < U256::from(chunks_count));
         }
     }
+
+    #[test]
+    fn storage_logs_chunk_digest_depends_on_all_fields() {
+        let log = SnapshotStorageLog {
+            key: StorageKey::new(
+                AccountTreeId::from_fixed_bytes([1; 20]),
+                H256::repeat_byte(1),
+            ),
+            value: H256::repeat_byte(2),
+            l1_batch_number_of_initial_write: L1BatchNumber(3),
+            enumeration_index: 4,
+        };
+        let chunk = SnapshotStorageLogsChunk {
+            storage_logs: vec![log.clone()],
+        };
+        let digest = chunk.digest();
+        assert_eq!(digest.entry_count, 1);
+
+        let modified_logs = [
+            SnapshotStorageLog {
+                value: H256::repeat_byte(3),
+                ..log.clone()
+            },
+            SnapshotStorageLog {
+                l1_batch_number_of_initial_write: L1BatchNumber(4),
+                ..log.clone()
+            },
+            SnapshotStorageLog {
+                enumeration_index: 5,
+                ..log.clone()
+            },
+        ];
+        for modified_log in modified_logs {
+            let modified_chunk = SnapshotStorageLogsChunk {
+                storage_logs: vec![modified_log],
+            };
+            assert_ne!(modified_chunk.digest().hash, digest.hash);
+        }
+
+        let empty_chunk = SnapshotStorageLogsChunk {
+            storage_logs: vec![],
+        };
+        assert_eq!(empty_chunk.digest().entry_count, 0);
+        assert_ne!(empty_chunk.digest().hash, digest.hash);
+    }
+
+    #[test]
+    fn manifest_hash_is_checked() {
+        let manifest = SnapshotManifest {
+            version: SnapshotVersion::Version0.into(),
+            l1_batch_number: L1BatchNumber(10),
+            l2_block_number: L2BlockNumber(20),
+            base_l1_batch_number: None,
+            expected_root_hash: H256::repeat_byte(1),
+            factory_deps: SnapshotObjectDigest {
+                entry_count: 1,
+                hash: H256::repeat_byte(2),
+            },
+            storage_logs_chunks: vec![SnapshotObjectDigest {
+                entry_count: 3,
+                hash: H256::repeat_byte(3),
+            }],
+            manifest_hash: H256::zero(),
+            signature: None,
+        };
+        manifest.check_hash().unwrap_err();
+        let manifest = manifest.seal();
+        manifest.check_hash().unwrap();
+
+        let differential_manifest = SnapshotManifest {
+            base_l1_batch_number: Some(L1BatchNumber(5)),
+            ..manifest.clone()
+        };
+        differential_manifest.check_hash().unwrap_err();
+
+        let mut corrupted_manifest = manifest;
+        corrupted_manifest.storage_logs_chunks[0].entry_count = 4;
+        corrupted_manifest.check_hash().unwrap_err();
+    }
+
+    #[test]
+    fn manifest_signature_is_checked() {
+        let manifest = SnapshotManifest {
+            version: SnapshotVersion::Version0.into(),
+            l1_batch_number: L1BatchNumber(10),
+            l2_block_number: L2BlockNumber(20),
+            base_l1_batch_number: None,
+            expected_root_hash: H256::repeat_byte(1),
+            factory_deps: SnapshotObjectDigest {
+                entry_count: 1,
+                hash: H256::repeat_byte(2),
+            },
+            storage_logs_chunks: vec![],
+            manifest_hash: H256::zero(),
+            signature: None,
+        }
+        .seal();
+        let signing_key = K256PrivateKey::from_bytes(H256::repeat_byte(0x11)).unwrap();
+        let other_key = K256PrivateKey::from_bytes(H256::repeat_byte(0x22)).unwrap();
+        manifest.check_signature(&signing_key.public()).unwrap_err();
+
+        let signed_manifest = manifest.sign(&signing_key).unwrap();
+        signed_manifest.check_hash().unwrap();
+        signed_manifest
+            .check_signature(&signing_key.public())
+            .unwrap();
+        signed_manifest
+            .check_signature(&other_key.public())
+            .unwrap_err();
+
+        // Re-sealing a modified manifest must invalidate the signature.
+        let mut forged_manifest = signed_manifest;
+        forged_manifest.expected_root_hash = H256::repeat_byte(0xff);
+        let forged_manifest = forged_manifest.seal();
+        forged_manifest.check_hash().unwrap();
+        forged_manifest
+            .check_signature(&signing_key.public())
+            .unwrap_err();
+    }
 }
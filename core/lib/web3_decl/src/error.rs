@@ -25,6 +25,8 @@ pub enum Web3Error {
     PrunedBlock(L2BlockNumber),
     #[error("L1 batch with such an ID is pruned; the first retained L1 batch is {0}")]
     PrunedL1Batch(L1BatchNumber),
+    #[error("Data of class `{0}` is pruned for the requested block; the first block with retained data is {1}")]
+    PrunedData(&'static str, L2BlockNumber),
     #[error("{}", _0.as_ref())]
     ProxyError(#[from] EnrichedClientError),
     #[error("{0}")]
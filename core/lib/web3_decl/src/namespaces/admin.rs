@@ -0,0 +1,52 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::{
+    api::{ConfigUpdateEntry, MempoolContents},
+    H256, U64,
+};
+
+/// Operator-only namespace allowing to control the sequencer at runtime. Should never be exposed publicly.
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "admin")
+)]
+pub trait AdminNamespace {
+    /// Gracefully pauses the sequencer: the state keeper seals the current L2 block and stops processing new transactions,
+    /// while transaction submissions via colocated API servers are rejected. Returns `false` if the sequencer
+    /// is already paused.
+    #[method(name = "pauseSequencer")]
+    async fn pause_sequencer(&self) -> RpcResult<bool>;
+
+    /// Resumes processing transactions. Returns `false` if the sequencer is not paused.
+    #[method(name = "resumeSequencer")]
+    async fn resume_sequencer(&self) -> RpcResult<bool>;
+
+    /// Requests sealing the current L1 batch. The batch is sealed as soon as it contains at least one transaction.
+    #[method(name = "sealBatch")]
+    async fn seal_batch(&self) -> RpcResult<()>;
+
+    /// Returns the summary of the mempool contents with up to `limit` oldest transactions.
+    #[method(name = "mempoolContents")]
+    async fn mempool_contents(&self, limit: Option<usize>) -> RpcResult<MempoolContents>;
+
+    /// Removes a pending L2 transaction from the mempool and marks it as rejected. Returns `false`
+    /// if there is no such pending transaction.
+    #[method(name = "removeTransaction")]
+    async fn remove_transaction(&self, hash: H256) -> RpcResult<bool>;
+
+    /// Sets the minimal L2 gas price used by the sequencer. Returns the previous price.
+    #[method(name = "setMinimalGasPrice")]
+    async fn set_minimal_gas_price(&self, price: U64) -> RpcResult<U64>;
+
+    /// Returns the audit log of seal params updates applied at runtime (oldest first). Only a limited number
+    /// of the most recent updates is retained.
+    #[method(name = "sealParamsAuditLog")]
+    async fn seal_params_audit_log(&self) -> RpcResult<Vec<ConfigUpdateEntry>>;
+}
@@ -17,8 +17,13 @@ use zksync_types::{
     rpc(server, namespace = "snapshots")
 )]
 pub trait SnapshotsNamespace {
+    /// Returns L1 batch numbers of all complete snapshots. Differential snapshots are only included
+    /// if `include_differential` is set; clients not supporting differential snapshots must not set it.
     #[method(name = "getAllSnapshots")]
-    async fn get_all_snapshots(&self) -> RpcResult<AllSnapshots>;
+    async fn get_all_snapshots(
+        &self,
+        include_differential: Option<bool>,
+    ) -> RpcResult<AllSnapshots>;
 
     #[method(name = "getSnapshot")]
     async fn get_snapshot_by_l1_batch_number(
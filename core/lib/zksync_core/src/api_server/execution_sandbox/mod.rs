@@ -6,7 +6,10 @@ use std::{
 use anyhow::Context as _;
 use rand::{thread_rng, Rng};
 use tokio::runtime::Handle;
-use zksync_dal::{pruning_dal::PruningInfo, Connection, Core, CoreDal, DalError};
+use zksync_dal::{
+    pruning_dal::{DataClassesPruningInfo, PrunedDataClass, PruningInfo},
+    Connection, Core, CoreDal, DalError,
+};
 use zksync_state::PostgresStorageCaches;
 use zksync_types::{
     api, fee_model::BatchFeeInput, AccountTreeId, Address, L1BatchNumber, L2BlockNumber, L2ChainId,
@@ -193,6 +196,7 @@ impl TxSharedArgs {
 #[derive(Debug, Clone, Copy)]
 struct BlockStartInfoInner {
     info: PruningInfo,
+    data_classes: DataClassesPruningInfo,
     cached_at: Instant,
 }
 
@@ -200,6 +204,19 @@ impl BlockStartInfoInner {
     // We make max age a bit random so that all threads don't start refreshing cache at the same time
     const MAX_RANDOM_DELAY: Duration = Duration::from_millis(100);
 
+    async fn load(storage: &mut Connection<'_, Core>, now: Instant) -> anyhow::Result<Self> {
+        let info = storage.pruning_dal().get_pruning_info().await?;
+        let data_classes = storage
+            .pruning_dal()
+            .get_data_classes_pruning_info()
+            .await?;
+        Ok(Self {
+            info,
+            data_classes,
+            cached_at: now,
+        })
+    }
+
     fn is_expired(&self, now: Instant, max_cache_age: Duration) -> bool {
         if let Some(expired_for) = (now - self.cached_at).checked_sub(max_cache_age) {
             if expired_for > Self::MAX_RANDOM_DELAY {
@@ -225,12 +242,9 @@ impl BlockStartInfo {
         storage: &mut Connection<'_, Core>,
         max_cache_age: Duration,
     ) -> anyhow::Result<Self> {
-        let info = storage.pruning_dal().get_pruning_info().await?;
+        let inner = BlockStartInfoInner::load(storage, Instant::now()).await?;
         Ok(Self {
-            cached_pruning_info: Arc::new(RwLock::new(BlockStartInfoInner {
-                info,
-                cached_at: Instant::now(),
-            })),
+            cached_pruning_info: Arc::new(RwLock::new(inner)),
             max_cache_age,
         })
     }
@@ -246,36 +260,33 @@ impl BlockStartInfo {
         &self,
         storage: &mut Connection<'_, Core>,
         now: Instant,
-    ) -> anyhow::Result<PruningInfo> {
-        let info = storage.pruning_dal().get_pruning_info().await?;
+    ) -> anyhow::Result<BlockStartInfoInner> {
+        let inner = BlockStartInfoInner::load(storage, now).await?;
 
         let mut new_cached_pruning_info = self
             .cached_pruning_info
             .write()
             .map_err(|_| anyhow::anyhow!("BlockStartInfo is poisoned"))?;
         Ok(if new_cached_pruning_info.cached_at < now {
-            *new_cached_pruning_info = BlockStartInfoInner {
-                info,
-                cached_at: now,
-            };
-            info
+            *new_cached_pruning_info = inner;
+            inner
         } else {
             // Got a newer cache already; no need to update it again.
-            new_cached_pruning_info.info
+            *new_cached_pruning_info
         })
     }
 
-    async fn get_pruning_info(
+    async fn get_cached_info(
         &self,
         storage: &mut Connection<'_, Core>,
-    ) -> anyhow::Result<PruningInfo> {
+    ) -> anyhow::Result<BlockStartInfoInner> {
         let inner = self.copy_inner();
         let now = Instant::now();
         if inner.is_expired(now, self.max_cache_age) {
             // Multiple threads may execute this query if we're very unlucky
             self.update_cache(storage, now).await
         } else {
-            Ok(inner.info)
+            Ok(inner)
         }
     }
 
@@ -283,7 +294,7 @@ impl BlockStartInfo {
         &self,
         storage: &mut Connection<'_, Core>,
     ) -> anyhow::Result<L2BlockNumber> {
-        let cached_pruning_info = self.get_pruning_info(storage).await?;
+        let cached_pruning_info = self.get_cached_info(storage).await?.info;
         let last_block = cached_pruning_info.last_soft_pruned_l2_block;
         if let Some(L2BlockNumber(last_block)) = last_block {
             return Ok(L2BlockNumber(last_block + 1));
@@ -291,11 +302,33 @@ impl BlockStartInfo {
         Ok(L2BlockNumber(0))
     }
 
+    /// Returns the first L2 block for which data of the specified class is retained. This block is never less
+    /// than [`Self::first_l2_block()`].
+    pub async fn first_l2_block_for_data_class(
+        &self,
+        class: PrunedDataClass,
+        storage: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L2BlockNumber> {
+        let cached_info = self.get_cached_info(storage).await?;
+        let first_l2_block = [
+            cached_info.info.last_soft_pruned_l2_block,
+            cached_info
+                .data_classes
+                .get(class)
+                .last_soft_pruned_l2_block,
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .map_or(L2BlockNumber(0), |number| number + 1);
+        Ok(first_l2_block)
+    }
+
     pub async fn first_l1_batch(
         &self,
         storage: &mut Connection<'_, Core>,
     ) -> anyhow::Result<L1BatchNumber> {
-        let cached_pruning_info = self.get_pruning_info(storage).await?;
+        let cached_pruning_info = self.get_cached_info(storage).await?.info;
         let last_batch = cached_pruning_info.last_soft_pruned_l1_batch;
         if let Some(L1BatchNumber(last_block)) = last_batch {
             return Ok(L1BatchNumber(last_block + 1));
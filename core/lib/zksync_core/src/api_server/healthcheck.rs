@@ -1,33 +1,126 @@
-use std::{net::SocketAddr, sync::Arc, time::Duration};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
 
-use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
 use tokio::sync::watch;
-use zksync_health_check::{AppHealth, AppHealthCheck};
+use zksync_config::configs::api::HealthCheckConfig;
+use zksync_db_connection::query_plans::{self, CapturedQueryPlan};
+use zksync_health_check::{AppHealth, AppHealthCheck, HealthTransition};
 
-async fn check_health(
-    app_health_check: State<Arc<AppHealthCheck>>,
-) -> (StatusCode, Json<AppHealth>) {
-    let response = app_health_check.check_health().await;
-    let response_code = if response.is_healthy() {
+/// Components checked by the liveness and readiness probes of the healthcheck server.
+/// Empty component lists mean that all components are checked.
+#[derive(Debug, Clone, Default)]
+pub struct HealthProbes {
+    pub liveness_components: Vec<String>,
+    pub readiness_components: Vec<String>,
+}
+
+impl From<&HealthCheckConfig> for HealthProbes {
+    fn from(config: &HealthCheckConfig) -> Self {
+        Self {
+            liveness_components: config.liveness_components.clone(),
+            readiness_components: config.readiness_components.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ServerState {
+    app_health_check: Arc<AppHealthCheck>,
+    probes: Arc<HealthProbes>,
+}
+
+fn status_code(is_ok: bool) -> StatusCode {
+    if is_ok {
         StatusCode::OK
     } else {
         StatusCode::SERVICE_UNAVAILABLE
-    };
-    (response_code, Json(response))
+    }
+}
+
+async fn check_health(State(state): State<ServerState>) -> (StatusCode, Json<AppHealth>) {
+    let response = state.app_health_check.check_health().await;
+    (status_code(response.is_healthy()), Json(response))
+}
+
+/// Liveness probe. Only fails if one of the checked components has shut down or panicked.
+async fn check_liveness(State(state): State<ServerState>) -> (StatusCode, Json<AppHealth>) {
+    let response = state
+        .app_health_check
+        .check_selected_health(&state.probes.liveness_components)
+        .await;
+    (status_code(response.is_alive()), Json(response))
+}
+
+/// Readiness probe. Fails unless all checked components are healthy.
+async fn check_readiness(State(state): State<ServerState>) -> (StatusCode, Json<AppHealth>) {
+    let response = state
+        .app_health_check
+        .check_selected_health(&state.probes.readiness_components)
+        .await;
+    (status_code(response.is_healthy()), Json(response))
+}
+
+async fn health_history(
+    State(state): State<ServerState>,
+) -> Json<HashMap<&'static str, Vec<HealthTransition>>> {
+    Json(state.app_health_check.health_history())
+}
+
+async fn check_component_health(
+    State(state): State<ServerState>,
+    Path(component): Path<String>,
+) -> Response {
+    match state
+        .app_health_check
+        .check_component_health(&component)
+        .await
+    {
+        Some(health) => (status_code(health.status().is_healthy()), Json(health)).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            format!("Unknown health check component: {component}"),
+        )
+            .into_response(),
+    }
+}
+
+async fn slow_query_plans() -> Json<Vec<CapturedQueryPlan>> {
+    Json(query_plans::captured_query_plans())
 }
 
 async fn run_server(
     bind_address: &SocketAddr,
     app_health_check: Arc<AppHealthCheck>,
+    probes: HealthProbes,
+    expose_slow_query_plans: bool,
     mut stop_receiver: watch::Receiver<bool>,
 ) {
     tracing::debug!(
-        "Starting healthcheck server with checks {app_health_check:?} on {bind_address}"
+        "Starting healthcheck server with checks {app_health_check:?} and probes {probes:?} on {bind_address}"
     );
 
-    let app = Router::new()
+    let state = ServerState {
+        app_health_check,
+        probes: Arc::new(probes),
+    };
+    // Static routes take precedence over `/health/:component`, so `live`, `ready` and `history` cannot be used
+    // as component names.
+    let mut app = Router::new()
         .route("/health", get(check_health))
-        .with_state(app_health_check);
+        .route("/health/live", get(check_liveness))
+        .route("/health/ready", get(check_readiness))
+        .route("/health/history", get(health_history))
+        .route("/health/:component", get(check_component_health));
+    if expose_slow_query_plans {
+        app = app.route("/debug/slow_query_plans", get(slow_query_plans));
+    }
+    let app = app.with_state(state);
 
     axum::Server::bind(bind_address)
         .serve(app.into_make_service())
@@ -49,10 +142,24 @@ pub struct HealthCheckHandle {
 }
 
 impl HealthCheckHandle {
-    pub fn spawn_server(addr: SocketAddr, app_health_check: Arc<AppHealthCheck>) -> Self {
+    /// Spawns the server. If `expose_slow_query_plans` is set, the server exposes query plans captured
+    /// for slow DB queries at `/debug/slow_query_plans`.
+    pub fn spawn_server(
+        addr: SocketAddr,
+        app_health_check: Arc<AppHealthCheck>,
+        probes: HealthProbes,
+        expose_slow_query_plans: bool,
+    ) -> Self {
         let (stop_sender, stop_receiver) = watch::channel(false);
         let server = tokio::spawn(async move {
-            run_server(&addr, app_health_check, stop_receiver).await;
+            run_server(
+                &addr,
+                app_health_check,
+                probes,
+                expose_slow_query_plans,
+                stop_receiver,
+            )
+            .await;
         });
 
         Self {
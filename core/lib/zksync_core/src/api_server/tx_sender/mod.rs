@@ -41,7 +41,11 @@ use crate::{
         },
         tx_sender::result::ApiCallResult,
     },
-    state_keeper::seal_criteria::{ConditionalSealer, NoopSealer, SealData},
+    state_keeper::{
+        seal_criteria::{ConditionalSealer, NoopSealer, SealData},
+        tx_filter::{NoopTxFilter, TxFilter},
+        StateKeeperControl,
+    },
     utils::pending_protocol_version,
 };
 
@@ -168,6 +172,10 @@ pub struct TxSenderBuilder {
     tx_sink: Arc<dyn TxSink>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Option<Arc<dyn ConditionalSealer>>,
+    /// Filter for submitted transactions.
+    tx_filter: Option<Arc<dyn TxFilter>>,
+    /// Control handle of the state keeper used to reject transactions while the sequencer is paused.
+    state_keeper_control: Option<StateKeeperControl>,
     /// Cache for tokens that are white-listed for AA.
     whitelisted_tokens_for_aa_cache: Option<Arc<RwLock<Vec<Address>>>>,
 }
@@ -183,6 +191,8 @@ impl TxSenderBuilder {
             replica_connection_pool,
             tx_sink,
             sealer: None,
+            tx_filter: None,
+            state_keeper_control: None,
             whitelisted_tokens_for_aa_cache: None,
         }
     }
@@ -192,6 +202,18 @@ impl TxSenderBuilder {
         self
     }
 
+    pub fn with_tx_filter(mut self, tx_filter: Arc<dyn TxFilter>) -> Self {
+        self.tx_filter = Some(tx_filter);
+        self
+    }
+
+    /// Makes the sender reject transactions while the state keeper controlled by the provided handle is paused.
+    /// Should only be used if the API server runs in the same process as the state keeper.
+    pub fn with_state_keeper_control(mut self, control: StateKeeperControl) -> Self {
+        self.state_keeper_control = Some(control);
+        self
+    }
+
     pub fn with_whitelisted_tokens_for_aa(mut self, cache: Arc<RwLock<Vec<Address>>>) -> Self {
         self.whitelisted_tokens_for_aa_cache = Some(cache);
         self
@@ -206,6 +228,8 @@ impl TxSenderBuilder {
     ) -> TxSender {
         // Use noop sealer if no sealer was explicitly provided.
         let sealer = self.sealer.unwrap_or_else(|| Arc::new(NoopSealer));
+        // Accept all transactions if no filter was explicitly provided.
+        let tx_filter = self.tx_filter.unwrap_or_else(|| Arc::new(NoopTxFilter));
         let whitelisted_tokens_for_aa_cache =
             self.whitelisted_tokens_for_aa_cache.unwrap_or_else(|| {
                 Arc::new(RwLock::new(self.config.whitelisted_tokens_for_aa.clone()))
@@ -221,6 +245,8 @@ impl TxSenderBuilder {
             storage_caches,
             whitelisted_tokens_for_aa_cache,
             sealer,
+            tx_filter,
+            state_keeper_control: self.state_keeper_control,
             executor: TransactionExecutor::Real,
         }))
     }
@@ -279,6 +305,10 @@ pub struct TxSenderInner {
     pub(super) whitelisted_tokens_for_aa_cache: Arc<RwLock<Vec<Address>>>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Arc<dyn ConditionalSealer>,
+    /// Operator-defined filter for submitted transactions.
+    tx_filter: Arc<dyn TxFilter>,
+    /// Control handle of the state keeper, if it runs in the same process.
+    state_keeper_control: Option<StateKeeperControl>,
     pub(super) executor: TransactionExecutor,
 }
 
@@ -319,6 +349,15 @@ impl TxSender {
     ) -> Result<(L2TxSubmissionResult, VmExecutionResultAndLogs), SubmitTxError> {
         let tx_hash = tx.hash();
         let stage_latency = SANDBOX_METRICS.start_tx_submit_stage(tx_hash, SubmitTxStage::Validate);
+        if let Some(control) = &self.0.state_keeper_control {
+            if control.is_paused() {
+                return Err(SubmitTxError::SequencerPaused);
+            }
+        }
+        self.0
+            .tx_filter
+            .check_tx(&tx.clone().into())
+            .map_err(SubmitTxError::RejectedByFilter)?;
         let mut connection = self.acquire_replica_connection().await?;
         let protocol_version = pending_protocol_version(&mut connection).await?;
         drop(connection);
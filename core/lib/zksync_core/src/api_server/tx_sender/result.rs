@@ -72,6 +72,12 @@ pub enum SubmitTxError {
     ProxyError(#[from] EnrichedClientError),
     #[error("not enough gas to publish compressed bytecodes")]
     FailedToPublishCompressedBytecodes,
+    /// Transaction was rejected by the operator-defined transaction filter.
+    #[error("transaction rejected by filter: {0}")]
+    RejectedByFilter(String),
+    /// Sequencer is paused by the operator and doesn't accept new transactions.
+    #[error("sequencer is paused for maintenance; try again later")]
+    SequencerPaused,
     /// Catch-all internal error (e.g., database error) that should not be exposed to the caller.
     #[error("internal error")]
     Internal(#[from] anyhow::Error),
@@ -107,6 +113,8 @@ impl SubmitTxError {
             Self::IntrinsicGas => "intrinsic-gas",
             Self::ProxyError(_) => "proxy-error",
             Self::FailedToPublishCompressedBytecodes => "failed-to-publish-compressed-bytecodes",
+            Self::RejectedByFilter(_) => "rejected-by-filter",
+            Self::SequencerPaused => "sequencer-paused",
             Self::Internal(_) => "internal",
         }
     }
@@ -10,14 +10,60 @@ use zksync_types::{get_nonce_key, L1BatchNumber, L2BlockNumber, StorageLog};
 use zksync_utils::u256_to_h256;
 
 use super::*;
-use crate::api_server::execution_sandbox::{
-    testonly::MockTransactionExecutor, VmConcurrencyBarrier,
+use crate::{
+    api_server::execution_sandbox::{testonly::MockTransactionExecutor, VmConcurrencyBarrier},
+    state_keeper::{tx_filter::TxFilterRules, StateKeeperConfigSource, StateKeeperControl},
 };
 
 pub(crate) async fn create_test_tx_sender(
     pool: ConnectionPool<Core>,
     l2_chain_id: L2ChainId,
     tx_executor: TransactionExecutor,
+) -> (TxSender, VmConcurrencyBarrier) {
+    create_test_tx_sender_with_filter(pool, l2_chain_id, tx_executor, Arc::new(NoopTxFilter)).await
+}
+
+/// Creates a test tx sender with the specified transaction filter (set via [`TxSenderBuilder::with_tx_filter()`]).
+async fn create_test_tx_sender_with_filter(
+    pool: ConnectionPool<Core>,
+    l2_chain_id: L2ChainId,
+    tx_executor: TransactionExecutor,
+    tx_filter: Arc<dyn TxFilter>,
+) -> (TxSender, VmConcurrencyBarrier) {
+    let batch_fee_model_input_provider = Arc::new(MockBatchFeeParamsProvider::default());
+    build_test_tx_sender(
+        pool,
+        l2_chain_id,
+        tx_executor,
+        tx_filter,
+        batch_fee_model_input_provider,
+    )
+    .await
+}
+
+/// Creates a test tx sender with the specified fee input provider, which can be shared with other components.
+pub(crate) async fn create_test_tx_sender_with_fee_input_provider(
+    pool: ConnectionPool<Core>,
+    l2_chain_id: L2ChainId,
+    batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+) -> (TxSender, VmConcurrencyBarrier) {
+    let tx_executor = MockTransactionExecutor::default().into();
+    build_test_tx_sender(
+        pool,
+        l2_chain_id,
+        tx_executor,
+        Arc::new(NoopTxFilter),
+        batch_fee_model_input_provider,
+    )
+    .await
+}
+
+async fn build_test_tx_sender(
+    pool: ConnectionPool<Core>,
+    l2_chain_id: L2ChainId,
+    tx_executor: TransactionExecutor,
+    tx_filter: Arc<dyn TxFilter>,
+    batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
 ) -> (TxSender, VmConcurrencyBarrier) {
     let web3_config = Web3JsonRpcConfig::for_tests();
     let state_keeper_config = StateKeeperConfig::for_tests();
@@ -30,15 +76,16 @@ pub(crate) async fn create_test_tx_sender(
     );
 
     let storage_caches = PostgresStorageCaches::new(1, 1);
-    let batch_fee_model_input_provider = Arc::new(MockBatchFeeParamsProvider::default());
     let (mut tx_sender, vm_barrier) = crate::build_tx_sender(
         &tx_sender_config,
         &web3_config,
-        &state_keeper_config,
+        StateKeeperConfigSource::new(state_keeper_config),
         pool.clone(),
         pool,
         batch_fee_model_input_provider,
         storage_caches,
+        tx_filter,
+        None,
     )
     .await;
 
@@ -191,3 +238,58 @@ async fn submitting_tx_requires_one_connection() {
         .unwrap()
         .expect("transaction is not persisted");
 }
+
+#[tokio::test]
+async fn submitting_tx_rejected_by_filter() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let tx = create_l2_transaction(10, 100);
+    let tx_executor = MockTransactionExecutor::default().into();
+    let rules = TxFilterRules {
+        denied_senders: [tx.initiator_account()].into(),
+        ..TxFilterRules::default()
+    };
+    let (tx_sender, _) = create_test_tx_sender_with_filter(
+        pool.clone(),
+        L2ChainId::default(),
+        tx_executor,
+        Arc::new(rules),
+    )
+    .await;
+
+    let err = tx_sender.submit_tx(tx).await.unwrap_err();
+    assert_eq!(err.prom_error_code(), "rejected-by-filter");
+    assert_matches!(err, SubmitTxError::RejectedByFilter(reason) if reason.contains("sender"));
+}
+
+#[tokio::test]
+async fn submitting_tx_while_sequencer_is_paused() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let tx = create_l2_transaction(10, 100);
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (mut tx_sender, _) =
+        create_test_tx_sender(pool.clone(), L2ChainId::default(), tx_executor).await;
+    let control = StateKeeperControl::default();
+    Arc::get_mut(&mut tx_sender.0).unwrap().state_keeper_control = Some(control.clone());
+
+    control.pause();
+    let err = tx_sender.submit_tx(tx.clone()).await.unwrap_err();
+    assert_eq!(err.prom_error_code(), "sequencer-paused");
+    assert_matches!(err, SubmitTxError::SequencerPaused);
+
+    control.resume();
+    // The transaction may be rejected for other reasons (e.g., insufficient balance), but not because of the pause.
+    let res = tx_sender.submit_tx(tx).await;
+    assert!(!matches!(res, Err(SubmitTxError::SequencerPaused)));
+}
@@ -0,0 +1,74 @@
+//! Admin JSON-RPC server.
+
+use std::{collections::HashSet, net::SocketAddr, sync::Arc};
+
+use anyhow::Context as _;
+use tokio::sync::{oneshot, watch};
+use zksync_web3_decl::{
+    jsonrpsee::server::{RpcServiceBuilder, ServerBuilder},
+    namespaces::AdminNamespaceServer,
+};
+
+pub use super::namespaces::AdminHandles;
+use super::{
+    backend_jsonrpsee::{MetadataLayer, MethodTracer},
+    namespaces::AdminNamespace,
+};
+
+/// HTTP JSON-RPC server exposing the `admin` namespace. Unlike the [main API server](super::ApiServer),
+/// this server is run in the same process as the state keeper, and must only be reachable by node operators.
+#[derive(Debug)]
+pub struct AdminApiServer {
+    addr: SocketAddr,
+    handles: AdminHandles,
+    local_addr_sender: Option<oneshot::Sender<SocketAddr>>,
+}
+
+impl AdminApiServer {
+    pub fn new(addr: SocketAddr, handles: AdminHandles) -> Self {
+        Self {
+            addr,
+            handles,
+            local_addr_sender: None,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_local_addr_sender(mut self, sender: oneshot::Sender<SocketAddr>) -> Self {
+        self.local_addr_sender = Some(sender);
+        self
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let method_tracer = Arc::new(MethodTracer::default());
+        let rpc = AdminNamespace::new(self.handles, method_tracer.clone()).into_rpc();
+        let registered_method_names = Arc::new(rpc.method_names().collect::<HashSet<_>>());
+        let rpc_middleware = RpcServiceBuilder::new()
+            .layer(MetadataLayer::new(registered_method_names, method_tracer));
+
+        let server = ServerBuilder::default()
+            .http_only()
+            .set_rpc_middleware(rpc_middleware)
+            .build(self.addr)
+            .await
+            .context("Failed building admin JSON-RPC server")?;
+        let local_addr = server
+            .local_addr()
+            .context("Failed getting local address for admin JSON-RPC server")?;
+        let server_handle = server.start(rpc);
+        tracing::info!("Initialized admin API on {local_addr:?}");
+        if let Some(sender) = self.local_addr_sender {
+            sender.send(local_addr).ok();
+        }
+
+        if stop_receiver.changed().await.is_err() {
+            tracing::warn!(
+                "Stop signal sender for admin JSON-RPC server was dropped without sending a signal"
+            );
+        }
+        tracing::info!("Stop signal received, admin JSON-RPC server is shutting down");
+        server_handle.stop().ok();
+        server_handle.stopped().await;
+        Ok(())
+    }
+}
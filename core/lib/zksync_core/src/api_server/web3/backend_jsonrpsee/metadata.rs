@@ -3,6 +3,7 @@
 use std::{cell::RefCell, mem, sync::Arc, time::Instant};
 
 use thread_local::ThreadLocal;
+use zksync_dal::ReadScope;
 use zksync_types::api;
 use zksync_web3_decl::{
     error::Web3Error,
@@ -24,6 +25,9 @@ pub(crate) struct MethodMetadata {
     pub block_diff: Option<u32>,
     /// Did this call return an app-level error?
     pub has_app_error: bool,
+    /// Scope for read-only DB connections acquired by the call. Ensures that all connections are established
+    /// to the same database if read replicas are used.
+    pub read_scope: ReadScope,
 }
 
 impl MethodMetadata {
@@ -34,6 +38,7 @@ impl MethodMetadata {
             block_id: None,
             block_diff: None,
             has_app_error: false,
+            read_scope: ReadScope::default(),
         }
     }
 }
@@ -88,6 +93,17 @@ impl MethodTracer {
         }
     }
 
+    /// Returns the scope for read-only DB connections for the current JSON-RPC method call.
+    ///
+    /// This should be called inside JSON-RPC method handlers; otherwise, this method returns `None`.
+    pub fn read_scope(&self) -> Option<ReadScope> {
+        let cell = self.inner.get_or_default();
+        let metadata = cell.borrow();
+        metadata
+            .as_ref()
+            .map(|metadata| metadata.read_scope.clone())
+    }
+
     pub(super) fn new_call<'a>(
         self: &Arc<Self>,
         name: &'static str,
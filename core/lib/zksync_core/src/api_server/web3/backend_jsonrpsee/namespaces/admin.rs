@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use zksync_types::{
+    api::{ConfigUpdateEntry, MempoolContents},
+    H256, U64,
+};
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::AdminNamespaceServer};
+
+use crate::api_server::web3::namespaces::AdminNamespace;
+
+#[async_trait]
+impl AdminNamespaceServer for AdminNamespace {
+    async fn pause_sequencer(&self) -> RpcResult<bool> {
+        Ok(self.pause_sequencer_impl())
+    }
+
+    async fn resume_sequencer(&self) -> RpcResult<bool> {
+        Ok(self.resume_sequencer_impl())
+    }
+
+    async fn seal_batch(&self) -> RpcResult<()> {
+        self.seal_batch_impl();
+        Ok(())
+    }
+
+    async fn mempool_contents(&self, limit: Option<usize>) -> RpcResult<MempoolContents> {
+        Ok(self.mempool_contents_impl(limit))
+    }
+
+    async fn remove_transaction(&self, hash: H256) -> RpcResult<bool> {
+        self.remove_transaction_impl(hash)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn set_minimal_gas_price(&self, price: U64) -> RpcResult<U64> {
+        Ok(self.set_minimal_gas_price_impl(price))
+    }
+
+    async fn seal_params_audit_log(&self) -> RpcResult<Vec<ConfigUpdateEntry>> {
+        Ok(self.seal_params_audit_log_impl())
+    }
+}
@@ -10,7 +10,7 @@ use tokio::{
 };
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
 use zksync_config::configs::api::{MaxResponseSize, MaxResponseSizeOverrides};
-use zksync_dal::{ConnectionPool, Core};
+use zksync_dal::{ConnectionPool, Core, RoutingConnectionPool};
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::L2BlockNumber;
 use zksync_web3_decl::{
@@ -39,7 +39,7 @@ use self::{
         ZksNamespace,
     },
     pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent},
-    state::{Filters, InternalApiConfig, RpcState, SealedL2BlockNumber},
+    state::{Filters, InternalApiConfig, L2ToL1LogTreesCache, RpcState, SealedL2BlockNumber},
 };
 use crate::{
     api_server::{
@@ -51,6 +51,7 @@ use crate::{
     utils::wait_for_l1_batch,
 };
 
+pub mod admin;
 pub mod backend_jsonrpsee;
 pub mod mempool_cache;
 pub(super) mod metrics;
@@ -133,6 +134,7 @@ struct OptionalApiParams {
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tree_api: Option<Arc<dyn TreeApiClient>>,
     mempool_cache: Option<MempoolCache>,
+    read_replicas_pool: Option<RoutingConnectionPool<Core>>,
     extended_tracing: bool,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
@@ -279,6 +281,20 @@ impl ApiBuilder {
         self
     }
 
+    /// Configures a routing pool to serve read-only API requests from read replicas. If not called,
+    /// all requests are served by the main pool.
+    ///
+    /// The replication lag of replicas is not updated by the server; it's the caller's responsibility
+    /// to run [`RoutingConnectionPool::lag_monitor()`].
+    pub fn with_read_replicas(mut self, pool: RoutingConnectionPool<Core>) -> Self {
+        tracing::info!(
+            "Using {} read replica(s) for API requests",
+            pool.replica_count()
+        );
+        self.optional.read_replicas_pool = Some(pool);
+        self
+    }
+
     pub fn with_extended_tracing(mut self, extended_tracing: bool) -> Self {
         self.optional.extended_tracing = extended_tracing;
         self
@@ -355,6 +371,7 @@ impl ApiServer {
             current_method: self.method_tracer,
             installed_filters,
             connection_pool: self.pool,
+            read_replicas_pool: self.optional.read_replicas_pool,
             tx_sender: self.tx_sender,
             sync_state: self.optional.sync_state,
             api_config: self.config,
@@ -362,6 +379,7 @@ impl ApiServer {
             mempool_cache: self.optional.mempool_cache,
             last_sealed_l2_block,
             tree_api: self.optional.tree_api,
+            l2_to_l1_log_trees: L2ToL1LogTreesCache::default(),
         })
     }
 
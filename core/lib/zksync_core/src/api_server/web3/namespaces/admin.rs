@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use zksync_dal::{ConnectionPool, Core, CoreDal, DalError};
+use zksync_node_fee_model::MainNodeFeeInputProvider;
+use zksync_types::{
+    api::{ConfigUpdateEntry, MempoolContents},
+    H256, U64,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::{
+    api_server::web3::backend_jsonrpsee::MethodTracer,
+    state_keeper::{MempoolGuard, StateKeeperConfigSource, StateKeeperControl},
+};
+
+/// Handles to the main node components controlled via the `admin` namespace.
+#[derive(Debug, Clone)]
+pub struct AdminHandles {
+    state_keeper: StateKeeperControl,
+    mempool: MempoolGuard,
+    fee_input_provider: Arc<MainNodeFeeInputProvider>,
+    config_source: StateKeeperConfigSource,
+    pool: ConnectionPool<Core>,
+}
+
+impl AdminHandles {
+    pub fn new(
+        state_keeper: StateKeeperControl,
+        mempool: MempoolGuard,
+        fee_input_provider: Arc<MainNodeFeeInputProvider>,
+        config_source: StateKeeperConfigSource,
+        pool: ConnectionPool<Core>,
+    ) -> Self {
+        Self {
+            state_keeper,
+            mempool,
+            fee_input_provider,
+            config_source,
+            pool,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AdminNamespace {
+    handles: AdminHandles,
+    method_tracer: Arc<MethodTracer>,
+}
+
+impl AdminNamespace {
+    const DEFAULT_MEMPOOL_CONTENTS_LIMIT: usize = 100;
+    const MAX_MEMPOOL_CONTENTS_LIMIT: usize = 10_000;
+    /// Error recorded for transactions removed via [`Self::remove_transaction_impl()`].
+    const REMOVED_TX_ERROR: &'static str = "removed by operator";
+
+    pub fn new(handles: AdminHandles, method_tracer: Arc<MethodTracer>) -> Self {
+        Self {
+            handles,
+            method_tracer,
+        }
+    }
+
+    pub(crate) fn current_method(&self) -> &MethodTracer {
+        &self.method_tracer
+    }
+
+    pub fn pause_sequencer_impl(&self) -> bool {
+        self.handles.state_keeper.pause()
+    }
+
+    pub fn resume_sequencer_impl(&self) -> bool {
+        self.handles.state_keeper.resume()
+    }
+
+    pub fn seal_batch_impl(&self) {
+        self.handles.state_keeper.request_l1_batch_seal();
+    }
+
+    pub fn mempool_contents_impl(&self, limit: Option<usize>) -> MempoolContents {
+        let limit = limit
+            .unwrap_or(Self::DEFAULT_MEMPOOL_CONTENTS_LIMIT)
+            .min(Self::MAX_MEMPOOL_CONTENTS_LIMIT);
+        self.handles.mempool.contents(limit)
+    }
+
+    pub async fn remove_transaction_impl(&self, hash: H256) -> Result<bool, Web3Error> {
+        let mut storage = self
+            .handles
+            .pool
+            .connection_tagged("api")
+            .await
+            .map_err(DalError::generalize)?;
+        // The transaction must be marked as rejected in Postgres *before* it's removed from the mempool;
+        // otherwise, it could be reloaded by the mempool fetcher.
+        let rejected = storage
+            .transactions_dal()
+            .reject_pending_l2_transaction(hash, Self::REMOVED_TX_ERROR)
+            .await
+            .map_err(DalError::generalize)?;
+        drop(storage);
+
+        let removed = self.handles.mempool.clone().remove_transaction(hash);
+        tracing::info!(
+            "Processed request to remove transaction {hash:?}: rejected in Postgres: {rejected}, \
+             removed from mempool: {removed}"
+        );
+        Ok(rejected || removed)
+    }
+
+    pub fn set_minimal_gas_price_impl(&self, price: U64) -> U64 {
+        let prev_price = self
+            .handles
+            .fee_input_provider
+            .set_minimal_l2_gas_price(price.as_u64());
+        prev_price.into()
+    }
+
+    pub fn seal_params_audit_log_impl(&self) -> Vec<ConfigUpdateEntry> {
+        self.handles.config_source.audit_log()
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use anyhow::Context as _;
 use multivm::{interface::ExecutionResult, vm_latest::constants::BATCH_COMPUTATIONAL_GAS_LIMIT};
 use once_cell::sync::OnceCell;
-use zksync_dal::{CoreDal, DalError};
+use zksync_dal::{pruning_dal::PrunedDataClass, CoreDal, DalError};
 use zksync_system_constants::MAX_ENCODED_TX_SIZE;
 use zksync_types::{
     api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
@@ -71,6 +71,10 @@ impl DebugNamespace {
         let block_number = self.state.resolve_block(&mut connection, block_id).await?;
         self.current_method()
             .set_block_diff(self.state.last_sealed_l2_block.diff(block_number));
+        self.state
+            .start_info
+            .ensure_data_not_pruned(PrunedDataClass::CallTraces, block_number, &mut connection)
+            .await?;
 
         let call_traces = connection
             .blocks_web3_dal()
@@ -114,6 +118,13 @@ impl DebugNamespace {
             .get_call_trace(tx_hash)
             .await
             .map_err(DalError::generalize)?;
+        if call_trace.is_none() {
+            // Distinguish between unknown transactions and transactions with pruned traces.
+            self.state
+                .start_info
+                .ensure_tx_data_not_pruned(PrunedDataClass::CallTraces, tx_hash, &mut connection)
+                .await?;
+        }
         Ok(call_trace.map(|call_trace| {
             let mut result: DebugCall = call_trace.into();
             if only_top_call {
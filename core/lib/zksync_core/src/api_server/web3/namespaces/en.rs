@@ -1,6 +1,6 @@
 use anyhow::Context as _;
 use zksync_config::{configs::EcosystemContracts, GenesisConfig};
-use zksync_dal::{CoreDal, DalError};
+use zksync_dal::{pruning_dal::PrunedDataClass, CoreDal, DalError};
 use zksync_types::{api::en, tokens::TokenInfo, Address, L1BatchNumber, L2BlockNumber, H256};
 use zksync_web3_decl::error::Web3Error;
 
@@ -43,6 +43,16 @@ impl EnNamespace {
         include_transactions: bool,
     ) -> Result<Option<en::SyncBlock>, Web3Error> {
         let mut storage = self.state.acquire_connection().await?;
+        if include_transactions {
+            self.state
+                .start_info
+                .ensure_data_not_pruned(
+                    PrunedDataClass::TransactionPayloads,
+                    block_number,
+                    &mut storage,
+                )
+                .await?;
+        }
         Ok(storage
             .sync_dal()
             .sync_block(block_number, include_transactions)
@@ -1,5 +1,5 @@
 use anyhow::Context as _;
-use zksync_dal::{CoreDal, DalError};
+use zksync_dal::{pruning_dal::PrunedDataClass, CoreDal, DalError};
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
@@ -231,6 +231,14 @@ impl EthNamespace {
         self.set_block_diff(block_number);
 
         let transactions = if full_transactions {
+            self.state
+                .start_info
+                .ensure_data_not_pruned(
+                    PrunedDataClass::TransactionPayloads,
+                    block_number,
+                    &mut storage,
+                )
+                .await?;
             let mut transactions = storage
                 .transactions_web3_dal()
                 .get_transactions(&block.transactions, self.state.api_config.l2_chain_id)
@@ -325,6 +333,14 @@ impl EthNamespace {
             return Ok(None);
         };
         self.set_block_diff(block_number); // only report block diff for existing L2 blocks
+        self.state
+            .start_info
+            .ensure_data_not_pruned(
+                PrunedDataClass::TransactionPayloads,
+                block_number,
+                &mut storage,
+            )
+            .await?;
 
         let mut receipts = storage
             .transactions_web3_dal()
@@ -433,11 +449,25 @@ impl EthNamespace {
         let mut storage = self.state.acquire_connection().await?;
         let chain_id = self.state.api_config.l2_chain_id;
         let mut transaction = match id {
-            TransactionId::Hash(hash) => storage
-                .transactions_web3_dal()
-                .get_transaction_by_hash(hash, chain_id)
-                .await
-                .map_err(DalError::generalize)?,
+            TransactionId::Hash(hash) => {
+                let transaction = storage
+                    .transactions_web3_dal()
+                    .get_transaction_by_hash(hash, chain_id)
+                    .await
+                    .map_err(DalError::generalize)?;
+                if transaction.is_none() {
+                    // Distinguish between unknown transactions and transactions with pruned payloads.
+                    self.state
+                        .start_info
+                        .ensure_tx_data_not_pruned(
+                            PrunedDataClass::TransactionPayloads,
+                            hash,
+                            &mut storage,
+                        )
+                        .await?;
+                }
+                transaction
+            }
 
             TransactionId::Block(block_id, idx) => {
                 let Ok(idx) = u32::try_from(idx) else {
@@ -450,6 +480,14 @@ impl EthNamespace {
                 else {
                     return Ok(None);
                 };
+                self.state
+                    .start_info
+                    .ensure_data_not_pruned(
+                        PrunedDataClass::TransactionPayloads,
+                        block_number,
+                        &mut storage,
+                    )
+                    .await?;
 
                 storage
                     .transactions_web3_dal()
@@ -475,6 +513,13 @@ impl EthNamespace {
             .get_transaction_receipts(&[hash])
             .await
             .context("get_transaction_receipts")?;
+        if receipts.is_empty() {
+            // Distinguish between unknown transactions and transactions with pruned payloads.
+            self.state
+                .start_info
+                .ensure_tx_data_not_pruned(PrunedDataClass::TransactionPayloads, hash, &mut storage)
+                .await?;
+        }
         Ok(receipts.into_iter().next())
     }
 
@@ -750,6 +795,10 @@ impl EthNamespace {
                 };
 
                 let mut storage = self.state.acquire_connection().await?;
+                self.state
+                    .start_info
+                    .ensure_data_not_pruned(PrunedDataClass::Events, *from_block, &mut storage)
+                    .await?;
 
                 // Check if there is more than one block in range and there are more than `req_entities_limit` logs that satisfies filter.
                 // In this case we should return error and suggest requesting logs with smaller block range.
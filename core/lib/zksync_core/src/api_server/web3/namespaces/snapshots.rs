@@ -22,13 +22,19 @@ impl SnapshotsNamespace {
         &self.state.current_method
     }
 
-    pub async fn get_all_snapshots_impl(&self) -> Result<AllSnapshots, Web3Error> {
+    pub async fn get_all_snapshots_impl(
+        &self,
+        include_differential: bool,
+    ) -> Result<AllSnapshots, Web3Error> {
         let mut storage_processor = self.state.acquire_connection().await?;
         let mut snapshots_dal = storage_processor.snapshots_dal();
-        Ok(snapshots_dal
-            .get_all_complete_snapshots()
-            .await
-            .map_err(DalError::generalize)?)
+        // Differential snapshots are opt-in, so that outdated clients don't mistake them for full snapshots.
+        let snapshots = if include_differential {
+            snapshots_dal.get_all_complete_snapshots().await
+        } else {
+            snapshots_dal.get_all_complete_full_snapshots().await
+        };
+        Ok(snapshots.map_err(DalError::generalize)?)
     }
 
     pub async fn get_snapshot_by_l1_batch_number_impl(
@@ -74,6 +80,7 @@ impl SnapshotsNamespace {
             version: snapshot_metadata.version.into(),
             l1_batch_number: snapshot_metadata.l1_batch_number,
             l2_block_number,
+            base_l1_batch_number: snapshot_metadata.base_l1_batch_number,
             storage_logs_chunks: chunks,
             factory_deps_filepath: snapshot_metadata.factory_deps_filepath,
         }))
@@ -2,8 +2,7 @@ use std::{collections::HashMap, convert::TryInto};
 
 use anyhow::Context as _;
 use multivm::interface::VmExecutionResultAndLogs;
-use zksync_dal::{Connection, Core, CoreDal, DalError};
-use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_dal::{pruning_dal::PrunedDataClass, Connection, Core, CoreDal, DalError};
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
@@ -299,8 +298,12 @@ impl ZksNamespace {
             .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
         let tree_size = l2_to_l1_logs_tree_size(protocol_version);
 
-        let (root, proof) = MiniMerkleTree::new(merkle_tree_leaves, Some(tree_size))
-            .merkle_root_and_path(l1_log_index);
+        let tree = self.state.l2_to_l1_log_trees.get_or_build(
+            l1_batch_number,
+            merkle_tree_leaves.collect(),
+            tree_size,
+        );
+        let (root, proof) = tree.merkle_root_and_path(l1_log_index);
         Ok(Some(L2ToL1LogProof {
             proof,
             root,
@@ -388,6 +391,14 @@ impl ZksNamespace {
             .start_info
             .ensure_not_pruned(block_number, &mut storage)
             .await?;
+        self.state
+            .start_info
+            .ensure_data_not_pruned(
+                PrunedDataClass::TransactionPayloads,
+                block_number,
+                &mut storage,
+            )
+            .await?;
 
         Ok(storage
             .transactions_web3_dal()
@@ -406,6 +417,13 @@ impl ZksNamespace {
             .get_transaction_details(hash)
             .await
             .map_err(DalError::generalize)?;
+        if tx_details.is_none() {
+            // Distinguish between unknown transactions and transactions with pruned payloads.
+            self.state
+                .start_info
+                .ensure_tx_data_not_pruned(PrunedDataClass::TransactionPayloads, hash, &mut storage)
+                .await?;
+        }
         drop(storage);
 
         if tx_details.is_none() {
@@ -1,5 +1,6 @@
 use std::{
     future::Future,
+    num::NonZeroUsize,
     sync::{
         atomic::{AtomicU32, Ordering},
         Arc,
@@ -8,7 +9,7 @@ use std::{
 };
 
 use anyhow::Context as _;
-use futures::TryFutureExt;
+use futures::{future, TryFutureExt};
 use lru::LruCache;
 use tokio::sync::{watch, Mutex};
 use vise::GaugeGuard;
@@ -16,10 +17,15 @@ use zksync_config::{
     configs::{api::Web3JsonRpcConfig, chain::L1BatchCommitDataGeneratorMode, ContractsConfig},
     GenesisConfig,
 };
-use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, DalError};
+use zksync_dal::{
+    pruning_dal::PrunedDataClass, Connection, ConnectionPool, Core, CoreDal, DalError,
+    RoutingConnectionPool,
+};
+use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_types::{
-    api, l2::L2Tx, transaction_request::CallRequest, Address, L1BatchNumber, L1ChainId,
-    L2BlockNumber, L2ChainId, H256, U256, U64,
+    api, commitment::SerializeCommitment, l2::L2Tx, l2_to_l1_log::L2ToL1Log,
+    transaction_request::CallRequest, Address, L1BatchNumber, L1ChainId, L2BlockNumber, L2ChainId,
+    H256, U256, U64,
 };
 use zksync_web3_decl::{error::Web3Error, types::Filter};
 
@@ -89,6 +95,40 @@ impl BlockStartInfo {
             }
         }
     }
+
+    /// Checks whether data of the specified class is retained for the specified L2 block.
+    pub(super) async fn ensure_data_not_pruned(
+        &self,
+        class: PrunedDataClass,
+        block_number: L2BlockNumber,
+        storage: &mut Connection<'_, Core>,
+    ) -> Result<(), Web3Error> {
+        let first_l2_block = self.first_l2_block_for_data_class(class, storage).await?;
+        if block_number < first_l2_block {
+            return Err(Web3Error::PrunedData(class.as_str(), first_l2_block));
+        }
+        Ok(())
+    }
+
+    /// Checks whether data of the specified class is retained for the L2 block containing the specified transaction.
+    /// Unknown transactions and transactions not included into an L2 block are not checked.
+    pub(super) async fn ensure_tx_data_not_pruned(
+        &self,
+        class: PrunedDataClass,
+        tx_hash: H256,
+        storage: &mut Connection<'_, Core>,
+    ) -> Result<(), Web3Error> {
+        let block_number = storage
+            .transactions_web3_dal()
+            .get_l2_block_number_of_transaction(tx_hash)
+            .await
+            .map_err(DalError::generalize)?;
+        if let Some(block_number) = block_number {
+            self.ensure_data_not_pruned(class, block_number, storage)
+                .await?;
+        }
+        Ok(())
+    }
 }
 
 /// Configuration values for the API.
@@ -240,6 +280,9 @@ pub(crate) struct RpcState {
     pub(super) current_method: Arc<MethodTracer>,
     pub(super) installed_filters: Option<Arc<Mutex<Filters>>>,
     pub(super) connection_pool: ConnectionPool<Core>,
+    /// Pool routing read-only connections to read replicas. If set, it's used instead of `connection_pool`
+    /// by [`Self::acquire_connection()`].
+    pub(super) read_replicas_pool: Option<RoutingConnectionPool<Core>>,
     pub(super) tree_api: Option<Arc<dyn TreeApiClient>>,
     pub(super) tx_sender: TxSender,
     pub(super) sync_state: Option<SyncState>,
@@ -249,6 +292,7 @@ pub(crate) struct RpcState {
     pub(super) start_info: BlockStartInfo,
     pub(super) mempool_cache: Option<MempoolCache>,
     pub(super) last_sealed_l2_block: SealedL2BlockNumber,
+    pub(super) l2_to_l1_log_trees: L2ToL1LogTreesCache,
 }
 
 impl RpcState {
@@ -281,9 +325,19 @@ impl RpcState {
     pub(crate) fn acquire_connection(
         &self,
     ) -> impl Future<Output = Result<Connection<'_, Core>, Web3Error>> + '_ {
-        self.connection_pool
-            .connection_tagged("api")
-            .map_err(|err| err.generalize().into())
+        let connection = if let Some(pool) = &self.read_replicas_pool {
+            // Pin all connections acquired by the current method call to the same database, so that
+            // the returned data is consistent.
+            let connection = if let Some(scope) = self.current_method.read_scope() {
+                future::Either::Left(pool.scoped_read_connection_tagged(scope, "api"))
+            } else {
+                future::Either::Right(pool.read_connection_tagged("api"))
+            };
+            future::Either::Left(connection)
+        } else {
+            future::Either::Right(self.connection_pool.connection_tagged("api"))
+        };
+        connection.map_err(|err| err.generalize().into())
     }
 
     /// Resolves the specified block ID to a block number, which is guaranteed to be present in the node storage.
@@ -520,6 +574,74 @@ impl Filters {
     }
 }
 
+/// Merkle tree of L2-to-L1 logs in an L1 batch used to generate log proofs.
+#[derive(Debug)]
+pub(crate) struct L2ToL1LogsTree {
+    /// Serialized logs the tree is built from. Used to check that a cached tree is up to date,
+    /// since logs in an L1 batch can change if the batch is reverted.
+    leaves: Vec<[u8; L2ToL1Log::SERIALIZED_SIZE]>,
+    tree_size: usize,
+    tree: MiniMerkleTree<{ L2ToL1Log::SERIALIZED_SIZE }>,
+}
+
+impl L2ToL1LogsTree {
+    /// Returns the root hash of the tree and the Merkle path for the log with the specified index.
+    pub fn merkle_root_and_path(&self, index: usize) -> (H256, Vec<H256>) {
+        self.tree.merkle_root_and_path(index)
+    }
+}
+
+/// LRU cache of [`L2ToL1LogsTree`]s keyed by the L1 batch number. Allows to avoid rebuilding a tree
+/// for each requested log proof; proofs are usually requested for many logs in the same L1 batch
+/// (e.g., when finalizing withdrawals).
+#[derive(Debug, Clone)]
+pub(crate) struct L2ToL1LogTreesCache(
+    Arc<std::sync::Mutex<LruCache<L1BatchNumber, Arc<L2ToL1LogsTree>>>>,
+);
+
+impl Default for L2ToL1LogTreesCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl L2ToL1LogTreesCache {
+    const DEFAULT_CAPACITY: NonZeroUsize = match NonZeroUsize::new(16) {
+        Some(capacity) => capacity,
+        None => unreachable!(),
+    };
+
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self(Arc::new(std::sync::Mutex::new(LruCache::new(capacity))))
+    }
+
+    /// Returns a tree for the specified L1 batch. The tree is built from `leaves` if it's not cached,
+    /// or if the cached tree was built from different leaves.
+    pub fn get_or_build(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        leaves: Vec<[u8; L2ToL1Log::SERIALIZED_SIZE]>,
+        tree_size: usize,
+    ) -> Arc<L2ToL1LogsTree> {
+        let cached_tree = self.0.lock().unwrap().get(&l1_batch_number).cloned();
+        if let Some(tree) = cached_tree {
+            if tree.tree_size == tree_size && tree.leaves == leaves {
+                return tree;
+            }
+        }
+
+        // Build the tree outside the lock so that other requests aren't blocked.
+        let tree = MiniMerkleTree::new(leaves.iter().copied(), Some(tree_size));
+        let tree = Arc::new(L2ToL1LogsTree {
+            leaves,
+            tree_size,
+            tree,
+        });
+        self.0.lock().unwrap().put(l1_batch_number, tree.clone());
+        tree
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::NaiveDateTime;
@@ -558,4 +680,37 @@ mod tests {
         assert!(filters.0.contains(&idx2));
         assert!(!filters.0.contains(&idx3));
     }
+
+    #[test]
+    fn l2_to_l1_log_trees_are_cached() {
+        use super::*;
+
+        let cache = L2ToL1LogTreesCache::new(NonZeroUsize::new(1).unwrap());
+        let leaves = vec![[1_u8; 88], [2_u8; 88], [3_u8; 88]];
+        let tree = cache.get_or_build(L1BatchNumber(1), leaves.clone(), 4);
+        let expected_tree = MiniMerkleTree::new(leaves.iter().copied(), Some(4));
+        assert_eq!(
+            tree.merkle_root_and_path(1),
+            expected_tree.merkle_root_and_path(1)
+        );
+
+        let cached_tree = cache.get_or_build(L1BatchNumber(1), leaves.clone(), 4);
+        assert!(Arc::ptr_eq(&cached_tree, &tree));
+
+        // The tree must be rebuilt if the logs in the L1 batch change (e.g., after a revert).
+        let new_leaves = vec![[1_u8; 88], [4_u8; 88]];
+        let new_tree = cache.get_or_build(L1BatchNumber(1), new_leaves.clone(), 4);
+        assert!(!Arc::ptr_eq(&new_tree, &tree));
+        let expected_tree = MiniMerkleTree::new(new_leaves.into_iter(), Some(4));
+        assert_eq!(
+            new_tree.merkle_root_and_path(1),
+            expected_tree.merkle_root_and_path(1)
+        );
+
+        // Caching another L1 batch should evict the first one.
+        let other_tree = cache.get_or_build(L1BatchNumber(2), leaves.clone(), 4);
+        let tree = cache.get_or_build(L1BatchNumber(1), leaves, 4);
+        assert!(!Arc::ptr_eq(&other_tree, &tree));
+        assert_eq!(cache.0.lock().unwrap().len(), 1);
+    }
 }
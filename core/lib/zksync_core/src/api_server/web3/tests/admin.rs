@@ -0,0 +1,210 @@
+//! Tests for the `admin` Web3 namespace.
+
+use std::sync::Arc;
+
+use tokio::sync::oneshot;
+use zksync_config::{configs::eth_sender::PubdataSendingMode, GasAdjusterConfig};
+use zksync_eth_client::clients::MockEthereum;
+use zksync_node_fee_model::{
+    l1_gas_price::{GasAdjuster, RollupPubdataPricing},
+    BatchFeeModelInputProvider, MainNodeFeeInputProvider,
+};
+use zksync_types::fee_model::{FeeModelConfig, FeeModelConfigV1, FeeParams};
+use zksync_web3_decl::namespaces::{AdminNamespaceClient, EthNamespaceClient};
+
+use super::*;
+use crate::{
+    api_server::{
+        tx_sender::tests::create_test_tx_sender_with_fee_input_provider,
+        web3::admin::{AdminApiServer, AdminHandles},
+    },
+    state_keeper::{MempoolGuard, SealParamsUpdate, StateKeeperConfigSource, StateKeeperControl},
+};
+
+async fn create_fee_input_provider(minimal_l2_gas_price: u64) -> MainNodeFeeInputProvider {
+    let eth_client = MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9]);
+    let gas_adjuster_config = GasAdjusterConfig {
+        default_priority_fee_per_gas: 10,
+        max_base_fee_samples: 10,
+        pricing_formula_parameter_a: 1.0,
+        pricing_formula_parameter_b: 1.0,
+        internal_l1_pricing_multiplier: 1.0,
+        internal_enforced_l1_gas_price: None,
+        internal_enforced_pubdata_price: None,
+        poll_period: 10,
+        max_l1_gas_price: None,
+        num_samples_for_blob_base_fee_estimate: 10,
+        internal_pubdata_pricing_multiplier: 1.0,
+        max_blob_base_fee: None,
+    };
+    let gas_adjuster = GasAdjuster::new(
+        Box::new(eth_client),
+        gas_adjuster_config,
+        PubdataSendingMode::Calldata,
+        Arc::new(RollupPubdataPricing),
+    )
+    .await
+    .unwrap();
+
+    MainNodeFeeInputProvider::new(
+        Arc::new(gas_adjuster),
+        FeeModelConfig::V1(FeeModelConfigV1 {
+            minimal_l2_gas_price,
+        }),
+    )
+}
+
+#[tokio::test]
+async fn admin_namespace_basics() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    let tx = create_l2_transaction(1, 2);
+    storage
+        .transactions_dal()
+        .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+        .await
+        .unwrap();
+    let mut mempool = MempoolGuard::from_storage(&mut storage, 100).await;
+    drop(storage);
+    mempool.insert(vec![tx.clone().into()], HashMap::new());
+
+    let control = StateKeeperControl::default();
+    let fee_input_provider = Arc::new(create_fee_input_provider(100).await);
+    let config_source = StateKeeperConfigSource::new(StateKeeperConfig::for_tests());
+    let handles = AdminHandles::new(
+        control.clone(),
+        mempool,
+        fee_input_provider.clone(),
+        config_source.clone(),
+        pool.clone(),
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (local_addr_sender, local_addr) = oneshot::channel();
+    let server = AdminApiServer::new((Ipv4Addr::LOCALHOST, 0).into(), handles)
+        .with_local_addr_sender(local_addr_sender);
+    let server_task = tokio::spawn(server.run(stop_receiver));
+    let local_addr = local_addr.await.unwrap();
+    let client = <HttpClient>::builder()
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+
+    assert!(client.pause_sequencer().await.unwrap());
+    assert!(control.is_paused());
+    assert!(!client.pause_sequencer().await.unwrap());
+    assert!(client.resume_sequencer().await.unwrap());
+    assert!(!control.is_paused());
+    client.seal_batch().await.unwrap();
+
+    let contents = client.mempool_contents(None).await.unwrap();
+    assert_eq!(contents.l2_transaction_count, 1);
+    assert_eq!(contents.transactions.len(), 1);
+    assert_eq!(contents.transactions[0].hash, tx.hash());
+    assert_eq!(contents.transactions[0].nonce, Some(tx.nonce()));
+    let contents = client.mempool_contents(Some(0)).await.unwrap();
+    assert_eq!(contents.l2_transaction_count, 1);
+    assert_eq!(contents.transactions, []);
+
+    assert!(client.remove_transaction(tx.hash()).await.unwrap());
+    assert!(!client.remove_transaction(tx.hash()).await.unwrap());
+    let contents = client.mempool_contents(None).await.unwrap();
+    assert_eq!(contents.l2_transaction_count, 0);
+    let mut storage = pool.connection().await.unwrap();
+    let synced_txs = storage
+        .transactions_dal()
+        .sync_mempool(&[], &[], 0, 0, 100)
+        .await
+        .unwrap();
+    assert!(synced_txs.is_empty(), "{synced_txs:?}");
+
+    let prev_price = client.set_minimal_gas_price(1_000.into()).await.unwrap();
+    assert_eq!(prev_price, 100.into());
+    let FeeParams::V1(params) = fee_input_provider.get_fee_model_params() else {
+        panic!("unexpected fee params");
+    };
+    assert_eq!(params.config.minimal_l2_gas_price, 1_000);
+
+    assert_eq!(client.seal_params_audit_log().await.unwrap(), []);
+    let update = SealParamsUpdate {
+        transaction_slots: Some(10),
+        ..SealParamsUpdate::default()
+    };
+    config_source.update(&update, "test").unwrap();
+    let audit_log = client.seal_params_audit_log().await.unwrap();
+    assert_eq!(audit_log, config_source.audit_log());
+    assert_eq!(audit_log.len(), 1);
+    assert_eq!(audit_log[0].origin, "test");
+
+    stop_sender.send_replace(true);
+    server_task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn minimal_gas_price_update_is_visible_via_eth_api() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let mempool = MempoolGuard::from_storage(&mut storage, 100).await;
+    drop(storage);
+
+    // The fee input provider is shared between the admin server and the Web3 API, like in the main node.
+    let fee_input_provider = Arc::new(create_fee_input_provider(100).await);
+    let handles = AdminHandles::new(
+        StateKeeperControl::default(),
+        mempool,
+        fee_input_provider.clone(),
+        StateKeeperConfigSource::new(StateKeeperConfig::for_tests()),
+        pool.clone(),
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (local_addr_sender, local_addr) = oneshot::channel();
+    let admin_server = AdminApiServer::new((Ipv4Addr::LOCALHOST, 0).into(), handles)
+        .with_local_addr_sender(local_addr_sender);
+    let admin_server_task = tokio::spawn(admin_server.run(stop_receiver.clone()));
+    let admin_addr = local_addr.await.unwrap();
+    let admin_client = <HttpClient>::builder()
+        .build(format!("http://{admin_addr}/"))
+        .unwrap();
+
+    let web3_config = Web3JsonRpcConfig::for_tests();
+    let api_config = InternalApiConfig::new(
+        &web3_config,
+        &ContractsConfig::for_tests(),
+        &GenesisConfig::for_tests(),
+    );
+    let (tx_sender, vm_barrier) = create_test_tx_sender_with_fee_input_provider(
+        pool.clone(),
+        api_config.l2_chain_id,
+        fee_input_provider,
+    )
+    .await;
+    let mut server_handles = ApiBuilder::jsonrpsee_backend(api_config, pool)
+        .http(0)
+        .with_tx_sender(tx_sender)
+        .with_vm_barrier(vm_barrier)
+        .enable_api_namespaces(Namespace::DEFAULT.to_vec())
+        .build()
+        .expect("Unable to build API server")
+        .run(stop_receiver)
+        .await
+        .expect("Failed spawning JSON-RPC server");
+    let api_addr = server_handles.wait_until_ready().await;
+    let client = <HttpClient>::builder()
+        .build(format!("http://{api_addr}/"))
+        .unwrap();
+
+    // L1 gas prices returned by the mock Ethereum client are negligible, so the L2 gas price is defined
+    // by the minimal L2 gas price.
+    assert_eq!(client.gas_price().await.unwrap(), 100.into());
+    let prev_price = admin_client
+        .set_minimal_gas_price(1_000_000.into())
+        .await
+        .unwrap();
+    assert_eq!(prev_price, 100.into());
+    assert_eq!(client.gas_price().await.unwrap(), 1_000_000.into());
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+    admin_server_task.await.unwrap().unwrap();
+}
@@ -24,7 +24,10 @@ use zksync_config::{
     },
     GenesisConfig,
 };
-use zksync_dal::{transactions_dal::L2TxSubmissionResult, Connection, ConnectionPool, CoreDal};
+use zksync_dal::{
+    pruning_dal::PrunedDataClass, transactions_dal::L2TxSubmissionResult, Connection,
+    ConnectionPool, CoreDal,
+};
 use zksync_health_check::CheckHealth;
 use zksync_node_genesis::{insert_genesis_batch, mock_genesis_config, GenesisParams};
 use zksync_node_test_utils::{
@@ -57,6 +60,7 @@ use crate::api_server::{
     execution_sandbox::testonly::MockTransactionExecutor, tx_sender::tests::create_test_tx_sender,
 };
 
+mod admin;
 mod debug;
 mod filters;
 mod snapshots;
@@ -239,6 +243,7 @@ async fn spawn_server(
     };
     let server_handles = server_builder
         .with_polling_interval(POLL_INTERVAL)
+        .with_pruning_info_refresh_interval(POLL_INTERVAL)
         .with_tx_sender(tx_sender)
         .with_vm_barrier(vm_barrier)
         .with_pub_sub_events(pub_sub_events_sender)
@@ -950,6 +955,107 @@ async fn transaction_receipts() {
     test_http_server(TransactionReceiptsTest).await;
 }
 
+#[derive(Debug)]
+struct TransactionsWithPrunedPayloadsTest;
+
+#[async_trait]
+impl HttpTest for TransactionsWithPrunedPayloadsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await?;
+        let tx = create_l2_transaction(10, 200);
+        let tx_hash = tx.hash();
+        store_l2_block(
+            &mut storage,
+            L2BlockNumber(1),
+            &[execute_l2_transaction(tx)],
+        )
+        .await?;
+
+        storage
+            .pruning_dal()
+            .soft_prune_data_class(PrunedDataClass::TransactionPayloads, L2BlockNumber(1))
+            .await?;
+        storage
+            .pruning_dal()
+            .hard_prune_data_class(PrunedDataClass::TransactionPayloads, 10)
+            .await?;
+        drop(storage);
+        // Wait until the server refreshes its cached pruning info (the refresh interval is randomized by up to 100ms).
+        tokio::time::sleep(POLL_INTERVAL + Duration::from_millis(150)).await;
+
+        let expected_first_block = L2BlockNumber(2);
+        let error = client.get_transaction_by_hash(tx_hash).await.unwrap_err();
+        assert_pruned_data_error(&error, "transaction_payloads", expected_first_block);
+        let error = client.get_transaction_receipt(tx_hash).await.unwrap_err();
+        assert_pruned_data_error(&error, "transaction_payloads", expected_first_block);
+        let error = client.get_transaction_details(tx_hash).await.unwrap_err();
+        assert_pruned_data_error(&error, "transaction_payloads", expected_first_block);
+        let error = client
+            .get_transaction_by_block_number_and_index(1.into(), 0.into())
+            .await
+            .unwrap_err();
+        assert_pruned_data_error(&error, "transaction_payloads", expected_first_block);
+        let error = client
+            .get_raw_block_transactions(L2BlockNumber(1))
+            .await
+            .unwrap_err();
+        assert_pruned_data_error(&error, "transaction_payloads", expected_first_block);
+        let error = client
+            .get_block_by_number(1.into(), true)
+            .await
+            .unwrap_err();
+        assert_pruned_data_error(&error, "transaction_payloads", expected_first_block);
+        let error = client
+            .sync_l2_block(L2BlockNumber(1), true)
+            .await
+            .unwrap_err();
+        assert_pruned_data_error(&error, "transaction_payloads", expected_first_block);
+
+        // Methods not returning transaction payloads should work as usual.
+        let block = client
+            .get_block_by_number(1.into(), false)
+            .await?
+            .context("no block")?;
+        assert_eq!(block.transactions, [api::TransactionVariant::Hash(tx_hash)]);
+        let sync_block = client
+            .sync_l2_block(L2BlockNumber(1), false)
+            .await?
+            .context("no sync block")?;
+        assert_eq!(sync_block.number, L2BlockNumber(1));
+
+        // Unknown transactions should not be affected.
+        let unknown_tx = client
+            .get_transaction_by_hash(H256::repeat_byte(0xff))
+            .await?;
+        assert!(unknown_tx.is_none(), "{unknown_tx:?}");
+        let unknown_receipt = client
+            .get_transaction_receipt(H256::repeat_byte(0xff))
+            .await?;
+        assert!(unknown_receipt.is_none(), "{unknown_receipt:?}");
+        Ok(())
+    }
+}
+
+fn assert_pruned_data_error(error: &ClientError, class: &str, first_retained_block: L2BlockNumber) {
+    if let ClientError::Call(error) = error {
+        assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        assert!(
+            error.message().contains(&format!(
+                "Data of class `{class}` is pruned for the requested block; the first block with retained data is {first_retained_block}"
+            )),
+            "{error:?}"
+        );
+        assert!(error.data().is_none(), "{error:?}");
+    } else {
+        panic!("Unexpected error: {error:?}");
+    }
+}
+
+#[tokio::test]
+async fn transactions_with_pruned_payloads() {
+    test_http_server(TransactionsWithPrunedPayloadsTest).await;
+}
+
 #[derive(Debug)]
 struct AllAccountBalancesTest;
 
@@ -42,6 +42,7 @@ impl HttpTest for SnapshotBasicsTest {
             .add_snapshot(
                 SnapshotVersion::Version0,
                 L1BatchNumber(1),
+                None,
                 Self::CHUNK_COUNT,
                 "file:///factory_deps",
             )
@@ -55,7 +56,7 @@ impl HttpTest for SnapshotBasicsTest {
                 .await?;
         }
 
-        let all_snapshots = client.get_all_snapshots().await?;
+        let all_snapshots = client.get_all_snapshots(None).await?;
         if self.is_complete_snapshot() {
             assert_eq!(all_snapshots.snapshots_l1_batch_numbers, [L1BatchNumber(1)]);
         } else {
@@ -105,3 +106,66 @@ async fn snapshot_with_some_chunks() {
 async fn snapshot_with_all_chunks() {
     test_http_server(SnapshotBasicsTest::new(0..SnapshotBasicsTest::CHUNK_COUNT)).await;
 }
+
+#[derive(Debug)]
+struct DifferentialSnapshotsTest;
+
+#[async_trait]
+impl HttpTest for DifferentialSnapshotsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        const CHUNK_COUNT: u64 = 2;
+
+        let mut storage = pool.connection().await.unwrap();
+        for (number, base) in [(1, None), (2, Some(L1BatchNumber(1)))] {
+            let l1_batch_number = L1BatchNumber(number);
+            store_l2_block(
+                &mut storage,
+                L2BlockNumber(number),
+                &[execute_l2_transaction(create_l2_transaction(1, 2))],
+            )
+            .await?;
+            seal_l1_batch(&mut storage, l1_batch_number).await?;
+            storage
+                .snapshots_dal()
+                .add_snapshot(
+                    SnapshotVersion::Version0,
+                    l1_batch_number,
+                    base,
+                    CHUNK_COUNT,
+                    "file:///factory_deps",
+                )
+                .await?;
+            for chunk_id in 0..CHUNK_COUNT {
+                let path = format!("file:///storage_logs/{number}/chunk{chunk_id}");
+                storage
+                    .snapshots_dal()
+                    .add_storage_logs_filepath_for_snapshot(l1_batch_number, chunk_id, &path)
+                    .await?;
+            }
+        }
+
+        // Differential snapshots must not be returned unless explicitly requested.
+        for include_differential in [None, Some(false)] {
+            let all_snapshots = client.get_all_snapshots(include_differential).await?;
+            assert_eq!(all_snapshots.snapshots_l1_batch_numbers, [L1BatchNumber(1)]);
+        }
+        let all_snapshots = client.get_all_snapshots(Some(true)).await?;
+        assert_eq!(
+            all_snapshots.snapshots_l1_batch_numbers,
+            [L1BatchNumber(2), L1BatchNumber(1)]
+        );
+
+        let snapshot_header = client
+            .get_snapshot_by_l1_batch_number(L1BatchNumber(2))
+            .await?
+            .context("no snapshot for L1 batch #2")?;
+        assert_eq!(snapshot_header.l1_batch_number, L1BatchNumber(2));
+        assert_eq!(snapshot_header.base_l1_batch_number, Some(L1BatchNumber(1)));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn differential_snapshots_are_opt_in() {
+    test_http_server(DifferentialSnapshotsTest).await;
+}
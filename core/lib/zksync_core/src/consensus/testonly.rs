@@ -368,8 +368,7 @@ impl StateKeeperRunner {
                 self.actions_queue,
                 Box::<MockMainNodeClient>::default(),
                 L2ChainId::default(),
-            )
-            .await?;
+            )?;
             s.spawn_bg(async {
                 Ok(l2_block_sealer
                     .run()
@@ -17,8 +17,9 @@ use tokio::{
     task::JoinHandle,
 };
 use zksync_circuit_breaker::{
-    l1_txs::FailedL1TransactionChecker, replication_lag::ReplicationLagChecker,
-    CircuitBreakerChecker, CircuitBreakers,
+    l1_gas::L1BaseFeeChecker, l1_txs::FailedL1TransactionChecker,
+    replication_lag::ReplicationLagChecker, tree_lag::TreeLagChecker,
+    unproven_batches::UnprovenBatchesChecker, CircuitBreakerChecker, CircuitBreakers,
 };
 use zksync_commitment_generator::{
     input_generation::{InputGenerator, RollupInputGenerator, ValidiumInputGenerator},
@@ -41,7 +42,10 @@ use zksync_config::{
     ApiConfig, DBConfig, EthWatchConfig, GenesisConfig, PostgresConfig,
 };
 use zksync_contracts::governance_contract;
-use zksync_dal::{metrics::PostgresMetrics, ConnectionPool, Core, CoreDal};
+use zksync_dal::{
+    metrics::PostgresMetrics, system_dal, ConnectionPool, Core, CoreDal, RoutingConnectionPool,
+    RoutingConnectionPoolBuilder,
+};
 use zksync_db_connection::healthcheck::ConnectionPoolHealthCheck;
 use zksync_eth_client::{
     clients::{PKSigningClient, QueryClient},
@@ -68,6 +72,7 @@ use zksync_house_keeper::{
     periodic_job::PeriodicJob,
     waiting_to_queued_fri_witness_job_mover::WaitingToQueuedFriWitnessJobMover,
 };
+use zksync_node_db_pruner::{DbPruner, DbPrunerConfig};
 use zksync_node_fee_model::{
     l1_gas_price::{
         GasAdjusterSingleton, PubdataPricing, RollupPubdataPricing, ValidiumPubdataPricing,
@@ -84,15 +89,23 @@ use crate::{
     api_server::{
         contract_verification,
         execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
-        healthcheck::HealthCheckHandle,
+        healthcheck::{HealthCheckHandle, HealthProbes},
         tree::TreeApiHttpClient,
         tx_sender::{ApiContracts, TxSender, TxSenderBuilder, TxSenderConfig},
-        web3::{self, mempool_cache::MempoolCache, state::InternalApiConfig, Namespace},
+        web3::{
+            self,
+            admin::{AdminApiServer, AdminHandles},
+            mempool_cache::MempoolCache,
+            state::InternalApiConfig,
+            Namespace,
+        },
     },
     metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
     state_keeper::{
-        create_state_keeper, AsyncRocksdbCache, MempoolFetcher, MempoolGuard, OutputHandler,
-        SequencerSealer, StateKeeperPersistence,
+        create_state_keeper,
+        tx_filter::{ConfigTxFilter, NoopTxFilter, TxFilter},
+        AsyncRocksdbCache, MempoolFetcher, MempoolGuard, OutputHandler, SequencerSealer,
+        StateKeeperConfigSource, StateKeeperControl, StateKeeperPersistence,
     },
     utils::ensure_l1_batch_commit_data_generation_mode,
 };
@@ -181,6 +194,8 @@ pub enum Component {
     Consensus,
     /// Component generating commitment for L1 batches.
     CommitmentGenerator,
+    /// Component pruning old data from Postgres.
+    DbPruner,
 }
 
 #[derive(Debug)]
@@ -214,6 +229,7 @@ impl FromStr for Components {
             "proof_data_handler" => Ok(Components(vec![Component::ProofDataHandler])),
             "consensus" => Ok(Components(vec![Component::Consensus])),
             "commitment_generator" => Ok(Components(vec![Component::CommitmentGenerator])),
+            "db_pruner" => Ok(Components(vec![Component::DbPruner])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
@@ -243,6 +259,12 @@ pub async fn initialize_components(
     if let Some(threshold) = postgres_config.long_connection_threshold() {
         ConnectionPool::<Core>::global_config().set_long_connection_threshold(threshold)?;
     }
+    if let Some(sample_rate) = postgres_config.slow_query_plan_sample_rate {
+        ConnectionPool::<Core>::global_config().set_slow_query_plan_sample_rate(sample_rate)?;
+    }
+    if let Some(capacity) = postgres_config.slow_query_plan_capacity {
+        ConnectionPool::<Core>::global_config().set_slow_query_plan_capacity(capacity);
+    }
 
     let pool_size = postgres_config.max_connections()?;
     let pool_size_master = postgres_config
@@ -281,17 +303,11 @@ pub async fn initialize_components(
         .clone()
         .context("circuit_breaker_config")?;
 
-    let circuit_breaker_checker = CircuitBreakerChecker::new(
-        Arc::new(
-            circuit_breakers_for_components(components, &postgres_config, &circuit_breaker_config)
-                .await
-                .context("circuit_breakers_for_components")?,
-        ),
-        circuit_breaker_config.sync_interval(),
+    let circuit_breakers = Arc::new(
+        circuit_breakers_for_components(components, &postgres_config, &circuit_breaker_config)
+            .await
+            .context("circuit_breakers_for_components")?,
     );
-    circuit_breaker_checker.check().await.unwrap_or_else(|err| {
-        panic!("Circuit breaker triggered: {}", err);
-    });
 
     let query_client = QueryClient::new(eth.web3_url.clone()).context("Ethereum client")?;
     let query_client = Box::new(query_client);
@@ -310,6 +326,32 @@ pub async fn initialize_components(
         pubdata_pricing,
     );
 
+    // The L1 base fee checker is registered regardless of the components, since the base fee affects
+    // all components interacting with L1. This initializes the gas adjuster if no component uses it.
+    if let Some(base_fee_ceiling) = circuit_breaker_config.l1_base_fee_ceiling {
+        let l1_tx_params = gas_adjuster
+            .get_or_init()
+            .await
+            .context("gas_adjuster.get_or_init()")?;
+        circuit_breakers
+            .insert(Box::new(L1BaseFeeChecker {
+                l1_tx_params,
+                base_fee_ceiling,
+            }))
+            .await;
+    }
+
+    let circuit_breaker_checker = CircuitBreakerChecker::new(
+        circuit_breakers.clone(),
+        circuit_breaker_config.sync_interval(),
+    )
+    .with_soft_circuit_breakers(circuit_breaker_config.soft_circuit_breakers.clone())
+    .context("invalid soft circuit breakers")?;
+    app_health.insert_component(circuit_breaker_checker.health_check())?;
+    circuit_breaker_checker.check().await.unwrap_or_else(|err| {
+        panic!("Circuit breaker triggered: {}", err);
+    });
+
     let (stop_sender, stop_receiver) = watch::channel(false);
 
     // Prometheus exporter and circuit breaker checker should run for every component configuration.
@@ -335,10 +377,59 @@ pub async fn initialize_components(
         tokio::spawn(circuit_breaker_checker.run(stop_receiver.clone())),
     ];
 
+    // Control handle for the state keeper. Shared with the API servers if they run in the same process,
+    // so that transactions are rejected while the sequencer is paused.
+    let state_keeper_control = StateKeeperControl::default();
+    // Transaction filter, seal params and fee input provider shared by the state keeper and API servers,
+    // so that the filter file is loaded and reloaded only once, and seal params / minimal gas price updates
+    // are applied consistently.
+    let (tx_filter, config_source, batch_fee_input_provider): (Arc<dyn TxFilter>, _, _) =
+        if components.iter().any(|component| {
+            matches!(
+                component,
+                Component::HttpApi | Component::WsApi | Component::StateKeeper
+            )
+        }) {
+            let state_keeper_config = configs
+                .state_keeper_config
+                .as_ref()
+                .context("state_keeper_config")?;
+            let tx_filter = build_tx_filter(
+                state_keeper_config,
+                &mut task_futures,
+                stop_receiver.clone(),
+            )
+            .await
+            .context("build_tx_filter()")?;
+            let config_source = build_state_keeper_config_source(
+                state_keeper_config,
+                &mut task_futures,
+                stop_receiver.clone(),
+            );
+            let bounded_gas_adjuster = gas_adjuster
+                .get_or_init()
+                .await
+                .context("gas_adjuster.get_or_init()")?;
+            let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
+                bounded_gas_adjuster,
+                FeeModelConfig::from_state_keeper_config(state_keeper_config),
+            ));
+            (
+                tx_filter,
+                Some(config_source),
+                Some(batch_fee_input_provider),
+            )
+        } else {
+            (Arc::new(NoopTxFilter), None, None)
+        };
+
     if components.contains(&Component::WsApi)
         || components.contains(&Component::HttpApi)
         || components.contains(&Component::ContractVerificationApi)
     {
+        let colocated_state_keeper_control = components
+            .contains(&Component::StateKeeper)
+            .then(|| state_keeper_control.clone());
         let api_config = configs.api_config.clone().context("api_config")?;
         let state_keeper_config = configs
             .state_keeper_config
@@ -364,6 +455,15 @@ pub async fn initialize_components(
         // program termination.
         let mut storage_caches = None;
 
+        let read_replicas_pool = build_read_replicas_pool(
+            &postgres_config,
+            &replica_connection_pool,
+            &mut task_futures,
+            stop_receiver.clone(),
+        )
+        .await
+        .context("build_read_replicas_pool()")?;
+
         let mempool_cache = MempoolCache::new(api_config.web3_json_rpc.mempool_cache_size());
         let mempool_cache_update_task = mempool_cache.update_task(
             connection_pool.clone(),
@@ -386,29 +486,28 @@ pub async fn initialize_components(
 
             let started_at = Instant::now();
             tracing::info!("Initializing HTTP API");
-            let bounded_gas_adjuster = gas_adjuster
-                .get_or_init()
-                .await
-                .context("gas_adjuster.get_or_init()")?;
-            let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-                bounded_gas_adjuster,
-                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-            ));
             run_http_api(
                 &mut task_futures,
                 &app_health,
                 &postgres_config,
                 &tx_sender_config,
-                &state_keeper_config,
+                config_source
+                    .clone()
+                    .context("state keeper config source")?,
                 &internal_api_config,
                 &api_config,
                 connection_pool.clone(),
                 replica_connection_pool.clone(),
+                read_replicas_pool.clone(),
                 stop_receiver.clone(),
-                batch_fee_input_provider,
+                batch_fee_input_provider
+                    .clone()
+                    .context("batch fee input provider")?,
                 state_keeper_config.save_call_traces,
                 storage_caches.clone().unwrap(),
                 mempool_cache.clone(),
+                tx_filter.clone(),
+                colocated_state_keeper_control.clone(),
             )
             .await
             .context("run_http_api")?;
@@ -435,28 +534,27 @@ pub async fn initialize_components(
 
             let started_at = Instant::now();
             tracing::info!("initializing WS API");
-            let bounded_gas_adjuster = gas_adjuster
-                .get_or_init()
-                .await
-                .context("gas_adjuster.get_or_init()")?;
-            let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-                bounded_gas_adjuster,
-                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-            ));
             run_ws_api(
                 &mut task_futures,
                 &app_health,
                 &postgres_config,
                 &tx_sender_config,
-                &state_keeper_config,
+                config_source
+                    .clone()
+                    .context("state keeper config source")?,
                 &internal_api_config,
                 &api_config,
-                batch_fee_input_provider,
+                batch_fee_input_provider
+                    .clone()
+                    .context("batch fee input provider")?,
                 connection_pool.clone(),
                 replica_connection_pool.clone(),
+                read_replicas_pool,
                 stop_receiver.clone(),
                 storage_caches,
                 mempool_cache,
+                tx_filter.clone(),
+                colocated_state_keeper_control,
             )
             .await
             .context("run_ws_api")?;
@@ -499,18 +597,10 @@ pub async fn initialize_components(
     if components.contains(&Component::StateKeeper) {
         let started_at = Instant::now();
         tracing::info!("initializing State Keeper");
-        let bounded_gas_adjuster = gas_adjuster
-            .get_or_init()
-            .await
-            .context("gas_adjuster.get_or_init()")?;
         let state_keeper_config = configs
             .state_keeper_config
             .clone()
             .context("state_keeper_config")?;
-        let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-            bounded_gas_adjuster,
-            FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-        ));
         add_state_keeper_to_task_futures(
             &mut task_futures,
             &postgres_config,
@@ -523,11 +613,19 @@ pub async fn initialize_components(
             l2_chain_id,
             &db_config,
             &configs.mempool_config.clone().context("mempool_config")?,
-            batch_fee_input_provider,
+            batch_fee_input_provider.context("batch fee input provider")?,
+            tx_filter,
+            config_source.context("state keeper config source")?,
+            state_keeper_control.clone(),
+            configs
+                .api_config
+                .as_ref()
+                .and_then(|config| config.web3_json_rpc.admin_port),
             stop_receiver.clone(),
         )
         .await
         .context("add_state_keeper_to_task_futures()")?;
+        app_health.insert_component(state_keeper_control.health_check())?;
 
         let elapsed = started_at.elapsed();
         APP_METRICS.init_latency[&InitStage::StateKeeper].set(elapsed);
@@ -789,11 +887,31 @@ pub async fn initialize_components(
         ));
     }
 
+    if components.contains(&Component::DbPruner) {
+        let pruning_config = configs.pruning.as_ref().context("pruning_config")?;
+        if pruning_config.enabled {
+            let db_pruner = DbPruner::new(
+                DbPrunerConfig::for_main_node(pruning_config),
+                connection_pool.clone(),
+            );
+            app_health.insert_component(db_pruner.health_check())?;
+            task_futures.push(tokio::spawn(db_pruner.run(stop_receiver.clone())));
+        } else {
+            tracing::warn!(
+                "DB pruner component is requested, but pruning is disabled in the config"
+            );
+        }
+    }
+
     // Run healthcheck server for all components.
     let db_health_check = ConnectionPoolHealthCheck::new(replica_connection_pool);
     app_health.insert_custom_component(Arc::new(db_health_check))?;
-    let health_check_handle =
-        HealthCheckHandle::spawn_server(health_check_config.bind_addr(), app_health);
+    let health_check_handle = HealthCheckHandle::spawn_server(
+        health_check_config.bind_addr(),
+        app_health,
+        HealthProbes::from(&health_check_config),
+        health_check_config.expose_slow_query_plans,
+    );
 
     if let Some(task) = gas_adjuster.run_if_initialized(stop_receiver.clone()) {
         task_futures.push(task);
@@ -812,7 +930,11 @@ async fn add_state_keeper_to_task_futures(
     l2chain_id: L2ChainId,
     db_config: &DBConfig,
     mempool_config: &MempoolConfig,
-    batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    batch_fee_input_provider: Arc<MainNodeFeeInputProvider>,
+    tx_filter: Arc<dyn TxFilter>,
+    config_source: StateKeeperConfigSource,
+    control: StateKeeperControl,
+    admin_port: Option<u16>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let state_keeper_pool = ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
@@ -840,6 +962,13 @@ async fn add_state_keeper_to_task_futures(
             .context("`l2_shared_bridge_addr` config is missing")?,
         state_keeper_config.l2_block_seal_queue_capacity,
     );
+    // The protective reads writer can only be run with the node framework, so disabling protective reads here
+    // would leave L1 batches without protective reads required by the witness generator.
+    anyhow::ensure!(
+        state_keeper_config.protective_reads_persistence_enabled,
+        "Disabling protective reads persistence is only supported by the node framework, which can run \
+         the protective reads writer"
+    );
     task_futures.push(tokio::spawn(l2_block_sealer.run()));
 
     // One (potentially held long-term) connection for `AsyncCatchupTask` and another connection
@@ -860,7 +989,7 @@ async fn add_state_keeper_to_task_futures(
         cache_options,
     );
     let state_keeper = create_state_keeper(
-        state_keeper_config,
+        config_source.clone(),
         state_keeper_wallets,
         async_cache,
         l2chain_id,
@@ -869,9 +998,28 @@ async fn add_state_keeper_to_task_futures(
         mempool.clone(),
         batch_fee_input_provider.clone(),
         OutputHandler::new(Box::new(persistence)),
+        tx_filter,
         stop_receiver.clone(),
     )
     .await;
+    let state_keeper = state_keeper.with_control(control.clone());
+
+    if let Some(admin_port) = admin_port {
+        let admin_pool = ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build admin_pool")?;
+        let handles = AdminHandles::new(
+            control,
+            mempool.clone(),
+            batch_fee_input_provider.clone(),
+            config_source,
+            admin_pool,
+        );
+        // The admin server must not be reachable from the outside, so it only listens on the loopback interface.
+        let admin_server = AdminApiServer::new((Ipv4Addr::LOCALHOST, admin_port).into(), handles);
+        task_futures.push(tokio::spawn(admin_server.run(stop_receiver.clone())));
+    }
 
     let mut stop_receiver_clone = stop_receiver.clone();
     task_futures.push(tokio::task::spawn(async move {
@@ -910,7 +1058,7 @@ pub async fn start_eth_watch(
         diamond_proxy_addr,
         state_transition_manager_addr,
         governance.1,
-        config.confirmations_for_eth_event,
+        config.event_confirmations(),
     );
 
     let eth_watch = EthWatch::new(
@@ -961,6 +1109,15 @@ async fn add_trees_to_task_futures(
         MerkleTreeMode::Lightweight => None,
         MerkleTreeMode::Full => Some(store_factory.create_store().await),
     };
+    let checkpoint_store = if db_config
+        .merkle_tree
+        .checkpoint_interval_l1_batches
+        .is_some()
+    {
+        Some(store_factory.create_store().await)
+    } else {
+        None
+    };
 
     run_tree(
         task_futures,
@@ -970,6 +1127,7 @@ async fn add_trees_to_task_futures(
         api_config,
         &operation_config,
         object_store,
+        checkpoint_store,
         stop_receiver,
     )
     .await
@@ -985,6 +1143,7 @@ async fn run_tree(
     api_config: Option<&MerkleTreeApiConfig>,
     operation_manager: &OperationsManagerConfig,
     object_store: Option<Arc<dyn ObjectStore>>,
+    checkpoint_store: Option<Arc<dyn ObjectStore>>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let started_at = Instant::now();
@@ -1006,10 +1165,13 @@ async fn run_tree(
         .build()
         .await
         .context("failed to build connection pool for Merkle tree recovery")?;
-    let metadata_calculator = MetadataCalculator::new(config, object_store, pool)
+    let mut metadata_calculator = MetadataCalculator::new(config, object_store, pool)
         .await
         .context("failed initializing metadata_calculator")?
         .with_recovery_pool(recovery_pool);
+    if let Some(checkpoint_store) = checkpoint_store {
+        metadata_calculator = metadata_calculator.with_checkpoint_store(checkpoint_store);
+    }
 
     if let Some(api_config) = api_config {
         let address = (Ipv4Addr::UNSPECIFIED, api_config.port).into();
@@ -1200,23 +1362,106 @@ fn build_storage_caches(
     Ok(storage_caches)
 }
 
+/// Creates a pool routing read-only API requests to read replicas if they are configured, and spawns a task
+/// monitoring replication lag for the replicas.
+async fn build_read_replicas_pool(
+    postgres_config: &PostgresConfig,
+    replica_connection_pool: &ConnectionPool<Core>,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<Option<RoutingConnectionPool<Core>>> {
+    const LAG_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+    if postgres_config.read_replica_urls.is_empty() {
+        return Ok(None);
+    }
+    let pool_size = postgres_config.max_connections()?;
+    let mut builder = RoutingConnectionPoolBuilder::new(
+        replica_connection_pool.clone(),
+        system_dal::check_replication_lag,
+    );
+    for url in &postgres_config.read_replica_urls {
+        let mut replica = ConnectionPool::<Core>::builder(url.clone(), pool_size);
+        replica
+            .set_acquire_timeout(postgres_config.acquire_timeout())
+            .set_statement_timeout(postgres_config.statement_timeout());
+        builder.add_replica(replica);
+    }
+    if let Some(max_lag) = postgres_config.max_replication_lag() {
+        builder.set_max_replication_lag(max_lag);
+    }
+    let pool = builder.build().await?;
+
+    let lag_monitor = pool.lag_monitor(LAG_UPDATE_INTERVAL);
+    task_futures.push(tokio::spawn(lag_monitor.run(stop_receiver)));
+    Ok(Some(pool))
+}
+
+/// Creates a transaction filter as specified by the state keeper config. If the filter is loaded from a file,
+/// spawns a task periodically reloading it.
+async fn build_tx_filter(
+    state_keeper_config: &StateKeeperConfig,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<Arc<dyn TxFilter>> {
+    let Some(path) = &state_keeper_config.tx_filter_path else {
+        return Ok(Arc::new(NoopTxFilter));
+    };
+    let tx_filter = Arc::new(
+        ConfigTxFilter::new(path)
+            .await
+            .context("failed initializing transaction filter")?,
+    );
+    let reload_interval = state_keeper_config.tx_filter_reload_interval();
+    let reloaded_filter = tx_filter.clone();
+    task_futures.push(tokio::spawn(async move {
+        reloaded_filter
+            .run_reloader(reload_interval, stop_receiver)
+            .await
+    }));
+    Ok(tx_filter)
+}
+
+/// Creates a shared state keeper config source and spawns its task, which reports seal params and watches
+/// the seal params file (if one is configured).
+fn build_state_keeper_config_source(
+    state_keeper_config: &StateKeeperConfig,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+    stop_receiver: watch::Receiver<bool>,
+) -> StateKeeperConfigSource {
+    let config_source = StateKeeperConfigSource::new(state_keeper_config.clone());
+    let path = state_keeper_config
+        .seal_params_path
+        .as_ref()
+        .map(Into::into);
+    task_futures.push(tokio::spawn(config_source.clone().run(path, stop_receiver)));
+    config_source
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn build_tx_sender(
     tx_sender_config: &TxSenderConfig,
     web3_json_config: &Web3JsonRpcConfig,
-    state_keeper_config: &StateKeeperConfig,
+    config_source: StateKeeperConfigSource,
     replica_pool: ConnectionPool<Core>,
     master_pool: ConnectionPool<Core>,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     storage_caches: PostgresStorageCaches,
+    tx_filter: Arc<dyn TxFilter>,
+    state_keeper_control: Option<StateKeeperControl>,
 ) -> (TxSender, VmConcurrencyBarrier) {
-    let sequencer_sealer = SequencerSealer::new(state_keeper_config.clone());
+    let sequencer_sealer = SequencerSealer::from_source(config_source);
     let master_pool_sink = MasterPoolSink::new(master_pool);
-    let tx_sender_builder = TxSenderBuilder::new(
+    let mut tx_sender_builder = TxSenderBuilder::new(
         tx_sender_config.clone(),
         replica_pool.clone(),
         Arc::new(master_pool_sink),
     )
-    .with_sealer(Arc::new(sequencer_sealer));
+    .with_sealer(Arc::new(sequencer_sealer))
+    .with_tx_filter(tx_filter);
+    if let Some(control) = state_keeper_control {
+        tx_sender_builder = tx_sender_builder.with_state_keeper_control(control);
+    }
 
     let max_concurrency = web3_json_config.vm_concurrency_limit();
     let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
@@ -1241,25 +1486,30 @@ async fn run_http_api(
     app_health: &AppHealthCheck,
     postgres_config: &PostgresConfig,
     tx_sender_config: &TxSenderConfig,
-    state_keeper_config: &StateKeeperConfig,
+    config_source: StateKeeperConfigSource,
     internal_api: &InternalApiConfig,
     api_config: &ApiConfig,
     master_connection_pool: ConnectionPool<Core>,
     replica_connection_pool: ConnectionPool<Core>,
+    read_replicas_pool: Option<RoutingConnectionPool<Core>>,
     stop_receiver: watch::Receiver<bool>,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     with_debug_namespace: bool,
     storage_caches: PostgresStorageCaches,
     mempool_cache: MempoolCache,
+    tx_filter: Arc<dyn TxFilter>,
+    state_keeper_control: Option<StateKeeperControl>,
 ) -> anyhow::Result<()> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
         &api_config.web3_json_rpc,
-        state_keeper_config,
+        config_source,
         replica_connection_pool.clone(),
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
+        tx_filter,
+        state_keeper_control,
     )
     .await;
 
@@ -1285,6 +1535,9 @@ async fn run_http_api(
             .with_vm_barrier(vm_barrier)
             .with_mempool_cache(mempool_cache)
             .enable_api_namespaces(namespaces);
+    if let Some(pool) = read_replicas_pool {
+        api_builder = api_builder.with_read_replicas(pool);
+    }
     if let Some(tree_api_url) = api_config.web3_json_rpc.tree_api_url() {
         let tree_api = Arc::new(TreeApiHttpClient::new(tree_api_url));
         api_builder = api_builder.with_tree_api(tree_api.clone());
@@ -1307,24 +1560,29 @@ async fn run_ws_api(
     app_health: &AppHealthCheck,
     postgres_config: &PostgresConfig,
     tx_sender_config: &TxSenderConfig,
-    state_keeper_config: &StateKeeperConfig,
+    config_source: StateKeeperConfigSource,
     internal_api: &InternalApiConfig,
     api_config: &ApiConfig,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     master_connection_pool: ConnectionPool<Core>,
     replica_connection_pool: ConnectionPool<Core>,
+    read_replicas_pool: Option<RoutingConnectionPool<Core>>,
     stop_receiver: watch::Receiver<bool>,
     storage_caches: PostgresStorageCaches,
     mempool_cache: MempoolCache,
+    tx_filter: Arc<dyn TxFilter>,
+    state_keeper_control: Option<StateKeeperControl>,
 ) -> anyhow::Result<()> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
         &api_config.web3_json_rpc,
-        state_keeper_config,
+        config_source,
         replica_connection_pool.clone(),
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
+        tx_filter,
+        state_keeper_control,
     )
     .await;
     let updaters_pool = ConnectionPool::<Core>::singleton(postgres_config.replica_url()?)
@@ -1353,6 +1611,9 @@ async fn run_ws_api(
             .with_vm_barrier(vm_barrier)
             .with_mempool_cache(mempool_cache)
             .enable_api_namespaces(namespaces);
+    if let Some(pool) = read_replicas_pool {
+        api_builder = api_builder.with_read_replicas(pool);
+    }
     if let Some(tree_api_url) = api_config.web3_json_rpc.tree_api_url() {
         let tree_api = Arc::new(TreeApiHttpClient::new(tree_api_url));
         api_builder = api_builder.with_tree_api(tree_api.clone());
@@ -1405,5 +1666,33 @@ async fn circuit_breakers_for_components(
             }))
             .await;
     }
+
+    if let Some(unproven_batches_limit) = circuit_breaker_config.unproven_batches_limit {
+        if components.contains(&Component::ProofDataHandler) {
+            let pool = ConnectionPool::<Core>::singleton(postgres_config.replica_url()?)
+                .build()
+                .await?;
+            circuit_breakers
+                .insert(Box::new(UnprovenBatchesChecker {
+                    pool,
+                    unproven_batches_limit,
+                }))
+                .await;
+        }
+    }
+
+    if let Some(tree_lag_limit) = circuit_breaker_config.tree_lag_limit {
+        if components.contains(&Component::Tree) {
+            let pool = ConnectionPool::<Core>::singleton(postgres_config.replica_url()?)
+                .build()
+                .await?;
+            circuit_breakers
+                .insert(Box::new(TreeLagChecker {
+                    pool,
+                    tree_lag_limit,
+                }))
+                .await;
+        }
+    }
     Ok(circuit_breakers)
 }
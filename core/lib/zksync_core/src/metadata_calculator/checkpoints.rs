@@ -0,0 +1,560 @@
+//! Merkle tree checkpoints, i.e. RocksDB checkpoints of the tree consistent with a certain L1 batch
+//! that are uploaded to an object store in chunks. A fresh node can bootstrap its tree from the latest checkpoint
+//! and then catch up by processing the remaining L1 batches, which is much faster than recovering the tree
+//! from a Postgres snapshot.
+
+use std::{
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncWriteExt},
+    task::JoinHandle,
+};
+use zksync_merkle_tree::{domain::ZkSyncTreeReader, RocksDBWrapper};
+use zksync_object_store::{
+    serialize_using_bincode, Bucket, ObjectStore, ObjectStoreError, StoredObject,
+};
+use zksync_types::{L1BatchNumber, H256};
+
+use super::{
+    helpers::AsyncTree,
+    metrics::{CheckpointStage, CHECKPOINT_METRICS},
+};
+
+/// Maximum size of a single checkpoint chunk uploaded to the object store.
+const CHUNK_SIZE: usize = 64 << 20; // 64 MiB
+
+/// Information about a file in a tree checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct TreeCheckpointFile {
+    /// Name of the file relative to the checkpoint directory.
+    pub name: String,
+    /// Size of the file in bytes.
+    pub size: u64,
+    /// Number of chunks the file is split into.
+    pub chunk_count: u32,
+}
+
+/// Manifest of a tree checkpoint. It is uploaded after all checkpoint chunks, so its presence
+/// signals that the checkpoint is complete.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct TreeCheckpointManifest {
+    /// L1 batch the checkpoint is consistent with; i.e., the last L1 batch processed by the tree.
+    pub l1_batch_number: L1BatchNumber,
+    /// Root hash of the tree after processing `l1_batch_number`.
+    pub root_hash: H256,
+    /// Files in the checkpoint.
+    pub files: Vec<TreeCheckpointFile>,
+}
+
+impl StoredObject for TreeCheckpointManifest {
+    const BUCKET: Bucket = Bucket::MerkleTreeCheckpoints;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("checkpoint_l1_batch_{key}_manifest.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
+/// Pointer to the latest complete checkpoint in the object store.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct LatestTreeCheckpoint {
+    pub l1_batch_number: L1BatchNumber,
+    /// L1 batches of all checkpoints retained in the store (including `l1_batch_number`) in the ascending order.
+    /// Used to remove obsolete checkpoints.
+    pub retained_l1_batches: Vec<L1BatchNumber>,
+}
+
+impl StoredObject for LatestTreeCheckpoint {
+    const BUCKET: Bucket = Bucket::MerkleTreeCheckpoints;
+    type Key<'a> = ();
+
+    fn encode_key((): Self::Key<'_>) -> String {
+        "latest_checkpoint.bin".to_owned()
+    }
+
+    serialize_using_bincode!();
+}
+
+/// Key of a [`TreeCheckpointChunk`].
+#[derive(Debug, Clone, Copy)]
+pub(super) struct TreeCheckpointChunkKey<'a> {
+    pub l1_batch_number: L1BatchNumber,
+    pub file_name: &'a str,
+    pub chunk_id: u32,
+}
+
+/// Chunk of a file in a tree checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct TreeCheckpointChunk(pub Vec<u8>);
+
+impl StoredObject for TreeCheckpointChunk {
+    const BUCKET: Bucket = Bucket::MerkleTreeCheckpoints;
+    type Key<'a> = TreeCheckpointChunkKey<'a>;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!(
+            "checkpoint_l1_batch_{}_{}_part_{:0>4}",
+            key.l1_batch_number, key.file_name, key.chunk_id
+        )
+    }
+
+    serialize_using_bincode!();
+}
+
+/// Component periodically creating tree checkpoints and uploading them to the object store.
+///
+/// Checkpoints are created synchronously (this is cheap since RocksDB hard-links SST files),
+/// but are uploaded in a background task so that the tree can continue processing L1 batches.
+/// If the previous upload is still in progress when a new checkpoint is due, checkpointing is postponed.
+/// After a checkpoint is uploaded, only the configured number of the most recent checkpoints is retained
+/// in the object store; older checkpoints are removed.
+#[derive(Debug)]
+pub(super) struct TreeCheckpointer {
+    object_store: Arc<dyn ObjectStore>,
+    interval: NonZeroU32,
+    max_retained_checkpoints: usize,
+    checkpoint_path: PathBuf,
+    next_checkpoint_l1_batch: Option<L1BatchNumber>,
+    upload_task: Option<JoinHandle<anyhow::Result<()>>>,
+}
+
+impl TreeCheckpointer {
+    pub fn new(
+        object_store: Arc<dyn ObjectStore>,
+        interval: NonZeroU32,
+        max_retained_checkpoints: usize,
+        db_path: &str,
+    ) -> Self {
+        Self {
+            object_store,
+            interval,
+            // The latest checkpoint is always retained.
+            max_retained_checkpoints: max_retained_checkpoints.max(1),
+            checkpoint_path: PathBuf::from(format!("{db_path}.checkpoint")),
+            next_checkpoint_l1_batch: None,
+            upload_task: None,
+        }
+    }
+
+    /// Creates and starts uploading a checkpoint if it's due. Must be called after the tree is saved;
+    /// `first_processed_l1_batch` is the first L1 batch processed by the tree since the previous call.
+    ///
+    /// Checkpoints are created for L1 batches that are multiples of the configured interval, or for the first
+    /// L1 batch processed after such a multiple if the tree processed multiple L1 batches at once.
+    pub async fn maybe_checkpoint(
+        &mut self,
+        tree: &AsyncTree,
+        first_processed_l1_batch: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let next_l1_batch = tree.next_l1_batch_number();
+        let Some(last_processed_l1_batch) = next_l1_batch.0.checked_sub(1) else {
+            return Ok(()); // The tree is empty
+        };
+        let last_processed_l1_batch = L1BatchNumber(last_processed_l1_batch);
+
+        let next_checkpoint_l1_batch = *self
+            .next_checkpoint_l1_batch
+            .get_or_insert_with(|| self.next_multiple_of_interval(first_processed_l1_batch));
+        if last_processed_l1_batch < next_checkpoint_l1_batch {
+            return Ok(());
+        }
+
+        if let Some(upload_task) = &self.upload_task {
+            if !upload_task.is_finished() {
+                tracing::info!(
+                    "Postponing tree checkpoint for L1 batch #{last_processed_l1_batch}: previous checkpoint is still being uploaded"
+                );
+                return Ok(());
+            }
+            let upload_task = self.upload_task.take().unwrap();
+            Self::report_upload_result(upload_task.await);
+        }
+
+        let latency = CHECKPOINT_METRICS.latency[&CheckpointStage::Create].start();
+        let root_hash = tree.root_hash();
+        tree.create_checkpoint(self.checkpoint_path.clone())
+            .await
+            .with_context(|| {
+                format!("failed creating tree checkpoint for L1 batch #{last_processed_l1_batch}")
+            })?;
+        let elapsed = latency.observe();
+        tracing::info!(
+            "Created tree checkpoint for L1 batch #{last_processed_l1_batch} at `{}` in {elapsed:?}",
+            self.checkpoint_path.display()
+        );
+
+        let object_store = self.object_store.clone();
+        let checkpoint_path = self.checkpoint_path.clone();
+        self.upload_task = Some(tokio::spawn(upload_checkpoint(
+            object_store,
+            checkpoint_path,
+            last_processed_l1_batch,
+            root_hash,
+            self.max_retained_checkpoints,
+        )));
+        self.next_checkpoint_l1_batch =
+            Some(self.next_multiple_of_interval(last_processed_l1_batch + 1));
+        Ok(())
+    }
+
+    /// Returns the smallest L1 batch number that is greater than or equal to `l1_batch` and is a multiple
+    /// of the checkpoint interval.
+    fn next_multiple_of_interval(&self, l1_batch: L1BatchNumber) -> L1BatchNumber {
+        let interval = self.interval.get();
+        L1BatchNumber(l1_batch.0.div_ceil(interval).saturating_mul(interval))
+    }
+
+    fn report_upload_result(result: Result<anyhow::Result<()>, tokio::task::JoinError>) {
+        let err = match result {
+            Ok(Ok(())) => return,
+            Ok(Err(err)) => err,
+            Err(err) => anyhow::Error::new(err).context("tree checkpoint upload panicked"),
+        };
+        // Checkpoints are not critical for the tree operation, so we don't propagate the error.
+        tracing::error!("Failed uploading tree checkpoint: {err:#}");
+        CHECKPOINT_METRICS.failed_uploads.inc();
+    }
+
+    /// Waits for the currently running upload (if any) to complete.
+    pub async fn wait_for_upload(mut self) {
+        if let Some(upload_task) = self.upload_task.take() {
+            Self::report_upload_result(upload_task.await);
+        }
+    }
+}
+
+pub(super) async fn upload_checkpoint(
+    object_store: Arc<dyn ObjectStore>,
+    checkpoint_path: PathBuf,
+    l1_batch_number: L1BatchNumber,
+    root_hash: H256,
+    max_retained_checkpoints: usize,
+) -> anyhow::Result<()> {
+    let latency = CHECKPOINT_METRICS.latency[&CheckpointStage::Upload].start();
+    let mut file_names = vec![];
+    let mut dir = fs::read_dir(&checkpoint_path).await.with_context(|| {
+        format!(
+            "failed reading checkpoint directory `{}`",
+            checkpoint_path.display()
+        )
+    })?;
+    while let Some(entry) = dir.next_entry().await? {
+        let file_name = entry.file_name();
+        let file_name = file_name
+            .to_str()
+            .with_context(|| format!("non-UTF-8 file name in checkpoint: {file_name:?}"))?;
+        file_names.push(file_name.to_owned());
+    }
+    file_names.sort_unstable();
+
+    let mut files = Vec::with_capacity(file_names.len());
+    for name in file_names {
+        let file = upload_file(&*object_store, &checkpoint_path, l1_batch_number, name).await?;
+        files.push(file);
+    }
+    let total_size = files.iter().map(|file| file.size).sum::<u64>();
+
+    let manifest = TreeCheckpointManifest {
+        l1_batch_number,
+        root_hash,
+        files,
+    };
+    object_store
+        .put(l1_batch_number, &manifest)
+        .await
+        .context("failed uploading checkpoint manifest")?;
+
+    let mut retained_l1_batches = match object_store.get::<LatestTreeCheckpoint>(()).await {
+        Ok(latest) => latest.retained_l1_batches,
+        Err(ObjectStoreError::KeyNotFound(_)) => vec![],
+        Err(err) => return Err(err).context("failed getting latest checkpoint pointer"),
+    };
+    retained_l1_batches.retain(|&number| number < l1_batch_number);
+    retained_l1_batches.push(l1_batch_number);
+    let latest = LatestTreeCheckpoint {
+        l1_batch_number,
+        retained_l1_batches,
+    };
+    object_store
+        .put((), &latest)
+        .await
+        .context("failed updating latest checkpoint pointer")?;
+
+    fs::remove_dir_all(&checkpoint_path)
+        .await
+        .with_context(|| {
+            format!(
+                "failed removing checkpoint directory `{}`",
+                checkpoint_path.display()
+            )
+        })?;
+
+    let elapsed = latency.observe();
+    CHECKPOINT_METRICS
+        .last_uploaded_l1_batch
+        .set(l1_batch_number.0.into());
+    CHECKPOINT_METRICS.size.set(total_size);
+    tracing::info!(
+        "Uploaded tree checkpoint for L1 batch #{l1_batch_number} ({total_size} bytes in {} files) in {elapsed:?}",
+        manifest.files.len()
+    );
+
+    // Failing to remove obsolete checkpoints doesn't invalidate the uploaded checkpoint. Obsolete checkpoints
+    // are still listed in the pointer, so their removal will be retried after the next upload.
+    if let Err(err) =
+        remove_obsolete_checkpoints(&*object_store, latest, max_retained_checkpoints).await
+    {
+        tracing::warn!("Failed removing obsolete tree checkpoints: {err:#}");
+        CHECKPOINT_METRICS.failed_removals.inc();
+    }
+    Ok(())
+}
+
+/// Removes all checkpoints except for `max_retained_checkpoints` most recent ones and updates the latest
+/// checkpoint pointer accordingly.
+async fn remove_obsolete_checkpoints(
+    object_store: &dyn ObjectStore,
+    mut latest: LatestTreeCheckpoint,
+    max_retained_checkpoints: usize,
+) -> anyhow::Result<()> {
+    let obsolete_count = latest
+        .retained_l1_batches
+        .len()
+        .saturating_sub(max_retained_checkpoints);
+    if obsolete_count == 0 {
+        return Ok(());
+    }
+
+    let obsolete_l1_batches: Vec<_> = latest.retained_l1_batches.drain(..obsolete_count).collect();
+    for &l1_batch_number in &obsolete_l1_batches {
+        remove_checkpoint(object_store, l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("failed removing tree checkpoint for L1 batch #{l1_batch_number}")
+            })?;
+    }
+    object_store
+        .put((), &latest)
+        .await
+        .context("failed updating latest checkpoint pointer")?;
+    tracing::info!("Removed obsolete tree checkpoints for L1 batches {obsolete_l1_batches:?}");
+    Ok(())
+}
+
+async fn remove_checkpoint(
+    object_store: &dyn ObjectStore,
+    l1_batch_number: L1BatchNumber,
+) -> anyhow::Result<()> {
+    let manifest: TreeCheckpointManifest = match object_store.get(l1_batch_number).await {
+        Ok(manifest) => manifest,
+        Err(ObjectStoreError::KeyNotFound(_)) => return Ok(()), // The checkpoint is already removed
+        Err(err) => return Err(err).context("failed getting checkpoint manifest"),
+    };
+
+    // Chunks are removed before the manifest, so that removal can be retried if it fails midway.
+    for file in &manifest.files {
+        for chunk_id in 0..file.chunk_count {
+            let key = TreeCheckpointChunkKey {
+                l1_batch_number,
+                file_name: &file.name,
+                chunk_id,
+            };
+            match object_store.remove::<TreeCheckpointChunk>(key).await {
+                Ok(()) | Err(ObjectStoreError::KeyNotFound(_)) => { /* OK */ }
+                Err(err) => {
+                    return Err(err).with_context(|| {
+                        format!("failed removing chunk #{chunk_id} of file `{}`", file.name)
+                    });
+                }
+            }
+        }
+    }
+    object_store
+        .remove::<TreeCheckpointManifest>(l1_batch_number)
+        .await
+        .context("failed removing checkpoint manifest")
+}
+
+async fn upload_file(
+    object_store: &dyn ObjectStore,
+    checkpoint_path: &Path,
+    l1_batch_number: L1BatchNumber,
+    name: String,
+) -> anyhow::Result<TreeCheckpointFile> {
+    let path = checkpoint_path.join(&name);
+    let mut file = fs::File::open(&path)
+        .await
+        .with_context(|| format!("failed opening checkpoint file `{}`", path.display()))?;
+
+    let mut size = 0_u64;
+    let mut chunk_id = 0;
+    loop {
+        let mut buffer = Vec::with_capacity(CHUNK_SIZE);
+        (&mut file)
+            .take(CHUNK_SIZE as u64)
+            .read_to_end(&mut buffer)
+            .await
+            .with_context(|| format!("failed reading checkpoint file `{}`", path.display()))?;
+        // We always upload at least one chunk so that empty files are restored as well.
+        if buffer.is_empty() && chunk_id > 0 {
+            break;
+        }
+        let is_last_chunk = buffer.len() < CHUNK_SIZE;
+        size += buffer.len() as u64;
+
+        let key = TreeCheckpointChunkKey {
+            l1_batch_number,
+            file_name: &name,
+            chunk_id,
+        };
+        object_store
+            .put(key, &TreeCheckpointChunk(buffer))
+            .await
+            .with_context(|| format!("failed uploading chunk #{chunk_id} of file `{name}`"))?;
+        chunk_id += 1;
+        if is_last_chunk {
+            break;
+        }
+    }
+
+    tracing::debug!("Uploaded checkpoint file `{name}` ({size} bytes in {chunk_id} chunks)");
+    Ok(TreeCheckpointFile {
+        name,
+        size,
+        chunk_count: chunk_id,
+    })
+}
+
+/// Bootstraps the tree at `db_path` from the latest checkpoint in the object store.
+/// `db_path` must not contain a tree. Returns the L1 batch number the restored tree is consistent with,
+/// or `None` if there are no checkpoints in the store.
+pub(super) async fn restore_from_latest_checkpoint(
+    object_store: &dyn ObjectStore,
+    db_path: &Path,
+) -> anyhow::Result<Option<L1BatchNumber>> {
+    let latest = match object_store.get::<LatestTreeCheckpoint>(()).await {
+        Ok(latest) => latest,
+        Err(ObjectStoreError::KeyNotFound(_)) => return Ok(None),
+        Err(err) => return Err(err).context("failed getting latest checkpoint pointer"),
+    };
+    let l1_batch_number = latest.l1_batch_number;
+    let manifest: TreeCheckpointManifest =
+        object_store.get(l1_batch_number).await.with_context(|| {
+            format!("failed getting checkpoint manifest for L1 batch #{l1_batch_number}")
+        })?;
+    anyhow::ensure!(
+        manifest.l1_batch_number == l1_batch_number,
+        "checkpoint manifest is inconsistent: expected L1 batch #{l1_batch_number}, got #{}",
+        manifest.l1_batch_number
+    );
+
+    let latency = CHECKPOINT_METRICS.latency[&CheckpointStage::Download].start();
+    tracing::info!(
+        "Restoring Merkle tree from checkpoint for L1 batch #{l1_batch_number} ({} files)",
+        manifest.files.len()
+    );
+    let restore_path = PathBuf::from(format!("{}.restore", db_path.display()));
+    if fs::try_exists(&restore_path).await? {
+        fs::remove_dir_all(&restore_path).await?;
+    }
+    fs::create_dir_all(&restore_path).await.with_context(|| {
+        format!(
+            "failed creating restore directory `{}`",
+            restore_path.display()
+        )
+    })?;
+    for file in &manifest.files {
+        download_file(object_store, &restore_path, l1_batch_number, file).await?;
+    }
+
+    let path = restore_path.clone();
+    let (root_hash, next_l1_batch) = tokio::task::spawn_blocking(move || {
+        let db = RocksDBWrapper::new(&path)?;
+        let reader = ZkSyncTreeReader::new(db);
+        anyhow::Ok((reader.root_hash(), reader.next_l1_batch_number()))
+    })
+    .await
+    .context("panicked verifying restored tree")?
+    .context("failed opening restored tree")?;
+    anyhow::ensure!(
+        root_hash == manifest.root_hash && next_l1_batch == l1_batch_number + 1,
+        "restored tree is inconsistent with the checkpoint manifest: expected root hash {:?} for L1 batch #{l1_batch_number}, \
+         got {root_hash:?} with next L1 batch #{next_l1_batch}",
+        manifest.root_hash
+    );
+
+    // An empty directory may be created by the DB initialization code; it's safe to remove it.
+    if fs::try_exists(db_path).await? {
+        fs::remove_dir(db_path).await.with_context(|| {
+            format!(
+                "cannot restore tree checkpoint: `{}` exists and is not empty",
+                db_path.display()
+            )
+        })?;
+    }
+    fs::rename(&restore_path, db_path).await.with_context(|| {
+        format!(
+            "failed moving restored tree from `{}` to `{}`",
+            restore_path.display(),
+            db_path.display()
+        )
+    })?;
+
+    let elapsed = latency.observe();
+    tracing::info!(
+        "Restored Merkle tree from checkpoint for L1 batch #{l1_batch_number} with root hash {root_hash:?} in {elapsed:?}"
+    );
+    Ok(Some(l1_batch_number))
+}
+
+async fn download_file(
+    object_store: &dyn ObjectStore,
+    restore_path: &Path,
+    l1_batch_number: L1BatchNumber,
+    file: &TreeCheckpointFile,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !file.name.contains(['/', '\\']) && file.name != ".." && file.name != ".",
+        "invalid file name in checkpoint manifest: {:?}",
+        file.name
+    );
+    let path = restore_path.join(&file.name);
+    let mut local_file = fs::File::create(&path)
+        .await
+        .with_context(|| format!("failed creating file `{}`", path.display()))?;
+
+    let mut size = 0_u64;
+    for chunk_id in 0..file.chunk_count {
+        let key = TreeCheckpointChunkKey {
+            l1_batch_number,
+            file_name: &file.name,
+            chunk_id,
+        };
+        let TreeCheckpointChunk(bytes) = object_store.get(key).await.with_context(|| {
+            format!(
+                "failed downloading chunk #{chunk_id} of file `{}`",
+                file.name
+            )
+        })?;
+        size += bytes.len() as u64;
+        local_file.write_all(&bytes).await?;
+    }
+    local_file.sync_all().await?;
+
+    anyhow::ensure!(
+        size == file.size,
+        "size mismatch for checkpoint file `{}`: expected {} bytes, got {size}",
+        file.name,
+        file.size
+    );
+    Ok(())
+}
@@ -4,7 +4,7 @@ use std::{
     collections::{BTreeMap, HashSet},
     future,
     future::Future,
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
@@ -212,6 +212,10 @@ impl AsyncTree {
         self.mode
     }
 
+    pub fn set_parallel_updates(&mut self, parallel_updates: bool) {
+        self.as_mut().set_parallel_updates(parallel_updates);
+    }
+
     pub fn pruner(&mut self) -> PruningHandles {
         self.as_mut().pruner()
     }
@@ -231,11 +235,28 @@ impl AsyncTree {
         self.as_ref().next_l1_batch_number()
     }
 
-    #[cfg(test)]
     pub fn root_hash(&self) -> H256 {
         self.as_ref().root_hash()
     }
 
+    /// Creates a RocksDB checkpoint of the tree at the specified path. The checkpoint only reflects
+    /// changes persisted with [`Self::save()`].
+    pub async fn create_checkpoint(&self, path: PathBuf) -> anyhow::Result<()> {
+        let db = self.as_ref().reader().db().clone();
+        tokio::task::spawn_blocking(move || {
+            if path.exists() {
+                // Remove a stale checkpoint, e.g. one left after a failed upload.
+                std::fs::remove_dir_all(&path).with_context(|| {
+                    format!("failed removing stale checkpoint at `{}`", path.display())
+                })?;
+            }
+            db.create_checkpoint(&path)
+                .with_context(|| format!("failed creating checkpoint at `{}`", path.display()))
+        })
+        .await
+        .context("Merkle tree panicked creating checkpoint")?
+    }
+
     /// Returned errors are unrecoverable; the tree must not be used after an error is returned.
     pub async fn process_l1_batch(
         &mut self,
@@ -3,8 +3,8 @@
 use std::time::{Duration, Instant};
 
 use vise::{
-    Buckets, DurationAsSecs, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Info,
-    LatencyObserver, Metrics, Unit,
+    Buckets, Counter, DurationAsSecs, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram,
+    Info, LatencyObserver, Metrics, Unit,
 };
 use zksync_config::configs::database::MerkleTreeMode;
 use zksync_shared_metrics::{BlockStage, APP_METRICS};
@@ -252,3 +252,33 @@ pub(super) struct MetadataCalculatorRecoveryMetrics {
 #[vise::register]
 pub(super) static RECOVERY_METRICS: vise::Global<MetadataCalculatorRecoveryMetrics> =
     vise::Global::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub(super) enum CheckpointStage {
+    Create,
+    Upload,
+    Download,
+}
+
+/// Metrics for Merkle tree checkpoints uploaded to / downloaded from the object store.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_metadata_calculator_checkpoints")]
+pub(super) struct MetadataCalculatorCheckpointMetrics {
+    /// Number of the L1 batch of the latest uploaded checkpoint.
+    pub last_uploaded_l1_batch: Gauge<u64>,
+    /// Total size of the latest uploaded checkpoint.
+    #[metrics(unit = Unit::Bytes)]
+    pub size: Gauge<u64>,
+    /// Number of checkpoint uploads that have failed.
+    pub failed_uploads: Counter,
+    /// Number of failed attempts to remove obsolete checkpoints.
+    pub failed_removals: Counter,
+    /// Latency of a checkpoint stage.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub latency: Family<CheckpointStage, Histogram<Duration>>,
+}
+
+#[vise::register]
+pub(super) static CHECKPOINT_METRICS: vise::Global<MetadataCalculatorCheckpointMetrics> =
+    vise::Global::new();
@@ -3,6 +3,7 @@
 
 use std::{
     num::NonZeroU32,
+    path::Path,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -18,14 +19,16 @@ use zksync_health_check::{CheckHealth, HealthUpdater, ReactiveHealthCheck};
 use zksync_object_store::ObjectStore;
 
 pub(crate) use self::helpers::{AsyncTreeReader, MerkleTreeInfo};
-pub use self::{helpers::LazyAsyncTreeReader, pruning::MerkleTreePruningTask};
 use self::{
+    checkpoints::TreeCheckpointer,
     helpers::{create_db, Delayer, GenericAsyncTree, MerkleTreeHealth, MerkleTreeHealthCheck},
     metrics::{ConfigLabels, METRICS},
     pruning::PruningHandles,
     updater::TreeUpdater,
 };
+pub use self::{helpers::LazyAsyncTreeReader, pruning::MerkleTreePruningTask};
 
+mod checkpoints;
 mod helpers;
 mod metrics;
 mod pruning;
@@ -62,6 +65,15 @@ pub struct MetadataCalculatorConfig {
     pub memtable_capacity: usize,
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     pub stalled_writes_timeout: Duration,
+    /// Interval (in L1 batches) between tree checkpoints uploaded to the checkpoint store. Has no effect
+    /// if the checkpoint store is not set via [`MetadataCalculator::with_checkpoint_store()`].
+    pub checkpoint_interval: Option<NonZeroU32>,
+    /// Maximum number of the most recent checkpoints retained in the checkpoint store. The latest checkpoint
+    /// is always retained.
+    pub max_retained_checkpoints: usize,
+    /// Whether to split L1 batches into subtrees updated in parallel in the lightweight mode.
+    /// Has no effect in the full mode.
+    pub parallel_updates: bool,
 }
 
 impl MetadataCalculatorConfig {
@@ -80,6 +92,11 @@ impl MetadataCalculatorConfig {
             include_indices_and_filters_in_block_cache: false,
             memtable_capacity: merkle_tree_config.memtable_capacity(),
             stalled_writes_timeout: merkle_tree_config.stalled_writes_timeout(),
+            checkpoint_interval: merkle_tree_config
+                .checkpoint_interval_l1_batches
+                .and_then(NonZeroU32::new),
+            max_retained_checkpoints: merkle_tree_config.max_retained_checkpoints,
+            parallel_updates: merkle_tree_config.parallel_updates,
         }
     }
 }
@@ -90,6 +107,7 @@ pub struct MetadataCalculator {
     tree_reader: watch::Sender<Option<AsyncTreeReader>>,
     pruning_handles_sender: oneshot::Sender<PruningHandles>,
     object_store: Option<Arc<dyn ObjectStore>>,
+    checkpoint_store: Option<Arc<dyn ObjectStore>>,
     pool: ConnectionPool<Core>,
     recovery_pool: ConnectionPool<Core>,
     delayer: Delayer,
@@ -131,6 +149,7 @@ impl MetadataCalculator {
             tree_reader: watch::channel(None).0,
             pruning_handles_sender: oneshot::channel().0,
             object_store,
+            checkpoint_store: None,
             recovery_pool: pool.clone(),
             pool,
             delayer: Delayer::new(config.delay_interval),
@@ -147,6 +166,14 @@ impl MetadataCalculator {
         self
     }
 
+    /// Sets an object store for Merkle tree checkpoints. If the tree is not initialized on start, it will be
+    /// bootstrapped from the latest checkpoint in the store (if any). If [`MetadataCalculatorConfig::checkpoint_interval`]
+    /// is set, the calculator will periodically upload tree checkpoints to the store.
+    pub fn with_checkpoint_store(mut self, checkpoint_store: Arc<dyn ObjectStore>) -> Self {
+        self.checkpoint_store = Some(checkpoint_store);
+        self
+    }
+
     /// Returns a health check for this calculator.
     pub fn tree_health_check(&self) -> impl CheckHealth {
         MerkleTreeHealthCheck::new(self.health_updater.subscribe(), self.tree_reader())
@@ -170,6 +197,21 @@ impl MetadataCalculator {
         self.health_updater
             .update(MerkleTreeHealth::Initialization.into());
 
+        if let Some(checkpoint_store) = &self.checkpoint_store {
+            let db_path = Path::new(&self.config.db_path);
+            if Self::is_db_uninitialized(db_path).await? {
+                let restored_l1_batch =
+                    checkpoints::restore_from_latest_checkpoint(&**checkpoint_store, db_path)
+                        .await
+                        .context("failed restoring Merkle tree from checkpoint")?;
+                if restored_l1_batch.is_none() {
+                    tracing::info!(
+                        "No Merkle tree checkpoints in the store; initializing tree from scratch"
+                    );
+                }
+            }
+        }
+
         let started_at = Instant::now();
         let db = create_db(self.config.clone()).await.with_context(|| {
             format!(
@@ -186,6 +228,16 @@ impl MetadataCalculator {
         Ok(GenericAsyncTree::new(db, self.config.mode).await)
     }
 
+    async fn is_db_uninitialized(db_path: &Path) -> anyhow::Result<bool> {
+        if !tokio::fs::try_exists(db_path).await? {
+            return Ok(true);
+        }
+        let mut dir = tokio::fs::read_dir(db_path)
+            .await
+            .with_context(|| format!("failed reading `{}`", db_path.display()))?;
+        Ok(dir.next_entry().await?.is_none())
+    }
+
     pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let tree = self.create_tree().await?;
         let tree = tree
@@ -199,6 +251,7 @@ impl MetadataCalculator {
         let Some(mut tree) = tree else {
             return Ok(()); // recovery was aborted because a stop signal was received
         };
+        tree.set_parallel_updates(self.config.parallel_updates);
 
         let tree_reader = tree.reader();
         let tree_info = tree_reader.clone().info().await;
@@ -210,7 +263,23 @@ impl MetadataCalculator {
         self.health_updater
             .update(MerkleTreeHealth::MainLoop(tree_info).into());
 
-        let updater = TreeUpdater::new(tree, self.max_l1_batches_per_iter, self.object_store);
+        let checkpointer = self
+            .checkpoint_store
+            .zip(self.config.checkpoint_interval)
+            .map(|(store, interval)| {
+                TreeCheckpointer::new(
+                    store,
+                    interval,
+                    self.config.max_retained_checkpoints,
+                    &self.config.db_path,
+                )
+            });
+        let updater = TreeUpdater::new(
+            tree,
+            self.max_l1_batches_per_iter,
+            self.object_store,
+            checkpointer,
+        );
         updater
             .loop_updating_tree(self.delayer, &self.pool, stop_receiver)
             .await
@@ -25,7 +25,12 @@ use zksync_types::{
 use zksync_utils::u32_to_h256;
 
 use super::{
-    helpers::L1BatchWithLogs, GenericAsyncTree, MetadataCalculator, MetadataCalculatorConfig,
+    checkpoints::{
+        upload_checkpoint, LatestTreeCheckpoint, TreeCheckpointChunk, TreeCheckpointChunkKey,
+        TreeCheckpointManifest,
+    },
+    helpers::L1BatchWithLogs,
+    GenericAsyncTree, MetadataCalculator, MetadataCalculatorConfig,
 };
 
 const RUN_TIMEOUT: Duration = Duration::from_secs(30);
@@ -53,6 +58,9 @@ pub(super) fn mock_config(db_path: &Path) -> MetadataCalculatorConfig {
         include_indices_and_filters_in_block_cache: false,
         memtable_capacity: 16 << 20,            // 16 MiB
         stalled_writes_timeout: Duration::ZERO, // writes should never be stalled in tests
+        checkpoint_interval: None,
+        max_retained_checkpoints: 3,
+        parallel_updates: false,
     }
 }
 
@@ -211,6 +219,25 @@ async fn multi_l1_batch_workflow() {
     }
 }
 
+#[tokio::test]
+async fn lightweight_workflow_with_parallel_updates() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (calculator, _) = setup_calculator(temp_dir.path(), pool.clone()).await;
+    reset_db_state(&pool, 5).await;
+    let root_hash = run_calculator(calculator).await;
+
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (mut merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Lightweight);
+    merkle_tree_config.parallel_updates = true;
+    let calculator =
+        setup_calculator_with_options(&merkle_tree_config, &operation_config, pool, None).await;
+    assert!(calculator.config.parallel_updates);
+    let parallel_root_hash = run_calculator(calculator).await;
+    assert_eq!(parallel_root_hash, root_hash);
+}
+
 #[tokio::test]
 async fn running_metadata_calculator_with_additional_blocks() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -285,6 +312,99 @@ async fn shutting_down_calculator() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn uploading_and_restoring_tree_checkpoint() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (mut merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Lightweight);
+    merkle_tree_config.checkpoint_interval_l1_batches = Some(2);
+    let checkpoint_store = ObjectStoreFactory::mock().create_store().await;
+
+    let calculator =
+        setup_calculator_with_options(&merkle_tree_config, &operation_config, pool.clone(), None)
+            .await
+            .with_checkpoint_store(checkpoint_store.clone());
+    reset_db_state(&pool, 5).await;
+    let root_hash = run_calculator(calculator).await;
+
+    let latest: LatestTreeCheckpoint = checkpoint_store.get(()).await.unwrap();
+    assert_eq!(latest.l1_batch_number, L1BatchNumber(5));
+    let manifest: TreeCheckpointManifest = checkpoint_store.get(L1BatchNumber(5)).await.unwrap();
+    assert_eq!(manifest.root_hash, root_hash);
+    assert!(!manifest.files.is_empty());
+
+    // Bootstrap a new tree from the checkpoint.
+    let new_temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (new_merkle_tree_config, _) =
+        create_config(new_temp_dir.path(), MerkleTreeMode::Lightweight);
+    let calculator = setup_calculator_with_options(
+        &new_merkle_tree_config,
+        &operation_config,
+        pool.clone(),
+        None,
+    )
+    .await
+    .with_checkpoint_store(checkpoint_store);
+    let tree = calculator.create_tree().await.unwrap();
+    let GenericAsyncTree::Ready(tree) = tree else {
+        panic!("Unexpected tree state: {tree:?}");
+    };
+    assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(6));
+    assert_eq!(tree.root_hash(), root_hash);
+    drop(tree);
+
+    // The restored tree should catch up with new L1 batches.
+    let new_logs = gen_storage_logs(100..120, 2);
+    extend_db_state(&mut pool.connection().await.unwrap(), new_logs).await;
+    let new_root_hash = run_calculator(calculator).await;
+    assert_eq!(new_root_hash, expected_tree_hash(&pool).await);
+}
+
+#[tokio::test]
+async fn obsolete_tree_checkpoints_are_removed() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory");
+    let checkpoint_path = temp_dir.path().join("checkpoint");
+    let checkpoint_store = ObjectStoreFactory::mock().create_store().await;
+
+    for l1_batch_number in [2, 4, 6, 8].map(L1BatchNumber) {
+        tokio::fs::create_dir(&checkpoint_path).await.unwrap();
+        tokio::fs::write(checkpoint_path.join("CURRENT"), b"test")
+            .await
+            .unwrap();
+        upload_checkpoint(
+            checkpoint_store.clone(),
+            checkpoint_path.clone(),
+            l1_batch_number,
+            H256::repeat_byte(1),
+            2,
+        )
+        .await
+        .unwrap();
+    }
+
+    let latest: LatestTreeCheckpoint = checkpoint_store.get(()).await.unwrap();
+    assert_eq!(latest.l1_batch_number, L1BatchNumber(8));
+    assert_eq!(
+        latest.retained_l1_batches,
+        [L1BatchNumber(6), L1BatchNumber(8)]
+    );
+    for l1_batch_number in [2, 4, 6, 8].map(L1BatchNumber) {
+        let is_retained = l1_batch_number >= L1BatchNumber(6);
+        let manifest = checkpoint_store
+            .get::<TreeCheckpointManifest>(l1_batch_number)
+            .await;
+        assert_eq!(manifest.is_ok(), is_retained, "{l1_batch_number}");
+        let chunk_key = TreeCheckpointChunkKey {
+            l1_batch_number,
+            file_name: "CURRENT",
+            chunk_id: 0,
+        };
+        let chunk = checkpoint_store.get::<TreeCheckpointChunk>(chunk_key).await;
+        assert_eq!(chunk.is_ok(), is_retained, "{l1_batch_number}");
+    }
+}
+
 async fn test_postgres_backup_recovery(
     sleep_between_batches: bool,
     insert_batch_without_metadata: bool,
@@ -14,6 +14,7 @@ use zksync_types::{
 };
 
 use super::{
+    checkpoints::TreeCheckpointer,
     helpers::{AsyncTree, Delayer, L1BatchWithLogs},
     metrics::{TreeUpdateStage, METRICS},
     MetadataCalculator,
@@ -25,6 +26,7 @@ pub(super) struct TreeUpdater {
     tree: AsyncTree,
     max_l1_batches_per_iter: usize,
     object_store: Option<Arc<dyn ObjectStore>>,
+    checkpointer: Option<TreeCheckpointer>,
 }
 
 impl TreeUpdater {
@@ -32,11 +34,13 @@ impl TreeUpdater {
         tree: AsyncTree,
         max_l1_batches_per_iter: usize,
         object_store: Option<Arc<dyn ObjectStore>>,
+        checkpointer: Option<TreeCheckpointer>,
     ) -> Self {
         Self {
             tree,
             max_l1_batches_per_iter,
             object_store,
+            checkpointer,
         }
     }
 
@@ -186,9 +190,15 @@ impl TreeUpdater {
             );
         } else {
             tracing::info!("Updating Merkle tree with L1 batches #{l1_batch_numbers:?}");
+            let first_processed_l1_batch = *next_l1_batch_to_seal;
             *next_l1_batch_to_seal = self
                 .process_multiple_batches(&mut storage, l1_batch_numbers)
                 .await?;
+            if let Some(checkpointer) = &mut self.checkpointer {
+                checkpointer
+                    .maybe_checkpoint(&self.tree, first_processed_l1_batch)
+                    .await?;
+            }
         }
         Ok(())
     }
@@ -298,6 +308,11 @@ impl TreeUpdater {
                 () = delay => { /* The delay has passed */ }
             }
         }
+
+        if let Some(checkpointer) = self.checkpointer {
+            tracing::info!("Waiting for the tree checkpoint upload to finish");
+            checkpointer.wait_for_upload().await;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,462 @@
+//! Shared state keeper config with seal and timeout parameters that can be updated at runtime.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use zksync_config::configs::chain::StateKeeperConfig;
+pub use zksync_types::api::ConfigUpdateEntry;
+
+use super::metrics::CONFIG_SOURCE_METRICS;
+
+/// Update of seal and timeout parameters in [`StateKeeperConfig`]. Unspecified parameters retain their current values.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SealParamsUpdate {
+    pub transaction_slots: Option<usize>,
+    pub block_commit_deadline_ms: Option<u64>,
+    pub l2_block_commit_deadline_ms: Option<u64>,
+    pub l2_block_max_payload_size: Option<usize>,
+    pub max_single_tx_gas: Option<u32>,
+    pub reject_tx_at_geometry_percentage: Option<f64>,
+    pub reject_tx_at_eth_params_percentage: Option<f64>,
+    pub reject_tx_at_gas_percentage: Option<f64>,
+    pub close_block_at_geometry_percentage: Option<f64>,
+    pub close_block_at_eth_params_percentage: Option<f64>,
+    pub close_block_at_gas_percentage: Option<f64>,
+}
+
+impl SealParamsUpdate {
+    /// Applies this update to the `config`. Returns human-readable descriptions of changed params.
+    fn apply(&self, config: &mut StateKeeperConfig) -> Vec<String> {
+        fn set<T: Copy + PartialEq + fmt::Debug>(
+            changes: &mut Vec<String>,
+            name: &str,
+            target: &mut T,
+            value: Option<T>,
+        ) {
+            if let Some(value) = value {
+                if *target != value {
+                    changes.push(format!("{name}: {target:?} -> {value:?}"));
+                    *target = value;
+                }
+            }
+        }
+
+        let mut changes = vec![];
+        set(
+            &mut changes,
+            "transaction_slots",
+            &mut config.transaction_slots,
+            self.transaction_slots,
+        );
+        set(
+            &mut changes,
+            "block_commit_deadline_ms",
+            &mut config.block_commit_deadline_ms,
+            self.block_commit_deadline_ms,
+        );
+        set(
+            &mut changes,
+            "l2_block_commit_deadline_ms",
+            &mut config.l2_block_commit_deadline_ms,
+            self.l2_block_commit_deadline_ms,
+        );
+        set(
+            &mut changes,
+            "l2_block_max_payload_size",
+            &mut config.l2_block_max_payload_size,
+            self.l2_block_max_payload_size,
+        );
+        set(
+            &mut changes,
+            "max_single_tx_gas",
+            &mut config.max_single_tx_gas,
+            self.max_single_tx_gas,
+        );
+        set(
+            &mut changes,
+            "reject_tx_at_geometry_percentage",
+            &mut config.reject_tx_at_geometry_percentage,
+            self.reject_tx_at_geometry_percentage,
+        );
+        set(
+            &mut changes,
+            "reject_tx_at_eth_params_percentage",
+            &mut config.reject_tx_at_eth_params_percentage,
+            self.reject_tx_at_eth_params_percentage,
+        );
+        set(
+            &mut changes,
+            "reject_tx_at_gas_percentage",
+            &mut config.reject_tx_at_gas_percentage,
+            self.reject_tx_at_gas_percentage,
+        );
+        set(
+            &mut changes,
+            "close_block_at_geometry_percentage",
+            &mut config.close_block_at_geometry_percentage,
+            self.close_block_at_geometry_percentage,
+        );
+        set(
+            &mut changes,
+            "close_block_at_eth_params_percentage",
+            &mut config.close_block_at_eth_params_percentage,
+            self.close_block_at_eth_params_percentage,
+        );
+        set(
+            &mut changes,
+            "close_block_at_gas_percentage",
+            &mut config.close_block_at_gas_percentage,
+            self.close_block_at_gas_percentage,
+        );
+        changes
+    }
+}
+
+fn validate_seal_params(config: &StateKeeperConfig) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        config.transaction_slots > 0,
+        "`transaction_slots` must be positive"
+    );
+    anyhow::ensure!(
+        config.block_commit_deadline_ms > 0,
+        "`block_commit_deadline_ms` must be positive"
+    );
+    anyhow::ensure!(
+        config.l2_block_commit_deadline_ms > 0,
+        "`l2_block_commit_deadline_ms` must be positive"
+    );
+    anyhow::ensure!(
+        config.l2_block_max_payload_size > 0,
+        "`l2_block_max_payload_size` must be positive"
+    );
+    anyhow::ensure!(
+        config.max_single_tx_gas > 0,
+        "`max_single_tx_gas` must be positive"
+    );
+
+    let percentages = [
+        (
+            "reject_tx_at_geometry_percentage",
+            config.reject_tx_at_geometry_percentage,
+        ),
+        (
+            "reject_tx_at_eth_params_percentage",
+            config.reject_tx_at_eth_params_percentage,
+        ),
+        (
+            "reject_tx_at_gas_percentage",
+            config.reject_tx_at_gas_percentage,
+        ),
+        (
+            "close_block_at_geometry_percentage",
+            config.close_block_at_geometry_percentage,
+        ),
+        (
+            "close_block_at_eth_params_percentage",
+            config.close_block_at_eth_params_percentage,
+        ),
+        (
+            "close_block_at_gas_percentage",
+            config.close_block_at_gas_percentage,
+        ),
+    ];
+    for (name, value) in percentages {
+        anyhow::ensure!(
+            value > 0.0 && value <= 1.0,
+            "`{name}` must be in (0, 1], got {value}"
+        );
+    }
+    Ok(())
+}
+
+fn report_seal_params(config: &StateKeeperConfig) {
+    let params = [
+        ("transaction_slots", config.transaction_slots as f64),
+        (
+            "block_commit_deadline_ms",
+            config.block_commit_deadline_ms as f64,
+        ),
+        (
+            "l2_block_commit_deadline_ms",
+            config.l2_block_commit_deadline_ms as f64,
+        ),
+        (
+            "l2_block_max_payload_size",
+            config.l2_block_max_payload_size as f64,
+        ),
+        ("max_single_tx_gas", config.max_single_tx_gas.into()),
+        (
+            "reject_tx_at_geometry_percentage",
+            config.reject_tx_at_geometry_percentage,
+        ),
+        (
+            "reject_tx_at_eth_params_percentage",
+            config.reject_tx_at_eth_params_percentage,
+        ),
+        (
+            "reject_tx_at_gas_percentage",
+            config.reject_tx_at_gas_percentage,
+        ),
+        (
+            "close_block_at_geometry_percentage",
+            config.close_block_at_geometry_percentage,
+        ),
+        (
+            "close_block_at_eth_params_percentage",
+            config.close_block_at_eth_params_percentage,
+        ),
+        (
+            "close_block_at_gas_percentage",
+            config.close_block_at_gas_percentage,
+        ),
+    ];
+    for (name, value) in params {
+        CONFIG_SOURCE_METRICS.active_seal_params[&name].set(value);
+    }
+}
+
+#[derive(Debug)]
+struct ConfigSourceInner {
+    sender: watch::Sender<Arc<StateKeeperConfig>>,
+    audit_log: Mutex<VecDeque<ConfigUpdateEntry>>,
+}
+
+/// Shared watchable source of [`StateKeeperConfig`]. Seal and timeout parameters in the config can be updated
+/// at runtime (e.g., by [watching a file](Self::run())); all updates are validated, recorded
+/// in an audit log and reported via metrics.
+///
+/// Components reading seal params (e.g., [`SequencerSealer`](super::SequencerSealer) and [`MempoolIO`](super::MempoolIO))
+/// should use the current config from the source rather than a copy.
+#[derive(Debug, Clone)]
+pub struct StateKeeperConfigSource(Arc<ConfigSourceInner>);
+
+impl Default for StateKeeperConfigSource {
+    fn default() -> Self {
+        Self::new(StateKeeperConfig::default())
+    }
+}
+
+impl StateKeeperConfigSource {
+    /// Maximum number of entries retained in the audit log.
+    const MAX_AUDIT_LOG_LEN: usize = 100;
+    /// Interval between checks of the watched file.
+    const FILE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// Creates a source with the specified initial config.
+    pub fn new(config: StateKeeperConfig) -> Self {
+        Self(Arc::new(ConfigSourceInner {
+            sender: watch::channel(Arc::new(config)).0,
+            audit_log: Mutex::default(),
+        }))
+    }
+
+    /// Returns the current config.
+    pub fn current(&self) -> Arc<StateKeeperConfig> {
+        self.0.sender.borrow().clone()
+    }
+
+    /// Subscribes to config updates.
+    pub fn subscribe(&self) -> watch::Receiver<Arc<StateKeeperConfig>> {
+        self.0.sender.subscribe()
+    }
+
+    /// Returns the audit log of applied updates, from the oldest to the newest one.
+    pub fn audit_log(&self) -> Vec<ConfigUpdateEntry> {
+        self.0.audit_log.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Validates and applies the update. Returns the list of changed params (empty if the update is a no-op).
+    pub fn update(&self, update: &SealParamsUpdate, origin: &str) -> anyhow::Result<Vec<String>> {
+        let mut result = Ok(vec![]);
+        self.0.sender.send_if_modified(|config| {
+            let mut new_config = StateKeeperConfig::clone(config);
+            let changes = update.apply(&mut new_config);
+            if changes.is_empty() {
+                return false;
+            }
+            if let Err(err) = validate_seal_params(&new_config) {
+                result = Err(err);
+                return false;
+            }
+            *config = Arc::new(new_config);
+            result = Ok(changes);
+            true
+        });
+
+        match &result {
+            Ok(changes) if !changes.is_empty() => {
+                tracing::info!(
+                    "State keeper config updated by {origin}: {}",
+                    changes.join(", ")
+                );
+                CONFIG_SOURCE_METRICS.applied_updates.inc();
+                let mut audit_log = self.0.audit_log.lock().unwrap();
+                if audit_log.len() == Self::MAX_AUDIT_LOG_LEN {
+                    audit_log.pop_front();
+                }
+                audit_log.push_back(ConfigUpdateEntry {
+                    timestamp: Utc::now(),
+                    origin: origin.to_owned(),
+                    changes: changes.clone(),
+                });
+            }
+            Ok(_) => { /* no-op update */ }
+            Err(err) => {
+                tracing::warn!("Rejected state keeper config update by {origin}: {err:#}");
+                CONFIG_SOURCE_METRICS.rejected_updates.inc();
+            }
+        }
+        result
+    }
+
+    /// Runs the background task for this source: reports active seal params via metrics whenever they change and,
+    /// if `path` is specified, periodically reads [`SealParamsUpdate`] from the JSON file at this path and applies it
+    /// whenever the file changes. Errors reading or applying the file are logged, but do not terminate the task.
+    pub async fn run(
+        self,
+        path: Option<PathBuf>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let mut config_receiver = self.subscribe();
+        report_seal_params(&config_receiver.borrow_and_update());
+        let mut file_watcher = path.map(FileWatcher::new);
+        let mut poll_timer = tokio::time::interval(Self::FILE_POLL_INTERVAL);
+
+        while !*stop_receiver.borrow_and_update() {
+            tokio::select! {
+                _ = poll_timer.tick(), if file_watcher.is_some() => {
+                    if let Some(watcher) = &mut file_watcher {
+                        watcher.poll(&self).await;
+                    }
+                }
+                // The sender is owned by `self`, so it cannot be dropped.
+                _ = config_receiver.changed() => {
+                    report_seal_params(&config_receiver.borrow_and_update());
+                }
+                _ = stop_receiver.changed() => break,
+            }
+        }
+        tracing::info!("Stop signal received, state keeper config source task is shutting down");
+        Ok(())
+    }
+}
+
+/// Watcher of a JSON file with [`SealParamsUpdate`].
+#[derive(Debug)]
+struct FileWatcher {
+    path: PathBuf,
+    origin: String,
+    last_contents: Option<String>,
+}
+
+impl FileWatcher {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            origin: format!("file `{}`", path.display()),
+            path,
+            last_contents: None,
+        }
+    }
+
+    async fn poll(&mut self, source: &StateKeeperConfigSource) {
+        let origin = &self.origin;
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(contents) if self.last_contents.as_ref() != Some(&contents) => {
+                let update = serde_json::from_str::<SealParamsUpdate>(&contents)
+                    .with_context(|| format!("failed parsing {origin}"));
+                match update {
+                    Ok(update) => {
+                        // Errors are logged by `update()`.
+                        source.update(&update, origin).ok();
+                    }
+                    Err(err) => tracing::warn!("{err:#}"),
+                }
+                self.last_contents = Some(contents);
+            }
+            Ok(_) => { /* file is unchanged */ }
+            Err(err) => tracing::warn!("Failed reading {origin}: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updating_config() {
+        let source = StateKeeperConfigSource::new(StateKeeperConfig::for_tests());
+        let mut receiver = source.subscribe();
+        let update = SealParamsUpdate {
+            transaction_slots: Some(100),
+            close_block_at_gas_percentage: Some(0.5),
+            ..SealParamsUpdate::default()
+        };
+        let changes = source.update(&update, "test").unwrap();
+        assert_eq!(changes.len(), 2, "{changes:?}");
+        assert!(receiver.has_changed().unwrap());
+        let config = receiver.borrow_and_update().clone();
+        assert_eq!(config.transaction_slots, 100);
+        assert_eq!(config.close_block_at_gas_percentage, 0.5);
+        assert_eq!(source.current(), config);
+
+        // Repeated update is a no-op.
+        let changes = source.update(&update, "test").unwrap();
+        assert!(changes.is_empty(), "{changes:?}");
+        assert!(!receiver.has_changed().unwrap());
+
+        let audit_log = source.audit_log();
+        assert_eq!(audit_log.len(), 1);
+        assert_eq!(audit_log[0].origin, "test");
+        assert!(audit_log[0].changes[0].contains("transaction_slots"));
+    }
+
+    #[test]
+    fn invalid_updates_are_rejected() {
+        let source = StateKeeperConfigSource::new(StateKeeperConfig::for_tests());
+        let update = SealParamsUpdate {
+            transaction_slots: Some(0),
+            ..SealParamsUpdate::default()
+        };
+        let err = source.update(&update, "test").unwrap_err();
+        assert!(err.to_string().contains("transaction_slots"), "{err}");
+
+        let update = SealParamsUpdate {
+            block_commit_deadline_ms: Some(1_000),
+            reject_tx_at_gas_percentage: Some(1.5),
+            ..SealParamsUpdate::default()
+        };
+        let err = source.update(&update, "test").unwrap_err();
+        assert!(
+            err.to_string().contains("reject_tx_at_gas_percentage"),
+            "{err}"
+        );
+
+        // The config should not be partially updated.
+        assert_eq!(*source.current(), StateKeeperConfig::for_tests());
+        assert!(source.audit_log().is_empty());
+    }
+
+    #[test]
+    fn parsing_update() {
+        let update: SealParamsUpdate = serde_json::from_str(
+            r#"{ "transaction_slots": 50, "block_commit_deadline_ms": 1000 }"#,
+        )
+        .unwrap();
+        assert_eq!(update.transaction_slots, Some(50));
+        assert_eq!(update.block_commit_deadline_ms, Some(1_000));
+        assert_eq!(update.max_single_tx_gas, None);
+
+        serde_json::from_str::<SealParamsUpdate>(r#"{ "max_pubdata_per_batch": 1 }"#).unwrap_err();
+    }
+}
@@ -0,0 +1,217 @@
+//! Runtime control of the state keeper.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::L2BlockNumber;
+
+/// Pause state of the state keeper. Also used as health check details for [`StateKeeperControl`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+enum PauseState {
+    /// State keeper processes transactions as usual.
+    Running,
+    /// Pause is requested, but the state keeper hasn't sealed the current L2 block yet.
+    Pausing,
+    /// State keeper has sealed the current L2 block and doesn't fetch new transactions.
+    Paused { last_sealed_l2_block: L2BlockNumber },
+}
+
+impl From<PauseState> for Health {
+    fn from(state: PauseState) -> Self {
+        let status = match state {
+            PauseState::Running => HealthStatus::Ready,
+            PauseState::Pausing | PauseState::Paused { .. } => HealthStatus::Affected,
+        };
+        Health::from(status).with_details(state)
+    }
+}
+
+#[derive(Debug)]
+struct ControlInner {
+    state_sender: watch::Sender<PauseState>,
+    l1_batch_seal_requested: AtomicBool,
+    health_updater: HealthUpdater,
+}
+
+/// Handle allowing to control [`ZkSyncStateKeeper`](super::ZkSyncStateKeeper) at runtime, e.g. to pause
+/// processing transactions or to force sealing the current L1 batch. The handle is cheaply cloneable;
+/// all clones control the same state keeper.
+///
+/// Pausing the state keeper is graceful: the state keeper seals the current L2 block (if it contains any transactions),
+/// and then stops fetching new transactions. The pending L1 batch is kept in memory, so that after resuming,
+/// the state keeper continues from where it has stopped.
+#[derive(Debug, Clone)]
+pub struct StateKeeperControl(Arc<ControlInner>);
+
+impl Default for StateKeeperControl {
+    fn default() -> Self {
+        let (_, health_updater) = ReactiveHealthCheck::new("state_keeper_control");
+        health_updater.update(PauseState::Running.into());
+        Self(Arc::new(ControlInner {
+            state_sender: watch::channel(PauseState::Running).0,
+            l1_batch_seal_requested: AtomicBool::new(false),
+            health_updater,
+        }))
+    }
+}
+
+impl StateKeeperControl {
+    /// Returns the health check reporting whether the state keeper is paused.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.0.health_updater.subscribe()
+    }
+
+    /// Pauses the state keeper: it will seal the current L2 block and stop fetching new transactions
+    /// until [resumed](Self::resume()). Returns `false` if the state keeper is already paused.
+    pub fn pause(&self) -> bool {
+        let changed = self.update_state(|state| {
+            matches!(state, PauseState::Running).then_some(PauseState::Pausing)
+        });
+        if changed {
+            tracing::info!("State keeper is requested to pause");
+        }
+        changed
+    }
+
+    /// Resumes the state keeper after a [pause](Self::pause()). Returns `false` if the state keeper is not paused.
+    pub fn resume(&self) -> bool {
+        let changed = self.update_state(|state| {
+            (!matches!(state, PauseState::Running)).then_some(PauseState::Running)
+        });
+        if changed {
+            tracing::info!("State keeper is resumed");
+        }
+        changed
+    }
+
+    /// Marks the pause as complete once the state keeper has sealed the current L2 block. No-op if the state keeper
+    /// is not pausing.
+    pub(super) fn confirm_pause(&self, last_sealed_l2_block: L2BlockNumber) {
+        let changed = self.update_state(|state| {
+            matches!(state, PauseState::Pausing).then_some(PauseState::Paused {
+                last_sealed_l2_block,
+            })
+        });
+        if changed {
+            tracing::info!("State keeper is paused; last sealed L2 block: #{last_sealed_l2_block}");
+        }
+    }
+
+    fn update_state(&self, update: impl FnOnce(PauseState) -> Option<PauseState>) -> bool {
+        let mut new_state = None;
+        self.0.state_sender.send_if_modified(|state| {
+            new_state = update(*state);
+            if let Some(new_state) = new_state {
+                *state = new_state;
+            }
+            new_state.is_some()
+        });
+        if let Some(new_state) = new_state {
+            self.0.health_updater.update(new_state.into());
+        }
+        new_state.is_some()
+    }
+
+    /// Checks whether the state keeper is paused (or is pausing).
+    pub fn is_paused(&self) -> bool {
+        *self.0.state_sender.borrow() != PauseState::Running
+    }
+
+    /// Requests the state keeper to seal the current L1 batch. The batch is sealed as soon as it contains
+    /// at least one transaction.
+    pub fn request_l1_batch_seal(&self) {
+        tracing::info!("Requested sealing the current L1 batch");
+        self.0.l1_batch_seal_requested.store(true, Ordering::SeqCst);
+    }
+
+    pub(super) fn take_l1_batch_seal_request(&self) -> bool {
+        self.0.l1_batch_seal_requested.swap(false, Ordering::SeqCst)
+    }
+
+    /// Waits until the state keeper is resumed or the specified timeout expires.
+    pub(super) async fn wait_for_resume(&self, timeout: Duration) {
+        let mut state_receiver = self.0.state_sender.subscribe();
+        let wait = state_receiver.wait_for(|state| *state == PauseState::Running);
+        tokio::time::timeout(timeout, wait).await.ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_health_check::CheckHealth;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn pausing_and_resuming() {
+        let control = StateKeeperControl::default();
+        assert!(!control.is_paused());
+        assert!(!control.resume());
+        assert!(control.pause());
+        assert!(!control.pause());
+        assert!(control.is_paused());
+
+        let wait_task = tokio::spawn({
+            let control = control.clone();
+            async move { control.wait_for_resume(Duration::from_secs(60)).await }
+        });
+        assert!(control.resume());
+        tokio::time::timeout(Duration::from_secs(10), wait_task)
+            .await
+            .expect("timed out waiting for resume")
+            .unwrap();
+        assert!(!control.is_paused());
+    }
+
+    #[tokio::test]
+    async fn pause_health_check() {
+        let control = StateKeeperControl::default();
+        let health_check = control.health_check();
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+
+        control.pause();
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Affected);
+        assert_eq!(
+            health.details().unwrap(),
+            &serde_json::json!({ "state": "pausing" })
+        );
+
+        control.confirm_pause(L2BlockNumber(5));
+        // Repeated confirmations must not update the state.
+        control.confirm_pause(L2BlockNumber(6));
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Affected);
+        assert_eq!(
+            health.details().unwrap(),
+            &serde_json::json!({ "state": "paused", "last_sealed_l2_block": 5 })
+        );
+
+        control.resume();
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+        // Pause confirmation is ignored for a running state keeper.
+        control.confirm_pause(L2BlockNumber(7));
+        assert!(!control.is_paused());
+    }
+
+    #[test]
+    fn requesting_l1_batch_seal() {
+        let control = StateKeeperControl::default();
+        assert!(!control.take_l1_batch_seal_request());
+        control.clone().request_l1_batch_seal();
+        assert!(control.take_l1_batch_seal_request());
+        assert!(!control.take_l1_batch_seal_request());
+    }
+}
@@ -8,6 +8,7 @@ use std::{
 use anyhow::Context as _;
 use async_trait::async_trait;
 use multivm::{interface::Halt, utils::derive_base_fee_and_gas_per_pubdata};
+use tokio::sync::watch;
 use vm_utils::storage::L1BatchParamsProvider;
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::BaseSystemContracts;
@@ -22,6 +23,7 @@ use zksync_types::{
 use zksync_utils::time::millis_since_epoch;
 
 use crate::state_keeper::{
+    config_source::StateKeeperConfigSource,
     io::{
         common::{load_pending_batch, poll_iters, IoCursor},
         L1BatchParams, L2BlockParams, PendingBatchData, StateKeeperIO,
@@ -29,6 +31,7 @@ use crate::state_keeper::{
     mempool_actor::l2_tx_filter,
     metrics::KEEPER_METRICS,
     seal_criteria::{IoSealCriteria, L2BlockMaxPayloadSizeSealer, TimeoutSealer},
+    tx_filter::{NoopTxFilter, TxFilter},
     updates::UpdatesManager,
     MempoolGuard,
 };
@@ -43,7 +46,10 @@ pub struct MempoolIO {
     pool: ConnectionPool<Core>,
     timeout_sealer: TimeoutSealer,
     l2_block_max_payload_size_sealer: L2BlockMaxPayloadSizeSealer,
+    /// Receiver for config updates; if set, sealers are updated with new params.
+    config_receiver: Option<watch::Receiver<Arc<StateKeeperConfig>>>,
     filter: L2TxFilter,
+    tx_filter: Arc<dyn TxFilter>,
     l1_batch_params_provider: L1BatchParamsProvider,
     fee_account: Address,
     validation_computational_gas_limit: u32,
@@ -56,11 +62,13 @@ pub struct MempoolIO {
 
 impl IoSealCriteria for MempoolIO {
     fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
+        self.update_seal_params();
         self.timeout_sealer
             .should_seal_l1_batch_unconditionally(manager)
     }
 
     fn should_seal_l2_block(&mut self, manager: &UpdatesManager) -> bool {
+        self.update_seal_params();
         if self.timeout_sealer.should_seal_l2_block(manager) {
             return true;
         }
@@ -245,6 +253,14 @@ impl StateKeeperIO for MempoolIO {
                     self.reject(&tx, &Halt::TooBigGasLimit.to_string()).await?;
                     continue;
                 }
+                // Transactions could have been accepted by the API before the filter was updated.
+                if !tx.is_l1() {
+                    if let Err(reason) = self.tx_filter.check_tx(&tx) {
+                        tracing::info!("Transaction {:?} is filtered out: {reason}", tx.hash());
+                        self.reject(&tx, &format!("filtered out: {reason}")).await?;
+                        continue;
+                    }
+                }
                 return Ok(Some(tx));
             } else {
                 tokio::time::sleep(self.delay_interval).await;
@@ -418,8 +434,10 @@ impl MempoolIO {
             pool,
             timeout_sealer: TimeoutSealer::new(config),
             l2_block_max_payload_size_sealer: L2BlockMaxPayloadSizeSealer::new(config),
+            config_receiver: None,
             filter: L2TxFilter::default(),
             // ^ Will be initialized properly on the first newly opened batch
+            tx_filter: Arc::new(NoopTxFilter),
             l1_batch_params_provider,
             fee_account,
             validation_computational_gas_limit: config.validation_computational_gas_limit,
@@ -429,6 +447,45 @@ impl MempoolIO {
             chain_id,
         })
     }
+
+    /// Sets the filter applied to L2 transactions fetched from the mempool. Transactions rejected by the filter
+    /// are marked as rejected. By default, all transactions are accepted.
+    pub fn with_tx_filter(mut self, tx_filter: Arc<dyn TxFilter>) -> Self {
+        self.tx_filter = tx_filter;
+        self
+    }
+
+    /// Makes the IO read seal and timeout params from the specified config source, so that they can be updated
+    /// at runtime. Params from the config source take precedence over ones in the config passed to the constructor.
+    pub fn with_config_source(mut self, config_source: &StateKeeperConfigSource) -> Self {
+        let mut config_receiver = config_source.subscribe();
+        let config = config_receiver.borrow_and_update().clone();
+        self.set_seal_params(&config);
+        self.config_receiver = Some(config_receiver);
+        self
+    }
+
+    fn set_seal_params(&mut self, config: &StateKeeperConfig) {
+        self.timeout_sealer = TimeoutSealer::new(config);
+        self.l2_block_max_payload_size_sealer = L2BlockMaxPayloadSizeSealer::new(config);
+    }
+
+    fn update_seal_params(&mut self) {
+        let Some(config_receiver) = &mut self.config_receiver else {
+            return;
+        };
+        // The sender is never dropped while the source is alive; if it is, we just retain the current params.
+        if !config_receiver.has_changed().unwrap_or(false) {
+            return;
+        }
+        let config = config_receiver.borrow_and_update().clone();
+        self.set_seal_params(&config);
+        tracing::info!(
+            "Updated seal params for mempool IO: {:?}, {:?}",
+            self.timeout_sealer,
+            self.l2_block_max_payload_size_sealer
+        );
+    }
 }
 
 /// Getters required for testing the MempoolIO.
@@ -51,7 +51,7 @@ impl OutputHandler {
         self
     }
 
-    pub(crate) async fn initialize(&mut self, cursor: &IoCursor) -> anyhow::Result<()> {
+    pub async fn initialize(&mut self, cursor: &IoCursor) -> anyhow::Result<()> {
         for handler in &mut self.inner {
             handler
                 .initialize(cursor)
@@ -61,7 +61,7 @@ impl OutputHandler {
         Ok(())
     }
 
-    pub(crate) async fn handle_l2_block(
+    pub async fn handle_l2_block(
         &mut self,
         updates_manager: &UpdatesManager,
     ) -> anyhow::Result<()> {
@@ -79,7 +79,7 @@ impl OutputHandler {
         Ok(())
     }
 
-    pub(crate) async fn handle_l1_batch(
+    pub async fn handle_l1_batch(
         &mut self,
         updates_manager: &UpdatesManager,
     ) -> anyhow::Result<()> {
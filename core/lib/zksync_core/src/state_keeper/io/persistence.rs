@@ -73,7 +73,8 @@ impl StateKeeperPersistence {
     }
 
     /// Disables inserting protective reads to Postgres when persisting an L1 batch. This is only sound
-    /// if the node won't *ever* run a full Merkle tree (such a tree requires protective reads to generate witness inputs).
+    /// if the node won't *ever* run a full Merkle tree (such a tree requires protective reads to generate witness inputs),
+    /// or if protective reads are produced by another component (e.g., the protective reads writer VM runner).
     pub fn without_protective_reads(mut self) -> Self {
         self.insert_protective_reads = false;
         self
@@ -1,12 +1,15 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
+use assert_matches::assert_matches;
 use multivm::utils::derive_base_fee_and_gas_per_pubdata;
 use test_casing::test_casing;
+use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_mempool::L2TxFilter;
 use zksync_node_test_utils::{prepare_recovery_snapshot, DeploymentMode};
 use zksync_types::{
+    api::TransactionStatus,
     block::{BlockGasCount, L2BlockHasher},
     fee::TransactionExecutionMetrics,
     fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
@@ -20,9 +23,14 @@ use self::tester::Tester;
 use crate::state_keeper::{
     io::StateKeeperIO,
     mempool_actor::l2_tx_filter,
-    tests::{create_execution_result, create_transaction, Query, BASE_SYSTEM_CONTRACTS},
+    seal_criteria::IoSealCriteria,
+    tests::{
+        create_execution_result, create_transaction, create_updates_manager, Query,
+        BASE_SYSTEM_CONTRACTS,
+    },
+    tx_filter::TxFilterRules,
     updates::{L2BlockSealCommand, L2BlockUpdates, UpdatesManager},
-    StateKeeperOutputHandler, StateKeeperPersistence,
+    SealParamsUpdate, StateKeeperConfigSource, StateKeeperOutputHandler, StateKeeperPersistence,
 };
 
 mod tester;
@@ -534,3 +542,97 @@ async fn different_timestamp_for_l2_blocks_in_same_batch(deployment_mode: Deploy
         .expect("no new L2 block params");
     assert!(l2_block_params.timestamp > current_timestamp);
 }
+
+#[tokio::test]
+async fn transactions_rejected_by_tx_filter() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let tester = Tester::new(&DeploymentMode::Rollup);
+    tester.genesis(&connection_pool).await;
+    let mut storage = connection_pool.connection().await.unwrap();
+
+    let (mempool, mut mempool_guard) = tester.create_test_mempool_io(connection_pool.clone()).await;
+    let tx_filter = l2_tx_filter(
+        &tester.create_batch_fee_input_provider().await,
+        ProtocolVersionId::latest().into(),
+    )
+    .await
+    .unwrap();
+    let allowed_tx = tester.insert_tx(
+        &mut mempool_guard,
+        tx_filter.fee_per_gas,
+        tx_filter.gas_per_pubdata,
+    );
+    let denied_tx = tester.insert_tx(
+        &mut mempool_guard,
+        tx_filter.fee_per_gas,
+        tx_filter.gas_per_pubdata,
+    );
+    for tx in [&allowed_tx, &denied_tx] {
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+    }
+
+    let rules = TxFilterRules {
+        denied_senders: [denied_tx.initiator_account()].into(),
+        ..TxFilterRules::default()
+    };
+    let mut mempool = mempool.with_tx_filter(Arc::new(rules));
+    let (cursor, _) = mempool.initialize().await.unwrap();
+    mempool
+        .wait_for_new_batch_params(&cursor, Duration::from_secs(10))
+        .await
+        .unwrap()
+        .expect("no batch params generated");
+
+    let tx = mempool
+        .wait_for_next_tx(Duration::from_secs(1))
+        .await
+        .unwrap()
+        .expect("no transaction");
+    assert_eq!(tx.hash(), allowed_tx.hash());
+    let tx = mempool
+        .wait_for_next_tx(Duration::from_millis(100))
+        .await
+        .unwrap();
+    assert!(tx.is_none(), "{tx:?}");
+
+    let denied_tx_details = storage
+        .transactions_web3_dal()
+        .get_transaction_details(denied_tx.hash())
+        .await
+        .unwrap()
+        .expect("no transaction details");
+    assert_matches!(denied_tx_details.status, TransactionStatus::Failed);
+}
+
+#[tokio::test]
+async fn mempool_io_picks_up_seal_params_updates() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let tester = Tester::new(&DeploymentMode::Rollup);
+    let (mempool, _) = tester.create_test_mempool_io(connection_pool).await;
+    let config_source = StateKeeperConfigSource::new(StateKeeperConfig::for_tests());
+    let mut mempool = mempool.with_config_source(&config_source);
+
+    let mut manager = create_updates_manager();
+    manager.extend_from_executed_transaction(
+        create_transaction(10, 100),
+        create_execution_result(0, []),
+        vec![],
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+    // Make sure that the L2 block isn't sealed by timeout.
+    manager.l2_block.timestamp = seconds_since_epoch();
+    assert!(!mempool.should_seal_l2_block(&manager));
+
+    let update = SealParamsUpdate {
+        l2_block_max_payload_size: Some(1),
+        ..SealParamsUpdate::default()
+    };
+    config_source.update(&update, "test").unwrap();
+    assert!(mempool.should_seal_l2_block(&manager));
+}
@@ -15,6 +15,7 @@ use zksync_types::{
 
 use super::{
     batch_executor::{BatchExecutor, BatchExecutorHandle, TxExecutionResult},
+    control::StateKeeperControl,
     io::{IoCursor, L2BlockParams, OutputHandler, PendingBatchData, StateKeeperIO},
     metrics::{AGGREGATION_METRICS, KEEPER_METRICS, L1_BATCH_METRICS},
     seal_criteria::{ConditionalSealer, SealData, SealResolution},
@@ -61,6 +62,7 @@ pub struct ZkSyncStateKeeper {
     output_handler: OutputHandler,
     batch_executor_base: Box<dyn BatchExecutor>,
     sealer: Arc<dyn ConditionalSealer>,
+    control: StateKeeperControl,
 }
 
 impl ZkSyncStateKeeper {
@@ -77,9 +79,16 @@ impl ZkSyncStateKeeper {
             batch_executor_base,
             output_handler,
             sealer,
+            control: StateKeeperControl::default(),
         }
     }
 
+    /// Sets the handle allowing to control this state keeper at runtime.
+    pub fn with_control(mut self, control: StateKeeperControl) -> Self {
+        self.control = control;
+        self
+    }
+
     pub async fn run(mut self) -> anyhow::Result<()> {
         match self.run_inner().await {
             Ok(_) => unreachable!(),
@@ -446,6 +455,16 @@ impl ZkSyncStateKeeper {
         }
 
         while !self.is_canceled() {
+            if updates_manager.pending_executed_transactions_len() > 0
+                && self.control.take_l1_batch_seal_request()
+            {
+                tracing::info!(
+                    "L1 batch #{} is sealed as requested via state keeper control",
+                    updates_manager.l1_batch.number
+                );
+                return Ok(());
+            }
+
             if self
                 .io
                 .should_seal_l1_batch_unconditionally(updates_manager)
@@ -457,11 +476,20 @@ impl ZkSyncStateKeeper {
                 return Ok(());
             }
 
-            if self.io.should_seal_l2_block(updates_manager) {
+            // Read the pause state once per iteration, so that the pause is processed consistently.
+            let is_paused = self.control.is_paused();
+            let seal_l2_block_on_pause =
+                is_paused && !updates_manager.l2_block.executed_transactions.is_empty();
+            if seal_l2_block_on_pause || self.io.should_seal_l2_block(updates_manager) {
                 tracing::debug!(
-                    "L2 block #{} (L1 batch #{}) should be sealed as per sealing rules",
+                    "L2 block #{} (L1 batch #{}) should be sealed {}",
                     updates_manager.l2_block.number,
-                    updates_manager.l1_batch.number
+                    updates_manager.l1_batch.number,
+                    if seal_l2_block_on_pause {
+                        "before pausing the state keeper"
+                    } else {
+                        "as per sealing rules"
+                    }
                 );
                 self.seal_l2_block(updates_manager).await?;
 
@@ -479,6 +507,17 @@ impl ZkSyncStateKeeper {
                     .await;
             }
 
+            if is_paused {
+                // The current L2 block is empty at this point, so the pending L1 batch is kept in memory
+                // and is continued once the state keeper is resumed. Seal criteria are still checked while paused,
+                // so that the L1 batch is sealed in time if necessary.
+                self.control
+                    .confirm_pause(updates_manager.l2_block.number - 1);
+                tracing::trace!("State keeper is paused; not fetching new transactions");
+                self.control.wait_for_resume(POLL_WAIT_DURATION).await;
+                continue;
+            }
+
             let waiting_latency = KEEPER_METRICS.waiting_for_tx.start();
             let Some(tx) = self
                 .io
@@ -7,8 +7,8 @@ use std::{
 
 use multivm::interface::VmExecutionResultAndLogs;
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
-    Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    LatencyObserver, Metrics,
 };
 use zksync_mempool::MempoolStore;
 use zksync_shared_metrics::InteractionType;
@@ -444,3 +444,19 @@ impl BatchTipMetrics {
 
 #[vise::register]
 pub(crate) static BATCH_TIP_METRICS: vise::Global<BatchTipMetrics> = vise::Global::new();
+
+/// Metrics related to runtime updates of the state keeper config.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_state_keeper_config")]
+pub(super) struct ConfigSourceMetrics {
+    /// Currently active values of seal and timeout parameters that can be updated at runtime.
+    #[metrics(labels = ["param"])]
+    pub active_seal_params: LabeledFamily<&'static str, Gauge<f64>>,
+    /// Number of applied config updates.
+    pub applied_updates: Counter,
+    /// Number of config updates rejected because of failed validation.
+    pub rejected_updates: Counter,
+}
+
+#[vise::register]
+pub(super) static CONFIG_SOURCE_METRICS: vise::Global<ConfigSourceMetrics> = vise::Global::new();
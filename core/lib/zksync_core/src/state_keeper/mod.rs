@@ -1,28 +1,33 @@
 use std::sync::Arc;
 
 use tokio::sync::watch;
-use zksync_config::configs::{
-    chain::{MempoolConfig, StateKeeperConfig},
-    wallets,
-};
+use zksync_config::configs::{chain::MempoolConfig, wallets};
 use zksync_dal::{ConnectionPool, Core};
 use zksync_node_fee_model::BatchFeeModelInputProvider;
 use zksync_types::L2ChainId;
 
 pub use self::{
-    batch_executor::{main_executor::MainBatchExecutor, BatchExecutor},
+    batch_executor::{
+        main_executor::MainBatchExecutor, BatchExecutor, BatchExecutorHandle, TxExecutionResult,
+    },
+    config_source::{ConfigUpdateEntry, SealParamsUpdate, StateKeeperConfigSource},
+    control::StateKeeperControl,
     io::{
-        mempool::MempoolIO, L2BlockSealerTask, OutputHandler, StateKeeperIO,
-        StateKeeperOutputHandler, StateKeeperPersistence,
+        mempool::MempoolIO, IoCursor, L2BlockParams, L2BlockSealerTask, OutputHandler,
+        StateKeeperIO, StateKeeperOutputHandler, StateKeeperPersistence,
     },
     keeper::ZkSyncStateKeeper,
     mempool_actor::MempoolFetcher,
     seal_criteria::SequencerSealer,
     state_keeper_storage::AsyncRocksdbCache,
+    tx_filter::TxFilter,
     types::MempoolGuard,
+    updates::UpdatesManager,
 };
 
 mod batch_executor;
+mod config_source;
+mod control;
 pub(crate) mod io;
 mod keeper;
 mod mempool_actor;
@@ -31,13 +36,14 @@ pub mod seal_criteria;
 mod state_keeper_storage;
 #[cfg(test)]
 pub(crate) mod tests;
+pub mod tx_filter;
 pub(crate) mod types;
 pub(crate) mod updates;
 pub(crate) mod utils;
 
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn create_state_keeper(
-    state_keeper_config: StateKeeperConfig,
+    config_source: StateKeeperConfigSource,
     wallets: wallets::StateKeeper,
     async_cache: AsyncRocksdbCache,
     l2chain_id: L2ChainId,
@@ -46,8 +52,10 @@ pub(crate) async fn create_state_keeper(
     mempool: MempoolGuard,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     output_handler: OutputHandler,
+    tx_filter: Arc<dyn TxFilter>,
     stop_receiver: watch::Receiver<bool>,
 ) -> ZkSyncStateKeeper {
+    let state_keeper_config = config_source.current();
     let batch_executor_base = MainBatchExecutor::new(
         Arc::new(async_cache),
         state_keeper_config.save_call_traces,
@@ -64,9 +72,11 @@ pub(crate) async fn create_state_keeper(
         l2chain_id,
     )
     .await
-    .expect("Failed initializing main node I/O for state keeper");
+    .expect("Failed initializing main node I/O for state keeper")
+    .with_tx_filter(tx_filter)
+    .with_config_source(&config_source);
 
-    let sealer = SequencerSealer::new(state_keeper_config);
+    let sealer = SequencerSealer::from_source(config_source);
 
     ZkSyncStateKeeper::new(
         stop_receiver,
@@ -10,6 +10,7 @@ use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_types::ProtocolVersionId;
 
 use super::{criteria, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS};
+use crate::state_keeper::config_source::StateKeeperConfigSource;
 
 /// Checks if an L1 batch should be sealed after executing a transaction.
 pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
@@ -39,9 +40,12 @@ pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
 ///
 /// The checks are deterministic, i.e., should depend solely on execution metrics and [`StateKeeperConfig`].
 /// Non-deterministic seal criteria are expressed using [`IoSealCriteria`](super::IoSealCriteria).
+///
+/// The config is read from a [`StateKeeperConfigSource`], so updates of seal params are applied
+/// to subsequent checks.
 #[derive(Debug, Default)]
 pub struct SequencerSealer {
-    config: StateKeeperConfig,
+    config: StateKeeperConfigSource,
     sealers: Vec<Box<dyn SealCriterion>>,
 }
 
@@ -51,12 +55,13 @@ impl ConditionalSealer for SequencerSealer {
         data: &SealData,
         protocol_version: ProtocolVersionId,
     ) -> Option<&'static str> {
+        let config = self.config.current();
         for sealer in &self.sealers {
             const MOCK_BLOCK_TIMESTAMP: u128 = 0;
             const TX_COUNT: usize = 1;
 
             let resolution = sealer.should_seal(
-                &self.config,
+                &config,
                 MOCK_BLOCK_TIMESTAMP,
                 TX_COUNT,
                 data,
@@ -85,10 +90,11 @@ impl ConditionalSealer for SequencerSealer {
             block_data.execution_metrics
         );
 
+        let config = self.config.current();
         let mut final_seal_resolution = SealResolution::NoSeal;
         for sealer in &self.sealers {
             let seal_resolution = sealer.should_seal(
-                &self.config,
+                &config,
                 block_open_timestamp_ms,
                 tx_count,
                 block_data,
@@ -116,7 +122,12 @@ impl ConditionalSealer for SequencerSealer {
 
 impl SequencerSealer {
     pub fn new(config: StateKeeperConfig) -> Self {
-        let sealers = Self::default_sealers(&config);
+        Self::from_source(StateKeeperConfigSource::new(config))
+    }
+
+    /// Creates a sealer reading the config from the specified source.
+    pub fn from_source(config: StateKeeperConfigSource) -> Self {
+        let sealers = Self::default_sealers(&config.current());
         Self { config, sealers }
     }
 
@@ -125,7 +136,10 @@ impl SequencerSealer {
         config: StateKeeperConfig,
         sealers: Vec<Box<dyn SealCriterion>>,
     ) -> Self {
-        Self { config, sealers }
+        Self {
+            config: StateKeeperConfigSource::new(config),
+            sealers,
+        }
     }
 
     fn default_sealers(config: &StateKeeperConfig) -> Vec<Box<dyn SealCriterion>> {
@@ -203,8 +203,9 @@ mod tests {
     use zksync_utils::time::seconds_since_epoch;
 
     use super::*;
-    use crate::state_keeper::tests::{
-        create_execution_result, create_transaction, create_updates_manager,
+    use crate::state_keeper::{
+        tests::{create_execution_result, create_transaction, create_updates_manager},
+        SealParamsUpdate, StateKeeperConfigSource,
     };
 
     fn apply_tx_to_manager(tx: Transaction, manager: &mut UpdatesManager) {
@@ -273,4 +274,23 @@ mod tests {
             "L2 block with payload encoding size equal or greater than max payload size should be sealed"
         );
     }
+
+    #[test]
+    fn sequencer_sealer_picks_up_config_updates() {
+        let config_source = StateKeeperConfigSource::new(StateKeeperConfig::for_tests());
+        let sealer = SequencerSealer::from_source(config_source.clone());
+        let data = SealData::default();
+        let should_seal = |tx_count| {
+            sealer.should_seal_l1_batch(1, 0, tx_count, &data, &data, ProtocolVersionId::latest())
+        };
+        assert_eq!(should_seal(10), SealResolution::NoSeal);
+
+        let update = SealParamsUpdate {
+            transaction_slots: Some(10),
+            ..SealParamsUpdate::default()
+        };
+        config_source.update(&update, "test").unwrap();
+        assert_eq!(should_seal(9), SealResolution::NoSeal);
+        assert_eq!(should_seal(10), SealResolution::IncludeAndSeal);
+    }
 }
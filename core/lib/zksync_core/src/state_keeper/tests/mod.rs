@@ -46,7 +46,7 @@ use crate::state_keeper::{
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
     utils::l1_batch_base_cost,
-    ZkSyncStateKeeper,
+    StateKeeperControl, ZkSyncStateKeeper,
 };
 
 pub(super) static BASE_SYSTEM_CONTRACTS: Lazy<BaseSystemContracts> =
@@ -329,6 +329,62 @@ async fn batch_sealed_before_l2_block_does() {
         .await;
 }
 
+#[tokio::test]
+async fn batch_sealed_on_request() {
+    let config = StateKeeperConfig {
+        transaction_slots: 10,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+    let control = StateKeeperControl::default();
+    // The request should not lead to sealing an empty batch.
+    control.request_l1_batch_seal();
+
+    TestScenario::new()
+        .with_control(control)
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .l2_block_sealed("L2 block with the first tx")
+        .batch_sealed("Batch sealed on request")
+        .run(sealer)
+        .await;
+}
+
+#[tokio::test]
+async fn l2_block_sealed_on_pause() {
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+    let control = StateKeeperControl::default();
+    let pausing_control = control.clone();
+    let resuming_control = control.clone();
+    let mut paused = false;
+
+    TestScenario::new()
+        .with_control(control)
+        .seal_l2_block_when(move |updates| {
+            // Pause the state keeper once the first transaction is executed.
+            if !paused && updates.l2_block.executed_transactions.len() == 1 {
+                paused = pausing_control.pause();
+            }
+            false
+        })
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .l2_block_sealed_with("L2 block sealed on pause", move |updates| {
+            assert_eq!(updates.l2_block.executed_transactions.len(), 1);
+            assert!(resuming_control.is_paused());
+            resuming_control.resume();
+        })
+        .next_tx("Second tx after resume", random_tx(2), successful_exec())
+        .l2_block_sealed("L2 block with the second tx")
+        .batch_sealed_with("Batch with both txs", |updates| {
+            assert_eq!(updates.pending_executed_transactions_len(), 2);
+        })
+        .run(sealer)
+        .await;
+}
+
 #[tokio::test]
 async fn rejected_tx() {
     let config = StateKeeperConfig {
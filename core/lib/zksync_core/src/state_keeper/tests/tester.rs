@@ -28,7 +28,7 @@ use crate::state_keeper::{
     tests::{default_l1_batch_env, default_vm_batch_result, BASE_SYSTEM_CONTRACTS},
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
-    OutputHandler, StateKeeperOutputHandler, ZkSyncStateKeeper,
+    OutputHandler, StateKeeperControl, StateKeeperOutputHandler, ZkSyncStateKeeper,
 };
 
 const FEE_ACCOUNT: Address = Address::repeat_byte(0x11);
@@ -50,6 +50,7 @@ pub(crate) struct TestScenario {
     pending_batch: Option<PendingBatchData>,
     l1_batch_seal_fn: Box<SealFn>,
     l2_block_seal_fn: Box<SealFn>,
+    control: StateKeeperControl,
 }
 
 type SealFn = dyn FnMut(&UpdatesManager) -> bool + Send;
@@ -71,9 +72,16 @@ impl TestScenario {
             pending_batch: None,
             l1_batch_seal_fn: Box::new(|_| false),
             l2_block_seal_fn: Box::new(|_| false),
+            control: StateKeeperControl::default(),
         }
     }
 
+    /// Sets the control handle for the state keeper.
+    pub(crate) fn with_control(mut self, control: StateKeeperControl) -> Self {
+        self.control = control;
+        self
+    }
+
     /// Adds a pending batch data that would be fed into the state keeper.
     /// Note that during processing pending batch, state keeper do *not* call `seal_l2_block` method on the IO (since
     /// it only recovers the temporary state).
@@ -192,6 +200,7 @@ impl TestScenario {
         assert!(!self.actions.is_empty(), "Test scenario can't be empty");
 
         let batch_executor_base = TestBatchExecutorBuilder::new(&self);
+        let control = self.control.clone();
         let (stop_sender, stop_receiver) = watch::channel(false);
         let (io, output_handler) = TestIO::new(stop_sender, self);
         let state_keeper = ZkSyncStateKeeper::new(
@@ -200,7 +209,8 @@ impl TestScenario {
             Box::new(batch_executor_base),
             output_handler,
             Arc::new(sealer),
-        );
+        )
+        .with_control(control);
         let sk_thread = tokio::spawn(state_keeper.run());
 
         // We must assume that *theoretically* state keeper may ignore the stop signal from IO once scenario is
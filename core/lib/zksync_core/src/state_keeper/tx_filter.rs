@@ -0,0 +1,355 @@
+//! Operator-defined filtering of L2 transactions.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::RwLock,
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use serde::{de, Deserialize, Deserializer};
+use tokio::sync::watch;
+use zksync_types::{Address, Transaction};
+
+/// Filter for L2 transactions that allows the operator to reject transactions before they are executed.
+///
+/// The filter is consulted both when a transaction is submitted via the API (in which case the submission fails
+/// with a specific error), and when a transaction is fetched from the mempool by the state keeper (in which case
+/// the transaction is rejected). The latter ensures that transactions accepted before a filter update
+/// are not executed. L1 transactions are never passed to the filter.
+pub trait TxFilter: fmt::Debug + Send + Sync + 'static {
+    /// Checks whether the transaction should be accepted. Returns a human-readable reason if it should be rejected.
+    fn check_tx(&self, tx: &Transaction) -> Result<(), String>;
+}
+
+/// Filter accepting all transactions.
+#[derive(Debug, Clone, Copy)]
+pub struct NoopTxFilter;
+
+impl TxFilter for NoopTxFilter {
+    fn check_tx(&self, _tx: &Transaction) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// 4-byte function selector. Deserialized from a hex string, e.g. `"0xa9059cbb"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FunctionSelector(pub [u8; 4]);
+
+impl FunctionSelector {
+    /// Extracts the selector from transaction calldata. Returns `None` if the calldata is too short
+    /// (e.g., for plain transfers).
+    pub fn from_calldata(calldata: &[u8]) -> Option<Self> {
+        let selector = calldata.get(..4)?;
+        Some(Self(selector.try_into().unwrap()))
+    }
+}
+
+impl fmt::Display for FunctionSelector {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "0x{}", hex::encode(self.0))
+    }
+}
+
+impl FromStr for FunctionSelector {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s.strip_prefix("0x").unwrap_or(s)).context("invalid hex")?;
+        let bytes: [u8; 4] = bytes
+            .try_into()
+            .map_err(|bytes: Vec<u8>| anyhow::anyhow!("expected 4 bytes, got {}", bytes.len()))?;
+        Ok(Self(bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for FunctionSelector {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|err| de::Error::custom(format!("invalid function selector `{s}`: {err:#}")))
+    }
+}
+
+/// Filtering rules. A transaction is accepted if none of the deny-lists and all of the specified allow-lists match it.
+/// Allow-lists that are not specified (as opposed to empty ones) don't restrict transactions.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TxFilterRules {
+    /// Transaction initiators that are denied.
+    pub denied_senders: HashSet<Address>,
+    /// If specified, only transactions from these initiators are accepted.
+    pub allowed_senders: Option<HashSet<Address>>,
+    /// Target contracts that are denied.
+    pub denied_contracts: HashSet<Address>,
+    /// If specified, only transactions targeting these contracts are accepted.
+    pub allowed_contracts: Option<HashSet<Address>>,
+    /// Function selectors that are denied. Transactions without a selector (i.e., with calldata shorter
+    /// than 4 bytes) are not subject to selector lists.
+    pub denied_selectors: HashSet<FunctionSelector>,
+    /// If specified, only transactions calling functions with these selectors are accepted.
+    pub allowed_selectors: Option<HashSet<FunctionSelector>>,
+}
+
+impl TxFilterRules {
+    fn is_accepted<T: Eq + std::hash::Hash>(
+        value: &T,
+        denied: &HashSet<T>,
+        allowed: Option<&HashSet<T>>,
+    ) -> bool {
+        !denied.contains(value) && allowed.map_or(true, |allowed| allowed.contains(value))
+    }
+}
+
+/// Rules can be used as a static filter.
+impl TxFilter for TxFilterRules {
+    fn check_tx(&self, tx: &Transaction) -> Result<(), String> {
+        let sender = tx.initiator_account();
+        if !Self::is_accepted(&sender, &self.denied_senders, self.allowed_senders.as_ref()) {
+            return Err(format!("sender {sender:?} is not allowed"));
+        }
+
+        let contract = tx.recipient_account();
+        if !Self::is_accepted(
+            &contract,
+            &self.denied_contracts,
+            self.allowed_contracts.as_ref(),
+        ) {
+            return Err(format!("calling contract {contract:?} is not allowed"));
+        }
+
+        if let Some(selector) = FunctionSelector::from_calldata(&tx.execute.calldata) {
+            if !Self::is_accepted(
+                &selector,
+                &self.denied_selectors,
+                self.allowed_selectors.as_ref(),
+            ) {
+                return Err(format!("calling function {selector} is not allowed"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct LoadedRules {
+    raw: String,
+    rules: TxFilterRules,
+}
+
+/// [`TxFilter`] using [rules](TxFilterRules) loaded from a JSON file. The file can be changed while the node
+/// is running; changes are picked up by [`Self::run_reloader()`].
+#[derive(Debug)]
+pub struct ConfigTxFilter {
+    path: PathBuf,
+    loaded: RwLock<LoadedRules>,
+}
+
+impl ConfigTxFilter {
+    /// Creates a filter loading rules from the specified file.
+    pub async fn new(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let loaded = Self::load(&path).await?;
+        tracing::info!(
+            "Loaded transaction filter rules from `{}`: {:?}",
+            path.display(),
+            loaded.rules
+        );
+        Ok(Self {
+            path,
+            loaded: RwLock::new(loaded),
+        })
+    }
+
+    async fn load(path: &Path) -> anyhow::Result<LoadedRules> {
+        let raw = tokio::fs::read_to_string(path).await.with_context(|| {
+            format!(
+                "failed reading transaction filter rules from `{}`",
+                path.display()
+            )
+        })?;
+        let rules = serde_json::from_str(&raw).with_context(|| {
+            format!(
+                "failed parsing transaction filter rules from `{}`",
+                path.display()
+            )
+        })?;
+        Ok(LoadedRules { raw, rules })
+    }
+
+    /// Returns currently used rules.
+    pub fn rules(&self) -> TxFilterRules {
+        self.loaded.read().unwrap().rules.clone()
+    }
+
+    /// Reloads rules from the file if it has changed. Returns `true` if the rules were reloaded.
+    /// If the file cannot be read or parsed, previously loaded rules are retained.
+    pub async fn reload(&self) -> anyhow::Result<bool> {
+        let loaded = Self::load(&self.path).await?;
+        if loaded.raw == self.loaded.read().unwrap().raw {
+            return Ok(false);
+        }
+        tracing::info!(
+            "Reloaded transaction filter rules from `{}`: {:?}",
+            self.path.display(),
+            loaded.rules
+        );
+        *self.loaded.write().unwrap() = loaded;
+        Ok(true)
+    }
+
+    /// Periodically reloads rules from the file until a stop signal is received.
+    pub async fn run_reloader(
+        &self,
+        reload_interval: Duration,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            if let Err(err) = self.reload().await {
+                tracing::warn!(
+                    "Failed reloading transaction filter rules, keeping the previous ones: {err:#}"
+                );
+            }
+
+            if tokio::time::timeout(reload_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, transaction filter reloader is shutting down");
+        Ok(())
+    }
+}
+
+impl TxFilter for ConfigTxFilter {
+    fn check_tx(&self, tx: &Transaction) -> Result<(), String> {
+        self.loaded.read().unwrap().rules.check_tx(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{l2::L2Tx, Nonce};
+
+    use super::*;
+
+    fn create_tx(sender: Address, contract: Address, calldata: Vec<u8>) -> Transaction {
+        let mut tx = L2Tx::new(
+            contract,
+            calldata,
+            Nonce(0),
+            Default::default(),
+            sender,
+            0.into(),
+            None,
+            Default::default(),
+        );
+        tx.set_input(vec![], Default::default());
+        tx.into()
+    }
+
+    #[test]
+    fn parsing_rules() {
+        let raw = r#"{
+            "denied_senders": ["0x0000000000000000000000000000000000000001"],
+            "allowed_contracts": ["0x0000000000000000000000000000000000000002"],
+            "denied_selectors": ["0xa9059cbb"]
+        }"#;
+        let rules: TxFilterRules = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            rules.denied_senders,
+            HashSet::from([Address::from_low_u64_be(1)])
+        );
+        assert_eq!(
+            rules.allowed_contracts,
+            Some(HashSet::from([Address::from_low_u64_be(2)]))
+        );
+        assert_eq!(rules.allowed_senders, None);
+        assert_eq!(
+            rules.denied_selectors,
+            HashSet::from([FunctionSelector([0xa9, 0x05, 0x9c, 0xbb])])
+        );
+
+        let err = serde_json::from_str::<TxFilterRules>(r#"{ "denied_selectors": ["0x1234"] }"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("expected 4 bytes"), "{err}");
+        serde_json::from_str::<TxFilterRules>(r#"{ "unknown_list": [] }"#).unwrap_err();
+    }
+
+    #[test]
+    fn filtering_transactions() {
+        let sender = Address::repeat_byte(1);
+        let contract = Address::repeat_byte(2);
+        let selector = FunctionSelector([1, 2, 3, 4]);
+        let calldata = vec![1, 2, 3, 4, 5];
+        let tx = create_tx(sender, contract, calldata.clone());
+
+        TxFilterRules::default().check_tx(&tx).unwrap();
+
+        let rules = TxFilterRules {
+            denied_senders: HashSet::from([sender]),
+            ..TxFilterRules::default()
+        };
+        let err = rules.check_tx(&tx).unwrap_err();
+        assert!(err.contains("sender"), "{err}");
+
+        let rules = TxFilterRules {
+            allowed_contracts: Some(HashSet::from([Address::repeat_byte(3)])),
+            ..TxFilterRules::default()
+        };
+        let err = rules.check_tx(&tx).unwrap_err();
+        assert!(err.contains("contract"), "{err}");
+        let rules = TxFilterRules {
+            allowed_contracts: Some(HashSet::from([contract])),
+            ..TxFilterRules::default()
+        };
+        rules.check_tx(&tx).unwrap();
+
+        let rules = TxFilterRules {
+            denied_selectors: HashSet::from([selector]),
+            ..TxFilterRules::default()
+        };
+        let err = rules.check_tx(&tx).unwrap_err();
+        assert!(err.contains("0x01020304"), "{err}");
+        // Transactions without a selector are not subject to selector lists.
+        rules
+            .check_tx(&create_tx(sender, contract, vec![]))
+            .unwrap();
+
+        let rules = TxFilterRules {
+            allowed_selectors: Some(HashSet::new()),
+            ..TxFilterRules::default()
+        };
+        rules.check_tx(&tx).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn reloading_rules() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("tx_filter.json");
+        tokio::fs::write(&path, "{}").await.unwrap();
+        let filter = ConfigTxFilter::new(&path).await.unwrap();
+        assert_eq!(filter.rules(), TxFilterRules::default());
+        assert!(!filter.reload().await.unwrap());
+
+        let sender = Address::repeat_byte(1);
+        let tx = create_tx(sender, Address::repeat_byte(2), vec![]);
+        filter.check_tx(&tx).unwrap();
+
+        let raw = format!(r#"{{ "denied_senders": ["{sender:?}"] }}"#);
+        tokio::fs::write(&path, raw).await.unwrap();
+        assert!(filter.reload().await.unwrap());
+        filter.check_tx(&tx).unwrap_err();
+
+        // Malformed rules should not be applied.
+        tokio::fs::write(&path, "{ ").await.unwrap();
+        filter.reload().await.unwrap_err();
+        filter.check_tx(&tx).unwrap_err();
+        assert_eq!(filter.rules().denied_senders, HashSet::from([sender]));
+    }
+}
@@ -7,7 +7,12 @@ use multivm::interface::VmExecutionResultAndLogs;
 use zksync_dal::{Connection, Core, CoreDal};
 use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
+    api,
+    block::BlockGasCount,
+    l1::L1Tx,
+    l2::{L2Tx, L2TxCommonData},
+    tx::ExecutionMetrics,
+    Address, Nonce, PriorityOpId, Transaction, H256,
 };
 
 use super::{
@@ -67,6 +72,47 @@ impl MempoolGuard {
             .get_mempool_info()
     }
 
+    /// Removes an L2 transaction with the specified hash from the mempool. Returns `true` if the transaction was present
+    /// in the mempool.
+    ///
+    /// The caller is responsible for ensuring that the transaction won't be re-added to the mempool
+    /// (e.g., by marking it as rejected in Postgres).
+    pub fn remove_transaction(&mut self, hash: H256) -> bool {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .remove_l2_transaction(hash)
+            .is_some()
+    }
+
+    /// Returns the summary of the mempool contents including at most `limit` oldest transactions.
+    pub fn contents(&self, limit: usize) -> api::MempoolContents {
+        let mempool = self.0.lock().expect("failed to acquire mempool lock");
+        let stats = mempool.stats();
+        // Only select and copy the oldest transactions while holding the lock; sorting is performed after it's released.
+        let mut transactions: Vec<_> = mempool
+            .l1_transactions()
+            .map(MempoolTxRef::L1)
+            .chain(mempool.l2_transactions().map(MempoolTxRef::L2))
+            .collect();
+        if transactions.len() > limit {
+            if limit > 0 {
+                transactions
+                    .select_nth_unstable_by_key(limit - 1, MempoolTxRef::received_timestamp_ms);
+            }
+            transactions.truncate(limit);
+        }
+        let mut transactions: Vec<_> = transactions.into_iter().map(MempoolTxRef::to_api).collect();
+        drop(mempool);
+
+        transactions.sort_unstable_by_key(|tx| tx.received_timestamp_ms);
+        api::MempoolContents {
+            l1_transaction_count: stats.l1_transaction_count,
+            l2_transaction_count: stats.l2_transaction_count,
+            transactions,
+        }
+    }
+
     #[cfg(test)]
     pub fn stats(&self) -> zksync_mempool::MempoolStats {
         self.0
@@ -80,6 +126,53 @@ impl MempoolGuard {
     }
 }
 
+/// Reference to a transaction in the mempool.
+#[derive(Debug, Clone, Copy)]
+enum MempoolTxRef<'a> {
+    L1(&'a L1Tx),
+    L2(&'a L2Tx),
+}
+
+impl MempoolTxRef<'_> {
+    fn received_timestamp_ms(&self) -> u64 {
+        match self {
+            Self::L1(tx) => tx.received_timestamp_ms,
+            Self::L2(tx) => tx.received_timestamp_ms,
+        }
+    }
+
+    fn to_api(self) -> api::MempoolTransaction {
+        match self {
+            Self::L1(tx) => api::MempoolTransaction {
+                hash: tx.hash(),
+                initiator_address: tx.common_data.sender,
+                nonce: None,
+                priority_op_id: Some(tx.serial_id()),
+                max_fee_per_gas: tx.common_data.max_fee_per_gas,
+                gas_per_pubdata_limit: tx.common_data.gas_per_pubdata_limit,
+                received_timestamp_ms: tx.received_timestamp_ms,
+            },
+            Self::L2(tx) => {
+                let L2TxCommonData {
+                    nonce,
+                    fee,
+                    initiator_address,
+                    ..
+                } = &tx.common_data;
+                api::MempoolTransaction {
+                    hash: tx.hash(),
+                    initiator_address: *initiator_address,
+                    nonce: Some(*nonce),
+                    priority_op_id: None,
+                    max_fee_per_gas: fee.max_fee_per_gas,
+                    gas_per_pubdata_limit: fee.gas_per_pubdata_limit,
+                    received_timestamp_ms: tx.received_timestamp_ms,
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ExecutionMetricsForCriteria {
     pub l1_gas: BlockGasCount,
@@ -41,7 +41,7 @@ pub struct UpdatesManager {
 }
 
 impl UpdatesManager {
-    pub(crate) fn new(l1_batch_env: &L1BatchEnv, system_env: &SystemEnv) -> Self {
+    pub fn new(l1_batch_env: &L1BatchEnv, system_env: &SystemEnv) -> Self {
         let protocol_version = system_env.version;
         Self {
             batch_timestamp: l1_batch_env.timestamp,
@@ -70,7 +70,7 @@ impl UpdatesManager {
         self.base_system_contract_hashes
     }
 
-    pub(crate) fn io_cursor(&self) -> IoCursor {
+    pub fn io_cursor(&self) -> IoCursor {
         IoCursor {
             next_l2_block: self.l2_block.number + 1,
             prev_l2_block_hash: self.l2_block.get_l2_block_hash(),
@@ -98,11 +98,11 @@ impl UpdatesManager {
         }
     }
 
-    pub(crate) fn protocol_version(&self) -> ProtocolVersionId {
+    pub fn protocol_version(&self) -> ProtocolVersionId {
         self.protocol_version
     }
 
-    pub(crate) fn extend_from_executed_transaction(
+    pub fn extend_from_executed_transaction(
         &mut self,
         tx: Transaction,
         tx_execution_result: VmExecutionResultAndLogs,
@@ -123,7 +123,7 @@ impl UpdatesManager {
         );
     }
 
-    pub(crate) fn finish_batch(&mut self, finished_batch: FinishedL1Batch) {
+    pub fn finish_batch(&mut self, finished_batch: FinishedL1Batch) {
         assert!(
             self.l1_batch.finished.is_none(),
             "Cannot finish already finished batch"
@@ -148,7 +148,7 @@ impl UpdatesManager {
 
     /// Pushes a new L2 block with the specified timestamp into this manager. The previously
     /// held L2 block is considered sealed and is used to extend the L1 batch data.
-    pub(crate) fn push_l2_block(&mut self, l2_block_params: L2BlockParams) {
+    pub fn push_l2_block(&mut self, l2_block_params: L2BlockParams) {
         let new_l2_block_updates = L2BlockUpdates::new(
             l2_block_params.timestamp,
             self.l2_block.number + 1,
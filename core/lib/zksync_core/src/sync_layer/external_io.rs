@@ -34,34 +34,36 @@ use crate::state_keeper::{
 #[derive(Debug)]
 pub struct ExternalIO {
     pool: ConnectionPool<Core>,
-    l1_batch_params_provider: L1BatchParamsProvider,
+    /// Initialized lazily in [`StateKeeperIO::initialize()`], so that the IO can be created before
+    /// Postgres is initialized (e.g., before genesis or snapshot recovery is performed).
+    l1_batch_params_provider: Option<L1BatchParamsProvider>,
     actions: ActionQueue,
     main_node_client: Box<dyn MainNodeClient>,
     chain_id: L2ChainId,
 }
 
 impl ExternalIO {
-    pub async fn new(
+    pub fn new(
         pool: ConnectionPool<Core>,
         actions: ActionQueue,
         main_node_client: Box<dyn MainNodeClient>,
         chain_id: L2ChainId,
     ) -> anyhow::Result<Self> {
-        let mut storage = pool.connection_tagged("sync_layer").await?;
-        let l1_batch_params_provider = L1BatchParamsProvider::new(&mut storage)
-            .await
-            .context("failed initializing L1 batch params provider")?;
-        drop(storage);
-
         Ok(Self {
             pool,
-            l1_batch_params_provider,
+            l1_batch_params_provider: None,
             actions,
             main_node_client,
             chain_id,
         })
     }
 
+    fn l1_batch_params_provider(&self) -> anyhow::Result<&L1BatchParamsProvider> {
+        self.l1_batch_params_provider
+            .as_ref()
+            .context("ExternalIO is not initialized")
+    }
+
     async fn get_base_system_contract(
         &self,
         hash: H256,
@@ -134,6 +136,10 @@ impl StateKeeperIO for ExternalIO {
 
     async fn initialize(&mut self) -> anyhow::Result<(IoCursor, Option<PendingBatchData>)> {
         let mut storage = self.pool.connection_tagged("sync_layer").await?;
+        let l1_batch_params_provider = L1BatchParamsProvider::new(&mut storage)
+            .await
+            .context("failed initializing L1 batch params provider")?;
+        self.l1_batch_params_provider = Some(l1_batch_params_provider);
         let cursor = IoCursor::new(&mut storage).await?;
         tracing::info!(
             "Initialized the ExternalIO: current L1 batch number {}, current L2 block number {}",
@@ -142,7 +148,7 @@ impl StateKeeperIO for ExternalIO {
         );
 
         let pending_l2_block_header = self
-            .l1_batch_params_provider
+            .l1_batch_params_provider()?
             .load_first_l2_block_in_batch(&mut storage, cursor.l1_batch)
             .await
             .with_context(|| {
@@ -184,7 +190,7 @@ impl StateKeeperIO for ExternalIO {
         }
 
         let (system_env, l1_batch_env) = self
-            .l1_batch_params_provider
+            .l1_batch_params_provider()?
             .load_l1_batch_params(
                 &mut storage,
                 &pending_l2_block_header,
@@ -373,7 +379,7 @@ impl StateKeeperIO for ExternalIO {
         number: L1BatchNumber,
     ) -> anyhow::Result<ProtocolVersionId> {
         let mut storage = self.pool.connection_tagged("sync_layer").await?;
-        self.l1_batch_params_provider
+        self.l1_batch_params_provider()?
             .load_l1_batch_protocol_version(&mut storage, number)
             .await
             .with_context(|| format!("failed loading protocol version for L1 batch #{number}"))?
@@ -396,7 +402,7 @@ impl StateKeeperIO for ExternalIO {
         let mut storage = self.pool.connection_tagged("sync_layer").await?;
         let wait_latency = KEEPER_METRICS.wait_for_prev_hash_time.start();
         let (hash, _) = self
-            .l1_batch_params_provider
+            .l1_batch_params_provider()?
             .wait_for_l1_batch_params(&mut storage, l1_batch_number)
             .await
             .with_context(|| format!("error waiting for params for L1 batch #{l1_batch_number}"))?;
@@ -17,7 +17,7 @@ use zksync_types::{
     aggregated_operations::AggregatedActionType,
     ethabi::Token,
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotMetadata, SnapshotStorageLogsChunk,
+        SnapshotFactoryDependencies, SnapshotManifest, SnapshotMetadata, SnapshotStorageLogsChunk,
         SnapshotStorageLogsStorageKey,
     },
     web3::BlockNumber,
@@ -416,6 +416,22 @@ impl BlockReverter {
                 });
             combine_results(&mut overall_result, result);
 
+            tracing::info!(
+                "Removing manifest for snapshot for L1 batch #{}",
+                snapshot.l1_batch_number
+            );
+            let result = object_store
+                .remove::<SnapshotManifest>(snapshot.l1_batch_number)
+                .await
+                .or_else(ignore_not_found_errors)
+                .with_context(|| {
+                    format!(
+                        "failed removing manifest for snapshot for L1 batch #{}",
+                        snapshot.l1_batch_number
+                    )
+                });
+            combine_results(&mut overall_result, result);
+
             for chunk_id in 0..snapshot.storage_logs_filepaths.len() as u64 {
                 let key = SnapshotStorageLogsStorageKey {
                     l1_batch_number: snapshot.l1_batch_number,
@@ -217,6 +217,7 @@ async fn create_mock_snapshot(
         .add_snapshot(
             SnapshotVersion::Version0,
             l1_batch_number,
+            None,
             storage_logs_chunk_count,
             &factory_deps_key,
         )
@@ -243,6 +244,28 @@ async fn create_mock_snapshot(
             .await
             .unwrap();
     }
+
+    let empty_chunk = SnapshotStorageLogsChunk {
+        storage_logs: vec![],
+    };
+    let manifest = SnapshotManifest {
+        version: SnapshotVersion::Version0.into(),
+        l1_batch_number,
+        l2_block_number: L2BlockNumber(l1_batch_number.0),
+        base_l1_batch_number: None,
+        expected_root_hash: H256::zero(),
+        factory_deps: SnapshotFactoryDependencies {
+            factory_deps: vec![],
+        }
+        .digest(),
+        storage_logs_chunks: vec![empty_chunk.digest(); storage_logs_chunk_count as usize],
+        manifest_hash: H256::zero(),
+        signature: None,
+    };
+    object_store
+        .put(l1_batch_number, &manifest.seal())
+        .await
+        .unwrap();
 }
 
 #[test_casing(2, [false, true])]
@@ -290,6 +313,16 @@ async fn reverting_snapshot(remove_objects: bool) {
         factory_deps_result.unwrap();
     }
 
+    let manifest_result = object_store.get::<SnapshotManifest>(L1BatchNumber(7)).await;
+    if remove_objects {
+        assert_matches!(
+            manifest_result.unwrap_err(),
+            ObjectStoreError::KeyNotFound(_)
+        );
+    } else {
+        manifest_result.unwrap();
+    }
+
     for chunk_id in 0..5 {
         let key = SnapshotStorageLogsStorageKey {
             l1_batch_number: L1BatchNumber(7),
@@ -1,11 +1,18 @@
 //! Postgres pruning component.
 
-use std::{fmt, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
 use serde::Serialize;
 use tokio::sync::watch;
+use zksync_config::configs::PruningConfig;
+pub use zksync_dal::pruning_dal::PrunedDataClass;
 use zksync_dal::{pruning_dal::PruningInfo, Connection, ConnectionPool, Core, CoreDal};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::{L1BatchNumber, L2BlockNumber};
@@ -14,6 +21,7 @@ use self::{
     metrics::{MetricPruneType, METRICS},
     prune_conditions::{
         ConsistencyCheckerProcessedBatch, L1BatchExistsCondition, L1BatchOlderThanPruneCondition,
+        L1BatchRetainedCountCondition, L1BatchSnapshotCreatedCondition,
         NextL1BatchHasMetadataCondition, NextL1BatchWasExecutedCondition,
     },
 };
@@ -23,6 +31,17 @@ mod prune_conditions;
 #[cfg(test)]
 mod tests;
 
+/// Kind of the node the pruner runs on. Determines the set of conditions an L1 batch must satisfy to be pruned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DbPrunerMode {
+    /// An L1 batch must be executed on L1 and processed by the consistency checker.
+    #[default]
+    ExternalNode,
+    /// An L1 batch must be executed (and thus proven) on L1 and must be covered by a snapshot, so that
+    /// new external nodes can still recover from the main node.
+    MainNode,
+}
+
 /// Configuration
 #[derive(Debug)]
 pub struct DbPrunerConfig {
@@ -34,6 +53,62 @@ pub struct DbPrunerConfig {
     /// Minimum age of an L1 batch in order for it to be eligible for pruning. Setting this to zero
     /// will effectively disable this pruning criterion.
     pub minimum_l1_batch_age: Duration,
+    /// Minimum number of the latest sealed L1 batches that are never pruned. Setting this to zero
+    /// will effectively disable this pruning criterion.
+    pub minimum_retained_l1_batches: u32,
+    /// Node kind the pruner runs on.
+    pub mode: DbPrunerMode,
+    /// If set, the pruner doesn't remove any data; instead, it periodically reports the range of L1 batches
+    /// and L2 blocks that would be pruned.
+    pub dry_run: bool,
+    /// Retention periods for data classes that should be pruned earlier than the rest of L2 block data.
+    /// Data of a class is pruned for L2 blocks older than the retention period, regardless of other
+    /// pruning conditions.
+    pub data_class_retention: HashMap<PrunedDataClass, Duration>,
+}
+
+impl DbPrunerConfig {
+    /// Creates the pruner configuration for the main node.
+    pub fn for_main_node(config: &PruningConfig) -> Self {
+        let data_class_retention = [
+            (
+                PrunedDataClass::CallTraces,
+                config.call_traces_retention_sec,
+            ),
+            (PrunedDataClass::Events, config.events_retention_sec),
+            (
+                PrunedDataClass::TransactionPayloads,
+                config.transaction_payloads_retention_sec,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(class, retention_sec)| Some((class, Duration::from_secs(retention_sec?))))
+        .collect();
+
+        Self {
+            removal_delay: config.removal_delay(),
+            pruned_batch_chunk_size: config.chunk_size,
+            minimum_l1_batch_age: config.data_retention(),
+            minimum_retained_l1_batches: config.retained_l1_batches,
+            mode: DbPrunerMode::MainNode,
+            dry_run: config.dry_run,
+            data_class_retention,
+        }
+    }
+}
+
+/// Data that would be pruned by the pruner in the dry-run mode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct DryRunReport {
+    /// Inclusive range of L1 batches that would be pruned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    l1_batches: Option<(L1BatchNumber, L1BatchNumber)>,
+    /// Inclusive range of L2 blocks that would be pruned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    l2_blocks: Option<(L2BlockNumber, L2BlockNumber)>,
+    /// Inclusive ranges of L2 blocks for which data of a certain class would be pruned, keyed by the class name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    data_classes: BTreeMap<&'static str, (L2BlockNumber, L2BlockNumber)>,
 }
 
 #[derive(Debug, Serialize)]
@@ -46,6 +121,8 @@ struct DbPrunerHealth {
     last_hard_pruned_l1_batch: Option<L1BatchNumber>,
     #[serde(skip_serializing_if = "Option::is_none")]
     last_hard_pruned_l2_block: Option<L2BlockNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dry_run: Option<DryRunReport>,
 }
 
 impl From<PruningInfo> for DbPrunerHealth {
@@ -55,6 +132,7 @@ impl From<PruningInfo> for DbPrunerHealth {
             last_soft_pruned_l2_block: info.last_soft_pruned_l2_block,
             last_hard_pruned_l1_batch: info.last_hard_pruned_l1_batch,
             last_hard_pruned_l2_block: info.last_hard_pruned_l2_block,
+            dry_run: None,
         }
     }
 }
@@ -86,10 +164,25 @@ impl DbPruner {
             Arc::new(NextL1BatchWasExecutedCondition {
                 conn: connection_pool.clone(),
             }),
-            Arc::new(ConsistencyCheckerProcessedBatch {
-                conn: connection_pool.clone(),
-            }),
         ];
+        match config.mode {
+            DbPrunerMode::ExternalNode => {
+                conditions.push(Arc::new(ConsistencyCheckerProcessedBatch {
+                    conn: connection_pool.clone(),
+                }));
+            }
+            DbPrunerMode::MainNode => {
+                conditions.push(Arc::new(L1BatchSnapshotCreatedCondition {
+                    conn: connection_pool.clone(),
+                }));
+            }
+        }
+        if config.minimum_retained_l1_batches > 0 {
+            conditions.push(Arc::new(L1BatchRetainedCountCondition {
+                retained_count: config.minimum_retained_l1_batches,
+                conn: connection_pool.clone(),
+            }));
+        }
         if config.minimum_l1_batch_age > Duration::ZERO {
             // Do not add a condition if it's trivial in order to not clutter logs.
             conditions.push(Arc::new(L1BatchOlderThanPruneCondition {
@@ -245,26 +338,307 @@ impl DbPruner {
         Ok(())
     }
 
+    /// Soft-prunes data classes with configured retention. Returns `true` if there is soft-pruned data
+    /// that should be hard-pruned.
+    async fn soft_prune_data_classes(
+        &self,
+        storage: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<bool> {
+        let pruning_info = storage
+            .pruning_dal()
+            .get_data_classes_pruning_info()
+            .await?;
+
+        let mut has_data_to_hard_prune = false;
+        for class in PrunedDataClass::ALL {
+            let mut class_info = pruning_info.get(class);
+            let last_l2_block = self
+                .data_class_pruning_target(storage, class)
+                .await?
+                .filter(|&number| Some(number) > class_info.last_soft_pruned_l2_block);
+            if let Some(last_l2_block) = last_l2_block {
+                storage
+                    .pruning_dal()
+                    .soft_prune_data_class(class, last_l2_block)
+                    .await?;
+                tracing::info!(
+                    "Soft pruned `{}` data up to L2 block {last_l2_block}",
+                    class.as_str()
+                );
+                class_info.last_soft_pruned_l2_block = Some(last_l2_block);
+            }
+
+            if let Some(last_l2_block) = class_info.last_soft_pruned_l2_block {
+                METRICS.data_class_soft_pruned_l2_block[&class.as_str()]
+                    .set(last_l2_block.0.into());
+            }
+            // Hard-prune data even for classes without configured retention, so that pruning started
+            // before a config change is completed.
+            has_data_to_hard_prune |=
+                class_info.last_soft_pruned_l2_block > class_info.last_hard_pruned_l2_block;
+        }
+        Ok(has_data_to_hard_prune)
+    }
+
+    /// Returns the last L2 block for which data of the specified class should be pruned according to its configured
+    /// retention, capped at [`Self::max_data_class_l2_block()`]. Returns `None` if the class has no configured retention.
+    async fn data_class_pruning_target(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        class: PrunedDataClass,
+    ) -> anyhow::Result<Option<L2BlockNumber>> {
+        let Some(&retention) = self.config.data_class_retention.get(&class) else {
+            return Ok(None);
+        };
+        let max_l2_block_to_prune = self.max_data_class_l2_block(storage, class).await?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("current time is before UNIX epoch")?;
+        let cutoff_timestamp = now.saturating_sub(retention).as_secs();
+        let last_l2_block = storage
+            .blocks_dal()
+            .get_last_l2_block_before_timestamp(cutoff_timestamp)
+            .await?;
+        Ok(last_l2_block
+            .zip(max_l2_block_to_prune)
+            .map(|(number, max_number)| number.min(max_number)))
+    }
+
+    /// Returns the last L2 block for which data of the specified class may be pruned. Data classes are never pruned past
+    /// the last L1 batch executed on L1, since data for non-executed L1 batches may still be required by the node
+    /// (e.g., to re-execute the batches). Transaction payloads are additionally retained for L1 batches not processed
+    /// by all VM runners, since VM runners re-execute L1 batches.
+    async fn max_data_class_l2_block(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        class: PrunedDataClass,
+    ) -> anyhow::Result<Option<L2BlockNumber>> {
+        let Some(mut last_l1_batch) = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await?
+        else {
+            return Ok(None);
+        };
+        if class == PrunedDataClass::TransactionPayloads {
+            let first_unprocessed_l1_batch = storage
+                .vm_runner_dal()
+                .get_first_unprocessed_l1_batch()
+                .await?;
+            if let Some(first_unprocessed_l1_batch) = first_unprocessed_l1_batch {
+                let Some(last_processed_l1_batch) = first_unprocessed_l1_batch.0.checked_sub(1)
+                else {
+                    return Ok(None);
+                };
+                last_l1_batch = last_l1_batch.min(L1BatchNumber(last_processed_l1_batch));
+            }
+        }
+
+        let l2_block_range = storage
+            .blocks_dal()
+            .get_l2_block_range_of_l1_batch(last_l1_batch)
+            .await?;
+        Ok(l2_block_range.map(|(_, last_l2_block)| last_l2_block))
+    }
+
+    /// Hard-prunes soft-pruned data classes. Data is pruned in chunks of `pruned_batch_chunk_size` L2 blocks,
+    /// with each chunk pruned in a separate transaction, so that enabling pruning for a class on a long-running node
+    /// doesn't result in a single huge DB operation.
+    async fn hard_prune_data_classes(
+        &self,
+        storage: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<()> {
+        for class in PrunedDataClass::ALL {
+            loop {
+                let mut transaction = storage.start_transaction().await?;
+                let stats = transaction
+                    .pruning_dal()
+                    .hard_prune_data_class(class, self.config.pruned_batch_chunk_size)
+                    .await?;
+                transaction.commit().await?;
+
+                if let Some(last_l2_block) = stats.last_pruned_l2_block {
+                    tracing::info!(
+                        "Hard pruned `{}` data up to L2 block {last_l2_block}, affected {} rows",
+                        class.as_str(),
+                        stats.affected_rows
+                    );
+                    METRICS.data_class_affected_rows[&class.as_str()].observe(stats.affected_rows);
+                }
+                if !stats.has_more {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Finds the range of data that would be pruned given the current state of the node. Relies on prune conditions
+    /// being monotonic: if an L1 batch is prunable, all preceding batches are prunable as well.
+    async fn dry_run(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        current_pruning_info: &PruningInfo,
+    ) -> anyhow::Result<Option<DryRunReport>> {
+        let Some(earliest_l1_batch) = storage.blocks_dal().get_earliest_l1_batch_number().await?
+        else {
+            return Ok(None);
+        };
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .context("unreachable DB state: there's an earliest L1 batch, but no latest one")?;
+        let (first_l1_batch, first_l2_block) = match (
+            current_pruning_info.last_soft_pruned_l1_batch,
+            current_pruning_info.last_soft_pruned_l2_block,
+        ) {
+            (Some(l1_batch), Some(l2_block)) => (l1_batch + 1, l2_block + 1),
+            _ => {
+                let (first_l2_block, _) = storage
+                    .blocks_dal()
+                    .get_l2_block_range_of_l1_batch(earliest_l1_batch)
+                    .await?
+                    .with_context(|| {
+                        format!("earliest L1 batch #{earliest_l1_batch} has no L2 blocks")
+                    })?;
+                (earliest_l1_batch, first_l2_block)
+            }
+        };
+
+        // Mirror the stepping used by `soft_prune()`, so that the reported range matches the actual pruning.
+        let start_l1_batch = current_pruning_info
+            .last_soft_pruned_l1_batch
+            .unwrap_or(L1BatchNumber(0));
+        let chunk_size = self.config.pruned_batch_chunk_size;
+        // Binary search for the maximum number of chunks that can be pruned.
+        let (mut prunable_chunks, mut non_prunable_chunks) = (
+            0,
+            sealed_l1_batch.0.saturating_sub(start_l1_batch.0) / chunk_size + 1,
+        );
+        while non_prunable_chunks - prunable_chunks > 1 {
+            let chunks = (prunable_chunks + non_prunable_chunks) / 2;
+            let l1_batch_number = start_l1_batch + chunks * chunk_size;
+            if self.is_l1_batch_prunable(l1_batch_number).await {
+                prunable_chunks = chunks;
+            } else {
+                non_prunable_chunks = chunks;
+            }
+        }
+
+        let mut report = DryRunReport {
+            l1_batches: None,
+            l2_blocks: None,
+            data_classes: BTreeMap::new(),
+        };
+        let last_l1_batch = start_l1_batch + prunable_chunks * chunk_size;
+        if prunable_chunks > 0 && last_l1_batch >= first_l1_batch {
+            let (_, last_l2_block) = storage
+                .blocks_dal()
+                .get_l2_block_range_of_l1_batch(last_l1_batch)
+                .await?
+                .with_context(|| {
+                    format!("L1 batch #{last_l1_batch} is prunable, but has no L2 blocks")
+                })?;
+            report.l1_batches = Some((first_l1_batch, last_l1_batch));
+            report.l2_blocks = Some((first_l2_block, last_l2_block));
+        }
+
+        let data_classes_info = storage
+            .pruning_dal()
+            .get_data_classes_pruning_info()
+            .await?;
+        for class in PrunedDataClass::ALL {
+            let Some(last_l2_block) = self.data_class_pruning_target(storage, class).await? else {
+                continue;
+            };
+            // Data for L2 blocks covered by base pruning is removed anyway.
+            let first_class_l2_block = data_classes_info
+                .get(class)
+                .last_soft_pruned_l2_block
+                .map_or(first_l2_block, |number| (number + 1).max(first_l2_block));
+            if last_l2_block >= first_class_l2_block {
+                report
+                    .data_classes
+                    .insert(class.as_str(), (first_class_l2_block, last_l2_block));
+            }
+        }
+
+        let is_empty = report.l1_batches.is_none() && report.data_classes.is_empty();
+        Ok((!is_empty).then_some(report))
+    }
+
+    async fn run_dry_iteration(&self) -> anyhow::Result<()> {
+        let mut storage = self.connection_pool.connection_tagged("db_pruner").await?;
+        let current_pruning_info = storage.pruning_dal().get_pruning_info().await?;
+        let report = self.dry_run(&mut storage, &current_pruning_info).await?;
+        drop(storage);
+
+        let base_ranges = report
+            .as_ref()
+            .and_then(|report| report.l1_batches.zip(report.l2_blocks));
+        if let Some(((first_l1_batch, last_l1_batch), (first_l2_block, last_l2_block))) =
+            base_ranges
+        {
+            tracing::info!(
+                "Dry run: would prune L1 batches {first_l1_batch}..={last_l1_batch} and \
+                 L2 blocks {first_l2_block}..={last_l2_block}"
+            );
+            METRICS
+                .dry_run_prunable_l1_batches
+                .set((last_l1_batch.0 - first_l1_batch.0 + 1).into());
+        } else {
+            tracing::info!("Dry run: no L1 batches would be pruned");
+            METRICS.dry_run_prunable_l1_batches.set(0);
+        }
+        let data_classes = report.iter().flat_map(|report| &report.data_classes);
+        for (class, (first_l2_block, last_l2_block)) in data_classes {
+            tracing::info!(
+                "Dry run: would prune `{class}` data for L2 blocks {first_l2_block}..={last_l2_block}"
+            );
+        }
+
+        let mut health = DbPrunerHealth::from(current_pruning_info);
+        health.dry_run = report;
+        self.health_updater
+            .update(Health::from(HealthStatus::Ready).with_details(health));
+        Ok(())
+    }
+
     async fn run_single_iteration(&self) -> anyhow::Result<bool> {
+        if self.config.dry_run {
+            self.run_dry_iteration().await?;
+            return Ok(false);
+        }
+
         let mut storage = self.connection_pool.connection_tagged("db_pruner").await?;
         let current_pruning_info = storage.pruning_dal().get_pruning_info().await?;
         self.update_health(current_pruning_info);
 
-        // If this `if` is not entered, it means that the node has restarted after soft pruning
-        if current_pruning_info.last_soft_pruned_l1_batch
+        // If this condition doesn't hold, it means that the node has restarted after soft pruning
+        let pruning_done = if current_pruning_info.last_soft_pruned_l1_batch
             == current_pruning_info.last_hard_pruned_l1_batch
         {
-            let pruning_done = self.soft_prune(&mut storage).await?;
-            if !pruning_done {
-                return Ok(false);
-            }
+            self.soft_prune(&mut storage).await?
+        } else {
+            true
+        };
+        let data_classes_pruned = self.soft_prune_data_classes(&mut storage).await?;
+        if !pruning_done && !data_classes_pruned {
+            return Ok(false);
         }
         drop(storage); // Don't hold a connection across a timeout
 
         tokio::time::sleep(self.config.removal_delay).await;
         let mut storage = self.connection_pool.connection_tagged("db_pruner").await?;
-        self.hard_prune(&mut storage).await?;
-        Ok(true)
+        if pruning_done {
+            self.hard_prune(&mut storage).await?;
+        }
+        if data_classes_pruned {
+            self.hard_prune_data_classes(&mut storage).await?;
+        }
+        // Data class pruning doesn't influence the iteration delay; it advances continuously with time.
+        Ok(pruning_done)
     }
 
     pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
@@ -1,6 +1,9 @@
 use std::time::Duration;
 
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit};
+use vise::{
+    Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily, Metrics,
+    Unit,
+};
 use zksync_dal::pruning_dal::HardPruningStats;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
@@ -34,9 +37,17 @@ pub(super) struct DbPrunerMetrics {
     pub pruning_chunk_duration: Family<MetricPruneType, Histogram<Duration>>,
     /// Number of not-pruned L1 batches.
     pub not_pruned_l1_batches_count: Gauge<u64>,
+    /// Number of L1 batches that would be pruned if the pruner wasn't running in the dry-run mode.
+    pub dry_run_prunable_l1_batches: Gauge<u64>,
     /// Number of entities deleted during a single hard pruning iteration, grouped by entity type.
     #[metrics(buckets = ENTITY_COUNT_BUCKETS)]
     deleted_entities: Family<PrunedEntityType, Histogram<u64>>,
+    /// Last soft-pruned L2 block for each data class with configured retention.
+    #[metrics(labels = ["data_class"])]
+    pub data_class_soft_pruned_l2_block: LabeledFamily<&'static str, Gauge<u64>>,
+    /// Number of rows deleted or cleared during hard pruning of a single chunk of a data class.
+    #[metrics(labels = ["data_class"], buckets = ENTITY_COUNT_BUCKETS)]
+    pub data_class_affected_rows: LabeledFamily<&'static str, Histogram<u64>>,
 }
 
 impl DbPrunerMetrics {
@@ -139,3 +139,55 @@ impl PruneCondition for ConsistencyCheckerProcessedBatch {
         Ok(l1_batch_number <= last_processed_l1_batch)
     }
 }
+
+#[derive(Debug)]
+pub(super) struct L1BatchRetainedCountCondition {
+    pub retained_count: u32,
+    pub conn: ConnectionPool<Core>,
+}
+
+impl fmt::Display for L1BatchRetainedCountCondition {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "L1 batch is not among {} latest sealed batches",
+            self.retained_count
+        )
+    }
+}
+
+#[async_trait]
+impl PruneCondition for L1BatchRetainedCountCondition {
+    async fn is_batch_prunable(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<bool> {
+        let mut storage = self.conn.connection().await?;
+        let sealed_l1_batch = storage.blocks_dal().get_sealed_l1_batch_number().await?;
+        let is_retained = sealed_l1_batch.map_or(true, |sealed| {
+            sealed.0.saturating_sub(l1_batch_number.0) < self.retained_count
+        });
+        Ok(!is_retained)
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct L1BatchSnapshotCreatedCondition {
+    pub conn: ConnectionPool<Core>,
+}
+
+impl fmt::Display for L1BatchSnapshotCreatedCondition {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "L1 batch is covered by a snapshot")
+    }
+}
+
+#[async_trait]
+impl PruneCondition for L1BatchSnapshotCreatedCondition {
+    async fn is_batch_prunable(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<bool> {
+        let mut storage = self.conn.connection().await?;
+        let snapshots = storage.snapshots_dal().get_all_complete_snapshots().await?;
+        // Nodes recovering from the newest snapshot need all data after the snapshot L1 batch.
+        let newest_snapshot_l1_batch = snapshots.snapshots_l1_batch_numbers.into_iter().max();
+        Ok(newest_snapshot_l1_batch.map_or(false, |snapshot_l1_batch| {
+            l1_batch_number <= snapshot_l1_batch
+        }))
+    }
+}
@@ -2,13 +2,29 @@ use std::collections::HashMap;
 
 use assert_matches::assert_matches;
 use test_log::test;
-use zksync_dal::pruning_dal::PruningInfo;
+use zksync_dal::pruning_dal::{DataClassPruningInfo, PruningInfo};
 use zksync_db_connection::connection::Connection;
 use zksync_health_check::CheckHealth;
-use zksync_types::{block::L2BlockHeader, Address, L2BlockNumber, ProtocolVersion, H256};
+use zksync_node_test_utils::create_l1_batch;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, block::L2BlockHeader, snapshots::SnapshotVersion,
+    Address, L2BlockNumber, ProtocolVersion, H256,
+};
 
 use super::*;
 
+fn test_config(pruned_batch_chunk_size: u32) -> DbPrunerConfig {
+    DbPrunerConfig {
+        removal_delay: Duration::ZERO,
+        pruned_batch_chunk_size,
+        minimum_l1_batch_age: Duration::ZERO,
+        minimum_retained_l1_batches: 0,
+        mode: DbPrunerMode::ExternalNode,
+        dry_run: false,
+        data_class_retention: HashMap::new(),
+    }
+}
+
 #[derive(Debug)]
 struct ConditionMock {
     pub name: &'static str,
@@ -62,11 +78,7 @@ async fn is_l1_batch_prunable_works() {
             .with_response(L1BatchNumber(4), true),
     );
     let pruner = DbPruner::with_conditions(
-        DbPrunerConfig {
-            removal_delay: Duration::ZERO,
-            pruned_batch_chunk_size: 1,
-            minimum_l1_batch_age: Duration::ZERO,
-        },
+        test_config(1),
         ConnectionPool::test_pool().await,
         vec![failing_check, other_failing_check],
     );
@@ -122,6 +134,15 @@ async fn insert_l2_blocks(
     }
 }
 
+async fn insert_l1_batches(conn: &mut Connection<'_, Core>, l1_batches_count: u32) {
+    for l1_batch_number in 0..l1_batches_count {
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(l1_batch_number))
+            .await
+            .unwrap();
+    }
+}
+
 #[test(tokio::test)]
 async fn hard_pruning_ignores_conditions_checks() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -134,15 +155,8 @@ async fn hard_pruning_ignores_conditions_checks() {
         .unwrap();
 
     let nothing_prunable_check = Arc::new(ConditionMock::name("nothing prunable"));
-    let pruner = DbPruner::with_conditions(
-        DbPrunerConfig {
-            removal_delay: Duration::ZERO,
-            pruned_batch_chunk_size: 5,
-            minimum_l1_batch_age: Duration::ZERO,
-        },
-        pool.clone(),
-        vec![nothing_prunable_check],
-    );
+    let pruner =
+        DbPruner::with_conditions(test_config(5), pool.clone(), vec![nothing_prunable_check]);
     let health_check = pruner.health_check();
 
     pruner.run_single_iteration().await.unwrap();
@@ -170,11 +184,7 @@ async fn pruner_catches_up_with_hard_pruning_up_to_soft_pruning_boundary_ignorin
         .unwrap();
 
     let pruner = DbPruner::with_conditions(
-        DbPrunerConfig {
-            removal_delay: Duration::ZERO,
-            pruned_batch_chunk_size: 5,
-            minimum_l1_batch_age: Duration::ZERO,
-        },
+        test_config(5),
         pool.clone(),
         vec![], //No checks, so every batch is prunable
     );
@@ -211,11 +221,7 @@ async fn unconstrained_pruner_with_fresh_database() {
     insert_l2_blocks(&mut conn, 10, 2).await;
 
     let pruner = DbPruner::with_conditions(
-        DbPrunerConfig {
-            removal_delay: Duration::ZERO,
-            pruned_batch_chunk_size: 3,
-            minimum_l1_batch_age: Duration::ZERO,
-        },
+        test_config(3),
         pool.clone(),
         vec![], //No checks, so every batch is prunable
     );
@@ -254,11 +260,7 @@ async fn pruning_blocked_after_first_chunk() {
         Arc::new(ConditionMock::name("first chunk prunable").with_response(L1BatchNumber(3), true));
 
     let pruner = DbPruner::with_conditions(
-        DbPrunerConfig {
-            removal_delay: Duration::ZERO,
-            pruned_batch_chunk_size: 3,
-            minimum_l1_batch_age: Duration::ZERO,
-        },
+        test_config(3),
         pool.clone(),
         vec![first_chunk_prunable_check],
     );
@@ -295,15 +297,7 @@ async fn pruner_is_resistant_to_errors() {
     let erroneous_condition =
         Arc::new(ConditionMock::name("always returns true").with_response(L1BatchNumber(3), true));
 
-    let pruner = DbPruner::with_conditions(
-        DbPrunerConfig {
-            removal_delay: Duration::ZERO,
-            pruned_batch_chunk_size: 3,
-            minimum_l1_batch_age: Duration::ZERO,
-        },
-        pool.clone(),
-        vec![erroneous_condition],
-    );
+    let pruner = DbPruner::with_conditions(test_config(3), pool.clone(), vec![erroneous_condition]);
     pruner.run_single_iteration().await.unwrap_err();
 
     let mut health_check = pruner.health_check();
@@ -324,3 +318,364 @@ async fn pruner_is_resistant_to_errors() {
     stop_sender.send_replace(true);
     pruner_task_handle.await.unwrap().unwrap();
 }
+
+#[test(tokio::test)]
+async fn dry_run_reports_prunable_range_without_pruning() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_l2_blocks(&mut conn, 10, 2).await;
+    insert_l1_batches(&mut conn, 10).await;
+
+    let two_chunks_prunable_check = Arc::new(
+        ConditionMock::name("two chunks prunable")
+            .with_response(L1BatchNumber(3), true)
+            .with_response(L1BatchNumber(6), true)
+            .with_response(L1BatchNumber(9), false),
+    );
+    let pruner = DbPruner::with_conditions(
+        DbPrunerConfig {
+            dry_run: true,
+            data_class_retention: HashMap::from([(
+                PrunedDataClass::CallTraces,
+                Duration::from_secs(3_600),
+            )]),
+            ..test_config(3)
+        },
+        pool.clone(),
+        vec![two_chunks_prunable_check],
+    );
+    let health_check = pruner.health_check();
+    mark_l1_batch_as_executed(&mut conn, 7).await;
+
+    let current_pruning_info = conn.pruning_dal().get_pruning_info().await.unwrap();
+    let report = pruner
+        .dry_run(&mut conn, &current_pruning_info)
+        .await
+        .unwrap();
+    assert_eq!(
+        report,
+        Some(DryRunReport {
+            l1_batches: Some((L1BatchNumber(0), L1BatchNumber(6))),
+            l2_blocks: Some((L2BlockNumber(0), L2BlockNumber(13))),
+            data_classes: BTreeMap::from([("call_traces", (L2BlockNumber(0), L2BlockNumber(15)))]),
+        })
+    );
+
+    let pruning_done = pruner.run_single_iteration().await.unwrap();
+    assert!(!pruning_done);
+    assert_eq!(
+        conn.pruning_dal().get_pruning_info().await.unwrap(),
+        PruningInfo::default()
+    );
+    let health = health_check.check_health().await;
+    assert_matches!(health.status(), HealthStatus::Ready);
+    let health_details = health.details().unwrap();
+    assert_eq!(
+        health_details["dry_run"],
+        serde_json::json!({
+            "l1_batches": [0, 6],
+            "l2_blocks": [0, 13],
+            "data_classes": { "call_traces": [0, 15] },
+        })
+    );
+    assert_eq!(
+        conn.pruning_dal()
+            .get_data_classes_pruning_info()
+            .await
+            .unwrap()
+            .get(PrunedDataClass::CallTraces),
+        DataClassPruningInfo::default()
+    );
+}
+
+#[test(tokio::test)]
+async fn dry_run_starts_from_earliest_l1_batch() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_l2_blocks(&mut conn, 10, 2).await;
+    // Emulate a node recovered from a snapshot: L1 batches before #4 are not present.
+    for l1_batch_number in 4..10 {
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(l1_batch_number))
+            .await
+            .unwrap();
+    }
+
+    let prunable_check = Arc::new(
+        ConditionMock::name("prunable")
+            .with_response(L1BatchNumber(3), true)
+            .with_response(L1BatchNumber(6), true)
+            .with_response(L1BatchNumber(9), false),
+    );
+    let pruner = DbPruner::with_conditions(
+        DbPrunerConfig {
+            dry_run: true,
+            ..test_config(3)
+        },
+        pool.clone(),
+        vec![prunable_check],
+    );
+    let current_pruning_info = conn.pruning_dal().get_pruning_info().await.unwrap();
+    let report = pruner
+        .dry_run(&mut conn, &current_pruning_info)
+        .await
+        .unwrap();
+    assert_eq!(
+        report,
+        Some(DryRunReport {
+            l1_batches: Some((L1BatchNumber(4), L1BatchNumber(6))),
+            l2_blocks: Some((L2BlockNumber(8), L2BlockNumber(13))),
+            data_classes: BTreeMap::new(),
+        })
+    );
+}
+
+#[test(tokio::test)]
+async fn retained_l1_batches_are_not_prunable() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_l2_blocks(&mut conn, 10, 2).await;
+    insert_l1_batches(&mut conn, 10).await;
+
+    let condition = L1BatchRetainedCountCondition {
+        retained_count: 3,
+        conn: pool.clone(),
+    };
+    assert!(condition.is_batch_prunable(L1BatchNumber(6)).await.unwrap());
+    assert!(!condition.is_batch_prunable(L1BatchNumber(7)).await.unwrap());
+    assert!(!condition.is_batch_prunable(L1BatchNumber(9)).await.unwrap());
+}
+
+async fn mark_l1_batch_as_executed(conn: &mut Connection<'_, Core>, l1_batch_number: u32) {
+    conn.eth_sender_dal()
+        .insert_bogus_confirmed_eth_tx(
+            L1BatchNumber(l1_batch_number),
+            AggregatedActionType::Execute,
+            H256::from_low_u64_be(l1_batch_number.into()),
+            chrono::Utc::now(),
+        )
+        .await
+        .unwrap();
+}
+
+#[test(tokio::test)]
+async fn data_classes_are_pruned_according_to_retention() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    // All L2 blocks have zero timestamp, so they are older than any reasonable retention period.
+    insert_l2_blocks(&mut conn, 5, 2).await;
+    insert_l1_batches(&mut conn, 5).await;
+
+    let pruner = DbPruner::with_conditions(
+        DbPrunerConfig {
+            data_class_retention: HashMap::from([(
+                PrunedDataClass::CallTraces,
+                Duration::from_secs(3_600),
+            )]),
+            ..test_config(1)
+        },
+        pool.clone(),
+        vec![],
+    );
+
+    // No L1 batches are executed, so no data can be pruned.
+    assert!(!pruner.soft_prune_data_classes(&mut conn).await.unwrap());
+    assert_eq!(
+        conn.pruning_dal()
+            .get_data_classes_pruning_info()
+            .await
+            .unwrap()
+            .get(PrunedDataClass::CallTraces),
+        DataClassPruningInfo::default()
+    );
+
+    mark_l1_batch_as_executed(&mut conn, 3).await;
+    assert!(pruner.soft_prune_data_classes(&mut conn).await.unwrap());
+    let info = conn
+        .pruning_dal()
+        .get_data_classes_pruning_info()
+        .await
+        .unwrap();
+    let call_traces_info = info.get(PrunedDataClass::CallTraces);
+    // L2 blocks from non-executed L1 batch #4 must be retained.
+    assert_eq!(
+        call_traces_info.last_soft_pruned_l2_block,
+        Some(L2BlockNumber(7))
+    );
+    assert_eq!(call_traces_info.last_hard_pruned_l2_block, None);
+    assert_eq!(
+        info.get(PrunedDataClass::Events),
+        DataClassPruningInfo::default()
+    );
+    // General pruning info must not be affected.
+    assert_eq!(
+        conn.pruning_dal().get_pruning_info().await.unwrap(),
+        PruningInfo::default()
+    );
+
+    pruner.hard_prune_data_classes(&mut conn).await.unwrap();
+    let info = conn
+        .pruning_dal()
+        .get_data_classes_pruning_info()
+        .await
+        .unwrap();
+    assert_eq!(
+        info.get(PrunedDataClass::CallTraces)
+            .last_hard_pruned_l2_block,
+        Some(L2BlockNumber(7))
+    );
+    assert!(!pruner.soft_prune_data_classes(&mut conn).await.unwrap());
+
+    mark_l1_batch_as_executed(&mut conn, 4).await;
+    assert!(pruner.soft_prune_data_classes(&mut conn).await.unwrap());
+    let info = conn
+        .pruning_dal()
+        .get_data_classes_pruning_info()
+        .await
+        .unwrap();
+    assert_eq!(
+        info.get(PrunedDataClass::CallTraces)
+            .last_soft_pruned_l2_block,
+        Some(L2BlockNumber(9))
+    );
+}
+
+#[test(tokio::test)]
+async fn transaction_payloads_are_retained_for_vm_runners() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_l2_blocks(&mut conn, 5, 2).await;
+    insert_l1_batches(&mut conn, 5).await;
+    mark_l1_batch_as_executed(&mut conn, 4).await;
+    conn.vm_runner_dal()
+        .mark_l1_batch_as_processed("protective_reads_writer", L1BatchNumber(2))
+        .await
+        .unwrap();
+
+    let pruner = DbPruner::with_conditions(
+        DbPrunerConfig {
+            data_class_retention: HashMap::from([
+                (PrunedDataClass::CallTraces, Duration::from_secs(3_600)),
+                (
+                    PrunedDataClass::TransactionPayloads,
+                    Duration::from_secs(3_600),
+                ),
+            ]),
+            ..test_config(2)
+        },
+        pool.clone(),
+        vec![],
+    );
+    assert!(pruner.soft_prune_data_classes(&mut conn).await.unwrap());
+    pruner.hard_prune_data_classes(&mut conn).await.unwrap();
+
+    let info = conn
+        .pruning_dal()
+        .get_data_classes_pruning_info()
+        .await
+        .unwrap();
+    // Call traces are not used by VM runners, so they are pruned up to the last executed L1 batch.
+    assert_eq!(
+        info.get(PrunedDataClass::CallTraces),
+        DataClassPruningInfo {
+            last_soft_pruned_l2_block: Some(L2BlockNumber(9)),
+            last_hard_pruned_l2_block: Some(L2BlockNumber(9)),
+        }
+    );
+    // Payloads for L1 batches not processed by the VM runner must be retained.
+    assert_eq!(
+        info.get(PrunedDataClass::TransactionPayloads),
+        DataClassPruningInfo {
+            last_soft_pruned_l2_block: Some(L2BlockNumber(5)),
+            last_hard_pruned_l2_block: Some(L2BlockNumber(5)),
+        }
+    );
+}
+
+#[test]
+fn main_node_config_is_converted() {
+    let config = PruningConfig {
+        enabled: true,
+        chunk_size: 5,
+        removal_delay_sec: 30.try_into().unwrap(),
+        data_retention_sec: 3_600,
+        retained_l1_batches: 100,
+        dry_run: true,
+        call_traces_retention_sec: Some(60),
+        events_retention_sec: None,
+        transaction_payloads_retention_sec: Some(120),
+    };
+    let config = DbPrunerConfig::for_main_node(&config);
+
+    assert_eq!(config.removal_delay, Duration::from_secs(30));
+    assert_eq!(config.pruned_batch_chunk_size, 5);
+    assert_eq!(config.minimum_l1_batch_age, Duration::from_secs(3_600));
+    assert_eq!(config.minimum_retained_l1_batches, 100);
+    assert_eq!(config.mode, DbPrunerMode::MainNode);
+    assert!(config.dry_run);
+    assert_eq!(
+        config.data_class_retention,
+        HashMap::from([
+            (PrunedDataClass::CallTraces, Duration::from_secs(60)),
+            (
+                PrunedDataClass::TransactionPayloads,
+                Duration::from_secs(120)
+            ),
+        ])
+    );
+}
+
+#[test(tokio::test)]
+async fn main_node_pruner_requires_snapshots() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let pruner = DbPruner::new(
+        DbPrunerConfig {
+            mode: DbPrunerMode::MainNode,
+            ..test_config(1)
+        },
+        pool.clone(),
+    );
+    let conditions: Vec<_> = pruner
+        .prune_conditions
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert!(
+        conditions.contains(&"L1 batch is covered by a snapshot".to_owned()),
+        "{conditions:?}"
+    );
+    assert!(
+        !conditions.contains(&"L1 batch was processed by consistency checker".to_owned()),
+        "{conditions:?}"
+    );
+}
+
+#[test(tokio::test)]
+async fn l1_batches_not_covered_by_snapshot_are_not_prunable() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let condition = L1BatchSnapshotCreatedCondition { conn: pool.clone() };
+    // No snapshots at all.
+    assert!(!condition.is_batch_prunable(L1BatchNumber(1)).await.unwrap());
+
+    conn.snapshots_dal()
+        .add_snapshot(
+            SnapshotVersion::Version0,
+            L1BatchNumber(3),
+            None,
+            1,
+            "gs:///bucket/factory_deps.bin",
+        )
+        .await
+        .unwrap();
+    // The snapshot is not complete yet.
+    assert!(!condition.is_batch_prunable(L1BatchNumber(1)).await.unwrap());
+
+    conn.snapshots_dal()
+        .add_storage_logs_filepath_for_snapshot(L1BatchNumber(3), 0, "gs:///bucket/chunk.bin")
+        .await
+        .unwrap();
+    assert!(condition.is_batch_prunable(L1BatchNumber(1)).await.unwrap());
+    assert!(condition.is_batch_prunable(L1BatchNumber(3)).await.unwrap());
+    assert!(!condition.is_batch_prunable(L1BatchNumber(4)).await.unwrap());
+}
@@ -1,7 +1,7 @@
 use std::fmt;
 
 use zksync_contracts::verifier_contract;
-pub(super) use zksync_eth_client::Error as EthClientError;
+pub use zksync_eth_client::Error as EthClientError;
 use zksync_eth_client::{CallFunctionArgs, ClientError, EthInterface};
 use zksync_types::{
     ethabi::Contract,
@@ -23,8 +23,12 @@ pub trait EthClient: 'static + fmt::Debug + Send + Sync {
     async fn finalized_block_number(&self) -> Result<u64, EthClientError>;
     /// Returns scheduler verification key hash by verifier address.
     async fn scheduler_vk_hash(&self, verifier_address: Address) -> Result<H256, EthClientError>;
+    /// Returns the hash of the L1 block with the specified number, or `None` if the block is not present on L1.
+    async fn block_hash(&self, number: u64) -> Result<Option<H256>, EthClientError>;
     /// Sets list of topics to return events for.
     fn set_topics(&mut self, topics: Vec<H256>);
+    /// Sets list of contracts to return events for in addition to the core contracts.
+    fn set_extra_contracts(&mut self, contracts: Vec<Address>);
 }
 
 pub const RETRY_LIMIT: usize = 5;
@@ -36,6 +40,7 @@ const TOO_MANY_RESULTS_ALCHEMY: &str = "response size exceeded";
 pub struct EthHttpQueryClient {
     client: Box<dyn EthInterface>,
     topics: Vec<H256>,
+    extra_contracts: Vec<Address>,
     diamond_proxy_addr: Address,
     governance_address: Address,
     // Only present for post-shared bridge chains.
@@ -60,6 +65,7 @@ impl EthHttpQueryClient {
         Self {
             client: client.for_component("watch"),
             topics: Vec::new(),
+            extra_contracts: Vec::new(),
             diamond_proxy_addr,
             state_transition_manager_address,
             governance_address,
@@ -83,6 +89,7 @@ impl EthHttpQueryClient {
                 ]
                 .into_iter()
                 .flatten()
+                .chain(self.extra_contracts.iter().copied())
                 .collect(),
             )
             .from_block(from)
@@ -197,7 +204,25 @@ impl EthClient for EthHttpQueryClient {
         }
     }
 
+    async fn block_hash(&self, number: u64) -> Result<Option<H256>, EthClientError> {
+        let Some(block) = self
+            .client
+            .block(BlockId::Number(BlockNumber::Number(number.into())))
+            .await?
+        else {
+            return Ok(None);
+        };
+        let hash = block
+            .hash
+            .ok_or_else(|| ClientError::Custom(format!("L1 block #{number} must contain hash")))?;
+        Ok(Some(hash))
+    }
+
     fn set_topics(&mut self, topics: Vec<H256>) {
         self.topics = topics;
     }
+
+    fn set_extra_contracts(&mut self, contracts: Vec<Address>) {
+        self.extra_contracts = contracts;
+    }
 }
@@ -41,6 +41,10 @@ impl GovernanceUpgradesEventProcessor {
 
 #[async_trait::async_trait]
 impl EventProcessor for GovernanceUpgradesEventProcessor {
+    fn name(&self) -> &'static str {
+        "governance_upgrades"
+    }
+
     async fn process_events(
         &mut self,
         storage: &mut Connection<'_, Core>,
@@ -1,7 +1,7 @@
 use std::fmt;
 
 use zksync_dal::{Connection, Core};
-use zksync_types::{web3::Log, H256};
+use zksync_types::{web3::Log, Address, H256};
 
 pub(crate) use self::{
     governance_upgrades::GovernanceUpgradesEventProcessor, priority_ops::PriorityOpsEventProcessor,
@@ -13,7 +13,7 @@ mod priority_ops;
 
 /// Errors issued by an [`EventProcessor`].
 #[derive(Debug, thiserror::Error)]
-pub(super) enum EventProcessorError {
+pub enum EventProcessorError {
     #[error("failed parsing a log into {log_kind}: {source:?}")]
     LogParse {
         log_kind: &'static str,
@@ -22,6 +22,18 @@ pub(super) enum EventProcessorError {
     },
     #[error("Eth client error: {0}")]
     Client(#[from] EthClientError),
+    /// L1 reorg affecting already processed L1 blocks. Such reorgs are considered fatal since processed events
+    /// (e.g., priority operations) may be no longer valid.
+    #[error(
+        "L1 reorg detected: hash of processed L1 block #{block_number} has changed from {expected_hash:?} \
+         to {actual_hash:?}; events processed starting from this block may be invalid"
+    )]
+    L1Reorg {
+        block_number: u64,
+        expected_hash: H256,
+        /// `None` if the block is no longer present on L1.
+        actual_hash: Option<H256>,
+    },
     /// Internal errors are considered fatal (i.e., they bubble up and lead to the watcher termination).
     #[error("internal processing error: {0:?}")]
     Internal(#[from] anyhow::Error),
@@ -38,8 +50,28 @@ impl EventProcessorError {
 
 /// Processor for a single type of events emitted by the L1 contract. [`EthWatch`](crate::EthWatch)
 /// feeds events to all processors one-by-one.
+///
+/// Besides the built-in processors, custom processors can be registered using
+/// [`EthWatch::add_event_processor()`](crate::EthWatch::add_event_processor()). Each processor has its own checkpoint
+/// (the last processed L1 block) persisted in Postgres, which is updated atomically with the changes made by the processor.
 #[async_trait::async_trait]
-pub(super) trait EventProcessor: 'static + fmt::Debug + Send + Sync {
+pub trait EventProcessor: 'static + fmt::Debug + Send + Sync {
+    /// Returns the processor name. The name is used as a key for the processor checkpoint, so it must be unique
+    /// among the processors of a watcher and must not change across node restarts.
+    fn name(&self) -> &'static str;
+
+    /// Returns the L1 block after which events should be processed if the processor has no checkpoint yet.
+    /// If not specified, the processor starts from the current watcher position, i.e. past events are not processed.
+    fn initial_l1_block(&self) -> Option<u64> {
+        None
+    }
+
+    /// Returns the addresses of L1 contracts emitting the processed events in addition to the core contracts
+    /// (the diamond proxy, the governance contract and the state transition manager) watched by default.
+    fn extra_contracts(&self) -> Vec<Address> {
+        Vec::new()
+    }
+
     /// Processes given events. All events are guaranteed to match [`Self::relevant_topic()`].
     async fn process_events(
         &mut self,
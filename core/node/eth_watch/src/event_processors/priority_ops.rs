@@ -33,6 +33,10 @@ impl PriorityOpsEventProcessor {
 
 #[async_trait::async_trait]
 impl EventProcessor for PriorityOpsEventProcessor {
+    fn name(&self) -> &'static str {
+        "priority_ops"
+    }
+
     async fn process_events(
         &mut self,
         storage: &mut Connection<'_, Core>,
@@ -1,27 +1,33 @@
 //! Ethereum watcher polls the Ethereum node for the relevant events, such as priority operations (aka L1 transactions),
 //! protocol upgrades etc.
 //! New events are accepted to the zkSync network once they have the sufficient amount of L1 confirmations.
+//! Hashes of processed L1 blocks are persisted and verified on each poll; if an L1 reorg affects processed events,
+//! the watcher halts with an error.
 
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use anyhow::Context as _;
 use tokio::sync::watch;
-use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_dal::{
+    eth_watcher_dal::ProcessedL1Block, Connection, ConnectionPool, Core, CoreDal, DalError,
+};
+use zksync_eth_client::ClientError;
 use zksync_system_constants::PRIORITY_EXPIRATION;
 use zksync_types::{
-    ethabi::Contract, web3::BlockNumber as Web3BlockNumber, Address, PriorityOpId,
-    ProtocolVersionId,
+    ethabi::Contract,
+    web3::{BlockNumber as Web3BlockNumber, Log},
+    Address, PriorityOpId, ProtocolVersionId, H256,
 };
 
-pub use self::client::EthHttpQueryClient;
 use self::{
-    client::{EthClient, RETRY_LIMIT},
-    event_processors::{
-        EventProcessor, EventProcessorError, GovernanceUpgradesEventProcessor,
-        PriorityOpsEventProcessor,
-    },
+    client::RETRY_LIMIT,
+    event_processors::{GovernanceUpgradesEventProcessor, PriorityOpsEventProcessor},
     metrics::{PollStage, METRICS},
 };
+pub use self::{
+    client::{EthClient, EthClientError, EthHttpQueryClient},
+    event_processors::{EventProcessor, EventProcessorError},
+};
 
 mod client;
 mod event_processors;
@@ -29,6 +35,11 @@ mod metrics;
 #[cfg(test)]
 mod tests;
 
+/// Number of L1 blocks for which processed block hashes are retained.
+const RETAINED_L1_BLOCK_HASHES: u64 = 10_000;
+/// Maximum number of processed block hashes checked when looking for the first block affected by an L1 reorg.
+const MAX_L1_REORG_DIAGNOSTICS_DEPTH: usize = 64;
+
 #[derive(Debug)]
 struct EthWatchState {
     last_seen_version_id: ProtocolVersionId,
@@ -36,12 +47,30 @@ struct EthWatchState {
     last_processed_ethereum_block: u64,
 }
 
+/// Event processor registered in [`EthWatch`] together with its checkpoint.
+#[derive(Debug)]
+struct RegisteredProcessor {
+    processor: Box<dyn EventProcessor>,
+    /// Last L1 block processed by the processor. `None` if the checkpoint is not loaded from Postgres yet.
+    last_processed_l1_block: Option<u64>,
+}
+
+impl RegisteredProcessor {
+    fn new(processor: Box<dyn EventProcessor>) -> Self {
+        Self {
+            processor,
+            last_processed_l1_block: None,
+        }
+    }
+}
+
 /// Ethereum watcher component.
 #[derive(Debug)]
 pub struct EthWatch {
     client: Box<dyn EthClient>,
     poll_interval: Duration,
-    event_processors: Vec<Box<dyn EventProcessor>>,
+    event_processors: Vec<RegisteredProcessor>,
+    /// L1 block used as a starting point for processors without a checkpoint.
     last_processed_ethereum_block: u64,
     pool: ConnectionPool<Core>,
 }
@@ -67,24 +96,64 @@ impl EthWatch {
             state.last_seen_version_id,
             governance_contract,
         );
-        let event_processors: Vec<Box<dyn EventProcessor>> = vec![
-            Box::new(priority_ops_processor),
-            Box::new(governance_upgrades_processor),
+        let event_processors = vec![
+            RegisteredProcessor::new(Box::new(priority_ops_processor)),
+            RegisteredProcessor::new(Box::new(governance_upgrades_processor)),
         ];
 
-        let topics = event_processors
-            .iter()
-            .map(|processor| processor.relevant_topic())
-            .collect();
-        client.set_topics(topics);
-
-        Ok(Self {
+        let mut this = Self {
             client,
             poll_interval,
             event_processors,
             last_processed_ethereum_block: state.last_processed_ethereum_block,
             pool,
-        })
+        };
+        this.update_client_filter();
+        Ok(this)
+    }
+
+    /// Registers an additional event processor. Processor names must be unique; the built-in processors are named
+    /// `priority_ops` and `governance_upgrades`.
+    pub fn add_event_processor(
+        &mut self,
+        processor: Box<dyn EventProcessor>,
+    ) -> anyhow::Result<()> {
+        let name = processor.name();
+        anyhow::ensure!(
+            self.event_processors
+                .iter()
+                .all(|registered| registered.processor.name() != name),
+            "event processor `{name}` is already registered"
+        );
+        tracing::info!(
+            "Registered event processor `{name}` for topic {:?}",
+            processor.relevant_topic()
+        );
+        self.event_processors
+            .push(RegisteredProcessor::new(processor));
+        self.update_client_filter();
+        Ok(())
+    }
+
+    fn update_client_filter(&mut self) {
+        let processors = self
+            .event_processors
+            .iter()
+            .map(|registered| &registered.processor);
+        let mut topics: Vec<_> = processors
+            .clone()
+            .map(|processor| processor.relevant_topic())
+            .collect();
+        topics.sort_unstable();
+        topics.dedup();
+        let mut extra_contracts: Vec<_> = processors
+            .flat_map(|processor| processor.extra_contracts())
+            .collect();
+        extra_contracts.sort_unstable();
+        extra_contracts.dedup();
+
+        self.client.set_topics(topics);
+        self.client.set_extra_contracts(extra_contracts);
     }
 
     async fn initialize_state(
@@ -144,6 +213,10 @@ impl EthWatch {
                     tracing::error!("Internal error processing new blocks: {err:?}");
                     return Err(err);
                 }
+                Err(err @ EventProcessorError::L1Reorg { .. }) => {
+                    tracing::error!("{err}; manual intervention is required");
+                    return Err(err.into());
+                }
                 Err(err) => {
                     // This is an error because otherwise we could potentially miss a priority operation
                     // thus entering priority mode, which is not desired.
@@ -152,6 +225,10 @@ impl EthWatch {
                         Self::initialize_state(&*self.client, &mut storage)
                             .await?
                             .last_processed_ethereum_block;
+                    // Reload processor checkpoints from Postgres on the next iteration.
+                    for registered in &mut self.event_processors {
+                        registered.last_processed_l1_block = None;
+                    }
                 }
             }
         }
@@ -160,39 +237,222 @@ impl EthWatch {
         Ok(())
     }
 
+    /// Loads missing processor checkpoints from Postgres and returns the minimum processed L1 block
+    /// across all processors.
+    async fn load_checkpoints(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+    ) -> Result<u64, EventProcessorError> {
+        let mut min_processed_block = u64::MAX;
+        for registered in &mut self.event_processors {
+            let last_processed_block = match registered.last_processed_l1_block {
+                Some(block) => block,
+                None => {
+                    let name = registered.processor.name();
+                    let checkpoint = storage
+                        .eth_watcher_dal()
+                        .get_processor_checkpoint(name)
+                        .await
+                        .map_err(DalError::generalize)?;
+                    let block = checkpoint
+                        .or_else(|| registered.processor.initial_l1_block())
+                        .unwrap_or(self.last_processed_ethereum_block);
+                    tracing::info!(
+                        "Event processor `{name}` starts after L1 block #{block} (checkpoint: {checkpoint:?})"
+                    );
+                    registered.last_processed_l1_block = Some(block);
+                    block
+                }
+            };
+            min_processed_block = min_processed_block.min(last_processed_block);
+        }
+        Ok(min_processed_block)
+    }
+
     #[tracing::instrument(skip_all)]
     async fn loop_iteration(
         &mut self,
         storage: &mut Connection<'_, Core>,
     ) -> Result<(), EventProcessorError> {
+        self.check_l1_reorg(storage).await?;
+        let from_block = self.load_checkpoints(storage).await?;
         let stage_latency = METRICS.poll_eth_node[&PollStage::Request].start();
         let to_block = self.client.finalized_block_number().await?;
-        if to_block <= self.last_processed_ethereum_block {
+        if to_block <= from_block {
             return Ok(());
         }
+        // The hash is fetched before events so that if an L1 reorg happens in between, the persisted hash
+        // is outdated (and thus the reorg is detected on the next iteration) rather than the events.
+        let to_block_hash = self.client.block_hash(to_block).await?.ok_or_else(|| {
+            EthClientError::from(ClientError::Custom(format!(
+                "L1 block #{to_block} is not present"
+            )))
+        })?;
 
         let events = self
             .client
             .get_events(
-                Web3BlockNumber::Number(self.last_processed_ethereum_block.into()),
+                Web3BlockNumber::Number(from_block.into()),
                 Web3BlockNumber::Number(to_block.into()),
                 RETRY_LIMIT,
             )
             .await?;
         stage_latency.observe();
+        let processed_blocks = Self::processed_blocks(to_block, to_block_hash, &events);
+
+        for registered in &mut self.event_processors {
+            let processor = &mut registered.processor;
+            let last_processed_block = registered
+                .last_processed_l1_block
+                .expect("checkpoints are loaded above");
+            if to_block <= last_processed_block {
+                continue;
+            }
 
-        for processor in &mut self.event_processors {
             let relevant_topic = processor.relevant_topic();
             let processor_events = events
                 .iter()
-                .filter(|event| event.topics.get(0) == Some(&relevant_topic))
+                .filter(|event| {
+                    event.topics.get(0) == Some(&relevant_topic)
+                        && event
+                            .block_number
+                            .map_or(true, |number| number.as_u64() > last_processed_block)
+                })
                 .cloned()
                 .collect();
+
+            // Changes made by the processor are committed together with its checkpoint.
+            let mut transaction = storage
+                .start_transaction()
+                .await
+                .map_err(DalError::generalize)?;
             processor
-                .process_events(storage, &*self.client, processor_events)
+                .process_events(&mut transaction, &*self.client, processor_events)
                 .await?;
+            transaction
+                .eth_watcher_dal()
+                .set_processor_checkpoint(processor.name(), to_block)
+                .await
+                .map_err(DalError::generalize)?;
+            transaction.commit().await.map_err(DalError::generalize)?;
+            registered.last_processed_l1_block = Some(to_block);
         }
+
+        // Hashes are saved once per poll after all processors have committed their changes. If the node
+        // stops in between, the hashes for this poll are missing, which only makes reorg detection
+        // for these blocks impossible; it never leads to false positives.
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(DalError::generalize)?;
+        let mut dal = transaction.eth_watcher_dal();
+        dal.insert_l1_block_hashes(&processed_blocks)
+            .await
+            .map_err(DalError::generalize)?;
+        dal.prune_l1_block_hashes(to_block.saturating_sub(RETAINED_L1_BLOCK_HASHES))
+            .await
+            .map_err(DalError::generalize)?;
+        transaction.commit().await.map_err(DalError::generalize)?;
         self.last_processed_ethereum_block = to_block;
         Ok(())
     }
+
+    /// Returns blocks containing processed events and the last processed block.
+    fn processed_blocks(
+        to_block: u64,
+        to_block_hash: H256,
+        events: &[Log],
+    ) -> Vec<ProcessedL1Block> {
+        let mut blocks: HashMap<u64, ProcessedL1Block> = events
+            .iter()
+            .filter_map(|event| {
+                let number = event.block_number?.as_u64();
+                let block = ProcessedL1Block {
+                    number,
+                    hash: event.block_hash?,
+                    has_events: true,
+                };
+                Some((number, block))
+            })
+            .collect();
+        blocks.entry(to_block).or_insert(ProcessedL1Block {
+            number: to_block,
+            hash: to_block_hash,
+            has_events: false,
+        });
+        let mut blocks: Vec<_> = blocks.into_values().collect();
+        blocks.sort_unstable_by_key(|block| block.number);
+        blocks
+    }
+
+    /// Checks that the latest processed L1 block is still canonical. Since block hashes are chained,
+    /// this detects reorgs affecting any of the previously processed blocks.
+    ///
+    /// If the reorg only affects processed blocks without events (e.g., the last processed block when
+    /// the number of confirmations for events is low), no events could have been processed incorrectly.
+    /// In this case, the watcher is rewound to the latest processed block that is still canonical, so that
+    /// events from the reorged blocks are fetched again. Otherwise, the reorg is fatal.
+    async fn check_l1_reorg(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+    ) -> Result<(), EventProcessorError> {
+        let processed_blocks = storage
+            .eth_watcher_dal()
+            .get_latest_l1_block_hashes(MAX_L1_REORG_DIAGNOSTICS_DEPTH)
+            .await
+            .map_err(DalError::generalize)?;
+
+        let mut reorged_blocks = vec![];
+        let mut last_canonical_block = None;
+        for block in processed_blocks {
+            let actual_hash = self.client.block_hash(block.number).await?;
+            if actual_hash == Some(block.hash) {
+                last_canonical_block = Some(block.number);
+                break;
+            }
+            reorged_blocks.push((block, actual_hash));
+        }
+        let Some((earliest_reorged_block, actual_hash)) = reorged_blocks.last().copied() else {
+            return Ok(()); // No blocks are processed yet, or the latest processed block is canonical
+        };
+
+        METRICS.l1_reorgs.inc();
+        let has_processed_events = reorged_blocks.iter().any(|(block, _)| block.has_events);
+        let last_canonical_block = match last_canonical_block {
+            Some(block) if !has_processed_events => block,
+            _ => {
+                return Err(EventProcessorError::L1Reorg {
+                    block_number: earliest_reorged_block.number,
+                    expected_hash: earliest_reorged_block.hash,
+                    actual_hash,
+                });
+            }
+        };
+
+        tracing::warn!(
+            "L1 reorg affected processed L1 blocks #{}..=#{} without events; rewinding to L1 block #{last_canonical_block}",
+            earliest_reorged_block.number,
+            reorged_blocks[0].0.number
+        );
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(DalError::generalize)?;
+        let mut dal = transaction.eth_watcher_dal();
+        dal.remove_l1_block_hashes_after(last_canonical_block)
+            .await
+            .map_err(DalError::generalize)?;
+        dal.rewind_processor_checkpoints(last_canonical_block)
+            .await
+            .map_err(DalError::generalize)?;
+        transaction.commit().await.map_err(DalError::generalize)?;
+
+        // Reload processor checkpoints from Postgres.
+        for registered in &mut self.event_processors {
+            registered.last_processed_l1_block = None;
+        }
+        self.last_processed_ethereum_block =
+            self.last_processed_ethereum_block.min(last_canonical_block);
+        Ok(())
+    }
 }
@@ -14,7 +14,7 @@ use zksync_types::{
 
 use crate::{
     client::{EthClient, EthClientError},
-    EthWatch,
+    EthWatch, EventProcessor, EventProcessorError,
 };
 
 #[derive(Debug)]
@@ -22,6 +22,9 @@ struct FakeEthClientData {
     transactions: HashMap<u64, Vec<Log>>,
     diamond_upgrades: HashMap<u64, Vec<Log>>,
     governance_upgrades: HashMap<u64, Vec<Log>>,
+    custom_logs: HashMap<u64, Vec<Log>>,
+    /// Reorged block hashes; `None` means that the block is no longer present on L1.
+    reorged_block_hashes: HashMap<u64, Option<H256>>,
     last_finalized_block_number: u64,
 }
 
@@ -31,6 +34,8 @@ impl FakeEthClientData {
             transactions: Default::default(),
             diamond_upgrades: Default::default(),
             governance_upgrades: Default::default(),
+            custom_logs: Default::default(),
+            reorged_block_hashes: Default::default(),
             last_finalized_block_number: 0,
         }
     }
@@ -54,6 +59,16 @@ impl FakeEthClientData {
         }
     }
 
+    fn add_custom_logs(&mut self, logs: &[Log]) {
+        for log in logs {
+            let eth_block = log.block_number.expect("no block number").as_u64();
+            self.custom_logs
+                .entry(eth_block)
+                .or_default()
+                .push(log.clone());
+        }
+    }
+
     fn set_last_finalized_block_number(&mut self, number: u64) {
         self.last_finalized_block_number = number;
     }
@@ -79,6 +94,26 @@ impl MockEthClient {
         self.inner.write().await.add_governance_upgrades(upgrades);
     }
 
+    async fn reorg_blocks(&mut self, block_numbers: impl IntoIterator<Item = u64>) {
+        let mut inner = self.inner.write().await;
+        for number in block_numbers {
+            inner
+                .reorged_block_hashes
+                .insert(number, Some(H256::random()));
+        }
+    }
+
+    async fn remove_blocks(&mut self, block_numbers: impl IntoIterator<Item = u64>) {
+        let mut inner = self.inner.write().await;
+        for number in block_numbers {
+            inner.reorged_block_hashes.insert(number, None);
+        }
+    }
+
+    async fn add_custom_logs(&mut self, logs: &[Log]) {
+        self.inner.write().await.add_custom_logs(logs);
+    }
+
     async fn set_last_finalized_block_number(&mut self, number: u64) {
         self.inner
             .write()
@@ -119,12 +154,23 @@ impl EthClient for MockEthClient {
             if let Some(ops) = self.inner.read().await.governance_upgrades.get(&number) {
                 logs.extend_from_slice(ops);
             }
+            if let Some(ops) = self.inner.read().await.custom_logs.get(&number) {
+                logs.extend_from_slice(ops);
+            }
         }
         Ok(logs)
     }
 
     fn set_topics(&mut self, _topics: Vec<Hash>) {}
 
+    fn set_extra_contracts(&mut self, _contracts: Vec<Address>) {}
+
+    async fn block_hash(&self, number: u64) -> Result<Option<H256>, EthClientError> {
+        let inner = self.inner.read().await;
+        let reorged_hash = inner.reorged_block_hashes.get(&number).copied();
+        Ok(reorged_hash.unwrap_or_else(|| Some(canonical_block_hash(number))))
+    }
+
     async fn scheduler_vk_hash(&self, _verifier_address: Address) -> Result<H256, EthClientError> {
         Ok(H256::zero())
     }
@@ -134,6 +180,10 @@ impl EthClient for MockEthClient {
     }
 }
 
+fn canonical_block_hash(number: u64) -> H256 {
+    H256::from_low_u64_be(number)
+}
+
 fn build_l1_tx(serial_id: u64, eth_block: u64) -> L1Tx {
     L1Tx {
         execute: Execute {
@@ -427,6 +477,243 @@ async fn test_overlapping_batches() {
     assert_eq!(tx.common_data.serial_id.0, 4);
 }
 
+/// Processor recording block numbers of all events with a custom topic.
+#[derive(Debug)]
+struct CustomEventProcessor {
+    initial_l1_block: Option<u64>,
+    processed_blocks: Arc<RwLock<Vec<u64>>>,
+}
+
+impl CustomEventProcessor {
+    const TOPIC: H256 = H256::repeat_byte(0xcc);
+}
+
+#[async_trait::async_trait]
+impl EventProcessor for CustomEventProcessor {
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+
+    fn initial_l1_block(&self) -> Option<u64> {
+        self.initial_l1_block
+    }
+
+    async fn process_events(
+        &mut self,
+        _storage: &mut Connection<'_, Core>,
+        _client: &dyn EthClient,
+        events: Vec<Log>,
+    ) -> Result<(), EventProcessorError> {
+        let mut processed_blocks = self.processed_blocks.write().await;
+        for event in events {
+            assert_eq!(event.topics[0], Self::TOPIC);
+            processed_blocks.push(event.block_number.unwrap().as_u64());
+        }
+        Ok(())
+    }
+
+    fn relevant_topic(&self) -> H256 {
+        Self::TOPIC
+    }
+}
+
+fn custom_log(eth_block: u64) -> Log {
+    Log {
+        address: Address::repeat_byte(0x2),
+        topics: vec![CustomEventProcessor::TOPIC],
+        data: Vec::new().into(),
+        block_hash: Some(canonical_block_hash(eth_block)),
+        block_number: Some(eth_block.into()),
+        transaction_hash: Some(H256::random()),
+        transaction_index: Some(0u64.into()),
+        log_index: Some(0u64.into()),
+        transaction_log_index: Some(0u64.into()),
+        log_type: None,
+        removed: None,
+    }
+}
+
+#[tokio::test]
+async fn custom_event_processor_resumes_from_checkpoint() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (mut watcher, mut client) = create_test_watcher(connection_pool.clone()).await;
+    let processed_blocks = Arc::<RwLock<Vec<u64>>>::default();
+    watcher
+        .add_event_processor(Box::new(CustomEventProcessor {
+            initial_l1_block: Some(5),
+            processed_blocks: processed_blocks.clone(),
+        }))
+        .unwrap();
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client
+        .add_custom_logs(&[custom_log(3), custom_log(10), custom_log(18)])
+        .await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(*processed_blocks.read().await, [10]);
+    let checkpoint = storage
+        .eth_watcher_dal()
+        .get_processor_checkpoint("custom")
+        .await
+        .unwrap();
+    assert_eq!(checkpoint, Some(15));
+
+    // Re-create the watcher; the processor should resume from its checkpoint.
+    let mut watcher = EthWatch::new(
+        Address::default(),
+        None,
+        &governance_contract(),
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await
+    .unwrap();
+    watcher
+        .add_event_processor(Box::new(CustomEventProcessor {
+            initial_l1_block: Some(0),
+            processed_blocks: processed_blocks.clone(),
+        }))
+        .unwrap();
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(*processed_blocks.read().await, [10, 18]);
+
+    let err = watcher
+        .add_event_processor(Box::new(CustomEventProcessor {
+            initial_l1_block: None,
+            processed_blocks,
+        }))
+        .unwrap_err();
+    assert!(err.to_string().contains("already registered"), "{err}");
+}
+
+#[tokio::test]
+async fn l1_reorg_halts_watcher() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (mut watcher, mut client) = create_test_watcher(connection_pool.clone()).await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 14)])
+        .await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let processed_blocks = storage
+        .eth_watcher_dal()
+        .get_latest_l1_block_hashes(10)
+        .await
+        .unwrap();
+    let processed_block_numbers: Vec<_> =
+        processed_blocks.iter().map(|block| block.number).collect();
+    assert_eq!(processed_block_numbers, [15, 14, 10]);
+
+    // A reorg not affecting processed blocks should not be detected.
+    client.reorg_blocks(16..20).await;
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    client.reorg_blocks(14..=20).await;
+    client.set_last_finalized_block_number(25).await;
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            EventProcessorError::L1Reorg { block_number: 14, expected_hash, .. }
+                if expected_hash == canonical_block_hash(14)
+        ),
+        "{err:?}"
+    );
+
+    // The reorg must be detected after the watcher is restarted as well.
+    let mut watcher = EthWatch::new(
+        Address::default(),
+        None,
+        &governance_contract(),
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await
+    .unwrap();
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    assert!(
+        matches!(err, EventProcessorError::L1Reorg { .. }),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn l1_reorg_of_blocks_without_events_is_rewound() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (mut watcher, mut client) = create_test_watcher(connection_pool.clone()).await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client.add_transactions(&[build_l1_tx(0, 10)]).await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    // Reorg the last processed block and add a priority op to the new chain.
+    client.reorg_blocks(15..=20).await;
+    client.add_transactions(&[build_l1_tx(1, 15)]).await;
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    let db_txs = get_all_db_txs(&mut storage).await;
+    assert_eq!(db_txs.len(), 2);
+    let processed_blocks = storage
+        .eth_watcher_dal()
+        .get_latest_l1_block_hashes(10)
+        .await
+        .unwrap();
+    let processed_block_numbers: Vec<_> =
+        processed_blocks.iter().map(|block| block.number).collect();
+    assert_eq!(processed_block_numbers, [20, 15, 10]);
+    let checkpoint = storage
+        .eth_watcher_dal()
+        .get_processor_checkpoint("priority_ops")
+        .await
+        .unwrap();
+    assert_eq!(checkpoint, Some(20));
+
+    // The watcher should continue working normally after the rewind.
+    client.add_transactions(&[build_l1_tx(2, 22)]).await;
+    client.set_last_finalized_block_number(25).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let db_txs = get_all_db_txs(&mut storage).await;
+    assert_eq!(db_txs.len(), 3);
+}
+
+#[tokio::test]
+async fn missing_l1_block_is_reported_as_reorg() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (mut watcher, mut client) = create_test_watcher(connection_pool.clone()).await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client.add_transactions(&[build_l1_tx(0, 10)]).await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    client.remove_blocks(10..=15).await;
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            EventProcessorError::L1Reorg {
+                block_number: 10,
+                actual_hash: None,
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+}
+
 async fn get_all_db_txs(storage: &mut Connection<'_, Core>) -> Vec<Transaction> {
     storage.transactions_dal().reset_mempool().await.unwrap();
     storage
@@ -478,7 +765,7 @@ fn tx_into_log(tx: L1Tx) -> Log {
             .expect("NewPriorityRequest event is missing in abi")
             .signature()],
         data: data.into(),
-        block_hash: Some(H256::repeat_byte(0x11)),
+        block_hash: Some(canonical_block_hash(eth_block.as_u64())),
         block_number: Some(eth_block),
         transaction_hash: Some(H256::random()),
         transaction_index: Some(0u64.into()),
@@ -522,7 +809,7 @@ fn upgrade_into_governor_log(upgrade: ProtocolUpgrade, eth_block: u64) -> Log {
             Default::default(),
         ],
         data: final_data.into(),
-        block_hash: Some(H256::repeat_byte(0x11)),
+        block_hash: Some(canonical_block_hash(eth_block)),
         block_number: Some(eth_block.into()),
         transaction_hash: Some(H256::random()),
         transaction_index: Some(0u64.into()),
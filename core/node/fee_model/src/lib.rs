@@ -1,4 +1,7 @@
-use std::{fmt, sync::Arc};
+use std::{
+    fmt, mem,
+    sync::{Arc, RwLock},
+};
 
 use anyhow::Context as _;
 use zksync_dal::{ConnectionPool, Core, CoreDal};
@@ -59,12 +62,13 @@ impl dyn BatchFeeModelInputProvider {
 #[derive(Debug)]
 pub struct MainNodeFeeInputProvider {
     provider: Arc<GasAdjuster>,
-    config: FeeModelConfig,
+    /// Config may be modified at runtime using [`Self::set_minimal_l2_gas_price()`].
+    config: RwLock<FeeModelConfig>,
 }
 
 impl BatchFeeModelInputProvider for MainNodeFeeInputProvider {
     fn get_fee_model_params(&self) -> FeeParams {
-        match self.config {
+        match self.config() {
             FeeModelConfig::V1(config) => FeeParams::V1(FeeParamsV1 {
                 config,
                 l1_gas_price: self.provider.estimate_effective_gas_price(),
@@ -80,7 +84,25 @@ impl BatchFeeModelInputProvider for MainNodeFeeInputProvider {
 
 impl MainNodeFeeInputProvider {
     pub fn new(provider: Arc<GasAdjuster>, config: FeeModelConfig) -> Self {
-        Self { provider, config }
+        Self {
+            provider,
+            config: RwLock::new(config),
+        }
+    }
+
+    fn config(&self) -> FeeModelConfig {
+        *self.config.read().expect("fee model config is poisoned")
+    }
+
+    /// Overrides the minimal L2 gas price used by this provider. Returns the previous value of the price.
+    pub fn set_minimal_l2_gas_price(&self, price: u64) -> u64 {
+        let mut config = self.config.write().expect("fee model config is poisoned");
+        let prev_price = match &mut *config {
+            FeeModelConfig::V1(config) => mem::replace(&mut config.minimal_l2_gas_price, price),
+            FeeModelConfig::V2(config) => mem::replace(&mut config.minimal_l2_gas_price, price),
+        };
+        tracing::info!("Changed minimal L2 gas price from {prev_price} to {price}");
+        prev_price
     }
 }
 
@@ -5,7 +5,7 @@ use crate::{metrics::HOUSE_KEEPER_METRICS, periodic_job::PeriodicJob};
 
 /// FriGpuProverArchiver is a task that periodically archives old fri GPU prover records.
 /// The task will archive the `dead` prover records that have not been updated for a certain amount of time.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FriGpuProverArchiver {
     pool: ConnectionPool<Prover>,
     archiving_interval_ms: u64,
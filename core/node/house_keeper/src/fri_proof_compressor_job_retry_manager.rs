@@ -6,7 +6,7 @@ use zksync_dal::ConnectionPool;
 
 use crate::periodic_job::PeriodicJob;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FriProofCompressorJobRetryManager {
     pool: ConnectionPool<Prover>,
     max_attempts: u32,
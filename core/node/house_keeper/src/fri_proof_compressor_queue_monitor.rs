@@ -7,7 +7,7 @@ use crate::periodic_job::PeriodicJob;
 
 const PROOF_COMPRESSOR_SERVICE_NAME: &str = "proof_compressor";
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FriProofCompressorStatsReporter {
     reporting_interval_ms: u64,
     pool: ConnectionPool<Prover>,
@@ -3,7 +3,7 @@ use zksync_dal::ConnectionPool;
 
 use crate::{metrics::HOUSE_KEEPER_METRICS, periodic_job::PeriodicJob};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FriProverJobArchiver {
     pool: ConnectionPool<Prover>,
     reporting_interval_ms: u64,
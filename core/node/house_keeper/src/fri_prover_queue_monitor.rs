@@ -5,7 +5,7 @@ use zksync_dal::{ConnectionPool, Core, CoreDal};
 
 use crate::periodic_job::PeriodicJob;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FriProverStatsReporter {
     reporting_interval_ms: u64,
     prover_connection_pool: ConnectionPool<Prover>,
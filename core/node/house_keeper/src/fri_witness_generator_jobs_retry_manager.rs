@@ -6,7 +6,7 @@ use zksync_types::prover_dal::StuckJobs;
 
 use crate::periodic_job::PeriodicJob;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FriWitnessGeneratorJobRetryManager {
     pool: ConnectionPool<Prover>,
     max_attempts: u32,
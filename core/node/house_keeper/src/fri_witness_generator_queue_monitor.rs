@@ -9,7 +9,7 @@ use crate::periodic_job::PeriodicJob;
 
 const FRI_WITNESS_GENERATOR_SERVICE_NAME: &str = "fri_witness_generator";
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FriWitnessGeneratorStatsReporter {
     reporting_interval_ms: u64,
     pool: ConnectionPool<Prover>,
@@ -4,7 +4,7 @@ use zksync_dal::ConnectionPool;
 
 use crate::periodic_job::PeriodicJob;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WaitingToQueuedFriWitnessJobMover {
     job_moving_interval_ms: u64,
     pool: ConnectionPool<Prover>,
@@ -0,0 +1,446 @@
+//! An incomplete example of how external node initialization looks like.
+//! This example reuses the main node env config for the shared parts of the configuration; options specific
+//! to the external node (e.g., the main node URL) are read from the `EN_*` env variables.
+
+use std::{collections::HashMap, num::NonZeroUsize, time::Duration};
+
+use anyhow::Context;
+use zksync_block_reverter::NodeRole;
+use zksync_config::{
+    configs::{
+        chain::{NetworkConfig, OperationsManagerConfig, StateKeeperConfig},
+        consensus::{ConsensusConfig, ConsensusSecrets},
+        ObservabilityConfig,
+    },
+    ApiConfig, ContractsConfig, DBConfig, EthConfig, GenesisConfig, ObjectStoreConfig,
+    PostgresConfig,
+};
+use zksync_core::{
+    api_server::{
+        tx_sender::{ApiContracts, TxSenderConfig},
+        web3::{state::InternalApiConfig, Namespace},
+    },
+    metadata_calculator::MetadataCalculatorConfig,
+    temp_config_store::decode_yaml_repr,
+};
+use zksync_env_config::FromEnv;
+use zksync_node_db_pruner::{DbPrunerConfig, DbPrunerMode};
+use zksync_node_framework::{
+    implementations::layers::{
+        batch_status_updater::BatchStatusUpdaterLayer,
+        block_reverter::BlockReverterLayer,
+        commitment_generator::CommitmentGeneratorLayer,
+        consensus::{ConsensusLayer, Mode as ConsensusMode},
+        consistency_checker::ConsistencyCheckerLayer,
+        healtcheck_server::HealthCheckLayer,
+        l1_batch_commit_data_generator::L1BatchCommitDataGeneratorLayer,
+        main_node_client::MainNodeClientLayer,
+        main_node_fee_params_fetcher::MainNodeFeeParamsFetcherLayer,
+        metadata_calculator::MetadataCalculatorLayer,
+        object_store::{ObjectStoreLayer, TreeCheckpointStoreLayer},
+        pools_layer::PoolsLayerBuilder,
+        pruning::PruningLayer,
+        query_eth_client::QueryEthClientLayer,
+        reorg_detector::{ReorgDetectorCheckerLayer, ReorgDetectorRunnerLayer},
+        sigint::SigintHandlerLayer,
+        state_keeper::{
+            external_io::ExternalIOLayer, main_batch_executor::MainBatchExecutorLayer,
+            StateKeeperLayer,
+        },
+        storage_initialization::ExternalNodeStorageInitLayer,
+        web3_api::{
+            caches::MempoolCacheLayer,
+            server::{Web3ServerLayer, Web3ServerOptionalConfig},
+            tree_api_client::TreeApiClientLayer,
+            tx_sender::{PostgresStorageCachesConfig, TxSenderLayer},
+            tx_sink::TxSinkLayer,
+        },
+    },
+    service::{ZkStackService, ZkStackServiceBuilder, ZkStackServiceError},
+};
+use zksync_protobuf_config::proto;
+use zksync_types::url::SensitiveUrl;
+
+/// Configuration options specific to the external node.
+#[derive(Debug)]
+struct ExternalNodeEnvConfig {
+    main_node_url: SensitiveUrl,
+    main_node_rate_limit_rps: NonZeroUsize,
+    snapshots_recovery_enabled: bool,
+    pruning_enabled: bool,
+}
+
+impl ExternalNodeEnvConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        fn read_flag(name: &str) -> anyhow::Result<bool> {
+            match std::env::var(name) {
+                Ok(value) => value.parse().with_context(|| format!("invalid {name}")),
+                Err(_) => Ok(false),
+            }
+        }
+
+        let main_node_url = std::env::var("EN_MAIN_NODE_URL")
+            .context("EN_MAIN_NODE_URL")?
+            .parse()
+            .context("invalid EN_MAIN_NODE_URL")?;
+        let main_node_rate_limit_rps = match std::env::var("EN_MAIN_NODE_RATE_LIMIT_RPS") {
+            Ok(value) => value
+                .parse()
+                .context("invalid EN_MAIN_NODE_RATE_LIMIT_RPS")?,
+            Err(_) => NonZeroUsize::new(100).unwrap(),
+        };
+        Ok(Self {
+            main_node_url,
+            main_node_rate_limit_rps,
+            snapshots_recovery_enabled: read_flag("EN_SNAPSHOTS_RECOVERY_ENABLED")?,
+            pruning_enabled: read_flag("EN_PRUNING_ENABLED")?,
+        })
+    }
+}
+
+struct ExternalNodeBuilder {
+    node: ZkStackServiceBuilder,
+    en_config: ExternalNodeEnvConfig,
+}
+
+impl ExternalNodeBuilder {
+    fn new() -> anyhow::Result<Self> {
+        Ok(Self {
+            node: ZkStackServiceBuilder::new(),
+            en_config: ExternalNodeEnvConfig::from_env()?,
+        })
+    }
+
+    fn add_sigint_handler_layer(mut self) -> anyhow::Result<Self> {
+        self.node.add_layer(SigintHandlerLayer);
+        Ok(self)
+    }
+
+    fn add_pools_layer(mut self) -> anyhow::Result<Self> {
+        let config = PostgresConfig::from_env()?;
+        let pools_layer = PoolsLayerBuilder::empty(config)
+            .with_master(true)
+            .with_replica(true)
+            .build();
+        self.node.add_layer(pools_layer);
+        Ok(self)
+    }
+
+    fn add_main_node_client_layer(mut self) -> anyhow::Result<Self> {
+        self.node.add_layer(MainNodeClientLayer::new(
+            self.en_config.main_node_url.clone(),
+            self.en_config.main_node_rate_limit_rps,
+        ));
+        Ok(self)
+    }
+
+    fn add_query_eth_client_layer(mut self) -> anyhow::Result<Self> {
+        let eth_client_config = EthConfig::from_env()?;
+        let query_eth_client_layer = QueryEthClientLayer::new(eth_client_config.web3_url);
+        self.node.add_layer(query_eth_client_layer);
+        Ok(self)
+    }
+
+    fn add_main_node_fee_params_fetcher_layer(mut self) -> anyhow::Result<Self> {
+        self.node.add_layer(MainNodeFeeParamsFetcherLayer);
+        Ok(self)
+    }
+
+    fn add_storage_initialization_layer(mut self) -> anyhow::Result<Self> {
+        let genesis_config = GenesisConfig::from_env()?;
+        if self.en_config.snapshots_recovery_enabled {
+            let object_store_config = ObjectStoreConfig::from_env()?;
+            self.node
+                .add_layer(ObjectStoreLayer::new(object_store_config));
+        }
+        self.node.add_layer(
+            ExternalNodeStorageInitLayer::new(genesis_config.l2_chain_id)
+                .with_snapshot_recovery(self.en_config.snapshots_recovery_enabled),
+        );
+        Ok(self)
+    }
+
+    fn add_reorg_detector_layers(mut self) -> anyhow::Result<Self> {
+        let db_config = DBConfig::from_env()?;
+        // Reverting executed batches is more-or-less safe for external nodes.
+        let block_reverter_layer = BlockReverterLayer::new(NodeRole::External)
+            .allow_rolling_back_executed_batches()
+            .enable_rolling_back_postgres()
+            .enable_rolling_back_merkle_tree(db_config.merkle_tree.path)
+            .enable_rolling_back_state_keeper_cache(db_config.state_keeper_db_path);
+        self.node
+            .add_layer(block_reverter_layer)
+            .add_layer(ReorgDetectorCheckerLayer)
+            .add_layer(ReorgDetectorRunnerLayer);
+        Ok(self)
+    }
+
+    fn add_state_keeper_layer(mut self) -> anyhow::Result<Self> {
+        let genesis_config = GenesisConfig::from_env()?;
+        let contracts_config = ContractsConfig::from_env()?;
+        let state_keeper_config = StateKeeperConfig::from_env()?;
+        let external_io_layer = ExternalIOLayer::new(
+            genesis_config.l2_chain_id,
+            contracts_config
+                .l2_shared_bridge_addr
+                .context("L2 shared bridge address is not set")?,
+            state_keeper_config.l2_block_seal_queue_capacity,
+        )
+        .with_protective_reads_persistence(
+            state_keeper_config.protective_reads_persistence_enabled,
+        );
+        // The main node may include transactions with uncompressed bytecodes, so compression is optional for EN.
+        let batch_executor_layer =
+            MainBatchExecutorLayer::new(DBConfig::from_env()?, state_keeper_config)
+                .with_optional_bytecode_compression(true);
+        self.node
+            .add_layer(external_io_layer)
+            .add_layer(batch_executor_layer)
+            .add_layer(StateKeeperLayer);
+        Ok(self)
+    }
+
+    fn add_consensus_layer(mut self) -> anyhow::Result<Self> {
+        fn read_consensus_secrets() -> anyhow::Result<Option<ConsensusSecrets>> {
+            let Ok(path) = std::env::var("EN_CONSENSUS_SECRETS_PATH") else {
+                return Ok(None);
+            };
+            let secrets = std::fs::read_to_string(&path).context(path)?;
+            Ok(Some(
+                decode_yaml_repr::<proto::consensus::Secrets>(&secrets)
+                    .context("failed decoding YAML")?,
+            ))
+        }
+
+        fn read_consensus_config() -> anyhow::Result<Option<ConsensusConfig>> {
+            let Ok(path) = std::env::var("EN_CONSENSUS_CONFIG_PATH") else {
+                return Ok(None);
+            };
+            let cfg = std::fs::read_to_string(&path).context(path)?;
+            Ok(Some(
+                decode_yaml_repr::<proto::consensus::Config>(&cfg)
+                    .context("failed decoding YAML")?,
+            ))
+        }
+
+        let genesis = GenesisConfig::from_env()?;
+        let config = read_consensus_config().context("read_consensus_config()")?;
+        let secrets = read_consensus_secrets().context("read_consensus_secrets()")?;
+
+        // In the external mode, the consensus layer runs the fetcher of L2 blocks from the main node
+        // (via P2P if consensus is configured, or via JSON-RPC otherwise).
+        self.node.add_layer(ConsensusLayer {
+            mode: ConsensusMode::External,
+            config,
+            secrets,
+            chain_id: genesis.l2_chain_id,
+        });
+        Ok(self)
+    }
+
+    fn add_pruning_layer(mut self) -> anyhow::Result<Self> {
+        if self.en_config.pruning_enabled {
+            self.node.add_layer(PruningLayer::new(DbPrunerConfig {
+                removal_delay: Duration::from_secs(60),
+                pruned_batch_chunk_size: 10,
+                minimum_l1_batch_age: Duration::from_secs(3_600),
+                minimum_retained_l1_batches: 0,
+                mode: DbPrunerMode::ExternalNode,
+                dry_run: false,
+                data_class_retention: HashMap::new(),
+            }));
+        }
+        Ok(self)
+    }
+
+    fn add_batch_status_updater_layer(mut self) -> anyhow::Result<Self> {
+        self.node.add_layer(BatchStatusUpdaterLayer);
+        Ok(self)
+    }
+
+    fn add_consistency_checker_layer(mut self) -> anyhow::Result<Self> {
+        let genesis_config = GenesisConfig::from_env()?;
+        let contracts_config = ContractsConfig::from_env()?;
+        self.node
+            .add_layer(L1BatchCommitDataGeneratorLayer::new(
+                genesis_config.l1_batch_commit_data_generator_mode,
+            ))
+            .add_layer(ConsistencyCheckerLayer::new(
+                contracts_config.diamond_proxy_addr,
+                10,
+            ));
+        Ok(self)
+    }
+
+    fn add_commitment_generator_layer(mut self) -> anyhow::Result<Self> {
+        let genesis = GenesisConfig::from_env()?;
+        self.node.add_layer(CommitmentGeneratorLayer::new(
+            genesis.l1_batch_commit_data_generator_mode,
+        ));
+        Ok(self)
+    }
+
+    fn add_metadata_calculator_layer(mut self) -> anyhow::Result<Self> {
+        let merkle_tree_env_config = DBConfig::from_env()?.merkle_tree;
+        let operations_manager_env_config = OperationsManagerConfig::from_env()?;
+        let metadata_calculator_config = MetadataCalculatorConfig::for_main_node(
+            &merkle_tree_env_config,
+            &operations_manager_env_config,
+        );
+        if merkle_tree_env_config
+            .checkpoint_interval_l1_batches
+            .is_some()
+        {
+            let object_store_config = ObjectStoreConfig::from_env()?;
+            self.node
+                .add_layer(TreeCheckpointStoreLayer::new(object_store_config));
+        }
+        self.node
+            .add_layer(MetadataCalculatorLayer::new(metadata_calculator_config));
+        Ok(self)
+    }
+
+    fn add_healthcheck_layer(mut self) -> anyhow::Result<Self> {
+        let healthcheck_config = ApiConfig::from_env()?.healthcheck;
+        self.node.add_layer(HealthCheckLayer(healthcheck_config));
+        Ok(self)
+    }
+
+    fn add_tx_sender_layer(mut self) -> anyhow::Result<Self> {
+        let state_keeper_config = StateKeeperConfig::from_env()?;
+        let rpc_config = ApiConfig::from_env()?.web3_json_rpc;
+        let network_config = NetworkConfig::from_env()?;
+        let postgres_storage_caches_config = PostgresStorageCachesConfig {
+            factory_deps_cache_size: rpc_config.factory_deps_cache_size() as u64,
+            initial_writes_cache_size: rpc_config.initial_writes_cache_size() as u64,
+            latest_values_cache_size: rpc_config.latest_values_cache_size() as u64,
+        };
+
+        // On external node, transactions are proxied to the main node. The fee account
+        // is irrelevant for the external node since it doesn't seal batches.
+        self.node.add_layer(TxSinkLayer::ProxySink);
+        self.node.add_layer(TxSenderLayer::new(
+            TxSenderConfig::new(
+                &state_keeper_config,
+                &rpc_config,
+                Default::default(),
+                network_config.zksync_network_id,
+            ),
+            postgres_storage_caches_config,
+            rpc_config.vm_concurrency_limit(),
+            ApiContracts::load_from_disk(),
+        ));
+        Ok(self)
+    }
+
+    fn add_api_caches_layer(mut self) -> anyhow::Result<Self> {
+        let rpc_config = ApiConfig::from_env()?.web3_json_rpc;
+        self.node.add_layer(MempoolCacheLayer::new(
+            rpc_config.mempool_cache_size(),
+            rpc_config.mempool_cache_update_interval(),
+        ));
+        Ok(self)
+    }
+
+    fn add_tree_api_client_layer(mut self) -> anyhow::Result<Self> {
+        let rpc_config = ApiConfig::from_env()?.web3_json_rpc;
+        self.node
+            .add_layer(TreeApiClientLayer::http(rpc_config.tree_api_url));
+        Ok(self)
+    }
+
+    fn web3_api_optional_config(rpc_config: &ApiConfig) -> Web3ServerOptionalConfig {
+        let rpc_config = &rpc_config.web3_json_rpc;
+        Web3ServerOptionalConfig {
+            namespaces: Some(Namespace::DEFAULT.to_vec()),
+            filters_limit: Some(rpc_config.filters_limit()),
+            subscriptions_limit: Some(rpc_config.subscriptions_limit()),
+            batch_request_size_limit: Some(rpc_config.max_batch_request_size()),
+            response_body_size_limit: Some(rpc_config.max_response_body_size()),
+            ..Default::default()
+        }
+    }
+
+    fn add_http_web3_api_layer(mut self) -> anyhow::Result<Self> {
+        let api_config = ApiConfig::from_env()?;
+        let contracts_config = ContractsConfig::from_env()?;
+        let genesis_config = GenesisConfig::from_env()?;
+        let optional_config = Self::web3_api_optional_config(&api_config);
+        self.node.add_layer(Web3ServerLayer::http(
+            api_config.web3_json_rpc.http_port,
+            InternalApiConfig::new(
+                &api_config.web3_json_rpc,
+                &contracts_config,
+                &genesis_config,
+            ),
+            optional_config,
+        ));
+        Ok(self)
+    }
+
+    fn add_ws_web3_api_layer(mut self) -> anyhow::Result<Self> {
+        let api_config = ApiConfig::from_env()?;
+        let contracts_config = ContractsConfig::from_env()?;
+        let genesis_config = GenesisConfig::from_env()?;
+        let optional_config = Web3ServerOptionalConfig {
+            websocket_requests_per_minute_limit: Some(
+                api_config
+                    .web3_json_rpc
+                    .websocket_requests_per_minute_limit(),
+            ),
+            ..Self::web3_api_optional_config(&api_config)
+        };
+        self.node.add_layer(Web3ServerLayer::ws(
+            api_config.web3_json_rpc.ws_port,
+            InternalApiConfig::new(
+                &api_config.web3_json_rpc,
+                &contracts_config,
+                &genesis_config,
+            ),
+            optional_config,
+        ));
+        Ok(self)
+    }
+
+    fn build(mut self) -> Result<ZkStackService, ZkStackServiceError> {
+        self.node.build()
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(log_format)
+        .build();
+
+    ExternalNodeBuilder::new()?
+        .add_sigint_handler_layer()?
+        .add_pools_layer()?
+        .add_main_node_client_layer()?
+        .add_query_eth_client_layer()?
+        .add_main_node_fee_params_fetcher_layer()?
+        .add_storage_initialization_layer()?
+        .add_reorg_detector_layers()?
+        .add_state_keeper_layer()?
+        .add_consensus_layer()?
+        .add_pruning_layer()?
+        .add_batch_status_updater_layer()?
+        .add_consistency_checker_layer()?
+        .add_commitment_generator_layer()?
+        .add_metadata_calculator_layer()?
+        .add_healthcheck_layer()?
+        .add_tx_sender_layer()?
+        .add_api_caches_layer()?
+        .add_tree_api_client_layer()?
+        .add_http_web3_api_layer()?
+        .add_ws_web3_api_layer()?
+        .build()?
+        .run()?;
+
+    Ok(())
+}
@@ -3,6 +3,7 @@
 //! initializes a single task with a health check server.
 
 use anyhow::Context;
+use clap::{Parser, ValueEnum};
 use zksync_config::{
     configs::{
         chain::{
@@ -12,9 +13,10 @@ use zksync_config::{
         consensus::{ConsensusConfig, ConsensusSecrets},
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
+        vm_runner::ProtectiveReadsWriterConfig,
         wallets::Wallets,
         FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, ObservabilityConfig,
-        ProofDataHandlerConfig,
+        ProofDataHandlerConfig, PruningConfig,
     },
     ApiConfig, ContractVerifierConfig, ContractsConfig, DBConfig, EthConfig, EthWatchConfig,
     GasAdjusterConfig, GenesisConfig, ObjectStoreConfig, PostgresConfig,
@@ -28,6 +30,7 @@ use zksync_core::{
     temp_config_store::decode_yaml_repr,
 };
 use zksync_env_config::FromEnv;
+use zksync_node_db_pruner::DbPrunerConfig;
 use zksync_node_framework::{
     implementations::layers::{
         circuit_breaker_checker::CircuitBreakerCheckerLayer,
@@ -40,17 +43,20 @@ use zksync_node_framework::{
         house_keeper::HouseKeeperLayer,
         l1_gas::SequencerL1GasLayer,
         metadata_calculator::MetadataCalculatorLayer,
-        object_store::ObjectStoreLayer,
+        object_store::{ObjectStoreLayer, TreeCheckpointStoreLayer},
         pk_signing_eth_client::PKSigningEthClientLayer,
         pools_layer::PoolsLayerBuilder,
         proof_data_handler::ProofDataHandlerLayer,
+        pruning::PruningLayer,
         query_eth_client::QueryEthClientLayer,
         sigint::SigintHandlerLayer,
         state_keeper::{
             main_batch_executor::MainBatchExecutorLayer, mempool_io::MempoolIOLayer,
             StateKeeperLayer,
         },
+        vm_runner::protective_reads::ProtectiveReadsWriterLayer,
         web3_api::{
+            admin::AdminApiLayer,
             caches::MempoolCacheLayer,
             server::{Web3ServerLayer, Web3ServerOptionalConfig},
             tree_api_client::TreeApiClientLayer,
@@ -58,10 +64,27 @@ use zksync_node_framework::{
             tx_sink::TxSinkLayer,
         },
     },
-    service::{ZkStackService, ZkStackServiceBuilder, ZkStackServiceError},
+    service::{WiringGraph, ZkStackService, ZkStackServiceBuilder, ZkStackServiceError},
 };
 use zksync_protobuf_config::proto;
 
+/// Format of the wiring graph printed in the dry-run mode.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GraphFormat {
+    Json,
+    Dot,
+}
+
+#[derive(Debug, Parser)]
+struct Cli {
+    /// Wire all the components without running them, and print the resulting resource / task dependency graph.
+    #[arg(long)]
+    dry_run: bool,
+    /// Format of the dependency graph printed in the dry-run mode.
+    #[arg(long, value_enum, default_value_t = GraphFormat::Json)]
+    graph_format: GraphFormat,
+}
+
 struct MainNodeBuilder {
     node: ZkStackServiceBuilder,
 }
@@ -122,7 +145,8 @@ impl MainNodeBuilder {
                 .sender
                 .context("eth_sender")?
                 .pubdata_sending_mode,
-        );
+        )
+        .with_base_fee_ceiling(CircuitBreakerConfig::from_env()?.l1_base_fee_ceiling);
         self.node.add_layer(sequencer_l1_gas_layer);
         Ok(self)
     }
@@ -141,8 +165,20 @@ impl MainNodeBuilder {
             &merkle_tree_env_config,
             &operations_manager_env_config,
         );
-        self.node
-            .add_layer(MetadataCalculatorLayer(metadata_calculator_config));
+        if merkle_tree_env_config
+            .checkpoint_interval_l1_batches
+            .is_some()
+        {
+            // Checkpoints are stored in the same way as other data produced by the main node.
+            let object_store_config = ObjectStoreConfig::from_env()?;
+            self.node
+                .add_layer(TreeCheckpointStoreLayer::new(object_store_config));
+        }
+        let tree_lag_limit = CircuitBreakerConfig::from_env()?.tree_lag_limit;
+        self.node.add_layer(
+            MetadataCalculatorLayer::new(metadata_calculator_config)
+                .with_tree_lag_limit(tree_lag_limit),
+        );
         Ok(self)
     }
 
@@ -174,9 +210,12 @@ impl MainNodeBuilder {
     }
 
     fn add_proof_data_handler_layer(mut self) -> anyhow::Result<Self> {
-        self.node.add_layer(ProofDataHandlerLayer::new(
-            ProofDataHandlerConfig::from_env()?,
-        ));
+        self.node.add_layer(
+            ProofDataHandlerLayer::new(ProofDataHandlerConfig::from_env()?)
+                .with_unproven_batches_limit(
+                    CircuitBreakerConfig::from_env()?.unproven_batches_limit,
+                ),
+        );
         Ok(self)
     }
 
@@ -296,6 +335,14 @@ impl MainNodeBuilder {
 
         Ok(self)
     }
+    fn add_admin_api_layer(mut self) -> anyhow::Result<Self> {
+        let rpc_config = ApiConfig::from_env()?.web3_json_rpc;
+        if let Some(admin_port) = rpc_config.admin_port {
+            self.node.add_layer(AdminApiLayer::new(admin_port));
+        }
+        Ok(self)
+    }
+
     fn add_eth_sender_layer(mut self) -> anyhow::Result<Self> {
         let eth_sender_config = EthConfig::from_env()?;
         let contracts_config = ContractsConfig::from_env()?;
@@ -339,6 +386,17 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
+    fn add_protective_reads_writer_layer(mut self) -> anyhow::Result<Self> {
+        let config = ProtectiveReadsWriterConfig::from_env()?;
+        let network_config = NetworkConfig::from_env()?;
+        self.node.add_layer(ProtectiveReadsWriterLayer::new(
+            config,
+            network_config.zksync_network_id,
+        ));
+
+        Ok(self)
+    }
+
     fn add_circuit_breaker_checker_layer(mut self) -> anyhow::Result<Self> {
         let circuit_breaker_config = CircuitBreakerConfig::from_env()?;
         self.node
@@ -347,6 +405,15 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
+    fn add_pruning_layer(mut self) -> anyhow::Result<Self> {
+        let config = PruningConfig::from_env()?;
+        if config.enabled {
+            self.node
+                .add_layer(PruningLayer::new(DbPrunerConfig::for_main_node(&config)));
+        }
+        Ok(self)
+    }
+
     fn add_contract_verification_api_layer(mut self) -> anyhow::Result<Self> {
         let config = ContractVerifierConfig::from_env()?;
         self.node.add_layer(ContractVerificationApiLayer(config));
@@ -397,9 +464,14 @@ impl MainNodeBuilder {
     fn build(mut self) -> Result<ZkStackService, ZkStackServiceError> {
         self.node.build()
     }
+
+    fn dry_run(mut self) -> Result<WiringGraph, ZkStackServiceError> {
+        self.node.dry_run()
+    }
 }
 
 fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
     let observability_config =
         ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
     let log_format: vlog::LogFormat = observability_config
@@ -410,7 +482,7 @@ fn main() -> anyhow::Result<()> {
         .with_log_format(log_format)
         .build();
 
-    MainNodeBuilder::new()
+    let node = MainNodeBuilder::new()
         .add_sigint_handler_layer()?
         .add_pools_layer()?
         .add_circuit_breaker_checker_layer()?
@@ -429,12 +501,33 @@ fn main() -> anyhow::Result<()> {
         .add_api_caches_layer()?
         .add_http_web3_api_layer()?
         .add_ws_web3_api_layer()?
+        .add_admin_api_layer()?
         .add_house_keeper_layer()?
         .add_commitment_generator_layer()?
+        .add_protective_reads_writer_layer()?
+        .add_pruning_layer()?
         .add_contract_verification_api_layer()?
-        .add_consensus_layer()?
-        .build()?
-        .run()?;
+        .add_consensus_layer()?;
+
+    if cli.dry_run {
+        let graph = node.dry_run()?;
+        for missing in &graph.missing_resources {
+            tracing::warn!(
+                "Resource {} was requested by {:?}, but wasn't provided",
+                missing.resource,
+                missing.requested_by
+            );
+        }
+        for resource in &graph.unused_resources {
+            tracing::warn!("Resource {resource} is provided, but not used by any layer");
+        }
+        match cli.graph_format {
+            GraphFormat::Json => println!("{}", graph.to_json()),
+            GraphFormat::Dot => print!("{}", graph.to_dot()),
+        }
+        return Ok(());
+    }
 
+    node.build()?.run()?;
     Ok(())
 }
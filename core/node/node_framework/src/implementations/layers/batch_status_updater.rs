@@ -0,0 +1,61 @@
+use zksync_core::sync_layer::batch_status_updater::BatchStatusUpdater;
+
+use crate::{
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        main_node_client::MainNodeClientResource,
+        pools::{MasterPool, PoolResource},
+    },
+    service::{ServiceContext, StopReceiver},
+    task::Task,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for the batch status updater of the external node, which updates commit / prove / execute
+/// statuses of L1 batches based on the data from the main node.
+///
+/// Requests:
+/// - `PoolResource<MasterPool>`
+/// - `MainNodeClientResource`
+/// - `AppHealthCheckResource` (adds a health check)
+#[derive(Debug)]
+pub struct BatchStatusUpdaterLayer;
+
+#[async_trait::async_trait]
+impl WiringLayer for BatchStatusUpdaterLayer {
+    fn layer_name(&self) -> &'static str {
+        "batch_status_updater_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let pool_resource = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let pool = pool_resource.get_singleton().await?;
+        let MainNodeClientResource(main_node_client) = context.get_resource().await?;
+
+        let updater = BatchStatusUpdater::new(main_node_client, pool);
+
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health
+            .insert_component(updater.health_check())
+            .map_err(WiringError::internal)?;
+
+        context.add_task(Box::new(BatchStatusUpdaterTask { updater }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct BatchStatusUpdaterTask {
+    updater: BatchStatusUpdater,
+}
+
+#[async_trait::async_trait]
+impl Task for BatchStatusUpdaterTask {
+    fn name(&self) -> &'static str {
+        "batch_status_updater"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.updater.run(stop_receiver.0).await
+    }
+}
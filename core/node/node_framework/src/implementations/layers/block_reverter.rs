@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use zksync_block_reverter::{BlockReverter, NodeRole};
+
+use crate::{
+    implementations::resources::{
+        pools::{MasterPool, PoolResource},
+        reverter::BlockReverterResource,
+    },
+    service::ServiceContext,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for the block reverter. The reverter can be used by other components
+/// (e.g., the reorg detector) to roll back the node state.
+///
+/// Requests:
+/// - `PoolResource<MasterPool>`
+///
+/// Provides:
+/// - `BlockReverterResource`
+#[derive(Debug)]
+pub struct BlockReverterLayer {
+    node_role: NodeRole,
+    allow_rolling_back_executed_batches: bool,
+    should_roll_back_postgres: bool,
+    state_keeper_cache_path: Option<String>,
+    merkle_tree_path: Option<String>,
+}
+
+impl BlockReverterLayer {
+    pub fn new(node_role: NodeRole) -> Self {
+        Self {
+            node_role,
+            allow_rolling_back_executed_batches: false,
+            should_roll_back_postgres: false,
+            state_keeper_cache_path: None,
+            merkle_tree_path: None,
+        }
+    }
+
+    pub fn allow_rolling_back_executed_batches(mut self) -> Self {
+        self.allow_rolling_back_executed_batches = true;
+        self
+    }
+
+    pub fn enable_rolling_back_postgres(mut self) -> Self {
+        self.should_roll_back_postgres = true;
+        self
+    }
+
+    pub fn enable_rolling_back_merkle_tree(mut self, path: String) -> Self {
+        self.merkle_tree_path = Some(path);
+        self
+    }
+
+    pub fn enable_rolling_back_state_keeper_cache(mut self, path: String) -> Self {
+        self.state_keeper_cache_path = Some(path);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for BlockReverterLayer {
+    fn layer_name(&self) -> &'static str {
+        "block_reverter_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let pool_resource = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let pool = pool_resource.get().await?;
+
+        let mut block_reverter = BlockReverter::new(self.node_role, pool);
+        if self.allow_rolling_back_executed_batches {
+            block_reverter.allow_rolling_back_executed_batches();
+        }
+        if self.should_roll_back_postgres {
+            block_reverter.enable_rolling_back_postgres();
+        }
+        if let Some(path) = self.merkle_tree_path {
+            block_reverter.enable_rolling_back_merkle_tree(path);
+        }
+        if let Some(path) = self.state_keeper_cache_path {
+            block_reverter.enable_rolling_back_state_keeper_cache(path);
+        }
+
+        context.insert_resource(BlockReverterResource(Arc::new(block_reverter)))?;
+        Ok(())
+    }
+}
@@ -2,7 +2,9 @@ use zksync_circuit_breaker::CircuitBreakerChecker;
 use zksync_config::configs::chain::CircuitBreakerConfig;
 
 use crate::{
-    implementations::resources::circuit_breakers::CircuitBreakersResource,
+    implementations::resources::{
+        circuit_breakers::CircuitBreakersResource, healthcheck::AppHealthCheckResource,
+    },
     service::{ServiceContext, StopReceiver},
     task::UnconstrainedTask,
     wiring_layer::{WiringError, WiringLayer},
@@ -24,7 +26,14 @@ impl WiringLayer for CircuitBreakerCheckerLayer {
             .await;
 
         let circuit_breaker_checker =
-            CircuitBreakerChecker::new(circuit_breaker_resource.breakers, self.0.sync_interval());
+            CircuitBreakerChecker::new(circuit_breaker_resource.breakers, self.0.sync_interval())
+                .with_soft_circuit_breakers(self.0.soft_circuit_breakers)
+                .map_err(|err| WiringError::Configuration(format!("{err:#}")))?;
+
+        let AppHealthCheckResource(app_health) = node.get_resource_or_default().await;
+        app_health
+            .insert_component(circuit_breaker_checker.health_check())
+            .map_err(WiringError::internal)?;
 
         // Create and insert task.
         let task = CircuitBreakerCheckerTask {
@@ -4,7 +4,7 @@ use zksync_dal::{ConnectionPool, Core};
 use crate::{
     implementations::resources::pools::{MasterPool, PoolResource, ReplicaPool},
     service::{ServiceContext, StopReceiver},
-    task::Task,
+    task::{RestartBackoff, RestartPolicy, Task, TaskFactory},
     wiring_layer::{WiringError, WiringLayer},
 };
 
@@ -28,16 +28,24 @@ impl WiringLayer for ContractVerificationApiLayer {
             .await?
             .get()
             .await?;
-        context.add_task(Box::new(ContractVerificationApiTask {
+        // The contract verification API is not critical for the node, so it's restarted on failure
+        // instead of shutting down the node.
+        let task = ContractVerificationApiTask {
             master_pool,
             replica_pool,
             config: self.0,
-        }));
+        };
+        context
+            .add_restartable_task(
+                Box::new(task),
+                RestartPolicy::OnFailure(RestartBackoff::default()),
+            )
+            .await?;
         Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ContractVerificationApiTask {
     master_pool: ConnectionPool<Core>,
     replica_pool: ConnectionPool<Core>,
@@ -60,3 +68,13 @@ impl Task for ContractVerificationApiTask {
         .await
     }
 }
+
+impl TaskFactory for ContractVerificationApiTask {
+    fn name(&self) -> &'static str {
+        "contract_verification_api"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(self.clone()))
+    }
+}
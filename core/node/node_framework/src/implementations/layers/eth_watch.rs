@@ -3,7 +3,7 @@ use std::time::Duration;
 use zksync_config::{ContractsConfig, EthWatchConfig};
 use zksync_contracts::governance_contract;
 use zksync_dal::{ConnectionPool, Core};
-use zksync_eth_watch::{EthHttpQueryClient, EthWatch};
+use zksync_eth_watch::{EthHttpQueryClient, EthWatch, EventProcessor};
 use zksync_types::{ethabi::Contract, Address};
 
 use crate::{
@@ -20,6 +20,7 @@ use crate::{
 pub struct EthWatchLayer {
     eth_watch_config: EthWatchConfig,
     contracts_config: ContractsConfig,
+    event_processors: Vec<Box<dyn EventProcessor>>,
 }
 
 impl EthWatchLayer {
@@ -27,8 +28,15 @@ impl EthWatchLayer {
         Self {
             eth_watch_config,
             contracts_config,
+            event_processors: Vec::new(),
         }
     }
+
+    /// Registers a custom event processor in addition to the built-in ones.
+    pub fn with_event_processor(mut self, processor: Box<dyn EventProcessor>) -> Self {
+        self.event_processors.push(processor);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -56,7 +64,7 @@ impl WiringLayer for EthWatchLayer {
                 .ecosystem_contracts
                 .map(|a| a.transparent_proxy_admin_addr),
             self.contracts_config.governance_addr,
-            self.eth_watch_config.confirmations_for_eth_event,
+            self.eth_watch_config.event_confirmations(),
         );
         context.add_task(Box::new(EthWatchTask {
             main_pool,
@@ -65,6 +73,7 @@ impl WiringLayer for EthWatchLayer {
             state_transition_manager_address,
             diamond_proxy_address: self.contracts_config.diamond_proxy_addr,
             poll_interval: self.eth_watch_config.poll_interval(),
+            event_processors: self.event_processors,
         }));
 
         Ok(())
@@ -79,6 +88,7 @@ struct EthWatchTask {
     state_transition_manager_address: Option<Address>,
     diamond_proxy_address: Address,
     poll_interval: Duration,
+    event_processors: Vec<Box<dyn EventProcessor>>,
 }
 
 #[async_trait::async_trait]
@@ -88,7 +98,7 @@ impl Task for EthWatchTask {
     }
 
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
-        let eth_watch = EthWatch::new(
+        let mut eth_watch = EthWatch::new(
             self.diamond_proxy_address,
             self.state_transition_manager_address,
             &self.governance_contract,
@@ -97,6 +107,9 @@ impl Task for EthWatchTask {
             self.poll_interval,
         )
         .await?;
+        for processor in self.event_processors {
+            eth_watch.add_event_processor(processor)?;
+        }
 
         eth_watch.run(stop_receiver.0).await
     }
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use zksync_config::configs::api::HealthCheckConfig;
-use zksync_core::api_server::healthcheck::HealthCheckHandle;
+use zksync_core::api_server::healthcheck::{HealthCheckHandle, HealthProbes};
 use zksync_health_check::AppHealthCheck;
 
 use crate::{
@@ -61,8 +61,12 @@ impl UnconstrainedTask for HealthCheckTask {
         mut self: Box<Self>,
         mut stop_receiver: StopReceiver,
     ) -> anyhow::Result<()> {
-        let handle =
-            HealthCheckHandle::spawn_server(self.config.bind_addr(), self.app_health_check.clone());
+        let handle = HealthCheckHandle::spawn_server(
+            self.config.bind_addr(),
+            self.app_health_check.clone(),
+            HealthProbes::from(&self.config),
+            self.config.expose_slow_query_plans,
+        );
         stop_receiver.0.changed().await?;
         handle.stop().await;
 
@@ -21,7 +21,7 @@ use zksync_house_keeper::{
 use crate::{
     implementations::resources::pools::{PoolResource, ProverPool, ReplicaPool},
     service::{ServiceContext, StopReceiver},
-    task::Task,
+    task::{RestartBackoff, RestartPolicy, Task, TaskFactory},
     wiring_layer::{WiringError, WiringLayer},
 };
 
@@ -68,18 +68,30 @@ impl WiringLayer for HouseKeeperLayer {
         let prover_pool_resource = context.get_resource::<PoolResource<ProverPool>>().await?;
         let prover_pool = prover_pool_resource.get().await?;
 
-        // initialize and add tasks
+        // initialize and add tasks; house keeper tasks are auxiliary, so they are restarted on failure
+        // instead of shutting down the node.
+        let restart_policy = RestartPolicy::OnFailure(RestartBackoff::default());
         let pool_for_metrics = replica_pool_resource.get_singleton().await?;
-        context.add_task(Box::new(PostgresMetricsScrapingTask { pool_for_metrics }));
+        context
+            .add_restartable_task(
+                Box::new(PostgresMetricsScrapingTask { pool_for_metrics }),
+                restart_policy,
+            )
+            .await?;
 
         let l1_batch_metrics_reporter = L1BatchMetricsReporter::new(
             self.house_keeper_config
                 .l1_batch_metrics_reporting_interval_ms,
             replica_pool.clone(),
         );
-        context.add_task(Box::new(L1BatchMetricsReporterTask {
-            l1_batch_metrics_reporter,
-        }));
+        context
+            .add_restartable_task(
+                Box::new(L1BatchMetricsReporterTask {
+                    l1_batch_metrics_reporter,
+                }),
+                restart_policy,
+            )
+            .await?;
 
         let fri_prover_job_retry_manager = FriProverJobRetryManager::new(
             self.fri_prover_config.max_attempts,
@@ -87,9 +99,14 @@ impl WiringLayer for HouseKeeperLayer {
             self.house_keeper_config.prover_job_retrying_interval_ms,
             prover_pool.clone(),
         );
-        context.add_task(Box::new(FriProverJobRetryManagerTask {
-            fri_prover_job_retry_manager,
-        }));
+        context
+            .add_restartable_task(
+                Box::new(FriProverJobRetryManagerTask {
+                    fri_prover_job_retry_manager,
+                }),
+                restart_policy,
+            )
+            .await?;
 
         let fri_witness_gen_job_retry_manager = FriWitnessGeneratorJobRetryManager::new(
             self.fri_witness_generator_config.max_attempts,
@@ -99,26 +116,41 @@ impl WiringLayer for HouseKeeperLayer {
                 .witness_generator_job_retrying_interval_ms,
             prover_pool.clone(),
         );
-        context.add_task(Box::new(FriWitnessGeneratorJobRetryManagerTask {
-            fri_witness_gen_job_retry_manager,
-        }));
+        context
+            .add_restartable_task(
+                Box::new(FriWitnessGeneratorJobRetryManagerTask {
+                    fri_witness_gen_job_retry_manager,
+                }),
+                restart_policy,
+            )
+            .await?;
 
         let waiting_to_queued_fri_witness_job_mover = WaitingToQueuedFriWitnessJobMover::new(
             self.house_keeper_config.witness_job_moving_interval_ms,
             prover_pool.clone(),
         );
-        context.add_task(Box::new(WaitingToQueuedFriWitnessJobMoverTask {
-            waiting_to_queued_fri_witness_job_mover,
-        }));
+        context
+            .add_restartable_task(
+                Box::new(WaitingToQueuedFriWitnessJobMoverTask {
+                    waiting_to_queued_fri_witness_job_mover,
+                }),
+                restart_policy,
+            )
+            .await?;
 
         if let Some((archiving_interval, archive_after)) =
             self.house_keeper_config.prover_job_archiver_params()
         {
             let fri_prover_job_archiver =
                 FriProverJobArchiver::new(prover_pool.clone(), archiving_interval, archive_after);
-            context.add_task(Box::new(FriProverJobArchiverTask {
-                fri_prover_job_archiver,
-            }));
+            context
+                .add_restartable_task(
+                    Box::new(FriProverJobArchiverTask {
+                        fri_prover_job_archiver,
+                    }),
+                    restart_policy,
+                )
+                .await?;
         }
 
         if let Some((archiving_interval, archive_after)) =
@@ -126,9 +158,14 @@ impl WiringLayer for HouseKeeperLayer {
         {
             let fri_prover_gpu_archiver =
                 FriGpuProverArchiver::new(prover_pool.clone(), archiving_interval, archive_after);
-            context.add_task(Box::new(FriProverGpuArchiverTask {
-                fri_prover_gpu_archiver,
-            }));
+            context
+                .add_restartable_task(
+                    Box::new(FriProverGpuArchiverTask {
+                        fri_prover_gpu_archiver,
+                    }),
+                    restart_policy,
+                )
+                .await?;
         }
 
         let fri_witness_generator_stats_reporter = FriWitnessGeneratorStatsReporter::new(
@@ -136,9 +173,14 @@ impl WiringLayer for HouseKeeperLayer {
             self.house_keeper_config
                 .witness_generator_stats_reporting_interval_ms,
         );
-        context.add_task(Box::new(FriWitnessGeneratorStatsReporterTask {
-            fri_witness_generator_stats_reporter,
-        }));
+        context
+            .add_restartable_task(
+                Box::new(FriWitnessGeneratorStatsReporterTask {
+                    fri_witness_generator_stats_reporter,
+                }),
+                restart_policy,
+            )
+            .await?;
 
         let fri_prover_stats_reporter = FriProverStatsReporter::new(
             self.house_keeper_config.prover_stats_reporting_interval_ms,
@@ -146,18 +188,28 @@ impl WiringLayer for HouseKeeperLayer {
             replica_pool.clone(),
             self.fri_prover_group_config,
         );
-        context.add_task(Box::new(FriProverStatsReporterTask {
-            fri_prover_stats_reporter,
-        }));
+        context
+            .add_restartable_task(
+                Box::new(FriProverStatsReporterTask {
+                    fri_prover_stats_reporter,
+                }),
+                restart_policy,
+            )
+            .await?;
 
         let fri_proof_compressor_stats_reporter = FriProofCompressorStatsReporter::new(
             self.house_keeper_config
                 .proof_compressor_stats_reporting_interval_ms,
             prover_pool.clone(),
         );
-        context.add_task(Box::new(FriProofCompressorStatsReporterTask {
-            fri_proof_compressor_stats_reporter,
-        }));
+        context
+            .add_restartable_task(
+                Box::new(FriProofCompressorStatsReporterTask {
+                    fri_proof_compressor_stats_reporter,
+                }),
+                restart_policy,
+            )
+            .await?;
 
         let fri_proof_compressor_retry_manager = FriProofCompressorJobRetryManager::new(
             self.fri_proof_compressor_config.max_attempts,
@@ -166,15 +218,20 @@ impl WiringLayer for HouseKeeperLayer {
                 .proof_compressor_job_retrying_interval_ms,
             prover_pool.clone(),
         );
-        context.add_task(Box::new(FriProofCompressorJobRetryManagerTask {
-            fri_proof_compressor_retry_manager,
-        }));
+        context
+            .add_restartable_task(
+                Box::new(FriProofCompressorJobRetryManagerTask {
+                    fri_proof_compressor_retry_manager,
+                }),
+                restart_policy,
+            )
+            .await?;
 
         Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct PostgresMetricsScrapingTask {
     pool_for_metrics: ConnectionPool<Core>,
 }
@@ -188,7 +245,7 @@ impl Task for PostgresMetricsScrapingTask {
     async fn run(self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
         tokio::select! {
             () = PostgresMetrics::run_scraping(self.pool_for_metrics, SCRAPE_INTERVAL) => {
-                tracing::warn!("Postgres metrics scraping unexpectedly stopped");
+                anyhow::bail!("Postgres metrics scraping unexpectedly stopped");
             }
             _ = stop_receiver.0.changed() => {
                 tracing::info!("Stop signal received, Postgres metrics scraping is shutting down");
@@ -198,7 +255,17 @@ impl Task for PostgresMetricsScrapingTask {
     }
 }
 
-#[derive(Debug)]
+impl TaskFactory for PostgresMetricsScrapingTask {
+    fn name(&self) -> &'static str {
+        "postgres_metrics_scraping"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[derive(Debug, Clone)]
 struct L1BatchMetricsReporterTask {
     l1_batch_metrics_reporter: L1BatchMetricsReporter,
 }
@@ -214,7 +281,17 @@ impl Task for L1BatchMetricsReporterTask {
     }
 }
 
-#[derive(Debug)]
+impl TaskFactory for L1BatchMetricsReporterTask {
+    fn name(&self) -> &'static str {
+        "l1_batch_metrics_reporter"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[derive(Debug, Clone)]
 struct FriProverJobRetryManagerTask {
     fri_prover_job_retry_manager: FriProverJobRetryManager,
 }
@@ -230,7 +307,17 @@ impl Task for FriProverJobRetryManagerTask {
     }
 }
 
-#[derive(Debug)]
+impl TaskFactory for FriProverJobRetryManagerTask {
+    fn name(&self) -> &'static str {
+        "fri_prover_job_retry_manager"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[derive(Debug, Clone)]
 struct FriWitnessGeneratorJobRetryManagerTask {
     fri_witness_gen_job_retry_manager: FriWitnessGeneratorJobRetryManager,
 }
@@ -248,7 +335,17 @@ impl Task for FriWitnessGeneratorJobRetryManagerTask {
     }
 }
 
-#[derive(Debug)]
+impl TaskFactory for FriWitnessGeneratorJobRetryManagerTask {
+    fn name(&self) -> &'static str {
+        "fri_witness_generator_job_retry_manager"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[derive(Debug, Clone)]
 struct WaitingToQueuedFriWitnessJobMoverTask {
     waiting_to_queued_fri_witness_job_mover: WaitingToQueuedFriWitnessJobMover,
 }
@@ -266,7 +363,17 @@ impl Task for WaitingToQueuedFriWitnessJobMoverTask {
     }
 }
 
-#[derive(Debug)]
+impl TaskFactory for WaitingToQueuedFriWitnessJobMoverTask {
+    fn name(&self) -> &'static str {
+        "waiting_to_queued_fri_witness_job_mover"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[derive(Debug, Clone)]
 struct FriWitnessGeneratorStatsReporterTask {
     fri_witness_generator_stats_reporter: FriWitnessGeneratorStatsReporter,
 }
@@ -284,7 +391,17 @@ impl Task for FriWitnessGeneratorStatsReporterTask {
     }
 }
 
-#[derive(Debug)]
+impl TaskFactory for FriWitnessGeneratorStatsReporterTask {
+    fn name(&self) -> &'static str {
+        "fri_witness_generator_stats_reporter"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[derive(Debug, Clone)]
 struct FriProverStatsReporterTask {
     fri_prover_stats_reporter: FriProverStatsReporter,
 }
@@ -300,7 +417,17 @@ impl Task for FriProverStatsReporterTask {
     }
 }
 
-#[derive(Debug)]
+impl TaskFactory for FriProverStatsReporterTask {
+    fn name(&self) -> &'static str {
+        "fri_prover_stats_reporter"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[derive(Debug, Clone)]
 struct FriProofCompressorStatsReporterTask {
     fri_proof_compressor_stats_reporter: FriProofCompressorStatsReporter,
 }
@@ -318,7 +445,17 @@ impl Task for FriProofCompressorStatsReporterTask {
     }
 }
 
-#[derive(Debug)]
+impl TaskFactory for FriProofCompressorStatsReporterTask {
+    fn name(&self) -> &'static str {
+        "fri_proof_compressor_stats_reporter"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[derive(Debug, Clone)]
 struct FriProofCompressorJobRetryManagerTask {
     fri_proof_compressor_retry_manager: FriProofCompressorJobRetryManager,
 }
@@ -336,7 +473,17 @@ impl Task for FriProofCompressorJobRetryManagerTask {
     }
 }
 
-#[derive(Debug)]
+impl TaskFactory for FriProofCompressorJobRetryManagerTask {
+    fn name(&self) -> &'static str {
+        "fri_proof_compressor_job_retry_manager"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[derive(Debug, Clone)]
 struct FriProverJobArchiverTask {
     fri_prover_job_archiver: FriProverJobArchiver,
 }
@@ -352,6 +499,17 @@ impl Task for FriProverJobArchiverTask {
     }
 }
 
+impl TaskFactory for FriProverJobArchiverTask {
+    fn name(&self) -> &'static str {
+        "fri_prover_job_archiver"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[derive(Debug, Clone)]
 struct FriProverGpuArchiverTask {
     fri_prover_gpu_archiver: FriGpuProverArchiver,
 }
@@ -366,3 +524,13 @@ impl Task for FriProverGpuArchiverTask {
         self.fri_prover_gpu_archiver.run(stop_receiver.0).await
     }
 }
+
+impl TaskFactory for FriProverGpuArchiverTask {
+    fn name(&self) -> &'static str {
+        "fri_prover_gpu_archiver"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(self.clone()))
+    }
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use anyhow::Context;
+use zksync_circuit_breaker::l1_gas::L1BaseFeeChecker;
 use zksync_config::{
     configs::{
         chain::{L1BatchCommitDataGeneratorMode, StateKeeperConfig},
@@ -16,7 +17,9 @@ use zksync_types::fee_model::FeeModelConfig;
 
 use crate::{
     implementations::resources::{
-        eth_interface::EthInterfaceResource, fee_input::FeeInputResource,
+        circuit_breakers::CircuitBreakersResource,
+        eth_interface::EthInterfaceResource,
+        fee_input::{FeeInputResource, MainNodeFeeInputResource},
         l1_tx_params::L1TxParamsResource,
     },
     service::{ServiceContext, StopReceiver},
@@ -30,6 +33,7 @@ pub struct SequencerL1GasLayer {
     genesis_config: GenesisConfig,
     pubdata_sending_mode: PubdataSendingMode,
     state_keeper_config: StateKeeperConfig,
+    base_fee_ceiling: Option<u64>,
 }
 
 impl SequencerL1GasLayer {
@@ -44,8 +48,15 @@ impl SequencerL1GasLayer {
             genesis_config,
             pubdata_sending_mode,
             state_keeper_config,
+            base_fee_ceiling: None,
         }
     }
+
+    /// Sets the maximum L1 base fee (in wei) checked by a circuit breaker.
+    pub fn with_base_fee_ceiling(mut self, ceiling: Option<u64>) -> Self {
+        self.base_fee_ceiling = ceiling;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -75,10 +86,21 @@ impl WiringLayer for SequencerL1GasLayer {
             gas_adjuster.clone(),
             FeeModelConfig::from_state_keeper_config(&self.state_keeper_config),
         ));
-        context.insert_resource(FeeInputResource(batch_fee_input_provider))?;
+        context.insert_resource(FeeInputResource(batch_fee_input_provider.clone()))?;
+        context.insert_resource(MainNodeFeeInputResource(batch_fee_input_provider))?;
 
         context.insert_resource(L1TxParamsResource(gas_adjuster.clone()))?;
 
+        if let Some(base_fee_ceiling) = self.base_fee_ceiling {
+            let CircuitBreakersResource { breakers } = context.get_resource_or_default().await;
+            breakers
+                .insert(Box::new(L1BaseFeeChecker {
+                    l1_tx_params: gas_adjuster.clone(),
+                    base_fee_ceiling,
+                }))
+                .await;
+        }
+
         context.add_task(Box::new(GasAdjusterTask { gas_adjuster }));
         Ok(())
     }
@@ -0,0 +1,43 @@
+use std::num::NonZeroUsize;
+
+use anyhow::Context;
+use zksync_types::url::SensitiveUrl;
+use zksync_web3_decl::client::{BoxedL2Client, L2Client};
+
+use crate::{
+    implementations::resources::main_node_client::MainNodeClientResource,
+    service::ServiceContext,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for the JSON-RPC client of the main node used by the external node components.
+#[derive(Debug)]
+pub struct MainNodeClientLayer {
+    url: SensitiveUrl,
+    rate_limit_rps: NonZeroUsize,
+}
+
+impl MainNodeClientLayer {
+    pub fn new(url: SensitiveUrl, rate_limit_rps: NonZeroUsize) -> Self {
+        Self {
+            url,
+            rate_limit_rps,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for MainNodeClientLayer {
+    fn layer_name(&self) -> &'static str {
+        "main_node_client_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let main_node_client = L2Client::http(self.url)
+            .context("Failed creating JSON-RPC client for main node")?
+            .with_allowed_requests_per_second(self.rate_limit_rps)
+            .build();
+        context.insert_resource(MainNodeClientResource(BoxedL2Client::new(main_node_client)))?;
+        Ok(())
+    }
+}
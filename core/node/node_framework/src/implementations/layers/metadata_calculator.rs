@@ -1,13 +1,15 @@
 use std::sync::Arc;
 
 use anyhow::Context as _;
+use zksync_circuit_breaker::tree_lag::TreeLagChecker;
 use zksync_core::metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig};
 use zksync_storage::RocksDB;
 
 use crate::{
     implementations::resources::{
+        circuit_breakers::CircuitBreakersResource,
         healthcheck::AppHealthCheckResource,
-        object_store::ObjectStoreResource,
+        object_store::{ObjectStoreResource, TreeCheckpointStoreResource},
         pools::{MasterPool, PoolResource, ReplicaPool},
     },
     service::{ServiceContext, StopReceiver},
@@ -22,10 +24,31 @@ use crate::{
 /// - Resolves `PoolResource<MasterPool>`.
 /// - Resolves `PoolResource<ReplicaPool>`.
 /// - Resolves `ObjectStoreResource` (optional).
+/// - Resolves `TreeCheckpointStoreResource` (optional). If provided, the tree is bootstrapped from the latest
+///   checkpoint if it's not initialized, and checkpoints are uploaded if enabled in the config.
 /// - Adds `tree_health_check` to the `ResourceCollection<HealthCheckResource>`.
+/// - Adds the tree lag checker to `CircuitBreakersResource` if the tree lag limit is set.
 /// - Adds `metadata_calculator` to the node.
 #[derive(Debug)]
-pub struct MetadataCalculatorLayer(pub MetadataCalculatorConfig);
+pub struct MetadataCalculatorLayer {
+    config: MetadataCalculatorConfig,
+    tree_lag_limit: Option<u32>,
+}
+
+impl MetadataCalculatorLayer {
+    pub fn new(config: MetadataCalculatorConfig) -> Self {
+        Self {
+            config,
+            tree_lag_limit: None,
+        }
+    }
+
+    /// Sets the maximum lag of the Merkle tree (in L1 batches) checked by a circuit breaker.
+    pub fn with_tree_lag_limit(mut self, limit: Option<u32>) -> Self {
+        self.tree_lag_limit = limit;
+        self
+    }
+}
 
 #[derive(Debug)]
 pub struct MetadataCalculatorTask {
@@ -56,13 +79,35 @@ impl WiringLayer for MetadataCalculatorLayer {
             );
         }
 
-        let metadata_calculator = MetadataCalculator::new(
-            self.0,
+        if let Some(tree_lag_limit) = self.tree_lag_limit {
+            let replica_pool = context
+                .get_resource::<PoolResource<ReplicaPool>>()
+                .await?
+                .get_singleton()
+                .await?;
+            let CircuitBreakersResource { breakers } = context.get_resource_or_default().await;
+            breakers
+                .insert(Box::new(TreeLagChecker {
+                    pool: replica_pool,
+                    tree_lag_limit,
+                }))
+                .await;
+        }
+
+        let checkpoint_store = context
+            .get_resource::<TreeCheckpointStoreResource>()
+            .await
+            .ok(); // OK to be None.
+        let mut metadata_calculator = MetadataCalculator::new(
+            self.config,
             object_store.map(|store_resource| store_resource.0),
             main_pool,
         )
         .await?
         .with_recovery_pool(recovery_pool);
+        if let Some(TreeCheckpointStoreResource(checkpoint_store)) = checkpoint_store {
+            metadata_calculator = metadata_calculator.with_checkpoint_store(checkpoint_store);
+        }
 
         let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
         app_health
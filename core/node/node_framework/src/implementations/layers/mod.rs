@@ -1,3 +1,5 @@
+pub mod batch_status_updater;
+pub mod block_reverter;
 pub mod circuit_breaker_checker;
 pub mod commitment_generator;
 pub mod consensus;
@@ -9,13 +11,19 @@ pub mod healtcheck_server;
 pub mod house_keeper;
 pub mod l1_batch_commit_data_generator;
 pub mod l1_gas;
+pub mod main_node_client;
+pub mod main_node_fee_params_fetcher;
 pub mod metadata_calculator;
 pub mod object_store;
 pub mod pk_signing_eth_client;
 pub mod pools_layer;
 pub mod prometheus_exporter;
 pub mod proof_data_handler;
+pub mod pruning;
 pub mod query_eth_client;
+pub mod reorg_detector;
 pub mod sigint;
 pub mod state_keeper;
+pub mod storage_initialization;
+pub mod vm_runner;
 pub mod web3_api;
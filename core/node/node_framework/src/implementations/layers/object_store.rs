@@ -2,7 +2,7 @@ use zksync_config::ObjectStoreConfig;
 use zksync_object_store::ObjectStoreFactory;
 
 use crate::{
-    implementations::resources::object_store::ObjectStoreResource,
+    implementations::resources::object_store::{ObjectStoreResource, TreeCheckpointStoreResource},
     service::ServiceContext,
     wiring_layer::{WiringError, WiringLayer},
 };
@@ -30,3 +30,32 @@ impl WiringLayer for ObjectStoreLayer {
         Ok(())
     }
 }
+
+/// Wiring layer for the object store storing Merkle tree checkpoints.
+///
+/// ## Effects
+///
+/// - Adds `TreeCheckpointStoreResource` to the resources.
+#[derive(Debug)]
+pub struct TreeCheckpointStoreLayer {
+    config: ObjectStoreConfig,
+}
+
+impl TreeCheckpointStoreLayer {
+    pub fn new(config: ObjectStoreConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for TreeCheckpointStoreLayer {
+    fn layer_name(&self) -> &'static str {
+        "tree_checkpoint_store_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let object_store = ObjectStoreFactory::new(self.config).create_store().await;
+        context.insert_resource(TreeCheckpointStoreResource(object_store))?;
+        Ok(())
+    }
+}
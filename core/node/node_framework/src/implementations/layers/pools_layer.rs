@@ -1,8 +1,16 @@
+use std::time::Duration;
+
 use zksync_config::configs::PostgresConfig;
+use zksync_dal::{
+    system_dal, ConnectionPool, Core, RoutingConnectionPool, RoutingConnectionPoolBuilder,
+};
 
 use crate::{
-    implementations::resources::pools::{MasterPool, PoolResource, ProverPool, ReplicaPool},
-    service::ServiceContext,
+    implementations::resources::pools::{
+        MasterPool, PoolResource, ProverPool, ReadReplicasPoolResource, ReplicaPool,
+    },
+    service::{ServiceContext, StopReceiver},
+    task::Task,
     wiring_layer::{WiringError, WiringLayer},
 };
 
@@ -49,6 +57,13 @@ impl PoolsLayerBuilder {
     }
 }
 
+/// Wiring layer for connection pools.
+///
+/// ## Effects
+///
+/// - Adds `PoolResource`s for the enabled pools.
+/// - If the replica pool is enabled and read replicas are configured, adds `ReadReplicasPoolResource`
+///   and the `replication_lag_monitor` task.
 #[derive(Debug)]
 pub struct PoolsLayer {
     config: PostgresConfig,
@@ -79,11 +94,21 @@ impl WiringLayer for PoolsLayer {
         }
 
         if self.with_replica {
-            context.insert_resource(PoolResource::<ReplicaPool>::new(
+            let replica_pool = PoolResource::<ReplicaPool>::new(
                 self.config.replica_url()?,
                 self.config.max_connections()?,
                 self.config.statement_timeout(),
-            ))?;
+            );
+            if !self.config.read_replica_urls.is_empty() {
+                let read_replicas_pool = self
+                    .build_read_replicas_pool(replica_pool.get().await?)
+                    .await?;
+                context.add_task(Box::new(ReplicationLagMonitorTask {
+                    pool: read_replicas_pool.clone(),
+                }));
+                context.insert_resource(ReadReplicasPoolResource(read_replicas_pool))?;
+            }
+            context.insert_resource(replica_pool)?;
         }
 
         if self.with_prover {
@@ -97,3 +122,46 @@ impl WiringLayer for PoolsLayer {
         Ok(())
     }
 }
+
+impl PoolsLayer {
+    async fn build_read_replicas_pool(
+        &self,
+        replica_pool: ConnectionPool<Core>,
+    ) -> anyhow::Result<RoutingConnectionPool<Core>> {
+        let pool_size = self.config.max_connections()?;
+        let mut builder =
+            RoutingConnectionPoolBuilder::new(replica_pool, system_dal::check_replication_lag);
+        for url in &self.config.read_replica_urls {
+            let mut replica = ConnectionPool::<Core>::builder(url.clone(), pool_size);
+            replica
+                .set_acquire_timeout(self.config.acquire_timeout())
+                .set_statement_timeout(self.config.statement_timeout());
+            builder.add_replica(replica);
+        }
+        if let Some(max_lag) = self.config.max_replication_lag() {
+            builder.set_max_replication_lag(max_lag);
+        }
+        builder.build().await
+    }
+}
+
+#[derive(Debug)]
+struct ReplicationLagMonitorTask {
+    pool: RoutingConnectionPool<Core>,
+}
+
+#[async_trait::async_trait]
+impl Task for ReplicationLagMonitorTask {
+    fn name(&self) -> &'static str {
+        "replication_lag_monitor"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        const LAG_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+        self.pool
+            .lag_monitor(LAG_UPDATE_INTERVAL)
+            .run(stop_receiver.0)
+            .await
+    }
+}
@@ -1,13 +1,15 @@
 use std::sync::Arc;
 
+use zksync_circuit_breaker::unproven_batches::UnprovenBatchesChecker;
 use zksync_config::configs::ProofDataHandlerConfig;
 use zksync_dal::{ConnectionPool, Core};
 use zksync_object_store::ObjectStore;
 
 use crate::{
     implementations::resources::{
+        circuit_breakers::CircuitBreakersResource,
         object_store::ObjectStoreResource,
-        pools::{MasterPool, PoolResource},
+        pools::{MasterPool, PoolResource, ReplicaPool},
     },
     service::{ServiceContext, StopReceiver},
     task::Task,
@@ -20,18 +22,28 @@ use crate::{
 ///
 /// - Resolves `PoolResource<MasterPool>`.
 /// - Resolves `ObjectStoreResource`.
+/// - Resolves `PoolResource<ReplicaPool>` and adds the unproven batches checker to `CircuitBreakersResource`
+///   if the unproven batches limit is set.
 /// - Adds `proof_data_handler` to the node.
 #[derive(Debug)]
 pub struct ProofDataHandlerLayer {
     proof_data_handler_config: ProofDataHandlerConfig,
+    unproven_batches_limit: Option<u64>,
 }
 
 impl ProofDataHandlerLayer {
     pub fn new(proof_data_handler_config: ProofDataHandlerConfig) -> Self {
         Self {
             proof_data_handler_config,
+            unproven_batches_limit: None,
         }
     }
+
+    /// Sets the maximum number of unproven L1 batches checked by a circuit breaker.
+    pub fn with_unproven_batches_limit(mut self, limit: Option<u64>) -> Self {
+        self.unproven_batches_limit = limit;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -46,6 +58,21 @@ impl WiringLayer for ProofDataHandlerLayer {
 
         let object_store = context.get_resource::<ObjectStoreResource>().await?;
 
+        if let Some(unproven_batches_limit) = self.unproven_batches_limit {
+            let replica_pool = context
+                .get_resource::<PoolResource<ReplicaPool>>()
+                .await?
+                .get_singleton()
+                .await?;
+            let CircuitBreakersResource { breakers } = context.get_resource_or_default().await;
+            breakers
+                .insert(Box::new(UnprovenBatchesChecker {
+                    pool: replica_pool,
+                    unproven_batches_limit,
+                }))
+                .await;
+        }
+
         context.add_task(Box::new(ProofDataHandlerTask {
             proof_data_handler_config: self.proof_data_handler_config,
             blob_store: object_store.0,
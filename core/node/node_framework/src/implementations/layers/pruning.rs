@@ -0,0 +1,65 @@
+use zksync_node_db_pruner::{DbPruner, DbPrunerConfig};
+
+use crate::{
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        pools::{MasterPool, PoolResource},
+    },
+    service::{ServiceContext, StopReceiver},
+    task::Task,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for the Postgres pruner of the node state.
+///
+/// Requests:
+/// - `PoolResource<MasterPool>`
+/// - `AppHealthCheckResource` (adds a health check)
+#[derive(Debug)]
+pub struct PruningLayer {
+    config: DbPrunerConfig,
+}
+
+impl PruningLayer {
+    pub fn new(config: DbPrunerConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for PruningLayer {
+    fn layer_name(&self) -> &'static str {
+        "pruning_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let pool_resource = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let main_pool = pool_resource.get().await?;
+
+        let db_pruner = DbPruner::new(self.config, main_pool);
+
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health
+            .insert_component(db_pruner.health_check())
+            .map_err(WiringError::internal)?;
+
+        context.add_task(Box::new(DbPrunerTask { db_pruner }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct DbPrunerTask {
+    db_pruner: DbPruner,
+}
+
+#[async_trait::async_trait]
+impl Task for DbPrunerTask {
+    fn name(&self) -> &'static str {
+        "db_pruner"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.db_pruner.run(stop_receiver.0).await
+    }
+}
@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use zksync_core::reorg_detector::{self, ReorgDetector};
+
+use crate::{
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        main_node_client::MainNodeClientResource,
+        pools::{MasterPool, PoolResource},
+        reverter::BlockReverterResource,
+    },
+    precondition::Precondition,
+    service::{ServiceContext, StopReceiver},
+    task::Task,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+const TRANSIENT_ERROR_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Wiring layer for the reorg detector check performed on node startup. If a reorg is detected,
+/// the node state is rolled back to the last correct L1 batch using the block reverter (if it's provided;
+/// otherwise, the check fails).
+///
+/// Requests:
+/// - `PoolResource<MasterPool>`
+/// - `MainNodeClientResource`
+/// - `BlockReverterResource` (optional)
+#[derive(Debug)]
+pub struct ReorgDetectorCheckerLayer;
+
+#[async_trait::async_trait]
+impl WiringLayer for ReorgDetectorCheckerLayer {
+    fn layer_name(&self) -> &'static str {
+        "reorg_detector_checker_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let MainNodeClientResource(main_node_client) = context.get_resource().await?;
+        let pool_resource = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let pool = pool_resource.get().await?;
+        let block_reverter = match context.get_resource::<BlockReverterResource>().await {
+            Ok(reverter) => Some(reverter),
+            Err(WiringError::ResourceLacking { .. }) => None,
+            Err(err) => return Err(err),
+        };
+
+        context.add_precondition(Box::new(CheckerPrecondition {
+            reorg_detector: ReorgDetector::new(main_node_client, pool),
+            block_reverter,
+        }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct CheckerPrecondition {
+    reorg_detector: ReorgDetector,
+    block_reverter: Option<BlockReverterResource>,
+}
+
+#[async_trait::async_trait]
+impl Precondition for CheckerPrecondition {
+    fn name(&self) -> &'static str {
+        "reorg_detector_checker"
+    }
+
+    async fn check(mut self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        // The check is safe to run concurrently with storage initialization: it trivially passes on
+        // an empty database, and reverting L1 batches never touches genesis or snapshot recovery data.
+        loop {
+            match self.reorg_detector.check_consistency().await {
+                Ok(()) => return Ok(()),
+                Err(reorg_detector::Error::ReorgDetected(last_correct_l1_batch)) => {
+                    let Some(BlockReverterResource(reverter)) = &self.block_reverter else {
+                        anyhow::bail!(
+                            "Reorg detected, but the block reverter is not configured; \
+                             cannot revert to L1 batch #{last_correct_l1_batch}"
+                        );
+                    };
+                    tracing::info!("Reverting to L1 batch number {last_correct_l1_batch}");
+                    reverter.roll_back(last_correct_l1_batch).await?;
+                    tracing::info!("Revert successfully completed");
+                    return Ok(());
+                }
+                Err(err) if err.is_transient() => {
+                    tracing::warn!(
+                        "Transient error during reorg check: {err}; retrying after a delay"
+                    );
+                    if tokio::time::timeout(
+                        TRANSIENT_ERROR_RETRY_INTERVAL,
+                        stop_receiver.0.changed(),
+                    )
+                    .await
+                    .is_ok()
+                    {
+                        anyhow::bail!("stop signal received before the reorg check has completed");
+                    }
+                }
+                Err(err) => return Err(err).context("reorg_detector.check_consistency()"),
+            }
+        }
+    }
+}
+
+/// Wiring layer for the reorg detector running in the background. Unlike [`ReorgDetectorCheckerLayer`],
+/// the runner doesn't roll back the node state; instead, it exits with an error if a reorg is detected,
+/// so that the reorg is handled on the next node startup.
+///
+/// Requests:
+/// - `PoolResource<MasterPool>`
+/// - `MainNodeClientResource`
+/// - `AppHealthCheckResource` (adds a health check)
+#[derive(Debug)]
+pub struct ReorgDetectorRunnerLayer;
+
+#[async_trait::async_trait]
+impl WiringLayer for ReorgDetectorRunnerLayer {
+    fn layer_name(&self) -> &'static str {
+        "reorg_detector_runner_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let MainNodeClientResource(main_node_client) = context.get_resource().await?;
+        let pool_resource = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let pool = pool_resource.get().await?;
+
+        let reorg_detector = ReorgDetector::new(main_node_client, pool);
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health
+            .insert_component(reorg_detector.health_check().clone())
+            .map_err(WiringError::internal)?;
+
+        context.add_task(Box::new(RunnerTask { reorg_detector }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct RunnerTask {
+    reorg_detector: ReorgDetector,
+}
+
+#[async_trait::async_trait]
+impl Task for RunnerTask {
+    fn name(&self) -> &'static str {
+        "reorg_detector_runner"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.reorg_detector
+            .run(stop_receiver.0)
+            .await
+            .context("reorg_detector.run()")
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use zksync_core::{
+    state_keeper::{seal_criteria::NoopSealer, OutputHandler, StateKeeperPersistence},
+    sync_layer::{external_io::ExternalIO, ActionQueue, SyncState},
+};
+use zksync_types::{Address, L2ChainId};
+
+use super::mempool_io::L2BlockSealerTask;
+use crate::{
+    implementations::resources::{
+        action_queue::ActionQueueSenderResource,
+        healthcheck::AppHealthCheckResource,
+        main_node_client::MainNodeClientResource,
+        pools::{MasterPool, PoolResource},
+        state_keeper::{ConditionalSealerResource, OutputHandlerResource, StateKeeperIOResource},
+        sync_state::SyncStateResource,
+    },
+    resource::Unique,
+    service::ServiceContext,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for the external node state keeper IO, which executes L2 blocks received
+/// from the main node.
+///
+/// Requests:
+/// - `PoolResource<MasterPool>`
+/// - `MainNodeClientResource`
+///
+/// Provides:
+/// - `StateKeeperIOResource`
+/// - `OutputHandlerResource`
+/// - `ConditionalSealerResource` (no-op sealer; batches are sealed by the main node)
+/// - `SyncStateResource`
+/// - `ActionQueueSenderResource` (to be consumed by the fetcher)
+#[derive(Debug)]
+pub struct ExternalIOLayer {
+    chain_id: L2ChainId,
+    l2_shared_bridge_addr: Address,
+    l2_block_seal_queue_capacity: usize,
+    protective_reads_persistence_enabled: bool,
+}
+
+impl ExternalIOLayer {
+    pub fn new(
+        chain_id: L2ChainId,
+        l2_shared_bridge_addr: Address,
+        l2_block_seal_queue_capacity: usize,
+    ) -> Self {
+        Self {
+            chain_id,
+            l2_shared_bridge_addr,
+            l2_block_seal_queue_capacity,
+            protective_reads_persistence_enabled: true,
+        }
+    }
+
+    /// Enables or disables persisting protective reads. Disabling it is only sound if the node
+    /// will never run a full Merkle tree.
+    pub fn with_protective_reads_persistence(mut self, enabled: bool) -> Self {
+        self.protective_reads_persistence_enabled = enabled;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ExternalIOLayer {
+    fn layer_name(&self) -> &'static str {
+        "external_io_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        // Fetch required resources.
+        let master_pool = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let MainNodeClientResource(main_node_client) = context.get_resource().await?;
+
+        // Create `SyncState` resource.
+        let sync_state = SyncState::default();
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health
+            .insert_custom_component(Arc::new(sync_state.clone()))
+            .map_err(WiringError::internal)?;
+        context.insert_resource(SyncStateResource(sync_state.clone()))?;
+
+        // Create `ActionQueueSender` resource.
+        let (action_queue_sender, action_queue) = ActionQueue::new();
+        context.insert_resource(ActionQueueSenderResource(Unique::new(action_queue_sender)))?;
+
+        // Create L2 block sealer task and output handler.
+        let (persistence, l2_block_sealer) = StateKeeperPersistence::new(
+            master_pool
+                .get_singleton()
+                .await
+                .context("Get master pool")?,
+            self.l2_shared_bridge_addr,
+            self.l2_block_seal_queue_capacity,
+        );
+        let mut persistence = persistence.with_tx_insertion();
+        if !self.protective_reads_persistence_enabled {
+            tracing::warn!("Disabling persisting protective reads; this should be safe, but is considered an experimental option at the moment");
+            persistence = persistence.without_protective_reads();
+        }
+        let output_handler =
+            OutputHandler::new(Box::new(persistence)).with_handler(Box::new(sync_state));
+        context.insert_resource(OutputHandlerResource(Unique::new(output_handler)))?;
+        context.add_task(Box::new(L2BlockSealerTask(l2_block_sealer)));
+
+        // Create IO resource. The IO doesn't access Postgres until it's initialized by the state keeper,
+        // so it's safe to create it before the storage is initialized.
+        let io_pool = master_pool.get().await.context("Get master pool")?;
+        let io = ExternalIO::new(
+            io_pool,
+            action_queue,
+            Box::new(main_node_client.for_component("external_io")),
+            self.chain_id,
+        )
+        .context("Failed initializing I/O for external node state keeper")?;
+        context.insert_resource(StateKeeperIOResource(Unique::new(Box::new(io))))?;
+
+        // Create sealer.
+        context.insert_resource(ConditionalSealerResource(Arc::new(NoopSealer)))?;
+
+        Ok(())
+    }
+}
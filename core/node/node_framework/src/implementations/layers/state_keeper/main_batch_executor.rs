@@ -17,17 +17,48 @@ use crate::{
 
 #[derive(Debug)]
 pub struct MainBatchExecutorLayer {
-    db_config: DBConfig,
-    state_keeper_config: StateKeeperConfig,
+    state_keeper_db_path: String,
+    cache_options: RocksdbStorageOptions,
+    save_call_traces: bool,
+    optional_bytecode_compression: bool,
 }
 
 impl MainBatchExecutorLayer {
     pub fn new(db_config: DBConfig, state_keeper_config: StateKeeperConfig) -> Self {
+        let cache_options = RocksdbStorageOptions {
+            block_cache_capacity: db_config
+                .experimental
+                .state_keeper_db_block_cache_capacity(),
+            max_open_files: db_config.experimental.state_keeper_db_max_open_files,
+        };
+        Self::custom(
+            db_config.state_keeper_db_path,
+            cache_options,
+            state_keeper_config.save_call_traces,
+        )
+    }
+
+    /// Creates a layer with the explicitly specified parameters. Useful for nodes that don't use
+    /// the main node configuration (e.g., the external node).
+    pub fn custom(
+        state_keeper_db_path: String,
+        cache_options: RocksdbStorageOptions,
+        save_call_traces: bool,
+    ) -> Self {
         Self {
-            db_config,
-            state_keeper_config,
+            state_keeper_db_path,
+            cache_options,
+            save_call_traces,
+            optional_bytecode_compression: false,
         }
     }
+
+    /// Makes bytecode compression optional for the executed transactions. Should be used
+    /// by external nodes, since the main node may have included transactions with uncompressed bytecodes.
+    pub fn with_optional_bytecode_compression(mut self, optional: bool) -> Self {
+        self.optional_bytecode_compression = optional;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -39,22 +70,15 @@ impl WiringLayer for MainBatchExecutorLayer {
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
         let master_pool = context.get_resource::<PoolResource<MasterPool>>().await?;
 
-        let cache_options = RocksdbStorageOptions {
-            block_cache_capacity: self
-                .db_config
-                .experimental
-                .state_keeper_db_block_cache_capacity(),
-            max_open_files: self.db_config.experimental.state_keeper_db_max_open_files,
-        };
         let (storage_factory, task) = AsyncRocksdbCache::new(
             master_pool.get_singleton().await?,
-            self.db_config.state_keeper_db_path,
-            cache_options,
+            self.state_keeper_db_path,
+            self.cache_options,
         );
         let builder = MainBatchExecutor::new(
             Arc::new(storage_factory),
-            self.state_keeper_config.save_call_traces,
-            false,
+            self.save_call_traces,
+            self.optional_bytecode_compression,
         );
 
         context.insert_resource(BatchExecutorResource(Unique::new(Box::new(builder))))?;
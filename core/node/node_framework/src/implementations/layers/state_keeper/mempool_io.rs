@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use zksync_config::{
@@ -9,15 +9,18 @@ use zksync_config::{
     ContractsConfig,
 };
 use zksync_core::state_keeper::{
-    self, MempoolFetcher, MempoolGuard, MempoolIO, OutputHandler, SequencerSealer,
-    StateKeeperPersistence,
+    self, tx_filter::ConfigTxFilter, MempoolFetcher, MempoolGuard, MempoolIO, OutputHandler,
+    SequencerSealer, StateKeeperConfigSource, StateKeeperPersistence,
 };
 
 use crate::{
     implementations::resources::{
         fee_input::FeeInputResource,
         pools::{MasterPool, PoolResource},
-        state_keeper::{ConditionalSealerResource, OutputHandlerResource, StateKeeperIOResource},
+        state_keeper::{
+            ConditionalSealerResource, MempoolGuardResource, OutputHandlerResource,
+            StateKeeperConfigSourceResource, StateKeeperIOResource, TxFilterResource,
+        },
     },
     resource::Unique,
     service::{ServiceContext, StopReceiver},
@@ -81,7 +84,7 @@ impl WiringLayer for MempoolIOLayer {
         let master_pool = context.get_resource::<PoolResource<MasterPool>>().await?;
 
         // Create miniblock sealer task.
-        let (persistence, l2_block_sealer) = StateKeeperPersistence::new(
+        let (mut persistence, l2_block_sealer) = StateKeeperPersistence::new(
             master_pool
                 .get_singleton()
                 .await
@@ -89,6 +92,12 @@ impl WiringLayer for MempoolIOLayer {
             self.contracts_config.l2_shared_bridge_addr.unwrap(),
             self.state_keeper_config.l2_block_seal_queue_capacity,
         );
+        if !self
+            .state_keeper_config
+            .protective_reads_persistence_enabled
+        {
+            persistence = persistence.without_protective_reads();
+        }
         let output_handler = OutputHandler::new(Box::new(persistence));
         context.insert_resource(OutputHandlerResource(Unique::new(output_handler)))?;
         context.add_task(Box::new(L2BlockSealerTask(l2_block_sealer)));
@@ -106,13 +115,26 @@ impl WiringLayer for MempoolIOLayer {
             mempool_fetcher_pool,
         );
         context.add_task(Box::new(MempoolFetcherTask(mempool_fetcher)));
+        context.insert_resource(MempoolGuardResource(mempool_guard.clone()))?;
+
+        // Create config source shared by the IO and the sealer; seal params may be updated at runtime.
+        let config_source = StateKeeperConfigSource::new(self.state_keeper_config.clone());
+        context.add_task(Box::new(ConfigSourceTask {
+            config_source: config_source.clone(),
+            seal_params_path: self
+                .state_keeper_config
+                .seal_params_path
+                .as_ref()
+                .map(Into::into),
+        }));
+        context.insert_resource(StateKeeperConfigSourceResource(config_source.clone()))?;
 
         // Create mempool IO resource.
         let mempool_db_pool = master_pool
             .get_singleton()
             .await
             .context("Get master pool")?;
-        let io = MempoolIO::new(
+        let mut io = MempoolIO::new(
             mempool_guard,
             batch_fee_input_provider,
             mempool_db_pool,
@@ -121,11 +143,27 @@ impl WiringLayer for MempoolIOLayer {
             self.mempool_config.delay_interval(),
             self.network_config.zksync_network_id,
         )
-        .await?;
+        .await?
+        .with_config_source(&config_source);
+
+        // Create transaction filter if it's configured; it's shared with the API server via a resource.
+        if let Some(path) = &self.state_keeper_config.tx_filter_path {
+            let tx_filter = Arc::new(
+                ConfigTxFilter::new(path)
+                    .await
+                    .context("failed initializing transaction filter")?,
+            );
+            io = io.with_tx_filter(tx_filter.clone());
+            context.insert_resource(TxFilterResource(tx_filter.clone()))?;
+            context.add_task(Box::new(TxFilterReloaderTask {
+                tx_filter,
+                reload_interval: self.state_keeper_config.tx_filter_reload_interval(),
+            }));
+        }
         context.insert_resource(StateKeeperIOResource(Unique::new(Box::new(io))))?;
 
         // Create sealer.
-        let sealer = SequencerSealer::new(self.state_keeper_config);
+        let sealer = SequencerSealer::from_source(config_source);
         context.insert_resource(ConditionalSealerResource(Arc::new(sealer)))?;
 
         Ok(())
@@ -133,7 +171,7 @@ impl WiringLayer for MempoolIOLayer {
 }
 
 #[derive(Debug)]
-struct L2BlockSealerTask(state_keeper::L2BlockSealerTask);
+pub(super) struct L2BlockSealerTask(pub(super) state_keeper::L2BlockSealerTask);
 
 #[async_trait::async_trait]
 impl Task for L2BlockSealerTask {
@@ -160,3 +198,41 @@ impl Task for MempoolFetcherTask {
         self.0.run(stop_receiver.0).await
     }
 }
+
+#[derive(Debug)]
+struct TxFilterReloaderTask {
+    tx_filter: Arc<ConfigTxFilter>,
+    reload_interval: Duration,
+}
+
+#[async_trait::async_trait]
+impl Task for TxFilterReloaderTask {
+    fn name(&self) -> &'static str {
+        "state_keeper/tx_filter_reloader"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.tx_filter
+            .run_reloader(self.reload_interval, stop_receiver.0)
+            .await
+    }
+}
+
+#[derive(Debug)]
+struct ConfigSourceTask {
+    config_source: StateKeeperConfigSource,
+    seal_params_path: Option<PathBuf>,
+}
+
+#[async_trait::async_trait]
+impl Task for ConfigSourceTask {
+    fn name(&self) -> &'static str {
+        "state_keeper/config_source"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.config_source
+            .run(self.seal_params_path, stop_receiver.0)
+            .await
+    }
+}
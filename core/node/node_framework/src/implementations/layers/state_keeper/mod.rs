@@ -2,18 +2,22 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use zksync_core::state_keeper::{
-    seal_criteria::ConditionalSealer, BatchExecutor, OutputHandler, StateKeeperIO,
-    ZkSyncStateKeeper,
+    seal_criteria::ConditionalSealer, BatchExecutor, OutputHandler, StateKeeperControl,
+    StateKeeperIO, ZkSyncStateKeeper,
 };
 use zksync_storage::RocksDB;
 
+pub mod external_io;
 pub mod main_batch_executor;
 pub mod mempool_io;
 
 use crate::{
-    implementations::resources::state_keeper::{
-        BatchExecutorResource, ConditionalSealerResource, OutputHandlerResource,
-        StateKeeperIOResource,
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        state_keeper::{
+            BatchExecutorResource, ConditionalSealerResource, OutputHandlerResource,
+            StateKeeperControlResource, StateKeeperIOResource,
+        },
     },
     service::{ServiceContext, StopReceiver},
     task::Task,
@@ -24,6 +28,10 @@ use crate::{
 /// - `StateKeeperIOResource`
 /// - `BatchExecutorResource`
 /// - `ConditionalSealerResource`
+/// - `AppHealthCheckResource` (adds a health check reporting whether the state keeper is paused)
+///
+/// Provides:
+/// - `StateKeeperControlResource`
 ///
 #[derive(Debug)]
 pub struct StateKeeperLayer;
@@ -55,11 +63,19 @@ impl WiringLayer for StateKeeperLayer {
             .context("HandleStateKeeperOutput was provided but taken by another task")?;
         let sealer = context.get_resource::<ConditionalSealerResource>().await?.0;
 
+        let control = StateKeeperControl::default();
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health
+            .insert_component(control.health_check())
+            .map_err(WiringError::internal)?;
+        context.insert_resource(StateKeeperControlResource(control.clone()))?;
+
         context.add_task(Box::new(StateKeeperTask {
             io,
             batch_executor_base,
             output_handler,
             sealer,
+            control,
         }));
         Ok(())
     }
@@ -71,6 +87,7 @@ struct StateKeeperTask {
     batch_executor_base: Box<dyn BatchExecutor>,
     output_handler: OutputHandler,
     sealer: Arc<dyn ConditionalSealer>,
+    control: StateKeeperControl,
 }
 
 #[async_trait::async_trait]
@@ -86,7 +103,8 @@ impl Task for StateKeeperTask {
             self.batch_executor_base,
             self.output_handler,
             self.sealer,
-        );
+        )
+        .with_control(self.control);
         let result = state_keeper.run().await;
 
         // Wait for all the instances of RocksDB to be destroyed.
@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_core::sync_layer::genesis::perform_genesis_if_needed;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_health_check::AppHealthCheck;
+use zksync_object_store::ObjectStore;
+use zksync_snapshots_applier::{SnapshotsApplierConfig, SnapshotsApplierTask};
+use zksync_types::{L1BatchNumber, L2ChainId};
+use zksync_web3_decl::client::BoxedL2Client;
+
+use crate::{
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        main_node_client::MainNodeClientResource,
+        object_store::ObjectStoreResource,
+        pools::{MasterPool, PoolResource},
+    },
+    precondition::Precondition,
+    service::{ServiceContext, StopReceiver},
+    task::UnconstrainedOneshotTask,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer that makes sure that the external node storage is initialized, either via genesis
+/// or via snapshot recovery. Other tasks are not started until the storage is initialized.
+///
+/// Requests:
+/// - `PoolResource<MasterPool>`
+/// - `MainNodeClientResource`
+/// - `ObjectStoreResource` (only if snapshot recovery is enabled)
+/// - `AppHealthCheckResource` (adds a health check for snapshot recovery)
+#[derive(Debug)]
+pub struct ExternalNodeStorageInitLayer {
+    l2_chain_id: L2ChainId,
+    snapshot_recovery_enabled: bool,
+}
+
+impl ExternalNodeStorageInitLayer {
+    pub fn new(l2_chain_id: L2ChainId) -> Self {
+        Self {
+            l2_chain_id,
+            snapshot_recovery_enabled: false,
+        }
+    }
+
+    /// Allows initializing storage from a snapshot if the node doesn't have any data.
+    pub fn with_snapshot_recovery(mut self, enabled: bool) -> Self {
+        self.snapshot_recovery_enabled = enabled;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ExternalNodeStorageInitLayer {
+    fn layer_name(&self) -> &'static str {
+        "external_node_storage_init_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let pool_resource = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let pool = pool_resource.get().await?;
+        let MainNodeClientResource(main_node_client) = context.get_resource().await?;
+        let blob_store = if self.snapshot_recovery_enabled {
+            Some(context.get_resource::<ObjectStoreResource>().await?.0)
+        } else {
+            None
+        };
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+
+        let (initialized_sender, initialized_receiver) = watch::channel(false);
+        context.add_unconstrained_oneshot_task(Box::new(StorageInitTask {
+            l2_chain_id: self.l2_chain_id,
+            pool,
+            main_node_client,
+            blob_store,
+            app_health,
+            initialized_sender,
+        }));
+        context.add_precondition(Box::new(StorageInitializedPrecondition(
+            initialized_receiver,
+        )));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+enum InitDecision {
+    /// Perform or check genesis.
+    Genesis,
+    /// Perform or check snapshot recovery.
+    SnapshotRecovery,
+}
+
+#[derive(Debug)]
+struct StorageInitTask {
+    l2_chain_id: L2ChainId,
+    pool: ConnectionPool<Core>,
+    main_node_client: BoxedL2Client,
+    blob_store: Option<Arc<dyn ObjectStore>>,
+    app_health: Arc<AppHealthCheck>,
+    initialized_sender: watch::Sender<bool>,
+}
+
+impl StorageInitTask {
+    async fn init_decision(&self) -> anyhow::Result<InitDecision> {
+        let mut storage = self.pool.connection_tagged("en").await?;
+        let genesis_l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_header(L1BatchNumber(0))
+            .await?;
+        let snapshot_recovery = storage
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await?;
+        drop(storage);
+
+        Ok(match (genesis_l1_batch, snapshot_recovery) {
+            (Some(batch), Some(snapshot_recovery)) => {
+                anyhow::bail!(
+                    "Node has both genesis L1 batch: {batch:?} and snapshot recovery information: {snapshot_recovery:?}. \
+                     This is not supported and can be caused by broken snapshot recovery."
+                );
+            }
+            (Some(_), None) => InitDecision::Genesis,
+            (None, Some(_)) => InitDecision::SnapshotRecovery,
+            (None, None) if self.blob_store.is_some() => InitDecision::SnapshotRecovery,
+            (None, None) => InitDecision::Genesis,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl UnconstrainedOneshotTask for StorageInitTask {
+    fn name(&self) -> &'static str {
+        "external_node_storage_init"
+    }
+
+    async fn run_unconstrained_oneshot(
+        self: Box<Self>,
+        _stop_receiver: StopReceiver,
+    ) -> anyhow::Result<()> {
+        let decision = self.init_decision().await?;
+        tracing::info!("Chosen node initialization strategy: {decision:?}");
+        match decision {
+            InitDecision::Genesis => {
+                let mut storage = self.pool.connection_tagged("en").await?;
+                perform_genesis_if_needed(
+                    &mut storage,
+                    self.l2_chain_id,
+                    &self.main_node_client.for_component("genesis"),
+                )
+                .await
+                .context("performing genesis failed")?;
+            }
+            InitDecision::SnapshotRecovery => {
+                let blob_store = self
+                    .blob_store
+                    .context("Snapshot recovery is required to proceed, but it is not enabled")?;
+                let snapshots_applier_task = SnapshotsApplierTask::new(
+                    SnapshotsApplierConfig::default(),
+                    self.pool,
+                    Box::new(self.main_node_client.for_component("snapshot_recovery")),
+                    blob_store,
+                );
+                self.app_health
+                    .insert_component(snapshots_applier_task.health_check())?;
+                snapshots_applier_task
+                    .run()
+                    .await
+                    .context("snapshot recovery failed")?;
+            }
+        }
+
+        self.initialized_sender.send_replace(true);
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct StorageInitializedPrecondition(watch::Receiver<bool>);
+
+#[async_trait::async_trait]
+impl Precondition for StorageInitializedPrecondition {
+    fn name(&self) -> &'static str {
+        "external_node_storage_initialized"
+    }
+
+    async fn check(mut self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        tokio::select! {
+            res = self.0.wait_for(|initialized| *initialized) => {
+                res.context("storage initialization task was dropped")?;
+                Ok(())
+            }
+            _ = stop_receiver.0.changed() => {
+                anyhow::bail!("stop signal received before storage was initialized")
+            }
+        }
+    }
+}
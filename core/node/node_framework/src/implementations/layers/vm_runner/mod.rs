@@ -0,0 +1,129 @@
+use std::sync::{Arc, Mutex};
+
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_core::state_keeper::{MainBatchExecutor, OutputHandler, StateKeeperOutputHandler};
+use zksync_types::L2ChainId;
+use zksync_vm_runner::{StorageSyncTask, VmRunner, VmRunnerStorage, VmRunnerStorageLoader};
+
+use crate::{
+    implementations::resources::pools::{MasterPool, PoolResource},
+    service::{ServiceContext, StopReceiver},
+    task::Task,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+pub mod protective_reads;
+
+/// Wiring layer for a [`VmRunner`] re-executing sealed L1 batches and feeding the results into output handlers.
+///
+/// The layer and its task are named after the provided loader, so that several VM runners with different loaders
+/// can be added to the same service.
+#[derive(Debug)]
+pub struct VmRunnerLayer<L> {
+    loader: L,
+    rocksdb_path: String,
+    chain_id: L2ChainId,
+    save_call_traces: bool,
+    optional_bytecode_compression: bool,
+    // Output handlers are not required to be `Sync`, unlike wiring layers.
+    output_handler: Mutex<OutputHandler>,
+}
+
+impl<L: VmRunnerStorageLoader + Clone> VmRunnerLayer<L> {
+    /// Creates a new layer. Batches are re-executed with the same call tracing settings as in the state keeper.
+    pub fn new(
+        loader: L,
+        rocksdb_path: String,
+        chain_id: L2ChainId,
+        state_keeper_config: &StateKeeperConfig,
+        output_handler: Box<dyn StateKeeperOutputHandler>,
+    ) -> Self {
+        Self {
+            loader,
+            rocksdb_path,
+            chain_id,
+            save_call_traces: state_keeper_config.save_call_traces,
+            optional_bytecode_compression: false,
+            output_handler: Mutex::new(OutputHandler::new(output_handler)),
+        }
+    }
+
+    /// Makes bytecode compression optional for the re-executed transactions. Should be used
+    /// by external nodes, since the main node may have included transactions with uncompressed bytecodes.
+    pub fn with_optional_bytecode_compression(mut self, optional: bool) -> Self {
+        self.optional_bytecode_compression = optional;
+        self
+    }
+
+    /// Adds an output handler. Handlers are executed in the order they were added.
+    #[must_use]
+    pub fn with_output_handler(self, handler: Box<dyn StateKeeperOutputHandler>) -> Self {
+        let output_handler = self
+            .output_handler
+            .into_inner()
+            .expect("output handler is poisoned");
+        Self {
+            output_handler: Mutex::new(output_handler.with_handler(handler)),
+            ..self
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<L: VmRunnerStorageLoader + Clone> WiringLayer for VmRunnerLayer<L> {
+    fn layer_name(&self) -> &'static str {
+        L::name()
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let pool_resource = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let pool = pool_resource.get().await?;
+
+        let (storage, sync_task) = VmRunnerStorage::new(
+            pool.clone(),
+            self.rocksdb_path,
+            self.loader.clone(),
+            self.chain_id,
+        )
+        .await?;
+        let storage = Arc::new(storage);
+        let batch_executor = MainBatchExecutor::new(
+            storage.clone(),
+            self.save_call_traces,
+            self.optional_bytecode_compression,
+        );
+        let runner = VmRunner::new(
+            pool,
+            self.loader,
+            storage,
+            Box::new(batch_executor),
+            self.output_handler
+                .into_inner()
+                .expect("output handler is poisoned"),
+        );
+
+        context.add_task(Box::new(VmRunnerTask { runner, sync_task }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct VmRunnerTask<L: VmRunnerStorageLoader> {
+    runner: VmRunner<L>,
+    sync_task: StorageSyncTask<L>,
+}
+
+#[async_trait::async_trait]
+impl<L: VmRunnerStorageLoader> Task for VmRunnerTask<L> {
+    fn name(&self) -> &'static str {
+        L::name()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        // The runner relies on the storage sync task to load batches, so both are run as a single task.
+        let sync_task = self.sync_task.run(stop_receiver.0.clone());
+        let runner = self.runner.run(&stop_receiver.0);
+        tokio::try_join!(sync_task, runner)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::Mutex;
+
+use zksync_config::configs::ProtectiveReadsWriterConfig;
+use zksync_core::state_keeper::OutputHandler;
+use zksync_types::{L1BatchNumber, L2ChainId};
+use zksync_vm_runner::{ProtectiveReadsLoader, ProtectiveReadsOutputHandler};
+
+use super::VmRunnerLayer;
+use crate::{
+    implementations::resources::{
+        object_store::ObjectStoreResource,
+        pools::{MasterPool, PoolResource},
+    },
+    service::ServiceContext,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for the protective reads writer, a [`VmRunnerLayer`] producing protective reads
+/// and basic witness inputs for sealed L1 batches.
+#[derive(Debug)]
+pub struct ProtectiveReadsWriterLayer {
+    config: ProtectiveReadsWriterConfig,
+    chain_id: L2ChainId,
+}
+
+impl ProtectiveReadsWriterLayer {
+    pub fn new(config: ProtectiveReadsWriterConfig, chain_id: L2ChainId) -> Self {
+        Self { config, chain_id }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ProtectiveReadsWriterLayer {
+    fn layer_name(&self) -> &'static str {
+        "protective_reads_writer_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let pool_resource = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let object_store = context.get_resource::<ObjectStoreResource>().await?.0;
+
+        let output_handler =
+            ProtectiveReadsOutputHandler::new(pool_resource.get().await?, object_store);
+        let loader = ProtectiveReadsLoader::new(
+            L1BatchNumber(self.config.first_processed_batch),
+            self.config.window_size,
+        );
+        // Call traces are not used by the writer, so they are not collected during re-execution.
+        let vm_runner_layer = VmRunnerLayer {
+            loader,
+            rocksdb_path: self.config.db_path,
+            chain_id: self.chain_id,
+            save_call_traces: false,
+            optional_bytecode_compression: false,
+            output_handler: Mutex::new(OutputHandler::new(Box::new(output_handler))),
+        };
+        Box::new(vm_runner_layer).wire(context).await
+    }
+}
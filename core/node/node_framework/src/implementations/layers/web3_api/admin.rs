@@ -0,0 +1,76 @@
+use std::net::Ipv4Addr;
+
+use zksync_core::api_server::web3::admin::{AdminApiServer, AdminHandles};
+
+use crate::{
+    implementations::resources::{
+        fee_input::MainNodeFeeInputResource,
+        pools::{MasterPool, PoolResource},
+        state_keeper::{
+            MempoolGuardResource, StateKeeperConfigSourceResource, StateKeeperControlResource,
+        },
+    },
+    service::{ServiceContext, StopReceiver},
+    task::Task,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for the admin JSON-RPC server exposing the `admin` namespace. Must be used together
+/// with the main node state keeper.
+///
+/// The server only listens on the loopback interface, so that it's not reachable from the outside.
+///
+/// Requests:
+/// - `PoolResource<MasterPool>`
+/// - `StateKeeperControlResource`
+/// - `MempoolGuardResource`
+/// - `MainNodeFeeInputResource`
+/// - `StateKeeperConfigSourceResource`
+#[derive(Debug)]
+pub struct AdminApiLayer {
+    port: u16,
+}
+
+impl AdminApiLayer {
+    pub fn new(port: u16) -> Self {
+        Self { port }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for AdminApiLayer {
+    fn layer_name(&self) -> &'static str {
+        "admin_api_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let pool = context
+            .get_resource::<PoolResource<MasterPool>>()
+            .await?
+            .get_singleton()
+            .await?;
+        let StateKeeperControlResource(control) = context.get_resource().await?;
+        let MempoolGuardResource(mempool) = context.get_resource().await?;
+        let MainNodeFeeInputResource(fee_input_provider) = context.get_resource().await?;
+        let StateKeeperConfigSourceResource(config_source) = context.get_resource().await?;
+
+        let handles = AdminHandles::new(control, mempool, fee_input_provider, config_source, pool);
+        let server = AdminApiServer::new((Ipv4Addr::LOCALHOST, self.port).into(), handles);
+        context.add_task(Box::new(AdminApiTask(server)));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct AdminApiTask(AdminApiServer);
+
+#[async_trait::async_trait]
+impl Task for AdminApiTask {
+    fn name(&self) -> &'static str {
+        "admin_api"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.0.run(stop_receiver.0).await
+    }
+}
@@ -9,7 +9,7 @@ use crate::{
     implementations::resources::{
         circuit_breakers::CircuitBreakersResource,
         healthcheck::AppHealthCheckResource,
-        pools::{PoolResource, ReplicaPool},
+        pools::{PoolResource, ReadReplicasPoolResource, ReplicaPool},
         sync_state::SyncStateResource,
         web3_api::{MempoolCacheResource, TreeApiClientResource, TxSenderResource},
     },
@@ -126,6 +126,11 @@ impl WiringLayer for Web3ServerLayer {
             Err(err) => return Err(err),
         };
         let MempoolCacheResource(mempool_cache) = context.get_resource().await?;
+        let read_replicas_pool = match context.get_resource::<ReadReplicasPoolResource>().await {
+            Ok(pool) => Some(pool.0),
+            Err(WiringError::ResourceLacking { .. }) => None,
+            Err(err) => return Err(err),
+        };
 
         // Build server.
         let mut api_builder =
@@ -136,6 +141,9 @@ impl WiringLayer for Web3ServerLayer {
         if let Some(client) = tree_api_client {
             api_builder = api_builder.with_tree_api(client);
         }
+        if let Some(pool) = read_replicas_pool {
+            api_builder = api_builder.with_read_replicas(pool);
+        }
         match self.transport {
             Transport::Http => {
                 api_builder = api_builder.http(self.port);
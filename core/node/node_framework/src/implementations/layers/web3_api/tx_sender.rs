@@ -10,7 +10,7 @@ use crate::{
     implementations::resources::{
         fee_input::FeeInputResource,
         pools::{PoolResource, ReplicaPool},
-        state_keeper::ConditionalSealerResource,
+        state_keeper::{ConditionalSealerResource, StateKeeperControlResource, TxFilterResource},
         web3_api::{TxSenderResource, TxSinkResource},
     },
     service::{ServiceContext, StopReceiver},
@@ -65,6 +65,18 @@ impl WiringLayer for TxSenderLayer {
             Err(WiringError::ResourceLacking { .. }) => None,
             Err(other) => return Err(other),
         };
+        let tx_filter = match context.get_resource::<TxFilterResource>().await {
+            Ok(tx_filter) => Some(tx_filter.0),
+            Err(WiringError::ResourceLacking { .. }) => None,
+            Err(other) => return Err(other),
+        };
+        // If the state keeper is run in the same process, transactions are rejected while it's paused.
+        let state_keeper_control = match context.get_resource::<StateKeeperControlResource>().await
+        {
+            Ok(control) => Some(control.0),
+            Err(WiringError::ResourceLacking { .. }) => None,
+            Err(other) => return Err(other),
+        };
         let fee_input = context.get_resource::<FeeInputResource>().await?.0;
 
         // Initialize Postgres caches.
@@ -96,6 +108,12 @@ impl WiringLayer for TxSenderLayer {
         if let Some(sealer) = sealer {
             tx_sender = tx_sender.with_sealer(sealer);
         }
+        if let Some(tx_filter) = tx_filter {
+            tx_sender = tx_sender.with_tx_filter(tx_filter);
+        }
+        if let Some(control) = state_keeper_control {
+            tx_sender = tx_sender.with_state_keeper_control(control);
+        }
         let tx_sender = tx_sender
             .build(
                 fee_input,
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use zksync_node_fee_model::BatchFeeModelInputProvider;
+use zksync_node_fee_model::{BatchFeeModelInputProvider, MainNodeFeeInputProvider};
 
 use crate::resource::Resource;
 
@@ -13,3 +13,14 @@ impl Resource for FeeInputResource {
         "common/fee_input".into()
     }
 }
+
+/// Wrapper for the main node fee input provider. Unlike [`FeeInputResource`], allows updating
+/// fee model parameters at runtime.
+#[derive(Debug, Clone)]
+pub struct MainNodeFeeInputResource(pub Arc<MainNodeFeeInputProvider>);
+
+impl Resource for MainNodeFeeInputResource {
+    fn name() -> String {
+        "common/main_node_fee_input".into()
+    }
+}
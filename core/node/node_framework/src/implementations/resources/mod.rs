@@ -8,6 +8,7 @@ pub mod l1_tx_params;
 pub mod main_node_client;
 pub mod object_store;
 pub mod pools;
+pub mod reverter;
 pub mod state_keeper;
 pub mod sync_state;
 pub mod web3_api;
@@ -13,3 +13,14 @@ impl Resource for ObjectStoreResource {
         "common/object_store".into()
     }
 }
+
+/// Wrapper for the object store used for Merkle tree checkpoints. Separate from [`ObjectStoreResource`]
+/// so that checkpoints can be stored independently from other data (e.g., snapshots on the external node).
+#[derive(Debug, Clone)]
+pub struct TreeCheckpointStoreResource(pub Arc<dyn ObjectStore>);
+
+impl Resource for TreeCheckpointStoreResource {
+    fn name() -> String {
+        "common/tree_checkpoint_store".into()
+    }
+}
@@ -9,7 +9,7 @@ use std::{
 
 use prover_dal::Prover;
 use tokio::sync::Mutex;
-use zksync_dal::{ConnectionPool, Core};
+use zksync_dal::{ConnectionPool, Core, RoutingConnectionPool};
 use zksync_db_connection::connection_pool::ConnectionPoolBuilder;
 use zksync_types::url::SensitiveUrl;
 
@@ -110,6 +110,17 @@ impl<P: PoolKind> PoolResource<P> {
     }
 }
 
+/// Pool routing read-only connections to read replicas of the core database, with the replica pool
+/// as the fallback. Only present if read replicas are configured.
+#[derive(Debug, Clone)]
+pub struct ReadReplicasPoolResource(pub RoutingConnectionPool<Core>);
+
+impl Resource for ReadReplicasPoolResource {
+    fn name() -> String {
+        "common/read_replicas_pool".into()
+    }
+}
+
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub struct MasterPool {}
@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use zksync_core::state_keeper::{
-    seal_criteria::ConditionalSealer, BatchExecutor, OutputHandler, StateKeeperIO,
+    seal_criteria::ConditionalSealer, BatchExecutor, MempoolGuard, OutputHandler,
+    StateKeeperConfigSource, StateKeeperControl, StateKeeperIO, TxFilter,
 };
 
 use crate::resource::{Resource, Unique};
@@ -41,3 +42,40 @@ impl Resource for ConditionalSealerResource {
         "state_keeper/conditional_sealer".into()
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct TxFilterResource(pub Arc<dyn TxFilter>);
+
+impl Resource for TxFilterResource {
+    fn name() -> String {
+        "state_keeper/tx_filter".into()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StateKeeperConfigSourceResource(pub StateKeeperConfigSource);
+
+impl Resource for StateKeeperConfigSourceResource {
+    fn name() -> String {
+        "state_keeper/config_source".into()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MempoolGuardResource(pub MempoolGuard);
+
+impl Resource for MempoolGuardResource {
+    fn name() -> String {
+        "state_keeper/mempool".into()
+    }
+}
+
+/// Wrapper for the handle controlling the state keeper at runtime (e.g., pausing it).
+#[derive(Debug, Clone)]
+pub struct StateKeeperControlResource(pub StateKeeperControl);
+
+impl Resource for StateKeeperControlResource {
+    fn name() -> String {
+        "state_keeper/control".into()
+    }
+}
@@ -1,10 +1,16 @@
 use std::any::type_name;
 
+use zksync_health_check::ReactiveHealthCheck;
+
+use super::supervisor::SupervisedTask;
 use crate::{
+    implementations::resources::healthcheck::AppHealthCheckResource,
     precondition::Precondition,
     resource::{Resource, ResourceId, StoredResource},
-    service::ZkStackService,
-    task::{OneshotTask, Task, UnconstrainedOneshotTask, UnconstrainedTask},
+    service::{TaskKind, ZkStackService},
+    task::{
+        OneshotTask, RestartPolicy, Task, TaskFactory, UnconstrainedOneshotTask, UnconstrainedTask,
+    },
     wiring_layer::WiringError,
 };
 
@@ -40,10 +46,49 @@ impl<'a> ServiceContext<'a> {
     /// are met.
     pub fn add_task(&mut self, task: Box<dyn Task>) -> &mut Self {
         tracing::info!("Layer {} has added a new task: {}", self.layer, task.name());
-        self.service.runnables.tasks.push(task);
+        self.service
+            .wiring_graph
+            .record_task(self.layer, task.name(), TaskKind::Task);
+        self.service
+            .runnables
+            .tasks
+            .push((self.layer.to_owned(), task));
         self
     }
 
+    /// Adds a task that will be restarted according to the provided restart policy instead of shutting down
+    /// the node once it exits. Tasks are created by the provided factory; the task is (re)started
+    /// after the wiring process is finished and all the preconditions are met.
+    ///
+    /// The number of restarts is reported via metrics and via a health check with the task name,
+    /// which is added to the [`AppHealthCheckResource`].
+    pub async fn add_restartable_task(
+        &mut self,
+        factory: Box<dyn TaskFactory>,
+        policy: RestartPolicy,
+    ) -> Result<&mut Self, WiringError> {
+        let name = factory.name();
+        tracing::info!(
+            "Layer {} has added a new restartable task: {name} (restart policy: {policy:?})",
+            self.layer
+        );
+        let (health_check, health_updater) = ReactiveHealthCheck::new(name);
+        let AppHealthCheckResource(app_health) = self.get_resource_or_default().await;
+        app_health
+            .insert_component(health_check)
+            .map_err(WiringError::internal)?;
+
+        self.service
+            .wiring_graph
+            .record_task(self.layer, name, TaskKind::RestartableTask);
+        let task = SupervisedTask::new(factory, policy, health_updater);
+        self.service
+            .runnables
+            .supervised_tasks
+            .push((self.layer.to_owned(), task));
+        Ok(self)
+    }
+
     /// Adds an unconstrained task to the service.
     /// Unconstrained tasks will be launched immediately after the wiring process is finished.
     pub fn add_unconstrained_task(&mut self, task: Box<dyn UnconstrainedTask>) -> &mut Self {
@@ -52,7 +97,13 @@ impl<'a> ServiceContext<'a> {
             self.layer,
             task.name()
         );
-        self.service.runnables.unconstrained_tasks.push(task);
+        self.service
+            .wiring_graph
+            .record_task(self.layer, task.name(), TaskKind::UnconstrainedTask);
+        self.service
+            .runnables
+            .unconstrained_tasks
+            .push((self.layer.to_owned(), task));
         self
     }
 
@@ -63,6 +114,11 @@ impl<'a> ServiceContext<'a> {
             self.layer,
             precondition.name()
         );
+        self.service.wiring_graph.record_task(
+            self.layer,
+            precondition.name(),
+            TaskKind::Precondition,
+        );
         self.service.runnables.preconditions.push(precondition);
         self
     }
@@ -74,6 +130,9 @@ impl<'a> ServiceContext<'a> {
             self.layer,
             task.name()
         );
+        self.service
+            .wiring_graph
+            .record_task(self.layer, task.name(), TaskKind::OneshotTask);
         self.service.runnables.oneshot_tasks.push(task);
         self
     }
@@ -88,6 +147,11 @@ impl<'a> ServiceContext<'a> {
             self.layer,
             task.name()
         );
+        self.service.wiring_graph.record_task(
+            self.layer,
+            task.name(),
+            TaskKind::UnconstrainedOneshotTask,
+        );
         self.service
             .runnables
             .unconstrained_oneshot_tasks
@@ -125,7 +189,14 @@ impl<'a> ServiceContext<'a> {
                 T::name(),
                 type_name::<T>()
             );
-            return Ok(downcast_clone(resource));
+            let resource = downcast_clone(resource);
+            self.service
+                .layer_dependencies
+                .record_consumer(self.layer, &ResourceId::of::<T>());
+            self.service
+                .wiring_graph
+                .record_requested(self.layer, T::name());
+            return Ok(resource);
         }
 
         tracing::info!(
@@ -137,6 +208,9 @@ impl<'a> ServiceContext<'a> {
 
         // No such resource.
         // The requester is allowed to decide whether this is an error or not.
+        self.service
+            .wiring_graph
+            .record_missing(self.layer, T::name());
         Err(WiringError::ResourceLacking {
             name: T::name(),
             id: ResourceId::of::<T>(),
@@ -153,11 +227,16 @@ impl<'a> ServiceContext<'a> {
             return resource;
         }
 
-        // No such resource, insert a new one.
+        // No such resource, insert a new one. Unlike with `insert_resource()`, the layer isn't recorded
+        // as the resource provider: the resource is created on demand by whichever layer requests it first,
+        // so other layers using it don't depend on this layer.
         let resource = f();
         self.service
             .resources
             .insert(ResourceId::of::<T>(), Box::new(resource.clone()));
+        self.service
+            .wiring_graph
+            .record_provided(self.layer, T::name());
         tracing::info!(
             "Layer {} has created a new resource {}",
             self.layer,
@@ -188,7 +267,15 @@ impl<'a> ServiceContext<'a> {
                 name: T::name(),
             });
         }
-        self.service.resources.insert(id, Box::new(resource));
+        self.service
+            .resources
+            .insert(id.clone(), Box::new(resource));
+        self.service
+            .layer_dependencies
+            .record_provider(self.layer, id);
+        self.service
+            .wiring_graph
+            .record_provided(self.layer, T::name());
         tracing::info!(
             "Layer {} has provided a new resource {}",
             self.layer,
@@ -0,0 +1,14 @@
+//! Metrics for the node service.
+
+use vise::{Counter, LabeledFamily, Metrics};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "node_framework")]
+pub(super) struct ServiceMetrics {
+    /// Number of restarts of a supervised task.
+    #[metrics(labels = ["task"])]
+    pub task_restarts: LabeledFamily<&'static str, Counter>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<ServiceMetrics> = vise::Global::new();
@@ -1,27 +1,37 @@
-use std::{collections::HashMap, time::Duration};
+use std::collections::HashMap;
 
 use anyhow::Context;
-use futures::{future::BoxFuture, FutureExt};
+use futures::future::BoxFuture;
 use tokio::{runtime::Runtime, sync::watch};
 use zksync_utils::panic_extractor::try_extract_panic_message;
 
-use self::runnables::Runnables;
-pub use self::{context::ServiceContext, error::ZkStackServiceError, stop_receiver::StopReceiver};
+pub use self::{
+    context::ServiceContext,
+    error::ZkStackServiceError,
+    stop_receiver::StopReceiver,
+    wiring_graph::{LayerNode, MissingResource, TaskKind, TaskNode, WiringGraph},
+};
+use self::{
+    runnables::Runnables,
+    shutdown::{LayerDependencies, RunningTask},
+};
 use crate::{
     resource::{ResourceId, StoredResource},
     service::runnables::TaskReprs,
+    task::{DEFAULT_SHUTDOWN_TIMEOUT, TOTAL_SHUTDOWN_TIMEOUT},
     wiring_layer::{WiringError, WiringLayer},
 };
 
 mod context;
 mod error;
+mod metrics;
 mod runnables;
+mod shutdown;
 mod stop_receiver;
+mod supervisor;
 #[cfg(test)]
 mod tests;
-
-// A reasonable amount of time for any task to finish the shutdown process
-const TASK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+mod wiring_graph;
 
 /// A builder for [`ZkStackService`].
 #[derive(Default, Debug)]
@@ -54,6 +64,13 @@ impl ZkStackServiceBuilder {
         self
     }
 
+    /// Performs wiring of all the added layers without running any tasks, and returns the resulting graph
+    /// of resources and tasks. Unlike [`run`](ZkStackService::run), wiring errors are not returned as an error;
+    /// instead, they are recorded in the graph for the corresponding layers.
+    pub fn dry_run(&mut self) -> Result<WiringGraph, ZkStackServiceError> {
+        Ok(self.build()?.dry_run())
+    }
+
     pub fn build(&mut self) -> Result<ZkStackService, ZkStackServiceError> {
         if tokio::runtime::Handle::try_current().is_ok() {
             return Err(ZkStackServiceError::RuntimeDetected);
@@ -69,6 +86,9 @@ impl ZkStackServiceBuilder {
             layers: std::mem::take(&mut self.layers),
             resources: Default::default(),
             runnables: Default::default(),
+            layer_dependencies: Default::default(),
+            wiring_graph: Default::default(),
+            layer_stop_senders: Default::default(),
             stop_sender,
             runtime,
         })
@@ -85,17 +105,22 @@ pub struct ZkStackService {
     layers: Vec<Box<dyn WiringLayer>>,
     /// Different kinds of tasks for the service.
     runnables: Runnables,
+    /// Dependencies between wiring layers used to determine the shutdown order.
+    layer_dependencies: LayerDependencies,
+    /// Graph of resources and tasks collected during wiring.
+    wiring_graph: WiringGraph,
 
-    /// Sender used to stop the tasks.
+    /// Sender used to stop oneshot tasks and preconditions.
     stop_sender: watch::Sender<bool>,
+    /// Senders used to stop long-running tasks added by each of the wiring layers.
+    layer_stop_senders: HashMap<String, watch::Sender<bool>>,
     /// Tokio runtime used to spawn tasks.
     runtime: Runtime,
 }
 
 impl ZkStackService {
-    /// Runs the system.
-    pub fn run(mut self) -> Result<(), ZkStackServiceError> {
-        // Initialize tasks.
+    /// Invokes all the wiring layers and returns the errors they've produced.
+    fn wire(&mut self) -> Vec<(String, WiringError)> {
         let wiring_layers = std::mem::take(&mut self.layers);
 
         let mut errors: Vec<(String, WiringError)> = Vec::new();
@@ -103,6 +128,10 @@ impl ZkStackService {
         let runtime_handle = self.runtime.handle().clone();
         for layer in wiring_layers {
             let name = layer.layer_name().to_string();
+            self.layer_dependencies.add_layer(&name);
+            self.wiring_graph.add_layer(&name);
+            self.layer_stop_senders
+                .insert(name.clone(), watch::channel(false).0);
             // We must process wiring layers sequentially and in the same order as they were added.
             let task_result =
                 runtime_handle.block_on(layer.wire(ServiceContext::new(&name, &mut self)));
@@ -110,10 +139,28 @@ impl ZkStackService {
                 // We don't want to bail on the first error, since it'll provide worse DevEx:
                 // People likely want to fix as much problems as they can in one go, rather than have
                 // to fix them one by one.
+                self.wiring_graph.record_error(&name, err.to_string());
                 errors.push((name, err));
                 continue;
             };
         }
+        self.wiring_graph.finalize();
+        errors
+    }
+
+    fn dry_run(mut self) -> WiringGraph {
+        let errors = self.wire();
+        for (layer, error) in &errors {
+            tracing::warn!("Wiring layer {layer} can't be initialized: {error}");
+        }
+        // Tasks are dropped without being run.
+        self.wiring_graph
+    }
+
+    /// Runs the system.
+    pub fn run(mut self) -> Result<(), ZkStackServiceError> {
+        // Initialize tasks.
+        let errors = self.wire();
 
         // Report all the errors we've met during the init.
         if !errors.is_empty() {
@@ -136,11 +183,13 @@ impl ZkStackService {
         // Collect long-running tasks.
         let stop_receiver = StopReceiver(self.stop_sender.subscribe());
         let TaskReprs {
-            mut long_running_tasks,
+            long_running_tasks,
             oneshot_tasks,
-        } = self
-            .runnables
-            .prepare_tasks(task_barrier.clone(), stop_receiver.clone());
+        } = self.runnables.prepare_tasks(
+            task_barrier.clone(),
+            stop_receiver.clone(),
+            &self.layer_stop_senders,
+        );
 
         // Wiring is now complete.
         for resource in self.resources.values_mut() {
@@ -152,19 +201,32 @@ impl ZkStackService {
         // stop signal.
         let oneshot_runner_system_task =
             oneshot_runner_task(oneshot_tasks, stop_receiver, only_oneshot_tasks);
-        long_running_tasks.push(oneshot_runner_system_task);
 
-        // Prepare tasks for running.
+        // Spawn the tasks.
         let rt_handle = self.runtime.handle().clone();
-        let join_handles: Vec<_> = long_running_tasks
+        let mut tasks: Vec<_> = long_running_tasks
             .into_iter()
-            .map(|task| rt_handle.spawn(task).fuse())
+            .map(|task| RunningTask {
+                name: task.name,
+                layer: Some(task.layer),
+                shutdown_timeout: task.shutdown_timeout,
+                handle: rt_handle.spawn(task.future),
+                finished: false,
+            })
             .collect();
+        tasks.push(RunningTask {
+            name: "oneshot_runner",
+            layer: None,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            handle: rt_handle.spawn(oneshot_runner_system_task),
+            finished: false,
+        });
 
         // Run the tasks until one of them exits.
-        let (resolved, _, remaining) = self
-            .runtime
-            .block_on(futures::future::select_all(join_handles));
+        let (resolved, resolved_idx, _) = self.runtime.block_on(futures::future::select_all(
+            tasks.iter_mut().map(|task| &mut task.handle),
+        ));
+        tasks[resolved_idx].finished = true;
         let result = match resolved {
             Ok(Ok(())) => Ok(()),
             Ok(Err(err)) => Err(err).context("Task failed"),
@@ -176,24 +238,21 @@ impl ZkStackService {
             }
         };
 
-        let remaining_tasks_with_timeout: Vec<_> = remaining
-            .into_iter()
-            .map(|task| async { tokio::time::timeout(TASK_SHUTDOWN_TIMEOUT, task).await })
-            .collect();
-
-        // Send stop signal to remaining tasks and wait for them to finish.
-        // Given that we are shutting down, we do not really care about returned values.
+        // Stop oneshot tasks and preconditions, then stop long-running tasks in the reverse dependency order:
+        // tasks of a layer are stopped only after all tasks of the layers depending on it are stopped.
         self.stop_sender.send(true).ok();
-        let execution_results = self
-            .runtime
-            .block_on(futures::future::join_all(remaining_tasks_with_timeout));
-        let execution_timeouts_count = execution_results.iter().filter(|&r| r.is_err()).count();
-        if execution_timeouts_count > 0 {
+        let force_dropped_tasks = self.runtime.block_on(self.layer_dependencies.stop_tasks(
+            &mut tasks,
+            &self.layer_stop_senders,
+            TOTAL_SHUTDOWN_TIMEOUT,
+        ));
+        if force_dropped_tasks.is_empty() {
+            tracing::info!("Remaining tasks finished without reaching timeouts");
+        } else {
             tracing::warn!(
-                "{execution_timeouts_count} tasks didn't finish in {TASK_SHUTDOWN_TIMEOUT:?} and were dropped"
+                "{} tasks didn't finish in time and were dropped: {force_dropped_tasks:?}",
+                force_dropped_tasks.len()
             );
-        } else {
-            tracing::info!("Remaining tasks finished without reaching timeouts");
         }
 
         result?;
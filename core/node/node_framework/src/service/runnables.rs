@@ -1,10 +1,10 @@
-use std::{fmt, sync::Arc};
+use std::{collections::HashMap, fmt, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use futures::future::BoxFuture;
-use tokio::sync::Barrier;
+use tokio::sync::{watch, Barrier};
 
-use super::StopReceiver;
+use super::{supervisor::SupervisedTask, StopReceiver};
 use crate::{
     precondition::Precondition,
     task::{OneshotTask, Task, UnconstrainedOneshotTask, UnconstrainedTask},
@@ -15,12 +15,14 @@ use crate::{
 pub(super) struct Runnables {
     /// Preconditions added to the service.
     pub(super) preconditions: Vec<Box<dyn Precondition>>,
-    /// Tasks added to the service.
-    pub(super) tasks: Vec<Box<dyn Task>>,
+    /// Tasks added to the service, together with the names of the layers that have added them.
+    pub(super) tasks: Vec<(String, Box<dyn Task>)>,
+    /// Tasks added to the service that are restarted according to their restart policy.
+    pub(super) supervised_tasks: Vec<(String, SupervisedTask)>,
     /// Oneshot tasks added to the service.
     pub(super) oneshot_tasks: Vec<Box<dyn OneshotTask>>,
     /// Unconstrained tasks added to the service.
-    pub(super) unconstrained_tasks: Vec<Box<dyn UnconstrainedTask>>,
+    pub(super) unconstrained_tasks: Vec<(String, Box<dyn UnconstrainedTask>)>,
     /// Unconstrained oneshot tasks added to the service.
     pub(super) unconstrained_oneshot_tasks: Vec<Box<dyn UnconstrainedOneshotTask>>,
 }
@@ -34,12 +36,22 @@ impl fmt::Debug for Runnables {
                 &$vec.iter().map(|x| x.name()).collect::<Vec<_>>()
             };
         }
+        // Same as `names!`, but for tasks stored together with the name of the layer.
+        macro_rules! layer_task_names {
+            ($vec:expr) => {
+                &$vec.iter().map(|(_, x)| x.name()).collect::<Vec<_>>()
+            };
+        }
 
         f.debug_struct("Runnables")
             .field("preconditions", names!(self.preconditions))
-            .field("tasks", names!(self.tasks))
+            .field("tasks", layer_task_names!(self.tasks))
+            .field("supervised_tasks", layer_task_names!(self.supervised_tasks))
             .field("oneshot_tasks", names!(self.oneshot_tasks))
-            .field("unconstrained_tasks", names!(self.unconstrained_tasks))
+            .field(
+                "unconstrained_tasks",
+                layer_task_names!(self.unconstrained_tasks),
+            )
             .field(
                 "unconstrained_oneshot_tasks",
                 names!(self.unconstrained_oneshot_tasks),
@@ -48,9 +60,18 @@ impl fmt::Debug for Runnables {
     }
 }
 
+/// A long-running task prepared to be run by the service.
+pub(super) struct LongRunningTask {
+    pub(super) name: &'static str,
+    /// Name of the layer that has added the task.
+    pub(super) layer: String,
+    pub(super) shutdown_timeout: Duration,
+    pub(super) future: BoxFuture<'static, anyhow::Result<()>>,
+}
+
 /// A unified representation of tasks that can be run by the service.
 pub(super) struct TaskReprs {
-    pub(super) long_running_tasks: Vec<BoxFuture<'static, anyhow::Result<()>>>,
+    pub(super) long_running_tasks: Vec<LongRunningTask>,
     pub(super) oneshot_tasks: Vec<BoxFuture<'static, anyhow::Result<()>>>,
 }
 
@@ -69,6 +90,7 @@ impl Runnables {
     pub(super) fn is_empty(&self) -> bool {
         // We don't consider preconditions to be tasks.
         self.tasks.is_empty()
+            && self.supervised_tasks.is_empty()
             && self.oneshot_tasks.is_empty()
             && self.unconstrained_tasks.is_empty()
             && self.unconstrained_oneshot_tasks.is_empty()
@@ -76,7 +98,9 @@ impl Runnables {
 
     /// Returns `true` if there are no long-running tasks in the collection.
     pub(super) fn is_oneshot_only(&self) -> bool {
-        self.tasks.is_empty() && self.unconstrained_tasks.is_empty()
+        self.tasks.is_empty()
+            && self.supervised_tasks.is_empty()
+            && self.unconstrained_tasks.is_empty()
     }
 
     /// Prepares a barrier that should be shared between tasks and preconditions.
@@ -84,22 +108,41 @@ impl Runnables {
     /// Barrier does not assume the existence of unconstrained tasks.
     pub(super) fn task_barrier(&self) -> Arc<Barrier> {
         Arc::new(Barrier::new(
-            self.tasks.len() + self.preconditions.len() + self.oneshot_tasks.len(),
+            self.tasks.len()
+                + self.supervised_tasks.len()
+                + self.preconditions.len()
+                + self.oneshot_tasks.len(),
         ))
     }
 
     /// Transforms the collection of tasks into a set of universal futures.
+    ///
+    /// Long-running tasks receive the stop signal of the layer that has added them, so that they can be stopped
+    /// in the dependency order. Oneshot tasks and preconditions receive the service-wide stop signal.
     pub(super) fn prepare_tasks(
         mut self,
         task_barrier: Arc<Barrier>,
         stop_receiver: StopReceiver,
+        layer_stop_senders: &HashMap<String, watch::Sender<bool>>,
     ) -> TaskReprs {
+        let layer_stop_receiver = |layer: &str| {
+            let sender = layer_stop_senders
+                .get(layer)
+                .unwrap_or_else(|| panic!("Layer {layer} doesn't have a stop signal"));
+            StopReceiver(sender.subscribe())
+        };
+
         let mut long_running_tasks = Vec::new();
-        self.collect_unconstrained_tasks(&mut long_running_tasks, stop_receiver.clone());
+        self.collect_unconstrained_tasks(&mut long_running_tasks, layer_stop_receiver);
         self.collect_tasks(
             &mut long_running_tasks,
             task_barrier.clone(),
-            stop_receiver.clone(),
+            layer_stop_receiver,
+        );
+        self.collect_supervised_tasks(
+            &mut long_running_tasks,
+            task_barrier.clone(),
+            layer_stop_receiver,
         );
 
         let mut oneshot_tasks = Vec::new();
@@ -123,37 +166,74 @@ impl Runnables {
 
     fn collect_unconstrained_tasks(
         &mut self,
-        tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
-        stop_receiver: StopReceiver,
+        tasks: &mut Vec<LongRunningTask>,
+        stop_receiver: impl Fn(&str) -> StopReceiver,
     ) {
-        for task in std::mem::take(&mut self.unconstrained_tasks) {
+        for (layer, task) in std::mem::take(&mut self.unconstrained_tasks) {
             let name = task.name();
-            let stop_receiver = stop_receiver.clone();
+            let shutdown_timeout = task.shutdown_timeout();
+            let stop_receiver = stop_receiver(&layer);
             let task_future = Box::pin(async move {
                 task.run_unconstrained(stop_receiver)
                     .await
                     .with_context(|| format!("Task {name} failed"))
             });
-            tasks.push(task_future);
+            tasks.push(LongRunningTask {
+                name,
+                layer,
+                shutdown_timeout,
+                future: task_future,
+            });
         }
     }
 
     fn collect_tasks(
         &mut self,
-        tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
+        tasks: &mut Vec<LongRunningTask>,
         task_barrier: Arc<Barrier>,
-        stop_receiver: StopReceiver,
+        stop_receiver: impl Fn(&str) -> StopReceiver,
     ) {
-        for task in std::mem::take(&mut self.tasks) {
+        for (layer, task) in std::mem::take(&mut self.tasks) {
             let name = task.name();
-            let stop_receiver = stop_receiver.clone();
+            let shutdown_timeout = task.shutdown_timeout();
+            let stop_receiver = stop_receiver(&layer);
             let task_barrier = task_barrier.clone();
             let task_future = Box::pin(async move {
                 task.run_with_barrier(stop_receiver, task_barrier)
                     .await
                     .with_context(|| format!("Task {name} failed"))
             });
-            tasks.push(task_future);
+            tasks.push(LongRunningTask {
+                name,
+                layer,
+                shutdown_timeout,
+                future: task_future,
+            });
+        }
+    }
+
+    fn collect_supervised_tasks(
+        &mut self,
+        tasks: &mut Vec<LongRunningTask>,
+        task_barrier: Arc<Barrier>,
+        stop_receiver: impl Fn(&str) -> StopReceiver,
+    ) {
+        for (layer, task) in std::mem::take(&mut self.supervised_tasks) {
+            let name = task.name();
+            let shutdown_timeout = task.shutdown_timeout();
+            let stop_receiver = stop_receiver(&layer);
+            let task_barrier = task_barrier.clone();
+            let task_future = Box::pin(async move {
+                task.run(stop_receiver, task_barrier)
+                    .await
+                    .with_context(|| format!("Task {name} failed"))
+            });
+            tasks.push(LongRunningTask {
+                name,
+                layer,
+                shutdown_timeout,
+                future: task_future,
+            });
         }
     }
 
@@ -0,0 +1,270 @@
+//! Dependency-ordered shutdown of the service.
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use tokio::{sync::watch, task::JoinHandle};
+
+use crate::resource::ResourceId;
+
+/// Long-running task spawned by the service.
+#[derive(Debug)]
+pub(super) struct RunningTask {
+    pub(super) name: &'static str,
+    /// Name of the layer that has added the task. System tasks don't belong to any layer and are stopped last.
+    pub(super) layer: Option<String>,
+    pub(super) shutdown_timeout: Duration,
+    pub(super) handle: JoinHandle<anyhow::Result<()>>,
+    /// Whether the task has already finished (i.e., its handle must not be polled anymore).
+    pub(super) finished: bool,
+}
+
+/// Dependencies between wiring layers derived from the resources they provide and consume.
+///
+/// A layer that requests a resource depends on the layer that has provided it. On shutdown, tasks
+/// added by a layer are stopped only after the tasks of all layers depending on it have stopped;
+/// e.g., tasks using a connection pool are stopped before the task maintaining the pool.
+#[derive(Debug, Default)]
+pub(super) struct LayerDependencies {
+    /// Layers in the wiring order.
+    layers: Vec<String>,
+    /// Layers that have provided each of the resources.
+    providers: HashMap<ResourceId, String>,
+    /// Layers that each layer depends on.
+    dependencies: HashMap<String, HashSet<String>>,
+}
+
+impl LayerDependencies {
+    pub(super) fn add_layer(&mut self, layer: &str) {
+        self.layers.push(layer.to_owned());
+    }
+
+    pub(super) fn record_provider(&mut self, layer: &str, resource: ResourceId) {
+        self.providers.insert(resource, layer.to_owned());
+    }
+
+    pub(super) fn record_consumer(&mut self, layer: &str, resource: &ResourceId) {
+        let Some(provider) = self.providers.get(resource) else {
+            return;
+        };
+        if provider != layer {
+            self.dependencies
+                .entry(layer.to_owned())
+                .or_default()
+                .insert(provider.clone());
+        }
+    }
+
+    /// Returns layers grouped into shutdown stages. Layers in the first stage should be stopped first,
+    /// and each subsequent stage should be stopped after all previous stages have stopped.
+    ///
+    /// If dependencies contain a cycle, no meaningful order can be established, so all layers are returned
+    /// as a single stage.
+    pub(super) fn shutdown_stages(&self) -> Vec<Vec<String>> {
+        let mut stage_by_layer: HashMap<&str, usize> = self
+            .layers
+            .iter()
+            .map(|layer| (layer.as_str(), 0))
+            .collect();
+        // If dependencies form a DAG, the longest dependency chain is bounded by the number of layers,
+        // so stages must converge in at most `layers.len()` iterations.
+        let mut converged = false;
+        for _ in 0..=self.layers.len() {
+            let mut changed = false;
+            for (layer, dependencies) in &self.dependencies {
+                let min_dependency_stage =
+                    stage_by_layer.get(layer.as_str()).copied().unwrap_or(0) + 1;
+                for dependency in dependencies {
+                    let stage = stage_by_layer.entry(dependency).or_insert(0);
+                    if *stage < min_dependency_stage {
+                        *stage = min_dependency_stage;
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                converged = true;
+                break;
+            }
+        }
+        if !converged {
+            tracing::warn!(
+                "Layer dependencies contain a cycle ({:?}); all layers will be stopped simultaneously",
+                self.dependencies
+            );
+            return vec![self.layers.clone()];
+        }
+
+        let stage_count = stage_by_layer.values().max().map_or(0, |&max| max + 1);
+        let mut stages = vec![vec![]; stage_count];
+        for layer in &self.layers {
+            stages[stage_by_layer[layer.as_str()]].push(layer.clone());
+        }
+        stages.retain(|stage| !stage.is_empty());
+        stages
+    }
+
+    /// Stops tasks in the order defined by [`Self::shutdown_stages()`]. Tasks not belonging to any layer are stopped
+    /// after all stages. Each task is given its own shutdown timeout; tasks that don't finish in time are aborted.
+    /// If stopping all tasks takes longer than `total_timeout`, all remaining tasks are aborted as well.
+    ///
+    /// Returns names of the tasks that were force-dropped.
+    pub(super) async fn stop_tasks(
+        &self,
+        tasks: &mut [RunningTask],
+        layer_stop_senders: &HashMap<String, watch::Sender<bool>>,
+        total_timeout: Duration,
+    ) -> Vec<&'static str> {
+        let mut force_dropped = vec![];
+        let stop_in_stages =
+            self.stop_tasks_in_stages(tasks, layer_stop_senders, &mut force_dropped);
+        if tokio::time::timeout(total_timeout, stop_in_stages)
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                "Tasks didn't stop in {total_timeout:?}; all remaining tasks will be dropped"
+            );
+            for task in tasks.iter_mut().filter(|task| !task.finished) {
+                task.handle.abort();
+                task.finished = true;
+                force_dropped.push(task.name);
+            }
+        }
+        force_dropped
+    }
+
+    async fn stop_tasks_in_stages(
+        &self,
+        tasks: &mut [RunningTask],
+        layer_stop_senders: &HashMap<String, watch::Sender<bool>>,
+        force_dropped: &mut Vec<&'static str>,
+    ) {
+        for (i, stage) in self.shutdown_stages().into_iter().enumerate() {
+            tracing::info!("Stopping tasks of layers {stage:?} (shutdown stage #{i})");
+            for layer in &stage {
+                if let Some(sender) = layer_stop_senders.get(layer) {
+                    sender.send_replace(true);
+                }
+            }
+            let stage_tasks = tasks.iter_mut().filter(|task| {
+                task.layer
+                    .as_ref()
+                    .map_or(false, |layer| stage.contains(layer))
+            });
+            force_dropped.extend(Self::wait_for_tasks(stage_tasks).await);
+        }
+
+        let system_tasks = tasks.iter_mut().filter(|task| task.layer.is_none());
+        force_dropped.extend(Self::wait_for_tasks(system_tasks).await);
+    }
+
+    async fn wait_for_tasks(tasks: impl Iterator<Item = &mut RunningTask>) -> Vec<&'static str> {
+        let tasks = tasks.filter(|task| !task.finished).map(|task| async move {
+            // Given that we are shutting down, we do not really care about returned values.
+            let result = tokio::time::timeout(task.shutdown_timeout, &mut task.handle).await;
+            task.finished = true;
+            if result.is_err() {
+                tracing::warn!(
+                    "Task {} didn't finish in {:?} and was dropped",
+                    task.name,
+                    task.shutdown_timeout
+                );
+                task.handle.abort();
+                Some(task.name)
+            } else {
+                None
+            }
+        });
+        futures::future::join_all(tasks)
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct First;
+    struct Second;
+    struct Third;
+
+    #[test]
+    fn shutdown_stages_follow_dependencies() {
+        let mut deps = LayerDependencies::default();
+        for layer in ["pools", "api", "state_keeper", "healthcheck"] {
+            deps.add_layer(layer);
+        }
+        deps.record_provider("pools", ResourceId::of::<First>());
+        deps.record_consumer("api", &ResourceId::of::<First>());
+        deps.record_consumer("state_keeper", &ResourceId::of::<First>());
+        deps.record_provider("state_keeper", ResourceId::of::<Second>());
+        deps.record_consumer("api", &ResourceId::of::<Second>());
+
+        let stages = deps.shutdown_stages();
+        assert_eq!(
+            stages,
+            [
+                vec!["api".to_owned(), "healthcheck".to_owned()],
+                vec!["state_keeper".to_owned()],
+                vec!["pools".to_owned()],
+            ]
+        );
+    }
+
+    #[test]
+    fn shutdown_stages_with_dependency_cycle() {
+        let mut deps = LayerDependencies::default();
+        for layer in ["pools", "first", "second"] {
+            deps.add_layer(layer);
+        }
+        deps.record_provider("pools", ResourceId::of::<First>());
+        deps.record_consumer("first", &ResourceId::of::<First>());
+        deps.record_consumer("second", &ResourceId::of::<First>());
+        deps.record_provider("first", ResourceId::of::<Second>());
+        deps.record_consumer("second", &ResourceId::of::<Second>());
+        // Cannot happen with sequential wiring, but the shutdown order should still be well-defined.
+        deps.record_provider("second", ResourceId::of::<Third>());
+        deps.record_consumer("first", &ResourceId::of::<Third>());
+
+        let stages = deps.shutdown_stages();
+        assert_eq!(
+            stages,
+            [vec![
+                "pools".to_owned(),
+                "first".to_owned(),
+                "second".to_owned()
+            ]]
+        );
+    }
+
+    #[tokio::test]
+    async fn total_shutdown_time_is_bounded() {
+        let mut deps = LayerDependencies::default();
+        deps.add_layer("hanging");
+        let (stop_sender, _) = watch::channel(false);
+        let layer_stop_senders = HashMap::from([("hanging".to_owned(), stop_sender)]);
+        let mut tasks = ["first", "second"].map(|name| RunningTask {
+            name,
+            layer: Some("hanging".to_owned()),
+            // Individual timeouts exceed the total timeout.
+            shutdown_timeout: Duration::from_secs(3_600),
+            handle: tokio::spawn(futures::future::pending()),
+            finished: false,
+        });
+
+        let force_dropped = deps
+            .stop_tasks(&mut tasks, &layer_stop_senders, Duration::from_millis(10))
+            .await;
+        assert_eq!(force_dropped, ["first", "second"]);
+        for task in tasks {
+            assert!(task.finished);
+            assert!(task.handle.await.unwrap_err().is_cancelled());
+        }
+    }
+}
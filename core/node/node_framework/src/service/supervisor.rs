@@ -0,0 +1,188 @@
+//! Supervision of restartable tasks.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::Barrier;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater};
+use zksync_utils::panic_extractor::try_extract_panic_message;
+
+use super::{metrics::METRICS, StopReceiver};
+use crate::task::{RestartPolicy, TaskFactory};
+
+/// Error returned by [`SupervisedTask::run_once()`] if the task has panicked.
+#[derive(Debug, thiserror::Error)]
+#[error("Task panicked: {0}")]
+struct TaskPanicked(String);
+
+/// Health details of a supervised task.
+#[derive(Debug, Serialize)]
+struct SupervisedTaskHealthDetails<'a> {
+    restart_policy: &'static str,
+    restarts: u64,
+    /// Set if the task has exited and will not be restarted, while the node continues running.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    finished: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_error: Option<&'a str>,
+}
+
+/// Task created by a [`TaskFactory`] and restarted according to its [`RestartPolicy`].
+pub(super) struct SupervisedTask {
+    factory: Box<dyn TaskFactory>,
+    policy: RestartPolicy,
+    health_updater: HealthUpdater,
+    restarts: u64,
+    finished: bool,
+    last_error: Option<String>,
+}
+
+impl SupervisedTask {
+    pub(super) fn new(
+        factory: Box<dyn TaskFactory>,
+        policy: RestartPolicy,
+        health_updater: HealthUpdater,
+    ) -> Self {
+        Self {
+            factory,
+            policy,
+            health_updater,
+            restarts: 0,
+            finished: false,
+            last_error: None,
+        }
+    }
+
+    pub(super) fn name(&self) -> &'static str {
+        self.factory.name()
+    }
+
+    pub(super) fn shutdown_timeout(&self) -> Duration {
+        self.factory.shutdown_timeout()
+    }
+
+    fn update_health(&self, status: HealthStatus) {
+        let details = SupervisedTaskHealthDetails {
+            restart_policy: self.policy.as_str(),
+            restarts: self.restarts,
+            finished: self.finished,
+            last_error: self.last_error.as_deref(),
+        };
+        self.health_updater
+            .update(Health::from(status).with_details(details));
+    }
+
+    /// Sets the terminal health status. The health updater is frozen afterwards, so that dropping it
+    /// doesn't discard health details.
+    fn finish(self, status: HealthStatus) {
+        self.update_health(status);
+        self.health_updater.freeze();
+    }
+
+    /// Records the terminal health status based on the result of the last task run.
+    fn finish_with_result(mut self, result: &anyhow::Result<()>) {
+        let status = match result {
+            Ok(()) => HealthStatus::ShutDown,
+            Err(err) => {
+                self.last_error = Some(format!("{err:#}"));
+                if err.is::<TaskPanicked>() {
+                    HealthStatus::Panicked
+                } else {
+                    HealthStatus::ShutDown
+                }
+            }
+        };
+        self.finish(status);
+    }
+
+    async fn run_once(&mut self, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let task = self.factory.create_task().context("failed creating task")?;
+        // Spawn the task separately, so that panics are caught and can be handled by the supervisor.
+        match tokio::spawn(task.run(stop_receiver)).await {
+            Ok(result) => result,
+            Err(panic_err) => {
+                let panic_msg = try_extract_panic_message(panic_err);
+                Err(TaskPanicked(panic_msg).into())
+            }
+        }
+    }
+
+    /// Runs the task, restarting it as prescribed by the restart policy. Returns once the task should not
+    /// be restarted anymore; the returned result is treated in the same way as for non-restartable tasks.
+    pub(super) async fn run(
+        mut self,
+        mut stop_receiver: StopReceiver,
+        preconditions_barrier: Arc<Barrier>,
+    ) -> anyhow::Result<()> {
+        tokio::select! {
+            _ = preconditions_barrier.wait() => {}
+            _ = stop_receiver.0.changed() => return Ok(()),
+        }
+
+        let name = self.name();
+        let Some(backoff) = self.policy.backoff() else {
+            self.update_health(HealthStatus::Ready);
+            let result = self.run_once(stop_receiver).await;
+            self.finish_with_result(&result);
+            return result;
+        };
+        let mut delay = backoff.initial_delay();
+        loop {
+            // Restarted tasks are still considered healthy, so that an auxiliary task doesn't make the entire node unhealthy.
+            let status = if self.restarts == 0 {
+                HealthStatus::Ready
+            } else {
+                HealthStatus::Affected
+            };
+            self.update_health(status);
+
+            let started_at = Instant::now();
+            let result = self.run_once(stop_receiver.clone()).await;
+            if *stop_receiver.0.borrow() {
+                self.finish_with_result(&result);
+                return result;
+            }
+            if started_at.elapsed() >= backoff.max_delay() {
+                delay = backoff.initial_delay();
+            }
+
+            let restart_on_success = matches!(self.policy, RestartPolicy::Always(_));
+            match result {
+                Ok(()) if !restart_on_success => {
+                    tracing::info!(
+                        "Task {name} has finished; the node will continue running without it"
+                    );
+                    // `ShutDown` would fail liveness checks for the node, which continues running normally.
+                    self.finished = true;
+                    self.update_health(HealthStatus::Ready);
+                    stop_receiver.0.changed().await.ok();
+                    self.finish(HealthStatus::ShutDown);
+                    return Ok(());
+                }
+                Ok(()) => {
+                    tracing::warn!("Task {name} has exited; restarting it in {delay:?}");
+                }
+                Err(err) => {
+                    tracing::warn!("Task {name} has failed: {err:#}; restarting it in {delay:?}");
+                    self.last_error = Some(format!("{err:#}"));
+                }
+            }
+            self.restarts += 1;
+            METRICS.task_restarts[&name].inc();
+            self.update_health(HealthStatus::Affected);
+
+            if tokio::time::timeout(delay, stop_receiver.0.changed())
+                .await
+                .is_ok()
+            {
+                self.finish(HealthStatus::ShutDown);
+                return Ok(());
+            }
+            delay = backoff.next_delay(delay);
+        }
+    }
+}
@@ -1,15 +1,21 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use anyhow::anyhow;
 use assert_matches::assert_matches;
 use tokio::{runtime::Runtime, sync::Barrier};
+use zksync_health_check::{Health, HealthStatus};
 
 use crate::{
+    implementations::resources::healthcheck::AppHealthCheckResource,
+    resource::Resource,
     service::{
-        ServiceContext, StopReceiver, WiringError, WiringLayer, ZkStackServiceBuilder,
-        ZkStackServiceError,
+        MissingResource, ServiceContext, StopReceiver, TaskKind, WiringError, WiringLayer,
+        ZkStackServiceBuilder, ZkStackServiceError,
     },
-    task::Task,
+    task::{RestartBackoff, RestartPolicy, Task, TaskFactory},
 };
 
 // `ZkStack` Service's `new()` method has to have a check for nested runtime.
@@ -232,3 +238,419 @@ fn test_task_run() {
     let res2 = *remaining_task_was_run.lock().unwrap();
     assert!(res2, "Incorrect resource value");
 }
+
+#[derive(Debug)]
+struct FlakyTaskFactory {
+    attempts: Arc<Mutex<usize>>,
+    failing_attempts: usize,
+    panics: bool,
+}
+
+impl TaskFactory for FlakyTaskFactory {
+    fn name(&self) -> &'static str {
+        "flaky_task"
+    }
+
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>> {
+        Ok(Box::new(FlakyTask {
+            attempts: self.attempts.clone(),
+            failing_attempts: self.failing_attempts,
+            panics: self.panics,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct FlakyTask {
+    attempts: Arc<Mutex<usize>>,
+    failing_attempts: usize,
+    panics: bool,
+}
+
+#[async_trait::async_trait]
+impl Task for FlakyTask {
+    fn name(&self) -> &'static str {
+        "flaky_task"
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let attempt = {
+            let mut attempts = self.attempts.lock().unwrap();
+            *attempts += 1;
+            *attempts
+        };
+        if attempt <= self.failing_attempts {
+            if self.panics {
+                panic!("flaky task panicked on attempt {attempt}");
+            }
+            anyhow::bail!("flaky task failed on attempt {attempt}");
+        }
+        Ok(())
+    }
+}
+
+/// Critical task that exits once the flaky task has been run the specified number of times.
+#[derive(Debug)]
+struct WatcherTask {
+    attempts: Arc<Mutex<usize>>,
+    expected_attempts: usize,
+}
+
+#[async_trait::async_trait]
+impl Task for WatcherTask {
+    fn name(&self) -> &'static str {
+        "watcher_task"
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        while *self.attempts.lock().unwrap() < self.expected_attempts {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct RestartableTasksLayer {
+    attempts: Arc<Mutex<usize>>,
+    policy: RestartPolicy,
+    panics: bool,
+    /// If set, the service is stopped once the flaky task has been run the specified number of times.
+    expected_attempts: Option<usize>,
+    app_health: AppHealthCheckResource,
+}
+
+impl RestartableTasksLayer {
+    fn new(policy: RestartPolicy) -> Self {
+        Self {
+            attempts: Arc::default(),
+            policy,
+            panics: false,
+            expected_attempts: Some(3),
+            app_health: AppHealthCheckResource::default(),
+        }
+    }
+
+    fn run(self) -> (Result<(), ZkStackServiceError>, usize, Health) {
+        let attempts = self.attempts.clone();
+        let app_health = self.app_health.0.clone();
+        let mut zk_stack_service = ZkStackServiceBuilder::new();
+        zk_stack_service.add_layer(self);
+        let result = zk_stack_service.build().unwrap().run();
+
+        let attempts = *attempts.lock().unwrap();
+        let health = Runtime::new()
+            .unwrap()
+            .block_on(app_health.check_component_health("flaky_task"))
+            .expect("no health check for flaky task");
+        (result, attempts, health)
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for RestartableTasksLayer {
+    fn layer_name(&self) -> &'static str {
+        "restartable_tasks_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        node.insert_resource(self.app_health)?;
+        let factory = FlakyTaskFactory {
+            attempts: self.attempts.clone(),
+            failing_attempts: 2,
+            panics: self.panics,
+        };
+        node.add_restartable_task(Box::new(factory), self.policy)
+            .await?;
+        if let Some(expected_attempts) = self.expected_attempts {
+            node.add_task(Box::new(WatcherTask {
+                attempts: self.attempts,
+                expected_attempts,
+            }));
+        }
+        Ok(())
+    }
+}
+
+fn test_backoff() -> RestartBackoff {
+    RestartBackoff::new(Duration::from_millis(1), Duration::from_millis(10), 2.0).unwrap()
+}
+
+#[test]
+fn restart_backoff_is_validated() {
+    let err =
+        RestartBackoff::new(Duration::from_millis(1), Duration::from_millis(10), 0.5).unwrap_err();
+    assert!(err.to_string().contains("multiplier"), "{err}");
+    let err = RestartBackoff::new(
+        Duration::from_millis(1),
+        Duration::from_millis(10),
+        f64::NAN,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("multiplier"), "{err}");
+    let err =
+        RestartBackoff::new(Duration::from_secs(1), Duration::from_millis(10), 2.0).unwrap_err();
+    assert!(err.to_string().contains("exceeds max delay"), "{err}");
+}
+
+#[test]
+fn failed_task_is_restarted() {
+    let (result, attempts, health) =
+        RestartableTasksLayer::new(RestartPolicy::OnFailure(test_backoff())).run();
+    result.unwrap();
+    // The task should've failed twice and then succeeded without being restarted again.
+    assert_eq!(attempts, 3);
+    assert_matches!(health.status(), HealthStatus::ShutDown);
+    let details = health.details().unwrap();
+    assert_eq!(details["restarts"], 2);
+    assert_eq!(details["finished"], true);
+    assert_eq!(details["last_error"], "flaky task failed on attempt 2");
+}
+
+#[test]
+fn panicked_task_is_restarted() {
+    let mut layer = RestartableTasksLayer::new(RestartPolicy::OnFailure(test_backoff()));
+    layer.panics = true;
+    let (result, attempts, health) = layer.run();
+    result.unwrap();
+    assert_eq!(attempts, 3);
+    let details = health.details().unwrap();
+    assert_eq!(details["restarts"], 2);
+    let last_error = details["last_error"].as_str().unwrap();
+    assert!(
+        last_error.contains("flaky task panicked on attempt 2"),
+        "{last_error}"
+    );
+}
+
+#[test]
+fn task_is_always_restarted() {
+    let mut layer = RestartableTasksLayer::new(RestartPolicy::Always(test_backoff()));
+    // Unlike with `OnFailure`, the task should be restarted after it has succeeded.
+    layer.expected_attempts = Some(5);
+    let (result, attempts, health) = layer.run();
+    result.unwrap();
+    assert!(attempts >= 5, "{attempts}");
+    assert_matches!(health.status(), HealthStatus::ShutDown);
+    let details = health.details().unwrap();
+    assert!(details["restarts"].as_u64().unwrap() >= 4, "{details}");
+    assert!(details.get("finished").is_none(), "{details}");
+}
+
+#[test]
+fn task_without_restart_policy_stops_service() {
+    let mut layer = RestartableTasksLayer::new(RestartPolicy::Never);
+    layer.expected_attempts = None;
+    let (result, attempts, health) = layer.run();
+    assert_matches!(result.unwrap_err(), ZkStackServiceError::Task(_));
+    assert_eq!(attempts, 1);
+    assert_matches!(health.status(), HealthStatus::ShutDown);
+    let details = health.details().unwrap();
+    assert_eq!(details["restarts"], 0);
+    assert_eq!(details["last_error"], "flaky task failed on attempt 1");
+}
+
+#[test]
+fn panicked_task_without_restart_policy_is_reported_in_health() {
+    let mut layer = RestartableTasksLayer::new(RestartPolicy::Never);
+    layer.panics = true;
+    layer.expected_attempts = None;
+    let (result, attempts, health) = layer.run();
+    assert_matches!(result.unwrap_err(), ZkStackServiceError::Task(_));
+    assert_eq!(attempts, 1);
+    assert_matches!(health.status(), HealthStatus::Panicked);
+}
+
+/// Resource used to record the order in which tasks are stopped.
+#[derive(Debug, Clone, Default)]
+struct StoppedTasksResource(Arc<Mutex<Vec<&'static str>>>);
+
+impl Resource for StoppedTasksResource {
+    fn name() -> String {
+        "test/stopped_tasks".into()
+    }
+}
+
+/// Task that records its name once it receives the stop signal.
+#[derive(Debug)]
+struct StoppableTask {
+    name: &'static str,
+    stop_delay: Duration,
+    stopped_tasks: StoppedTasksResource,
+}
+
+#[async_trait::async_trait]
+impl Task for StoppableTask {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn run(self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        stop_receiver.0.changed().await?;
+        tokio::time::sleep(self.stop_delay).await;
+        self.stopped_tasks.0.lock().unwrap().push(self.name);
+        Ok(())
+    }
+}
+
+/// Task that ignores the stop signal.
+#[derive(Debug)]
+struct HangingTask;
+
+#[async_trait::async_trait]
+impl Task for HangingTask {
+    fn name(&self) -> &'static str {
+        "hanging_task"
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        futures::future::pending().await
+    }
+
+    fn shutdown_timeout(&self) -> Duration {
+        Duration::from_millis(10)
+    }
+}
+
+#[derive(Debug)]
+struct ProviderLayer(StoppedTasksResource);
+
+#[async_trait::async_trait]
+impl WiringLayer for ProviderLayer {
+    fn layer_name(&self) -> &'static str {
+        "provider_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        node.insert_resource(self.0.clone())?;
+        node.add_task(Box::new(StoppableTask {
+            name: "provider_task",
+            stop_delay: Duration::ZERO,
+            stopped_tasks: self.0,
+        }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct ConsumerLayer;
+
+#[async_trait::async_trait]
+impl WiringLayer for ConsumerLayer {
+    fn layer_name(&self) -> &'static str {
+        "consumer_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        let stopped_tasks = node.get_resource::<StoppedTasksResource>().await?;
+        // The consumer takes longer to stop, so the provider would be stopped first if tasks were stopped concurrently.
+        node.add_task(Box::new(StoppableTask {
+            name: "consumer_task",
+            stop_delay: Duration::from_millis(50),
+            stopped_tasks,
+        }));
+        node.add_task(Box::new(HangingTask));
+        // Exits immediately, which initiates the shutdown.
+        node.add_task(Box::new(ErrorTask));
+        Ok(())
+    }
+}
+
+#[test]
+fn tasks_are_stopped_in_dependency_order() {
+    let stopped_tasks = StoppedTasksResource::default();
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service
+        .add_layer(ProviderLayer(stopped_tasks.clone()))
+        .add_layer(ConsumerLayer);
+
+    let result = zk_stack_service.build().unwrap().run();
+    assert_matches!(result.unwrap_err(), ZkStackServiceError::Task(_));
+    assert_eq!(
+        *stopped_tasks.0.lock().unwrap(),
+        ["consumer_task", "provider_task"]
+    );
+}
+
+#[derive(Debug, Clone)]
+struct UnavailableResource;
+
+impl Resource for UnavailableResource {
+    fn name() -> String {
+        "test/unavailable".into()
+    }
+}
+
+#[derive(Debug)]
+struct MissingResourceLayer;
+
+#[async_trait::async_trait]
+impl WiringLayer for MissingResourceLayer {
+    fn layer_name(&self) -> &'static str {
+        "missing_resource_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        node.get_resource::<UnavailableResource>().await?;
+        Ok(())
+    }
+}
+
+#[test]
+fn dry_run_returns_wiring_graph() {
+    let stopped_tasks = StoppedTasksResource::default();
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service
+        .add_layer(ProviderLayer(stopped_tasks.clone()))
+        .add_layer(ConsumerLayer)
+        .add_layer(MissingResourceLayer);
+
+    let graph = zk_stack_service.dry_run().unwrap();
+    // No tasks should be run.
+    assert!(stopped_tasks.0.lock().unwrap().is_empty());
+
+    let layer_names: Vec<_> = graph
+        .layers
+        .iter()
+        .map(|layer| layer.name.as_str())
+        .collect();
+    assert_eq!(
+        layer_names,
+        ["provider_layer", "consumer_layer", "missing_resource_layer"]
+    );
+    assert_eq!(graph.layers[0].provides, ["test/stopped_tasks"]);
+    assert_eq!(graph.layers[1].requests, ["test/stopped_tasks"]);
+    let consumer_tasks: Vec<_> = graph.layers[1]
+        .tasks
+        .iter()
+        .map(|task| (task.name.as_str(), task.kind))
+        .collect();
+    assert_eq!(
+        consumer_tasks,
+        [
+            ("consumer_task", TaskKind::Task),
+            ("hanging_task", TaskKind::Task),
+            ("error_task", TaskKind::Task),
+        ]
+    );
+    assert!(graph.layers[2].error.is_some());
+    assert_eq!(
+        graph.missing_resources,
+        [MissingResource {
+            resource: "test/unavailable".into(),
+            requested_by: vec!["missing_resource_layer".into()],
+        }]
+    );
+    assert!(graph.unused_resources.is_empty());
+
+    let dot = graph.to_dot();
+    assert!(dot.contains("\"layer:provider_layer\" -> \"resource:test/stopped_tasks\";"));
+    assert!(dot.contains("\"resource:test/stopped_tasks\" -> \"layer:consumer_layer\";"));
+
+    // If there are no consumers, the resource is unused.
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service.add_layer(ProviderLayer(stopped_tasks));
+    let graph = zk_stack_service.dry_run().unwrap();
+    assert_eq!(graph.unused_resources, ["test/stopped_tasks"]);
+    assert!(graph.missing_resources.is_empty());
+}
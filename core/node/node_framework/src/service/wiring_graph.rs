@@ -0,0 +1,242 @@
+//! Graph of resources and tasks produced by the wiring process.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+};
+
+use serde::Serialize;
+
+/// Kind of a runnable added by a wiring layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    Task,
+    RestartableTask,
+    UnconstrainedTask,
+    OneshotTask,
+    UnconstrainedOneshotTask,
+    Precondition,
+}
+
+/// Task (or precondition) added by a wiring layer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TaskNode {
+    pub name: String,
+    pub kind: TaskKind,
+}
+
+/// Information about a single wiring layer.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LayerNode {
+    pub name: String,
+    /// Resources provided by the layer.
+    pub provides: Vec<String>,
+    /// Resources successfully requested by the layer.
+    pub requests: Vec<String>,
+    /// Resources requested by the layer that were not available at the time of the request.
+    /// This may be fine if the resource is optional for the layer.
+    pub missing: Vec<String>,
+    /// Tasks and preconditions added by the layer.
+    pub tasks: Vec<TaskNode>,
+    /// Wiring error, if the layer has failed to wire.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Resource that was requested, but not provided by any layer wired before the requester.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingResource {
+    pub resource: String,
+    pub requested_by: Vec<String>,
+}
+
+/// Resource / task dependency graph collected during wiring.
+///
+/// Can be obtained without starting any tasks via [`ZkStackServiceBuilder::dry_run()`](super::ZkStackServiceBuilder::dry_run)
+/// and exported as JSON or Graphviz DOT.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WiringGraph {
+    /// Layers in the wiring order.
+    pub layers: Vec<LayerNode>,
+    /// Resources provided by a layer, but never requested by any other layer.
+    pub unused_resources: Vec<String>,
+    /// Resources requested by layers, but not provided at the time of the request.
+    pub missing_resources: Vec<MissingResource>,
+}
+
+impl WiringGraph {
+    pub(super) fn add_layer(&mut self, layer: &str) {
+        self.layers.push(LayerNode {
+            name: layer.to_owned(),
+            ..LayerNode::default()
+        });
+    }
+
+    fn layer_mut(&mut self, layer: &str) -> Option<&mut LayerNode> {
+        self.layers.iter_mut().rev().find(|node| node.name == layer)
+    }
+
+    pub(super) fn record_provided(&mut self, layer: &str, resource: String) {
+        if let Some(node) = self.layer_mut(layer) {
+            // The layer may have checked that the resource is missing before providing it.
+            node.missing.retain(|missing| *missing != resource);
+            push_unique(&mut node.provides, resource);
+        }
+    }
+
+    pub(super) fn record_requested(&mut self, layer: &str, resource: String) {
+        if let Some(node) = self.layer_mut(layer) {
+            push_unique(&mut node.requests, resource);
+        }
+    }
+
+    pub(super) fn record_missing(&mut self, layer: &str, resource: String) {
+        if let Some(node) = self.layer_mut(layer) {
+            push_unique(&mut node.missing, resource);
+        }
+    }
+
+    pub(super) fn record_task(&mut self, layer: &str, name: &str, kind: TaskKind) {
+        if let Some(node) = self.layer_mut(layer) {
+            node.tasks.push(TaskNode {
+                name: name.to_owned(),
+                kind,
+            });
+        }
+    }
+
+    pub(super) fn record_error(&mut self, layer: &str, error: String) {
+        if let Some(node) = self.layer_mut(layer) {
+            node.error = Some(error);
+        }
+    }
+
+    /// Computes unused and missing resources. Should be called once the wiring is complete.
+    pub(super) fn finalize(&mut self) {
+        let mut missing = BTreeMap::<&str, Vec<String>>::new();
+        for node in &self.layers {
+            for resource in &node.missing {
+                missing.entry(resource).or_default().push(node.name.clone());
+            }
+        }
+        self.missing_resources = missing
+            .into_iter()
+            .map(|(resource, requested_by)| MissingResource {
+                resource: resource.to_owned(),
+                requested_by,
+            })
+            .collect();
+
+        let mut unused = BTreeSet::new();
+        for node in &self.layers {
+            for resource in &node.provides {
+                let is_requested = self
+                    .layers
+                    .iter()
+                    .any(|other| other.name != node.name && other.requests.contains(resource));
+                if !is_requested {
+                    unused.insert(resource.clone());
+                }
+            }
+        }
+        self.unused_resources = unused.into_iter().collect();
+    }
+
+    /// Serializes the graph as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("failed serializing wiring graph")
+    }
+
+    /// Serializes the graph in the Graphviz DOT format. Layers are represented as boxes, resources as ellipses,
+    /// and tasks as notes. Failed layers and missing resources are highlighted in red; unused resources are grayed out.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph wiring {\n    rankdir=LR;\n");
+        let missing: BTreeSet<_> = self
+            .missing_resources
+            .iter()
+            .map(|missing| missing.resource.as_str())
+            .collect();
+        let mut resources = BTreeSet::new();
+        for node in &self.layers {
+            resources.extend(node.provides.iter().map(String::as_str));
+            resources.extend(node.requests.iter().map(String::as_str));
+            resources.extend(node.missing.iter().map(String::as_str));
+        }
+
+        for resource in resources {
+            let style = if missing.contains(resource) {
+                ", color=red, style=dashed"
+            } else if self
+                .unused_resources
+                .iter()
+                .any(|unused| unused == resource)
+            {
+                ", color=gray, fontcolor=gray"
+            } else {
+                ""
+            };
+            writeln!(
+                dot,
+                "    {} [label={}, shape=ellipse{style}];",
+                quote(&format!("resource:{resource}")),
+                quote(resource)
+            )
+            .unwrap();
+        }
+
+        for node in &self.layers {
+            let layer_id = quote(&format!("layer:{}", node.name));
+            let style = if node.error.is_some() {
+                ", color=red"
+            } else {
+                ""
+            };
+            writeln!(
+                dot,
+                "    {layer_id} [label={}, shape=box{style}];",
+                quote(&node.name)
+            )
+            .unwrap();
+
+            for resource in &node.provides {
+                let resource_id = quote(&format!("resource:{resource}"));
+                writeln!(dot, "    {layer_id} -> {resource_id};").unwrap();
+            }
+            for resource in &node.requests {
+                let resource_id = quote(&format!("resource:{resource}"));
+                writeln!(dot, "    {resource_id} -> {layer_id};").unwrap();
+            }
+            for resource in &node.missing {
+                let resource_id = quote(&format!("resource:{resource}"));
+                writeln!(
+                    dot,
+                    "    {resource_id} -> {layer_id} [color=red, style=dashed];"
+                )
+                .unwrap();
+            }
+            for task in &node.tasks {
+                let task_id = quote(&format!("task:{}/{}", node.name, task.name));
+                writeln!(
+                    dot,
+                    "    {task_id} [label={}, shape=note];",
+                    quote(&task.name)
+                )
+                .unwrap();
+                writeln!(dot, "    {layer_id} -> {task_id} [style=dotted];").unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn push_unique(values: &mut Vec<String>, value: String) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
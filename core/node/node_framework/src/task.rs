@@ -27,13 +27,35 @@
 //! The unrestricted tasks are rarely needed, but two common cases for them are:
 //! - A task that must be started as soon as possible, e.g. healthcheck server.
 //! - A task that may be a driving force for some precondition to be met.
+//!
+//! ## Restartable tasks
+//!
+//! By default, the node shuts down as soon as any long-running task exits. This is desired for critical tasks
+//! (e.g., the state keeper), but may be too strict for auxiliary ones (e.g., metrics reporters). Such tasks can be
+//! added via [`TaskFactory`] together with a [`RestartPolicy`]; the service will then recreate and restart the task
+//! according to the policy instead of shutting down the node.
+//!
+//! ## Shutdown
+//!
+//! Once the node starts shutting down, long-running tasks are stopped in the reverse dependency order of the layers
+//! that have added them: if a layer requests a resource provided by another layer, tasks of the former are stopped
+//! (and awaited) before tasks of the latter receive the stop signal. Each task has a shutdown timeout
+//! (see [`Task::shutdown_timeout()`]); tasks that don't stop in time are dropped and reported in logs. The entire
+//! shutdown process is additionally bounded by [`TOTAL_SHUTDOWN_TIMEOUT`].
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use tokio::sync::Barrier;
 
 use crate::service::StopReceiver;
 
+/// Default amount of time given to a long-running task to finish after it has received the stop signal.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum total amount of time given to all long-running tasks to stop. Once it elapses, tasks that haven't
+/// finished yet are dropped regardless of their individual shutdown timeouts.
+pub const TOTAL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(90);
+
 /// A task implementation.
 ///
 /// Note: any `Task` added to the service will only start after all the [preconditions](crate::precondition::Precondition)
@@ -55,6 +77,12 @@ pub trait Task: 'static + Send {
     ///
     /// Each task is expected to perform the required cleanup after receiving the stop signal.
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()>;
+
+    /// Amount of time given to the task to finish after it has received the stop signal. If the task doesn't finish
+    /// in time, it is dropped.
+    fn shutdown_timeout(&self) -> Duration {
+        DEFAULT_SHUTDOWN_TIMEOUT
+    }
 }
 
 impl dyn Task {
@@ -134,6 +162,11 @@ pub trait UnconstrainedTask: 'static + Send {
 
     /// Runs the task without waiting for any precondition to be met.
     async fn run_unconstrained(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()>;
+
+    /// See [`Task::shutdown_timeout()`].
+    fn shutdown_timeout(&self) -> Duration {
+        DEFAULT_SHUTDOWN_TIMEOUT
+    }
 }
 
 /// An unconstrained analog of [`OneshotTask`].
@@ -149,3 +182,115 @@ pub trait UnconstrainedOneshotTask: 'static + Send {
         stop_receiver: StopReceiver,
     ) -> anyhow::Result<()>;
 }
+
+/// Factory of [`Task`]s used by the service to restart a task according to its [`RestartPolicy`].
+///
+/// Since [`Task::run`] consumes the task, the service needs to create a fresh task instance for each restart.
+pub trait TaskFactory: 'static + Send {
+    /// Unique name of the created tasks.
+    fn name(&self) -> &'static str;
+
+    /// Creates a new instance of the task.
+    fn create_task(&mut self) -> anyhow::Result<Box<dyn Task>>;
+
+    /// See [`Task::shutdown_timeout()`].
+    fn shutdown_timeout(&self) -> Duration {
+        DEFAULT_SHUTDOWN_TIMEOUT
+    }
+}
+
+/// Policy determining whether a task created by a [`TaskFactory`] is restarted after it exits.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum RestartPolicy {
+    /// The task is never restarted; once it exits, the node shuts down. This is the behavior of tasks
+    /// added via [`ServiceContext::add_task()`](crate::service::ServiceContext::add_task()).
+    #[default]
+    Never,
+    /// The task is restarted if it returns an error or panics. If the task exits successfully, the node
+    /// continues running without it.
+    OnFailure(RestartBackoff),
+    /// The task is restarted whenever it exits, unless the node is shutting down.
+    Always(RestartBackoff),
+}
+
+impl RestartPolicy {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Never => "never",
+            Self::OnFailure(_) => "on_failure",
+            Self::Always(_) => "always",
+        }
+    }
+
+    pub(crate) fn backoff(&self) -> Option<RestartBackoff> {
+        match self {
+            Self::Never => None,
+            Self::OnFailure(backoff) | Self::Always(backoff) => Some(*backoff),
+        }
+    }
+}
+
+/// Exponential backoff applied between task restarts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RestartBackoff {
+    initial_delay: Duration,
+    max_delay: Duration,
+    multiplier: f64,
+}
+
+impl Default for RestartBackoff {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RestartBackoff {
+    /// Creates a backoff with the specified params.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `multiplier` is not a finite number `>= 1.0`, or if `initial_delay` exceeds `max_delay`.
+    pub fn new(
+        initial_delay: Duration,
+        max_delay: Duration,
+        multiplier: f64,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            multiplier.is_finite() && multiplier >= 1.0,
+            "backoff multiplier must be a finite number >= 1.0, got {multiplier}"
+        );
+        anyhow::ensure!(
+            initial_delay <= max_delay,
+            "initial backoff delay ({initial_delay:?}) exceeds max delay ({max_delay:?})"
+        );
+        Ok(Self {
+            initial_delay,
+            max_delay,
+            multiplier,
+        })
+    }
+
+    /// Delay before the first restart.
+    pub fn initial_delay(&self) -> Duration {
+        self.initial_delay
+    }
+
+    /// Maximum delay between restarts. If a task has been running for at least this long before exiting,
+    /// the delay is reset to [`Self::initial_delay()`].
+    pub fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+
+    /// Multiplier applied to the delay after each consecutive restart.
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    pub(crate) fn next_delay(&self, delay: Duration) -> Duration {
+        delay.mul_f64(self.multiplier).min(self.max_delay)
+    }
+}
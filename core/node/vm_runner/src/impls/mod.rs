@@ -0,0 +1,5 @@
+mod protective_reads;
+
+pub use protective_reads::{
+    ProtectiveReadsLoader, ProtectiveReadsOutputHandler, ProtectiveReadsWriter,
+};
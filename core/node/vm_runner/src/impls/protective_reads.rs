@@ -0,0 +1,194 @@
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use tokio::sync::watch;
+use zksync_core::state_keeper::{
+    MainBatchExecutor, OutputHandler, StateKeeperOutputHandler, UpdatesManager,
+};
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_object_store::ObjectStore;
+use zksync_prover_interface::inputs::VmRunWitnessInputData;
+use zksync_types::{AccountTreeId, L1BatchNumber, L2ChainId, StorageKey};
+use zksync_utils::u256_to_h256;
+
+use crate::{StorageSyncTask, VmRunner, VmRunnerStorage, VmRunnerStorageLoader};
+
+/// VM runner re-executing sealed L1 batches to produce protective reads and basic witness inputs for them.
+/// This allows producing these artifacts asynchronously, off the state keeper critical path.
+#[derive(Debug)]
+pub struct ProtectiveReadsWriter {
+    runner: VmRunner<ProtectiveReadsLoader>,
+}
+
+impl ProtectiveReadsWriter {
+    /// Creates a new writer together with a task synchronizing its storage. Both must be run
+    /// for the writer to make progress.
+    pub async fn new(
+        pool: ConnectionPool<Core>,
+        object_store: Arc<dyn ObjectStore>,
+        rocksdb_path: String,
+        chain_id: L2ChainId,
+        first_processed_batch: L1BatchNumber,
+        window_size: u32,
+    ) -> anyhow::Result<(Self, StorageSyncTask<ProtectiveReadsLoader>)> {
+        let loader = ProtectiveReadsLoader::new(first_processed_batch, window_size);
+        let (storage, sync_task) =
+            VmRunnerStorage::new(pool.clone(), rocksdb_path, loader.clone(), chain_id).await?;
+        let storage = Arc::new(storage);
+        let batch_executor = MainBatchExecutor::new(storage.clone(), false, false);
+        let output_handler = OutputHandler::new(Box::new(ProtectiveReadsOutputHandler::new(
+            pool.clone(),
+            object_store,
+        )));
+        let runner = VmRunner::new(
+            pool,
+            loader,
+            storage,
+            Box::new(batch_executor),
+            output_handler,
+        );
+        Ok((Self { runner }, sync_task))
+    }
+
+    /// Runs the writer until a stop signal is received.
+    pub async fn run(self, stop_receiver: &watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.runner.run(stop_receiver).await
+    }
+}
+
+/// Loader for [`ProtectiveReadsWriter`] progress.
+#[derive(Debug, Clone)]
+pub struct ProtectiveReadsLoader {
+    first_processed_batch: L1BatchNumber,
+    window_size: u32,
+}
+
+impl ProtectiveReadsLoader {
+    /// Creates a loader. Batches before `first_processed_batch` are considered processed if the writer
+    /// has no persisted progress; at most `window_size` batches are loaded ahead of the last processed one.
+    pub fn new(first_processed_batch: L1BatchNumber, window_size: u32) -> Self {
+        Self {
+            first_processed_batch,
+            window_size,
+        }
+    }
+}
+
+#[async_trait]
+impl VmRunnerStorageLoader for ProtectiveReadsLoader {
+    fn name() -> &'static str {
+        "protective_reads_writer"
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let progress = conn.vm_runner_dal().get_progress(Self::name()).await?;
+        Ok(progress.last_processed_l1_batch.unwrap_or(L1BatchNumber(
+            self.first_processed_batch.0.saturating_sub(1),
+        )))
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let latest_processed_batch = self.latest_processed_batch(conn).await?;
+        let sealed_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default();
+        Ok(sealed_batch.min(latest_processed_batch + self.window_size))
+    }
+}
+
+/// Output handler for [`ProtectiveReadsWriter`] persisting protective reads to Postgres and witness inputs
+/// to the object store.
+#[derive(Debug)]
+pub struct ProtectiveReadsOutputHandler {
+    pool: ConnectionPool<Core>,
+    object_store: Arc<dyn ObjectStore>,
+}
+
+impl ProtectiveReadsOutputHandler {
+    pub fn new(pool: ConnectionPool<Core>, object_store: Arc<dyn ObjectStore>) -> Self {
+        Self { pool, object_store }
+    }
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for ProtectiveReadsOutputHandler {
+    async fn handle_l2_block(&mut self, _updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_l1_batch(&mut self, updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        let l1_batch_number = updates_manager.l1_batch.number;
+        let finished_batch = updates_manager
+            .l1_batch
+            .finished
+            .as_ref()
+            .context("L1 batch is not actually finished")?;
+        let execution_state = &finished_batch.final_execution_state;
+        let read_logs: Vec<_> = execution_state
+            .deduplicated_storage_log_queries
+            .iter()
+            .filter(|log_query| !log_query.rw_flag)
+            .cloned()
+            .collect();
+        let protective_reads: Vec<_> = read_logs
+            .iter()
+            .map(|log| StorageKey::new(AccountTreeId::new(log.address), u256_to_h256(log.key)))
+            .collect();
+
+        let witness_input = VmRunWitnessInputData {
+            l1_batch_number,
+            protocol_version: updates_manager.protocol_version(),
+            used_bytecodes_hashes: execution_state.used_contract_hashes.clone(),
+            initial_heap_content: finished_batch
+                .final_bootloader_memory
+                .clone()
+                .unwrap_or_default(),
+            storage_refunds: execution_state.storage_refunds.clone(),
+            pubdata_costs: execution_state.pubdata_costs.clone(),
+            protective_reads: protective_reads.clone(),
+        };
+        let object_key = self
+            .object_store
+            .put(l1_batch_number, &witness_input)
+            .await
+            .context("failed saving witness input to object store")?;
+        tracing::debug!("Saved witness input for L1 batch #{l1_batch_number} to `{object_key}`");
+
+        let mut conn = self
+            .pool
+            .connection_tagged(ProtectiveReadsLoader::name())
+            .await?;
+        // Protective reads may have already been persisted by the state keeper. In this case, they must match
+        // the re-execution results; otherwise, either the state keeper or the VM runner is misconfigured.
+        let existing_reads = conn
+            .storage_logs_dedup_dal()
+            .get_protective_reads_for_l1_batch(l1_batch_number)
+            .await?;
+        if existing_reads.is_empty() {
+            conn.storage_logs_dedup_dal()
+                .insert_protective_reads(l1_batch_number, &read_logs)
+                .await?;
+        } else if existing_reads.len() != protective_reads.len()
+            || protective_reads
+                .iter()
+                .any(|key| !existing_reads.contains(key))
+        {
+            anyhow::bail!(
+                "Protective reads for L1 batch #{l1_batch_number} produced by re-execution ({} keys) differ \
+                 from ones persisted in Postgres ({} keys)",
+                protective_reads.len(),
+                existing_reads.len()
+            );
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,11 @@
+mod impls;
+mod metrics;
+mod process;
 mod storage;
 
 #[cfg(test)]
 mod tests;
 
-pub use storage::{BatchExecuteData, VmRunnerStorage, VmRunnerStorageLoader};
+pub use impls::{ProtectiveReadsLoader, ProtectiveReadsOutputHandler, ProtectiveReadsWriter};
+pub use process::VmRunner;
+pub use storage::{BatchExecuteData, StorageSyncTask, VmRunnerStorage, VmRunnerStorageLoader};
@@ -0,0 +1,22 @@
+//! Metrics for VM runners.
+
+use std::time::Duration;
+
+use vise::{Buckets, Gauge, Histogram, LabeledFamily, Metrics};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "vm_runner")]
+pub(super) struct VmRunnerMetrics {
+    /// Last L1 batch processed by a VM runner.
+    #[metrics(labels = ["runner"])]
+    pub last_processed_batch: LabeledFamily<&'static str, Gauge<u64>>,
+    /// Last L1 batch ready to be processed by a VM runner.
+    #[metrics(labels = ["runner"])]
+    pub last_ready_batch: LabeledFamily<&'static str, Gauge<u64>>,
+    /// Latency of executing an L1 batch and handling its outputs.
+    #[metrics(labels = ["runner"], buckets = Buckets::LATENCIES)]
+    pub batch_processing_time: LabeledFamily<&'static str, Histogram<Duration>>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<VmRunnerMetrics> = vise::Global::new();
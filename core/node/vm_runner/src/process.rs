@@ -0,0 +1,189 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use multivm::interface::L2BlockEnv;
+use tokio::sync::watch;
+use zksync_core::state_keeper::{
+    BatchExecutor, BatchExecutorHandle, L2BlockParams, OutputHandler, TxExecutionResult,
+    UpdatesManager,
+};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+use crate::{metrics::METRICS, BatchExecuteData, VmRunnerStorage, VmRunnerStorageLoader};
+
+/// Generic VM runner: sequentially re-executes sealed L1 batches and feeds execution results into
+/// the provided output handlers.
+///
+/// Batches are loaded from [`VmRunnerStorage`] and executed using a [`BatchExecutor`], which is expected
+/// to read storage from the same `VmRunnerStorage`. Once all output handlers have processed a batch,
+/// the batch is marked as processed in the `vm_runner_progress` table under the loader [name](VmRunnerStorageLoader::name()).
+/// The default implementation of [`VmRunnerStorageLoader::latest_processed_batch()`] reads this progress,
+/// so that the storage catches up with the runner automatically.
+#[derive(Debug)]
+pub struct VmRunner<L: VmRunnerStorageLoader> {
+    pool: ConnectionPool<Core>,
+    loader: L,
+    storage: Arc<VmRunnerStorage<L>>,
+    batch_executor: Box<dyn BatchExecutor>,
+    output_handler: OutputHandler,
+}
+
+impl<L: VmRunnerStorageLoader> VmRunner<L> {
+    /// Interval between checks whether a new L1 batch is ready to be processed.
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Creates a new VM runner. `loader` should behave identically to the loader used by `storage`
+    /// (e.g., be its clone).
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        loader: L,
+        storage: Arc<VmRunnerStorage<L>>,
+        batch_executor: Box<dyn BatchExecutor>,
+        output_handler: OutputHandler,
+    ) -> Self {
+        Self {
+            pool,
+            loader,
+            storage,
+            batch_executor,
+            output_handler,
+        }
+    }
+
+    /// Runs the VM runner until a stop signal is received.
+    pub async fn run(mut self, stop_receiver: &watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut conn = self.pool.connection_tagged(L::name()).await?;
+        let mut next_batch = self.loader.latest_processed_batch(&mut conn).await? + 1;
+        drop(conn);
+        tracing::info!(
+            "Starting VM runner `{}` from L1 batch #{next_batch}",
+            L::name()
+        );
+
+        let mut is_initialized = false;
+        let mut persisted_ready_batch = None;
+        while !*stop_receiver.borrow() {
+            let mut conn = self.pool.connection_tagged(L::name()).await?;
+            let last_ready_batch = self.loader.last_ready_to_be_loaded_batch(&mut conn).await?;
+            // The runner polls frequently, so the ready batch is only persisted when it changes.
+            if persisted_ready_batch != Some(last_ready_batch) {
+                conn.vm_runner_dal()
+                    .set_last_ready_l1_batch(L::name(), last_ready_batch)
+                    .await?;
+                persisted_ready_batch = Some(last_ready_batch);
+            }
+            drop(conn);
+            METRICS.last_ready_batch[&L::name()].set(last_ready_batch.0.into());
+
+            if next_batch > last_ready_batch {
+                tokio::time::sleep(Self::POLL_INTERVAL).await;
+                continue;
+            }
+            // The batch may be not yet loaded by the storage sync task.
+            let Some(batch_data) = self
+                .storage
+                .load_batch(next_batch)
+                .await
+                .with_context(|| format!("failed loading L1 batch #{next_batch}"))?
+            else {
+                tokio::time::sleep(Self::POLL_INTERVAL).await;
+                continue;
+            };
+
+            let updates_manager =
+                UpdatesManager::new(&batch_data.l1_batch_env, &batch_data.system_env);
+            if !is_initialized {
+                self.output_handler
+                    .initialize(&updates_manager.io_cursor())
+                    .await?;
+                is_initialized = true;
+            }
+
+            let latency = METRICS.batch_processing_time[&L::name()].start();
+            let Some(batch_executor) = self
+                .batch_executor
+                .init_batch(
+                    batch_data.l1_batch_env.clone(),
+                    batch_data.system_env.clone(),
+                    stop_receiver,
+                )
+                .await
+            else {
+                break;
+            };
+            self.process_batch(batch_executor, batch_data, updates_manager)
+                .await
+                .with_context(|| format!("failed processing L1 batch #{next_batch}"))?;
+            latency.observe();
+
+            self.pool
+                .connection_tagged(L::name())
+                .await?
+                .vm_runner_dal()
+                .mark_l1_batch_as_processed(L::name(), next_batch)
+                .await?;
+            METRICS.last_processed_batch[&L::name()].set(next_batch.0.into());
+            tracing::info!("VM runner `{}` processed L1 batch #{next_batch}", L::name());
+            next_batch += 1;
+        }
+        tracing::info!(
+            "Stop signal received, VM runner `{}` is shutting down",
+            L::name()
+        );
+        Ok(())
+    }
+
+    async fn process_batch(
+        &mut self,
+        batch_executor: BatchExecutorHandle,
+        batch_data: BatchExecuteData,
+        mut updates_manager: UpdatesManager,
+    ) -> anyhow::Result<()> {
+        for (index, l2_block) in batch_data.l2_blocks.into_iter().enumerate() {
+            // The first L2 block is started when the batch is initialized.
+            if index > 0 {
+                self.output_handler
+                    .handle_l2_block(&updates_manager)
+                    .await?;
+                updates_manager.push_l2_block(L2BlockParams {
+                    timestamp: l2_block.timestamp,
+                    virtual_blocks: l2_block.virtual_blocks,
+                });
+                batch_executor
+                    .start_next_l2_block(L2BlockEnv::from_l2_block_data(&l2_block))
+                    .await;
+            }
+
+            for tx in l2_block.txs {
+                let result = batch_executor.execute_tx(tx.clone()).await;
+                let TxExecutionResult::Success {
+                    tx_result,
+                    tx_metrics,
+                    compressed_bytecodes,
+                    call_tracer_result,
+                    ..
+                } = result
+                else {
+                    anyhow::bail!(
+                        "Re-executing transaction {:?} failed, although it was executed successfully by state keeper: {:?}",
+                        tx.hash(),
+                        result.err()
+                    );
+                };
+                updates_manager.extend_from_executed_transaction(
+                    tx,
+                    *tx_result,
+                    compressed_bytecodes,
+                    tx_metrics.l1_gas,
+                    tx_metrics.execution_metrics,
+                    call_tracer_result,
+                );
+            }
+        }
+
+        // The last L2 block in the batch is fictive; it's handled together with the batch, same as in state keeper.
+        let finished_batch = batch_executor.finish_batch().await;
+        updates_manager.finish_batch(finished_batch);
+        self.output_handler.handle_l1_batch(&updates_manager).await
+    }
+}
@@ -46,13 +46,19 @@ pub trait VmRunnerStorageLoader: Debug + Send + Sync + 'static {
 
     /// Returns the last L1 batch number that has been processed by this VM runner instance.
     ///
+    /// The default implementation returns the progress persisted by [`VmRunner`](crate::VmRunner),
+    /// or the genesis L1 batch if the runner has not processed any batches yet.
+    ///
     /// # Errors
     ///
     /// Propagates DB errors.
     async fn latest_processed_batch(
         &self,
         conn: &mut Connection<'_, Core>,
-    ) -> anyhow::Result<L1BatchNumber>;
+    ) -> anyhow::Result<L1BatchNumber> {
+        let progress = conn.vm_runner_dal().get_progress(Self::name()).await?;
+        Ok(progress.last_processed_l1_batch.unwrap_or(L1BatchNumber(0)))
+    }
 
     /// Returns the last L1 batch number that is ready to be loaded by this VM runner instance.
     ///
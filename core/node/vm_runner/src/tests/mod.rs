@@ -1,5 +1,6 @@
 use std::{collections::HashMap, ops, sync::Arc, time::Duration};
 
+use anyhow::Context as _;
 use async_trait::async_trait;
 use backon::{ConstantBuilder, ExponentialBuilder, Retryable};
 use rand::Rng;
@@ -10,21 +11,29 @@ use tokio::{
     task::JoinHandle,
 };
 use zksync_contracts::BaseSystemContractsHashes;
+use zksync_core::state_keeper::{
+    MainBatchExecutor, OutputHandler, StateKeeperOutputHandler, UpdatesManager,
+};
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
 use zksync_node_test_utils::{
     create_l1_batch_metadata, create_l2_block, create_l2_transaction, execute_l2_transaction,
     l1_batch_metadata_to_commitment_artifacts,
 };
+use zksync_object_store::ObjectStoreFactory;
+use zksync_prover_interface::inputs::VmRunWitnessInputData;
 use zksync_state::{PgOrRocksdbStorage, PostgresStorage, ReadStorage, ReadStorageFactory};
 use zksync_types::{
-    block::{BlockGasCount, L1BatchHeader},
+    block::{BlockGasCount, L1BatchHeader, L2BlockHasher},
     fee::TransactionExecutionMetrics,
-    AccountTreeId, L1BatchNumber, L2ChainId, ProtocolVersionId, StorageKey, StorageLog,
-    StorageLogKind, StorageValue, H160, H256,
+    AccountTreeId, L1BatchNumber, L2BlockNumber, L2ChainId, ProtocolVersionId, StorageKey,
+    StorageLog, StorageLogKind, StorageValue, H160, H256,
 };
 
-use super::{BatchExecuteData, VmRunnerStorage, VmRunnerStorageLoader};
+use super::{
+    BatchExecuteData, ProtectiveReadsLoader, ProtectiveReadsWriter, VmRunner, VmRunnerStorage,
+    VmRunnerStorageLoader,
+};
 
 #[derive(Debug, Default)]
 struct LoaderMock {
@@ -479,3 +488,308 @@ async fn access_vm_runner_storage() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Loader relying on the default implementation of `latest_processed_batch()`.
+#[derive(Debug)]
+struct ProgressLoader;
+
+#[async_trait]
+impl VmRunnerStorageLoader for ProgressLoader {
+    fn name() -> &'static str {
+        "progress_loader"
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default())
+    }
+}
+
+#[tokio::test]
+async fn storage_follows_persisted_progress() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = connection_pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    store_l2_blocks(
+        &mut conn,
+        1u32..=2u32,
+        genesis_params.base_system_contracts().hashes(),
+    )
+    .await?;
+
+    let loader = ProgressLoader;
+    assert_eq!(
+        loader.latest_processed_batch(&mut conn).await?,
+        L1BatchNumber(0)
+    );
+    drop(conn);
+
+    let db_dir = TempDir::new()?;
+    let (storage, task) = VmRunnerStorage::new(
+        connection_pool.clone(),
+        db_dir.path().to_str().unwrap().to_owned(),
+        loader,
+        L2ChainId::from(270),
+    )
+    .await?;
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let task_handle = tokio::spawn(task.run(stop_receiver));
+
+    storage.load_batch_eventually(L1BatchNumber(1)).await?;
+    storage.load_batch_eventually(L1BatchNumber(2)).await?;
+
+    connection_pool
+        .connection()
+        .await?
+        .vm_runner_dal()
+        .mark_l1_batch_as_processed(ProgressLoader::name(), L1BatchNumber(1))
+        .await?;
+    storage
+        .ensure_batch_unloads_eventually(L1BatchNumber(1))
+        .await?;
+    storage.load_batch_eventually(L1BatchNumber(2)).await?;
+
+    task_handle.abort();
+    Ok(())
+}
+
+#[tokio::test]
+async fn protective_reads_loader_respects_window() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = connection_pool.connection().await?;
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    store_l2_blocks(
+        &mut conn,
+        1u32..=5u32,
+        genesis_params.base_system_contracts().hashes(),
+    )
+    .await?;
+
+    let loader = ProtectiveReadsLoader::new(L1BatchNumber(2), 2);
+    assert_eq!(
+        loader.latest_processed_batch(&mut conn).await?,
+        L1BatchNumber(1)
+    );
+    assert_eq!(
+        loader.last_ready_to_be_loaded_batch(&mut conn).await?,
+        L1BatchNumber(3)
+    );
+
+    conn.vm_runner_dal()
+        .mark_l1_batch_as_processed(ProtectiveReadsLoader::name(), L1BatchNumber(4))
+        .await?;
+    assert_eq!(
+        loader.latest_processed_batch(&mut conn).await?,
+        L1BatchNumber(4)
+    );
+    // The window is capped by the last sealed batch.
+    assert_eq!(
+        loader.last_ready_to_be_loaded_batch(&mut conn).await?,
+        L1BatchNumber(5)
+    );
+    Ok(())
+}
+
+/// Stores an L1 batch consisting of an empty L2 block and a fictive L2 block right after genesis. Unlike
+/// [`store_l2_blocks()`], the batch can be executed by the VM.
+async fn store_executable_l1_batch(
+    conn: &mut Connection<'_, Core>,
+    contract_hashes: BaseSystemContractsHashes,
+) -> anyhow::Result<()> {
+    let mut prev_block_hash = conn
+        .blocks_dal()
+        .get_l2_block_header(L2BlockNumber(0))
+        .await?
+        .context("no genesis L2 block")?
+        .hash;
+    for number in [1, 2] {
+        let mut l2_block = create_l2_block(number);
+        l2_block.base_system_contracts_hashes = contract_hashes;
+        // L2 block hashes are checked by the system context during execution, so they must be valid.
+        l2_block.hash = L2BlockHasher::new(l2_block.number, l2_block.timestamp, prev_block_hash)
+            .finalize(ProtocolVersionId::latest());
+        prev_block_hash = l2_block.hash;
+        conn.blocks_dal().insert_l2_block(&l2_block).await?;
+    }
+
+    let header = L1BatchHeader::new(
+        L1BatchNumber(1),
+        1,
+        contract_hashes,
+        ProtocolVersionId::latest(),
+    );
+    conn.blocks_dal()
+        .insert_l1_batch(
+            &header,
+            &[],
+            BlockGasCount::default(),
+            &[],
+            &[],
+            Default::default(),
+        )
+        .await?;
+    conn.blocks_dal()
+        .mark_l2_blocks_as_executed_in_l1_batch(L1BatchNumber(1))
+        .await?;
+    Ok(())
+}
+
+/// Output handler recording numbers of the handled L1 batches.
+#[derive(Debug, Default)]
+struct RecordingOutputHandler(Arc<RwLock<Vec<L1BatchNumber>>>);
+
+#[async_trait]
+impl StateKeeperOutputHandler for RecordingOutputHandler {
+    async fn handle_l2_block(&mut self, _updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_l1_batch(&mut self, updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        self.0.write().await.push(updates_manager.l1_batch.number);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn vm_runner_processes_ready_batches() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = connection_pool.connection().await?;
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    store_executable_l1_batch(&mut conn, genesis_params.base_system_contracts().hashes()).await?;
+    drop(conn);
+
+    let db_dir = TempDir::new()?;
+    let (storage, sync_task) = VmRunnerStorage::new(
+        connection_pool.clone(),
+        db_dir.path().to_str().unwrap().to_owned(),
+        ProgressLoader,
+        L2ChainId::from(270),
+    )
+    .await?;
+    let storage = Arc::new(storage);
+    let handled_batches = Arc::<RwLock<Vec<_>>>::default();
+    let output_handler = RecordingOutputHandler(handled_batches.clone());
+    let runner = VmRunner::new(
+        connection_pool.clone(),
+        ProgressLoader,
+        storage.clone(),
+        Box::new(MainBatchExecutor::new(storage, false, false)),
+        OutputHandler::new(Box::new(output_handler)),
+    );
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let sync_task_handle = tokio::spawn(sync_task.run(stop_receiver.clone()));
+    let runner_handle = tokio::spawn(async move { runner.run(&stop_receiver).await });
+
+    let progress = (|| async {
+        let progress = connection_pool
+            .connection()
+            .await?
+            .vm_runner_dal()
+            .get_progress(ProgressLoader::name())
+            .await?;
+        anyhow::ensure!(
+            progress.last_processed_l1_batch == Some(L1BatchNumber(1)),
+            "L1 batch #1 is not processed yet"
+        );
+        Ok(progress)
+    })
+    .retry(
+        &ConstantBuilder::default()
+            .with_delay(Duration::from_millis(100))
+            .with_max_times(300),
+    )
+    .await?;
+    assert_eq!(progress.last_ready_l1_batch, Some(L1BatchNumber(1)));
+    assert_eq!(*handled_batches.read().await, [L1BatchNumber(1)]);
+
+    stop_sender.send_replace(true);
+    runner_handle.await??;
+    sync_task_handle.await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn protective_reads_writer_persists_reads_and_witness_inputs() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = connection_pool.connection().await?;
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    store_executable_l1_batch(&mut conn, genesis_params.base_system_contracts().hashes()).await?;
+    drop(conn);
+
+    let object_store = ObjectStoreFactory::mock().create_store().await;
+    let db_dir = TempDir::new()?;
+    let (writer, sync_task) = ProtectiveReadsWriter::new(
+        connection_pool.clone(),
+        object_store.clone(),
+        db_dir.path().to_str().unwrap().to_owned(),
+        L2ChainId::from(270),
+        L1BatchNumber(1),
+        1,
+    )
+    .await?;
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let sync_task_handle = tokio::spawn(sync_task.run(stop_receiver.clone()));
+    let writer_handle = tokio::spawn(async move { writer.run(&stop_receiver).await });
+
+    (|| async {
+        let progress = connection_pool
+            .connection()
+            .await?
+            .vm_runner_dal()
+            .get_progress(ProtectiveReadsLoader::name())
+            .await?;
+        anyhow::ensure!(
+            progress.last_processed_l1_batch == Some(L1BatchNumber(1)),
+            "L1 batch #1 is not processed yet"
+        );
+        Ok(())
+    })
+    .retry(
+        &ConstantBuilder::default()
+            .with_delay(Duration::from_millis(100))
+            .with_max_times(300),
+    )
+    .await?;
+    stop_sender.send_replace(true);
+    writer_handle.await??;
+    sync_task_handle.await??;
+
+    let protective_reads = connection_pool
+        .connection()
+        .await?
+        .storage_logs_dedup_dal()
+        .get_protective_reads_for_l1_batch(L1BatchNumber(1))
+        .await?;
+    // At the very least, the bootloader reads the system context.
+    assert!(!protective_reads.is_empty());
+
+    let witness_input: VmRunWitnessInputData = object_store.get(L1BatchNumber(1)).await?;
+    assert_eq!(witness_input.l1_batch_number, L1BatchNumber(1));
+    assert_eq!(witness_input.protocol_version, ProtocolVersionId::latest());
+    assert!(!witness_input.used_bytecodes_hashes.is_empty());
+    assert_eq!(witness_input.protective_reads.len(), protective_reads.len());
+    assert!(witness_input
+        .protective_reads
+        .iter()
+        .all(|key| protective_reads.contains(key)));
+    Ok(())
+}